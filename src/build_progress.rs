@@ -0,0 +1,362 @@
+//! Progress reporting and cancellation of index builds.
+//!
+//! Building the index of a large file can take a long time. A [`ProgressCallback`] set in
+//! [`BuildOptions::progress`] receives a [`BuildProgress`] snapshot as the build advances
+//! through its phases, and a [`CancellationToken`] set in
+//! [`BuildOptions::cancellation`] stops the build at the next column chunk, index chunk
+//! or index file, which then returns a cancellation error.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::{build_and_save_index_with_options, BuildOptions};
+//! use keywords::build_progress::{CancellationToken, ProgressCallback};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let (progress, mut updates) = ProgressCallback::channel();
+//! let cancellation = CancellationToken::new();
+//! tokio::spawn(async move {
+//!     while updates.changed().await.is_ok() {
+//!         let progress = updates.borrow_and_update().clone();
+//!         println!("{:?}: {} of {} bytes", progress.phase, progress.bytes_streamed, progress.total_bytes);
+//!     }
+//! });
+//!
+//! let options = BuildOptions {
+//!     progress: Some(progress),
+//!     cancellation: Some(cancellation.clone()),
+//!     ..Default::default()
+//! };
+//! // cancellation.cancel() from another task stops the build
+//! build_and_save_index_with_options("s3://bucket/data.parquet", None, None, None, &options).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BuildOptions::progress`]: crate::BuildOptions::progress
+//! [`BuildOptions::cancellation`]: crate::BuildOptions::cancellation
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use crate::checkpoint::CheckpointStatus;
+use crate::error::Error;
+
+/// Phase of an index build, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildPhase {
+    /// Reading the file and splitting its column chunks (or text batches) into keywords
+    #[default]
+    Streaming,
+    /// Laying the sorted keywords out in index chunks of about 1 MB
+    Chunking,
+    /// Serializing the index chunks and filters
+    Serializing,
+    /// Writing the index files to storage
+    Uploading,
+}
+
+/// Snapshot of the progress of an index build.
+///
+/// Counters of a phase keep their final values in the phases after it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BuildProgress {
+    /// Current phase
+    pub phase: BuildPhase,
+
+    /// Bytes of the source file read so far, and its size
+    pub bytes_streamed: u64,
+    pub total_bytes: u64,
+
+    /// Column chunks split into keywords so far, and the number to process (text files
+    /// count their batches of rows, with no known total)
+    pub column_chunks_processed: usize,
+    pub total_column_chunks: usize,
+
+    /// Distinct keywords held in memory; with a memory budget, those found since the
+    /// last spilled run
+    pub keywords_found: usize,
+
+    /// Index chunks laid out (when chunking) or serialized (afterwards), and their number
+    pub index_chunks: usize,
+    pub total_index_chunks: usize,
+
+    /// Bytes of index files written so far, and their total size
+    pub bytes_uploaded: u64,
+    pub total_upload_bytes: u64,
+
+    /// Whether the build resumed from its checkpoint (see [`BuildOptions::checkpoint_directory`])
+    ///
+    /// [`BuildOptions::checkpoint_directory`]: crate::BuildOptions::checkpoint_directory
+    pub checkpoint: CheckpointStatus,
+}
+
+/// Receives the progress of an index build.
+///
+/// The callback is called from the tasks and threads of the build, after every column
+/// chunk, index chunk and index file, so it should return quickly. Clones share the
+/// latest snapshot, which [`latest`](Self::latest) returns.
+#[derive(Clone)]
+pub struct ProgressCallback {
+    callback: Arc<dyn Fn(&BuildProgress) + Send + Sync>,
+    latest: Arc<Mutex<BuildProgress>>,
+}
+
+impl ProgressCallback {
+    /// Creates a progress callback calling `callback` with every update.
+    pub fn new(callback: impl Fn(&BuildProgress) + Send + Sync + 'static) -> Self {
+        ProgressCallback {
+            callback: Arc::new(callback),
+            latest: Arc::new(Mutex::new(BuildProgress::default())),
+        }
+    }
+
+    /// Creates a progress callback publishing every update to a `watch` channel, whose
+    /// receiver always holds the latest snapshot.
+    pub fn channel() -> (Self, watch::Receiver<BuildProgress>) {
+        let (sender, receiver) = watch::channel(BuildProgress::default());
+        let callback = ProgressCallback::new(move |progress| {
+            sender.send_replace(progress.clone());
+        });
+        (callback, receiver)
+    }
+
+    /// The latest progress snapshot.
+    pub fn latest(&self) -> BuildProgress {
+        self.latest.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Applies `update` to the latest snapshot and reports the result.
+    pub(crate) fn report(&self, update: impl FnOnce(&mut BuildProgress)) {
+        let progress = {
+            let mut latest = self.latest.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            update(&mut latest);
+            latest.clone()
+        };
+        (self.callback)(&progress);
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressCallback")
+            .field("latest", &self.latest())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Requests the cancellation of an index build.
+///
+/// Clones share the same state: cancelling any of them cancels the build.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the builds using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress callback and cancellation token of a build, cloned from its
+/// [`BuildOptions`](crate::BuildOptions) so that tasks of the build can own them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BuildMonitor {
+    progress: Option<ProgressCallback>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl BuildMonitor {
+    pub(crate) fn new(progress: Option<ProgressCallback>, cancellation: Option<CancellationToken>) -> Self {
+        BuildMonitor { progress, cancellation }
+    }
+
+    /// Reports progress, if a callback is set.
+    pub(crate) fn report(&self, update: impl FnOnce(&mut BuildProgress)) {
+        if let Some(progress) = &self.progress {
+            progress.report(update);
+        }
+    }
+
+    /// Whether the build was cancelled.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Returns an error if the build was cancelled.
+    pub(crate) fn check_cancelled(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use bytes::Bytes;
+    use crate::{build_and_save_index_with_options, build_index_in_memory_with_options, BuildOptions, ParquetSource};
+    use crate::index_data::TextFormat;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{build_index, string_parquet};
+    use crate::text_source::{process_text_file, TextOptions};
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS: usize = 600;
+    const ROWS_PER_GROUP: usize = 100;
+    const COLUMN_CHUNKS: usize = 2 * ROWS / ROWS_PER_GROUP;
+
+    fn create_parquet() -> Bytes {
+        let message: Vec<String> = (0..ROWS).map(|row| format!("GET /items/{} status:ok", row)).collect();
+        let host: Vec<String> = (0..ROWS).map(|row| format!("web-{}", row % 5)).collect();
+        string_parquet(vec![("message", message.into()), ("host", host.into())], ROWS_PER_GROUP)
+    }
+
+    /// A callback recording every snapshot it receives.
+    fn recording_callback() -> (ProgressCallback, Arc<Mutex<Vec<BuildProgress>>>) {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let recorded = updates.clone();
+        let callback = ProgressCallback::new(move |progress| recorded.lock().unwrap().push(progress.clone()));
+        (callback, updates)
+    }
+
+    #[tokio::test]
+    async fn test_progress_through_phases() {
+        let bytes = create_parquet();
+        for threads in [1, 2] {
+            let (callback, updates) = recording_callback();
+            let options = BuildOptions { progress: Some(callback.clone()), threads, ..Default::default() };
+            build_index(bytes.clone(), &options).await;
+
+            // Phases run in order, and streaming counters only grow
+            let updates = updates.lock().unwrap();
+            assert!(updates.windows(2).all(|pair| pair[0].phase as u8 <= pair[1].phase as u8));
+            assert!(updates.windows(2).all(|pair| pair[0].column_chunks_processed <= pair[1].column_chunks_processed));
+            for phase in [BuildPhase::Streaming, BuildPhase::Chunking, BuildPhase::Serializing, BuildPhase::Uploading] {
+                assert!(updates.iter().any(|progress| progress.phase == phase), "{:?} not reported", phase);
+            }
+
+            let last = callback.latest();
+            assert_eq!(&last, updates.last().unwrap());
+            assert_eq!(last.phase, BuildPhase::Uploading);
+            assert_eq!((last.bytes_streamed, last.total_bytes), (bytes.len() as u64, bytes.len() as u64));
+            assert_eq!((last.column_chunks_processed, last.total_column_chunks), (COLUMN_CHUNKS, COLUMN_CHUNKS));
+            assert!(last.keywords_found > ROWS);
+            assert!(last.total_index_chunks >= 1);
+            assert_eq!(last.index_chunks, last.total_index_chunks);
+            assert!(last.total_upload_bytes > 0);
+            assert_eq!(last.bytes_uploaded, last.total_upload_bytes);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_before_start() {
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let (callback, _) = recording_callback();
+        let options = BuildOptions { progress: Some(callback.clone()), cancellation: Some(cancellation), ..Default::default() };
+
+        let result = build_index_in_memory_with_options(ParquetSource::Bytes(create_parquet()), None, None, &options).await;
+        assert!(result.is_err_and(|error| error.to_string().contains("cancelled")));
+        assert_eq!(callback.latest().column_chunks_processed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_while_streaming() {
+        for threads in [1, 2] {
+            let cancellation = CancellationToken::new();
+            let token = cancellation.clone();
+            let callback = ProgressCallback::new(move |progress| {
+                if progress.column_chunks_processed == 1 {
+                    token.cancel();
+                }
+            });
+            let options = BuildOptions {
+                progress: Some(callback.clone()),
+                cancellation: Some(cancellation),
+                threads,
+                ..Default::default()
+            };
+
+            let result = build_index_in_memory_with_options(ParquetSource::Bytes(create_parquet()), None, None, &options).await;
+            assert!(result.is_err());
+
+            // The build stopped at the next column chunk, before the later phases
+            let last = callback.latest();
+            assert_eq!(last.phase, BuildPhase::Streaming);
+            assert!(last.column_chunks_processed < COLUMN_CHUNKS);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_before_upload() {
+        let path = "memory://build-progress-cancel-upload.parquet";
+        register_memory_file(path, create_parquet()).await.unwrap();
+
+        let cancellation = CancellationToken::new();
+        let token = cancellation.clone();
+        let callback = ProgressCallback::new(move |progress| {
+            if progress.phase == BuildPhase::Uploading {
+                token.cancel();
+            }
+        });
+        let options = BuildOptions { progress: Some(callback.clone()), cancellation: Some(cancellation), ..Default::default() };
+
+        assert!(build_and_save_index_with_options(path, None, None, None, &options).await.is_err());
+        assert_eq!(callback.latest().bytes_uploaded, 0);
+        assert!(KeywordSearcher::load(path, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_text_source_progress() {
+        let csv: String = std::iter::once("id,host\n".to_string())
+            .chain((0..ROWS).map(|row| format!("{},web-{}\n", row, row % 5)))
+            .collect();
+        let (callback, _) = recording_callback();
+        let options = BuildOptions { progress: Some(callback.clone()), ..Default::default() };
+        let text_options = TextOptions { rows_per_batch: ROWS_PER_GROUP, ..TextOptions::new(TextFormat::CSV) };
+
+        process_text_file(ParquetSource::Bytes(Bytes::from(csv.clone())), &text_options, None, None, &options).await.unwrap();
+        let last = callback.latest();
+        assert_eq!(last.phase, BuildPhase::Streaming);
+        assert_eq!((last.bytes_streamed, last.total_bytes), (csv.len() as u64, csv.len() as u64));
+        assert_eq!(last.column_chunks_processed, ROWS / ROWS_PER_GROUP);
+        assert!(last.keywords_found > 0);
+    }
+
+    #[test]
+    fn test_callback_shares_latest_snapshot() {
+        let (callback, receiver) = ProgressCallback::channel();
+        let clone = callback.clone();
+        callback.report(|progress| progress.total_bytes = 100);
+        clone.report(|progress| progress.bytes_streamed = 40);
+
+        let expected = BuildProgress { bytes_streamed: 40, total_bytes: 100, ..Default::default() };
+        assert_eq!(callback.latest(), expected);
+        assert_eq!(*receiver.borrow(), expected);
+    }
+
+    #[test]
+    fn test_cancellation_shared_by_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+}
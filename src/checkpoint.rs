@@ -0,0 +1,616 @@
+//! Resumable index builds: checkpointing the keyword data of completed row groups.
+//!
+//! With [`BuildOptions::checkpoint_directory`] set, the build flushes its keyword map to a
+//! numbered run file in that directory once row groups are completed (every
+//! [`BuildOptions::checkpoint_interval`] row groups), then records the checkpoint in a
+//! manifest:
+//!
+//! ```text
+//! <checkpoint_directory>/
+//! ├── checkpoint.rkyv   # Parquet file identity, completed row groups, column pool, runs
+//! ├── run-0.run         # Sorted keywords of the first row groups
+//! ├── run-1.run
+//! └── ...
+//! ```
+//!
+//! A build started again with the same directory reads the manifest and streams only the
+//! row groups after the last checkpoint, merging the runs back like the runs of a
+//! [memory budget](crate::BuildOptions::memory_budget). The checkpoint is only resumed if
+//! it was taken for the same file (path, etag, size and modification time) with the same
+//! excluded columns and the same settings that change the indexed keywords or their
+//! layout (included columns and types, exact value columns, cardinality check, error
+//! rates, granularity, page locations and detail limit); otherwise the build starts
+//! over. [`BuildProgress::checkpoint`](crate::build_progress::BuildProgress::checkpoint)
+//! tells which happened.
+//!
+//! [`build_and_save_index_with_options`](crate::build_and_save_index_with_options)
+//! removes the checkpoint once the index is saved. Callers of
+//! [`process_parquet_file_with_options`](crate::column_parquet_reader::process_parquet_file_with_options)
+//! remove it with [`remove_checkpoint`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::{build_and_save_index_with_options, BuildOptions};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let options = BuildOptions {
+//!     checkpoint_directory: Some("/var/tmp/events-checkpoint".into()),
+//!     checkpoint_interval: 10,
+//!     ..Default::default()
+//! };
+//! // After a failure, the same call resumes after the last checkpointed row group
+//! build_and_save_index_with_options("s3://bucket/events.parquet", None, None, None, &options).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`BuildOptions::checkpoint_directory`]: crate::BuildOptions::checkpoint_directory
+//! [`BuildOptions::checkpoint_interval`]: crate::BuildOptions::checkpoint_interval
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use hashbrown::HashMap;
+use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, to_bytes};
+use rkyv::rancor::Error as RkyvError;
+use rkyv::util::AlignedVec;
+use crate::error::Error;
+use crate::index_data::{ColumnDecision, IndexGranularity};
+use crate::keyword_shred::KeywordOneFile;
+use crate::keyword_spill::KeywordSpill;
+use crate::utils::column_pool::ColumnPool;
+use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::{BuildOptions, ParquetSource};
+
+/// Version of the checkpoint manifest; checkpoints of other versions are not resumed.
+const CHECKPOINT_VERSION: u32 = 4;
+
+/// File name of the checkpoint manifest.
+const MANIFEST_FILE: &str = "checkpoint.rkyv";
+
+/// The state of a build at its last checkpoint.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+struct CheckpointManifest {
+    version: u32,
+
+    /// Identity of the Parquet file, validated on resume
+    parquet_path: String,
+    parquet_etag: String,
+    parquet_size: u64,
+    parquet_last_modified: u64,
+
+    /// Columns excluded before sampling (sorted), validated on resume
+    excluded_columns: Vec<String>,
+
+    /// Settings of the build, validated on resume
+    settings: CheckpointSettings,
+
+    /// Sampling decisions, reused on resume instead of sampling again
+    column_decisions: Vec<ColumnDecision>,
+
+    /// Row groups whose keywords are all in the runs
+    completed_row_groups: u64,
+
+    /// Column names by column ID, and rows with keywords per column ID
+    column_pool: Vec<String>,
+    rows_with_keywords: Vec<(u32, u64)>,
+
+    /// Keywords of every run, `run-0.run` first
+    run_keywords: Vec<u64>,
+}
+
+/// Build settings that change the keywords of the runs, the sampling decisions or the
+/// index laid out from them.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq)]
+struct CheckpointSettings {
+    include_columns: Option<Vec<String>>,
+    /// Arrow data types, as displayed
+    column_types: Option<Vec<String>>,
+    exact_value_columns: Vec<String>,
+    split_levels: u64,
+    /// The whole cardinality check, as debug-formatted
+    cardinality_check: Option<String>,
+    error_rate: f64,
+    column_error_rates: Vec<(String, f64)>,
+    granularity: IndexGranularity,
+    page_locations: bool,
+    keyword_detail_limit: Option<u64>,
+}
+
+impl CheckpointSettings {
+    fn new(options: &BuildOptions, error_rate: f64) -> Self {
+        CheckpointSettings {
+            include_columns: options.include_columns.clone(),
+            column_types: options.column_types.as_ref()
+                .map(|types| types.iter().map(|data_type| data_type.to_string()).collect()),
+            exact_value_columns: options.exact_value_columns.clone(),
+            split_levels: options.split_levels() as u64,
+            cardinality_check: options.cardinality_check.as_ref().map(|check| format!("{:?}", check)),
+            error_rate,
+            column_error_rates: options.column_error_rates.clone(),
+            granularity: options.granularity,
+            page_locations: options.page_locations,
+            keyword_detail_limit: options.keyword_detail_limit.map(|limit| limit as u64),
+        }
+    }
+}
+
+impl CheckpointManifest {
+    /// Whether both manifests are for the same file, excluded columns and settings.
+    fn same_build(&self, other: &CheckpointManifest) -> bool {
+        self.version == other.version
+            && self.parquet_path == other.parquet_path
+            && self.parquet_etag == other.parquet_etag
+            && self.parquet_size == other.parquet_size
+            && self.parquet_last_modified == other.parquet_last_modified
+            && self.excluded_columns == other.excluded_columns
+            && self.settings == other.settings
+    }
+}
+
+/// What a build did with the checkpoint in its checkpoint directory.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CheckpointStatus {
+    /// No checkpoint directory, or no checkpoint in it: the build started from the beginning
+    #[default]
+    None,
+    /// The build resumed after the row groups completed at the checkpoint
+    Resumed { completed_row_groups: usize },
+    /// The checkpoint was taken for another version of the file, other columns or other
+    /// settings, so the build started over
+    Stale,
+    /// The runs of the checkpoint cannot be read (the error), so the build started over
+    Unreadable(String),
+}
+
+/// Checkpoints of an index build in progress.
+pub(crate) struct BuildCheckpoint {
+    directory: PathBuf,
+    interval: usize,
+    manifest: CheckpointManifest,
+
+    /// Rows with keywords per column ID in the row groups of the resumed checkpoint
+    resumed_rows: HashMap<u32, u64>,
+}
+
+/// A checkpoint opened for a build, with the state to resume from.
+pub(crate) struct OpenedCheckpoint {
+    pub(crate) checkpoint: BuildCheckpoint,
+
+    /// Spill writing the runs of the checkpoint, holding the resumed runs
+    pub(crate) spill: KeywordSpill,
+
+    /// Column pool of the resumed row groups (empty for a fresh build)
+    pub(crate) column_pool: ColumnPool,
+
+    /// Sampling decisions of a resumed build
+    pub(crate) column_decisions: Option<Vec<ColumnDecision>>,
+
+    /// Whether the checkpoint was resumed
+    pub(crate) status: CheckpointStatus,
+}
+
+impl BuildCheckpoint {
+    /// Opens the checkpoint in `directory` for a build of `source`, resuming it if it was
+    /// taken for the same file, excluded columns and settings, and otherwise starting over.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - Checkpoint directory, created if missing
+    /// * `options` - Options of the build, for the checkpoint interval, the memory
+    ///   budget and the settings to validate
+    /// * `error_rate` - False positive rate of the build's filters
+    /// * `source` - The Parquet file, which must be a path
+    /// * `excluded_columns` - Columns excluded before sampling
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `source` is not a path, if its metadata cannot be read, or if the
+    /// directory cannot be created.
+    pub(crate) async fn open(
+        directory: &Path,
+        options: &BuildOptions,
+        error_rate: f64,
+        source: &ParquetSource,
+        excluded_columns: &Option<HashSet<String>>,
+    ) -> Result<OpenedCheckpoint, Error> {
+        let ParquetSource::Path(path) = source else {
+            return Err(Error::InvalidArgument("Checkpoints need the path of the Parquet file, to validate it on resume".to_string()));
+        };
+        let (store, obj_path) = get_object_store(path).await?;
+        let head = store.head(&obj_path).await?;
+
+        let mut excluded_columns: Vec<String> = excluded_columns.iter().flatten().cloned().collect();
+        excluded_columns.sort();
+        let manifest = CheckpointManifest {
+            version: CHECKPOINT_VERSION,
+            parquet_path: path.clone(),
+            parquet_etag: head.e_tag.unwrap_or_default(),
+            parquet_size: head.size,
+            parquet_last_modified: head.last_modified.timestamp() as u64,
+            excluded_columns,
+            settings: CheckpointSettings::new(options, error_rate),
+            column_decisions: Vec::new(),
+            completed_row_groups: 0,
+            column_pool: Vec::new(),
+            rows_with_keywords: Vec::new(),
+            run_keywords: Vec::new(),
+        };
+        std::fs::create_dir_all(directory)?;
+        let interval = options.checkpoint_interval;
+        let budget = options.memory_budget.unwrap_or(usize::MAX);
+
+        let mut status = CheckpointStatus::None;
+        if let Some(saved) = read_manifest(directory) {
+            if !saved.same_build(&manifest) {
+                status = CheckpointStatus::Stale;
+            } else {
+                match KeywordSpill::checkpointed(budget, directory.to_path_buf(), &saved.run_keywords) {
+                    Ok(spill) => {
+                        let mut column_pool = ColumnPool::default();
+                        column_pool.strings = saved.column_pool.clone();
+                        column_pool.rebuild_lookup();
+                        return Ok(OpenedCheckpoint {
+                            column_decisions: Some(saved.column_decisions.clone()),
+                            status: CheckpointStatus::Resumed { completed_row_groups: saved.completed_row_groups as usize },
+                            checkpoint: BuildCheckpoint {
+                                directory: directory.to_path_buf(),
+                                interval,
+                                resumed_rows: saved.rows_with_keywords.iter().copied().collect(),
+                                manifest: saved,
+                            },
+                            spill,
+                            column_pool,
+                        });
+                    }
+                    Err(e) => status = CheckpointStatus::Unreadable(e.to_string()),
+                }
+            }
+            remove_checkpoint(directory)?;
+            std::fs::create_dir_all(directory)?;
+        }
+
+        Ok(OpenedCheckpoint {
+            checkpoint: BuildCheckpoint { directory: directory.to_path_buf(), interval, manifest, resumed_rows: HashMap::new() },
+            spill: KeywordSpill::checkpointed(budget, directory.to_path_buf(), &[])?,
+            column_pool: ColumnPool::new(),
+            column_decisions: None,
+            status,
+        })
+    }
+
+    /// Row groups completed at the last checkpoint, which the build skips.
+    pub(crate) fn completed_row_groups(&self) -> usize {
+        self.manifest.completed_row_groups as usize
+    }
+
+    /// Adds the rows with keywords of the resumed row groups to those of the row groups
+    /// streamed since, per column ID.
+    pub(crate) fn add_resumed_rows(&self, rows_with_keywords: &mut HashMap<u32, u64>) {
+        for (&column_id, &rows) in &self.resumed_rows {
+            *rows_with_keywords.entry(column_id).or_default() += rows;
+        }
+    }
+
+    /// Records the sampling decisions of a fresh build.
+    pub(crate) fn set_column_decisions(&mut self, column_decisions: Vec<ColumnDecision>) {
+        self.manifest.column_decisions = column_decisions;
+    }
+
+    /// Takes a checkpoint once `completed` row groups (of `total`) are all merged into
+    /// `keyword_map`, if the interval since the last checkpoint is reached or the last
+    /// row group is completed.
+    ///
+    /// The keyword map is flushed to a new run of `spill`, then the manifest is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows_with_keywords` - Rows with keywords per column ID in the row groups streamed
+    ///   since the resumed checkpoint
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the run or the manifest cannot be written.
+    pub(crate) fn row_groups_completed(
+        &mut self,
+        completed: usize,
+        total: usize,
+        keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+        column_pool: &ColumnPool,
+        rows_with_keywords: &HashMap<u32, u64>,
+        spill: &mut KeywordSpill,
+    ) -> Result<(), Error> {
+        let since_last = completed.saturating_sub(self.completed_row_groups());
+        if since_last == 0 || (since_last < self.interval.max(1) && completed < total) {
+            return Ok(());
+        }
+
+        spill.flush(keyword_map)?;
+
+        let mut total_rows = rows_with_keywords.clone();
+        self.add_resumed_rows(&mut total_rows);
+        let mut manifest = self.manifest.clone();
+        manifest.completed_row_groups = completed as u64;
+        manifest.column_pool = column_pool.strings.clone();
+        manifest.rows_with_keywords = total_rows.into_iter().collect();
+        manifest.run_keywords = spill.run_keywords();
+        write_manifest(&self.directory, &manifest)?;
+        self.manifest = manifest;
+        Ok(())
+    }
+}
+
+/// Removes the checkpoint in `directory`: its manifest and run files, then the directory
+/// itself if it is left empty.
+///
+/// # Errors
+///
+/// Returns error if a checkpoint file cannot be removed.
+pub fn remove_checkpoint(directory: &Path) -> Result<(), Error> {
+    remove_if_exists(&directory.join(MANIFEST_FILE))?;
+    remove_if_exists(&directory.join(format!("{}.tmp", MANIFEST_FILE)))?;
+    if let Ok(entries) = std::fs::read_dir(directory) {
+        for entry in entries {
+            let path = entry?.path();
+            let is_run = path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("run-") && name.ends_with(".run"));
+            if is_run {
+                remove_if_exists(&path)?;
+            }
+        }
+    }
+    let _ = std::fs::remove_dir(directory);
+    Ok(())
+}
+
+/// Whether `directory` holds a checkpoint.
+pub fn checkpoint_exists(directory: &Path) -> bool {
+    directory.join(MANIFEST_FILE).exists()
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the manifest in `directory`, or `None` if there is none or it cannot be read.
+fn read_manifest(directory: &Path) -> Option<CheckpointManifest> {
+    let bytes = std::fs::read(directory.join(MANIFEST_FILE)).ok()?;
+    let mut aligned_buffer = AlignedVec::<16>::with_capacity(bytes.len());
+    aligned_buffer.extend_from_slice(&bytes);
+    rkyv::from_bytes::<CheckpointManifest, RkyvError>(&aligned_buffer).ok()
+        .filter(|manifest| manifest.version == CHECKPOINT_VERSION)
+}
+
+/// Replaces the manifest in `directory`, through a temporary file so that an
+/// interrupted write leaves the previous checkpoint in place.
+fn write_manifest(directory: &Path, manifest: &CheckpointManifest) -> Result<(), Error> {
+    let bytes = to_bytes::<RkyvError>(manifest)
+        .map_err(|e| Error::Serialization(format!("Failed to serialize checkpoint: {}", e)))?;
+    let temporary = directory.join(format!("{}.tmp", MANIFEST_FILE));
+    std::fs::write(&temporary, &bytes)?;
+    std::fs::rename(&temporary, directory.join(MANIFEST_FILE))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use bytes::Bytes;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use crate::{build_and_save_index_with_options, BuildOptions, ParquetSource};
+    use crate::build_progress::{CancellationToken, ProgressCallback};
+    use crate::column_parquet_reader::process_parquet_file_with_options;
+    use crate::index_data::build_distributed_index;
+    use crate::record_batch_indexer::RecordBatchIndexer;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{string_batch, write_parquet};
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS_PER_GROUP: usize = 100;
+
+    /// Two columns in row groups of `ROWS_PER_GROUP` rows; `variant` changes the values.
+    fn create_parquet(rows: usize, variant: &str) -> Bytes {
+        let message: Vec<String> = (0..rows)
+            .map(|row| format!("GET /{}/items/{} status:{} trace-{:032x}", variant, row, row % 4, row * 7919))
+            .collect();
+        let host: Vec<String> = (0..rows).map(|row| format!("web-{}.{}.example.com", row % 13, variant)).collect();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(ROWS_PER_GROUP)
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_dictionary_enabled(false)
+            .build();
+        write_parquet(&string_batch(vec![("message", message.into()), ("host", host.into())]), props)
+    }
+
+    fn checkpoint_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("keywords-checkpoint-{}-{}", name, std::process::id()));
+        let _ = remove_checkpoint(&directory);
+        directory
+    }
+
+    fn checkpoint_options(directory: &Path, threads: usize, interval: usize) -> BuildOptions {
+        BuildOptions {
+            checkpoint_directory: Some(directory.to_path_buf()),
+            checkpoint_interval: interval,
+            threads,
+            ..Default::default()
+        }
+    }
+
+    /// Options cancelling the build once `chunks` column chunks are processed.
+    fn cancelled_after(chunks: usize, options: BuildOptions) -> BuildOptions {
+        let cancellation = CancellationToken::new();
+        let token = cancellation.clone();
+        let progress = ProgressCallback::new(move |progress| {
+            if progress.column_chunks_processed == chunks {
+                token.cancel();
+            }
+        });
+        BuildOptions { progress: Some(progress), cancellation: Some(cancellation), ..options }
+    }
+
+    /// Data file and rows with keywords per column of a build.
+    async fn build(path: &str, options: &BuildOptions) -> (Vec<u8>, Vec<(String, u64)>) {
+        let source = ParquetSource::Path(path.to_string());
+        let result = process_parquet_file_with_options(source.clone(), None, None, options).await.unwrap();
+        let files = build_distributed_index(&result, &source, 0.01, &BuildOptions::default()).await.unwrap();
+        let mut rows: Vec<(String, u64)> = result.column_rows_with_keywords.iter()
+            .map(|(&column_id, &rows)| (result.column_pool.get(column_id).unwrap().to_string(), rows))
+            .collect();
+        rows.sort();
+        (files.data.to_vec().unwrap(), rows)
+    }
+
+    #[tokio::test]
+    async fn test_resume_matches_fresh_build() {
+        let path = "memory://checkpoint-resume.parquet";
+        let row_groups = 8;
+        register_memory_file(path, create_parquet(row_groups * ROWS_PER_GROUP, "a")).await.unwrap();
+        let expected = build(path, &BuildOptions::default()).await;
+
+        for (threads, interval) in [(1, 0), (2, 0), (1, 3)] {
+            let directory = checkpoint_directory(&format!("resume-{}-{}", threads, interval));
+
+            // Uninterrupted builds with checkpoints index the same data
+            assert_eq!(build(path, &checkpoint_options(&directory, threads, interval)).await, expected);
+            remove_checkpoint(&directory).unwrap();
+
+            // Interrupted in the fourth row group, after a checkpoint
+            let source = ParquetSource::Path(path.to_string());
+            let options = cancelled_after(7, checkpoint_options(&directory, threads, interval));
+            assert!(process_parquet_file_with_options(source, None, None, &options).await.is_err());
+            assert!(checkpoint_exists(&directory));
+
+            // The restarted build streams only the row groups after the checkpoint
+            let (progress, _) = ProgressCallback::channel();
+            let options = BuildOptions { progress: Some(progress.clone()), ..checkpoint_options(&directory, threads, interval) };
+            assert_eq!(build(path, &options).await, expected);
+            let streamed = progress.latest().total_column_chunks;
+            assert!(streamed > 0 && streamed < 2 * row_groups, "streamed {} chunks", streamed);
+            assert!(matches!(progress.latest().checkpoint, CheckpointStatus::Resumed { completed_row_groups } if completed_row_groups > 0));
+
+            remove_checkpoint(&directory).unwrap();
+            assert!(!directory.exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_streamed_file() {
+        // Larger than the cached tail of the file, so resuming starts a range request
+        let path = "memory://checkpoint-streamed.parquet";
+        let row_groups = 300;
+        let bytes = create_parquet(row_groups * ROWS_PER_GROUP, "b");
+        assert!(bytes.len() > 2 * 1024 * 1024);
+        register_memory_file(path, bytes).await.unwrap();
+        let expected = build(path, &BuildOptions::default()).await;
+
+        // Interrupted after checkpointing the first half of the row groups
+        let directory = checkpoint_directory("streamed");
+        let options = cancelled_after(row_groups + 1, checkpoint_options(&directory, 1, 50));
+        let source = ParquetSource::Path(path.to_string());
+        assert!(process_parquet_file_with_options(source, None, None, &options).await.is_err());
+
+        let (progress, _) = ProgressCallback::channel();
+        let options = BuildOptions { progress: Some(progress.clone()), ..checkpoint_options(&directory, 1, 50) };
+        assert_eq!(build(path, &options).await, expected);
+        assert_eq!(progress.latest().total_column_chunks, row_groups);
+        assert!(progress.latest().bytes_streamed > 0);
+        remove_checkpoint(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_changed_file_starts_over() {
+        let path = "memory://checkpoint-changed.parquet";
+        register_memory_file(path, create_parquet(6 * ROWS_PER_GROUP, "old")).await.unwrap();
+        let directory = checkpoint_directory("changed");
+        let options = cancelled_after(5, checkpoint_options(&directory, 1, 0));
+        let source = ParquetSource::Path(path.to_string());
+        assert!(process_parquet_file_with_options(source, None, None, &options).await.is_err());
+        assert!(checkpoint_exists(&directory));
+
+        // A new version of the file gets a new etag, so nothing of the old one is kept
+        register_memory_file(path, create_parquet(6 * ROWS_PER_GROUP, "new")).await.unwrap();
+        let expected = build(path, &BuildOptions::default()).await;
+        assert_eq!(build(path, &checkpoint_options(&directory, 1, 0)).await, expected);
+        remove_checkpoint(&directory).unwrap();
+
+        // Excluding other columns starts over too
+        let options = cancelled_after(5, checkpoint_options(&directory, 1, 0));
+        let source = ParquetSource::Path(path.to_string());
+        assert!(process_parquet_file_with_options(source.clone(), None, None, &options).await.is_err());
+        let exclude = Some(["host".to_string()].into_iter().collect());
+        let options = checkpoint_options(&directory, 1, 0);
+        let result = process_parquet_file_with_options(source.clone(), exclude, None, &options).await.unwrap();
+        let files = build_distributed_index(&result, &source, 0.01, &options).await.unwrap();
+        let searcher = KeywordSearcher::from_serialized(&files, format!("{}.index", path), None).unwrap();
+        assert!(!searcher.filters.column_filters.contains_key("host"));
+        assert!(searcher.filters.column_filters["message"].might_contain("new"));
+        drop(result);
+        remove_checkpoint(&directory).unwrap();
+
+        // So does any setting that changes the indexed keywords
+        let changed_settings = [
+            BuildOptions { exact_value_columns: vec!["host".to_string()], ..checkpoint_options(&directory, 1, 0) },
+            BuildOptions { split_levels: Some(2), ..checkpoint_options(&directory, 1, 0) },
+            BuildOptions { include_columns: Some(vec!["message".to_string()]), ..checkpoint_options(&directory, 1, 0) },
+            BuildOptions { page_locations: true, ..checkpoint_options(&directory, 1, 0) },
+            BuildOptions { keyword_detail_limit: Some(10), ..checkpoint_options(&directory, 1, 0) },
+        ];
+        for changed in changed_settings {
+            let options = cancelled_after(5, checkpoint_options(&directory, 1, 0));
+            assert!(process_parquet_file_with_options(source.clone(), None, None, &options).await.is_err());
+            let (progress, _) = ProgressCallback::channel();
+            let options = BuildOptions { progress: Some(progress.clone()), ..changed };
+            process_parquet_file_with_options(source.clone(), None, None, &options).await.unwrap();
+            assert_eq!(progress.latest().checkpoint, CheckpointStatus::Stale);
+            remove_checkpoint(&directory).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saved_index_removes_checkpoint() {
+        let path = "memory://checkpoint-saved.parquet";
+        register_memory_file(path, create_parquet(4 * ROWS_PER_GROUP, "c")).await.unwrap();
+        let directory = checkpoint_directory("saved");
+
+        let options = BuildOptions { memory_budget: Some(1), ..checkpoint_options(&directory, 2, 0) };
+        let failed = cancelled_after(3, options.clone());
+        assert!(build_and_save_index_with_options(path, None, None, None, &failed).await.is_err());
+        assert!(checkpoint_exists(&directory));
+
+        build_and_save_index_with_options(path, None, None, None, &options).await.unwrap();
+        assert!(!checkpoint_exists(&directory));
+        assert!(!directory.exists());
+
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        let result = searcher.search("web-7.c.example.com", Some("host"), true).await.unwrap();
+        let rows: u64 = result.verified_matches.unwrap().column_details.iter()
+            .flat_map(|column| &column.row_groups)
+            .flat_map(|rg| &rg.row_ranges)
+            .map(|range| (range.end_row - range.start_row + 1) as u64)
+            .sum();
+        assert_eq!(rows, (0..4 * ROWS_PER_GROUP).filter(|row| row % 13 == 7).count() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_sources() {
+        let directory = checkpoint_directory("unsupported");
+        let options = checkpoint_options(&directory, 1, 0);
+
+        let source = ParquetSource::Bytes(create_parquet(ROWS_PER_GROUP, "d"));
+        assert!(process_parquet_file_with_options(source, None, None, &options).await.is_err());
+
+        let schema = Arc::new(Schema::new(vec![Field::new("message", DataType::Utf8, false)]));
+        assert!(RecordBatchIndexer::try_new(schema, None, None, &options).is_err());
+        assert!(!checkpoint_exists(&directory));
+    }
+}
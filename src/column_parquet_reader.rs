@@ -1,23 +1,34 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::rc::Rc;
 use std::sync::Arc;
 use arrow::array::*;
-use arrow::compute::cast;
+use arrow::compute::{cast_with_options, CastOptions};
 use arrow::datatypes::*;
 use bytes::{Buf, Bytes, BytesMut};
 use futures::StreamExt;
 use hashbrown::HashMap;
 use indexmap::IndexSet;
 use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
-use parquet::arrow::ProjectionMask;
-use parquet::file::metadata::ParquetMetaData;
+use parquet::arrow::{parquet_to_arrow_schema, ProjectionMask};
+use parquet::arrow::async_reader::ParquetObjectReader;
+use parquet::file::metadata::{PageIndexPolicy, ParquetMetaData, ParquetMetaDataReader};
 use parquet::file::reader::{ChunkReader, FileReader, Length, SerializedFileReader};
+use parquet::errors::ParquetError;
+use object_store::{GetOptions, GetRange};
 use tokio::sync::mpsc;
+use crate::error::Error;
+use crate::build_progress::{BuildMonitor, BuildProgress};
+use crate::column_sampling::{CardinalityCheck, ColumnSample};
+use crate::index_data::{ColumnAction, ColumnDecision, IndexGranularity, PageTable};
 use crate::index_structure::column_filter::ColumnFilter;
-use crate::keyword_shred::{build_column_keywords_map, perform_split, KeywordOneFile};
-use crate::{ParquetSource, ProcessResult};
+use crate::keyword_shred::{add_exact_value, build_column_keywords_map, is_delimiter, perform_split_levels, ChunkKeywords, KeywordOneFile, SplitValue, Tokenization, SPLIT_CHARS_COUNT};
+use crate::checkpoint::BuildCheckpoint;
+use crate::keyword_spill::KeywordSpill;
+use crate::{BuildOptions, ParquetSource, ProcessResult};
 use crate::utils::column_pool::ColumnPool;
 use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::utils::glob::column_matches;
+use crate::utils::nested_columns::{leaf_columns, leaf_values, LeafColumn};
 
 /// Files under this size are read entirely in a single request.
 pub const TWO_MB: u64 = 2 * 1024 * 1024;
@@ -47,8 +58,9 @@ pub struct MetadataWithCache {
 #[derive(Debug, Clone)]
 struct ColumnChunkInfo {
     column_name: String,
-    row_group: u16,
+    row_group: u32,
     column_index: usize,
+    leaf: Arc<LeafColumn>,
     start_offset: u64,
     size: u64,
 }
@@ -57,8 +69,9 @@ struct ColumnChunkInfo {
 struct ColumnChunk {
     bytes: Bytes,
     column_name: String,
-    row_group: u16,
+    row_group: u32,
     column_index: usize,
+    leaf: Arc<LeafColumn>,
     start_offset: u64,
 }
 
@@ -95,14 +108,14 @@ struct ColumnChunk {
 /// [4 bytes: metadata length (little-endian u32)]
 /// [4 bytes: "PAR1" magic number]
 /// ```
-fn read_metadata_length(footer_bytes: &[u8]) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+fn read_metadata_length(footer_bytes: &[u8]) -> Result<usize, Error> {
     if footer_bytes.len() < FOOTER_SIZE {
-        return Err("Footer too short".into());
+        return Err(ParquetError::General("Footer too short".to_string()).into());
     }
 
     let magic = &footer_bytes[footer_bytes.len() - 4..];
     if magic != b"PAR1" {
-        return Err("Invalid Parquet file - missing PAR1 magic number".into());
+        return Err(ParquetError::General("Invalid Parquet file - missing PAR1 magic number".to_string()).into());
     }
 
     let length_bytes = &footer_bytes[footer_bytes.len() - 8..footer_bytes.len() - 4];
@@ -164,20 +177,18 @@ fn read_metadata_length(footer_bytes: &[u8]) -> Result<usize, Box<dyn std::error
 /// - Integer underflow in offset calculations
 /// - Parquet metadata parsing fails
 /// - File is not a valid Parquet file
-async fn read_metadata(
+pub(crate) async fn read_metadata(
     parquet_source: ParquetSource,
     file_size_in: Option<u64>,
-) -> Result<MetadataWithCache, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<MetadataWithCache, Error> {
 
     match &parquet_source {
         ParquetSource::Path(path_str) => {
             let (store, path) = get_object_store(path_str).await?;
 
-            let file_size: u64 = if file_size_in.is_none() {
-                let object_meta = store.head(&path).await?;
-                object_meta.size
-            } else {
-                file_size_in.unwrap()
+            let file_size: u64 = match file_size_in {
+                Some(file_size) => file_size,
+                None => store.head(&path).await?.size,
             };
 
             let initial_read_size :u64 = if file_size <= TWO_MB {
@@ -213,7 +224,7 @@ async fn read_metadata(
                 // Need additional bytes
                 let additional_bytes_needed :usize = total_metadata_size - initial_bytes.len();
                 let additional_start :u64 = initial_start.checked_sub(additional_bytes_needed as u64)
-                    .ok_or("Subtraction Overflow on Parquet Metadata, Parquet file is corrupt")?;
+                    .ok_or_else(|| ParquetError::General("Subtraction Overflow on Parquet Metadata, Parquet file is corrupt".to_string()))?;
                 let additional_bytes = store.get_range(&path, additional_start..initial_start).await?;
 
                 let mut combined_mut = BytesMut::with_capacity(initial_bytes.len() + additional_bytes.len());
@@ -266,14 +277,20 @@ async fn read_metadata(
 ///
 /// * `file_path` - Path to the Parquet file (S3 or local)
 /// * `excluded_columns` - Optional set of column names to skip during processing
+/// * `tokenization` - How the values of each column are turned into keywords
 /// * `metadata_with_cache` - Pre-loaded Parquet metadata with optional cached file data
 /// * `keyword_map` - Mutable reference to the keyword map for storing extracted keywords
 /// * `column_pool` - Mutable reference to the column pool for interning column names
+/// * `threads` - Number of column chunks tokenized concurrently; `1` processes every
+///   chunk directly on the processor task
+/// * `spill` - Memory budget tracking; when given, `keyword_map` is flushed to sorted runs
+///   whenever it grows past the budget and then holds only the keywords since the last run
 ///
 /// # Returns
 ///
-/// Returns `Result<(), Box<dyn std::error::Error + Send + Sync>>`:
-/// - `Ok(())` - Processing completed successfully
+/// Returns `Result<HashMap<u32, u64>, Box<dyn std::error::Error + Send + Sync>>`:
+/// - `Ok(map)` - Processing completed successfully; maps column ID to the number of rows
+///   that produced at least one keyword in that column
 /// - `Err` - If streaming fails, column processing fails, or channel communication fails
 ///
 /// # Architecture
@@ -283,12 +300,28 @@ async fn read_metadata(
 /// │ Object Store│─────▶│ Reader Task  │─────▶│ Processor Task │
 /// │  / Cache    │      │ (spawned)    │      │  (main task)   │
 /// └─────────────┘      └──────────────┘      └────────────────┘
-///        │                    │                       │
-///        │              Channel buffer           keyword_map
-///        │              (1000 or 2 chunks)           │
-///        └──────────▶ Zero-copy slicing ◀───────────┘
+///        │                    │                   │       ▲
+///        │              Channel buffer            ▼       │ merge in
+///        │              (1000 or 2 chunks)   ┌──────────────┐ file order
+///        │                    │              │ Worker pool  │
+///        │                    │              │ (`threads`)  │
+///        │                    │              └──────────────┘
+///        └──────────▶ Zero-copy slicing
 /// ```
 ///
+/// With more than one thread, each column chunk is tokenized into its own keyword map
+/// on a blocking worker, converted to [`ChunkKeywords`], and merged into `keyword_map`
+/// by the processor task in file order. At most `threads` chunks are in flight at once.
+/// With a [`KeywordSpill`], the estimated size of each merged chunk is accounted after
+/// the merge, so runs always end on a column chunk boundary.
+///
+/// Every column chunk read and processed is reported to `monitor`, which is checked for
+/// cancellation before each chunk; a cancelled build returns an error.
+///
+/// With a [`BuildCheckpoint`] (which always comes with its `spill`), the row groups it
+/// completed are skipped, and the checkpoint is offered every row group boundary. The
+/// returned rows with keywords are those of the streamed row groups only.
+///
 /// # Performance Optimizations
 ///
 /// - **Single GET request**: Entire file streamed once
@@ -296,32 +329,52 @@ async fn read_metadata(
 /// - **Cached path**: For small files, slices directly from cached bytes
 /// - **Adaptive buffering**: 1000 chunks for small files, 2 for large files
 /// - **Overlapped I/O**: Reader and processor run concurrently
+/// - **Parallel tokenization**: Column chunks are split into keywords on `threads` workers
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn stream_and_process_parquet(
     excluded_columns: Option<HashSet<String>>,
+    tokenization: &ColumnTokenization,
     metadata_with_cache: &MetadataWithCache,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
-    column_pool: &mut ColumnPool
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    column_pool: &mut ColumnPool,
+    threads: usize,
+    mut spill: Option<&mut KeywordSpill>,
+    mut checkpoint: Option<&mut BuildCheckpoint>,
+    monitor: &BuildMonitor,
+) -> Result<HashMap<u32, u64>, Error> {
 
     // Extract metadata
     let metadata = metadata_with_cache.metadata.clone();
     let cache = metadata_with_cache.cached_file_data.clone();
 
+    // Rows that produced at least one keyword, per column ID
+    let mut rows_with_keywords: HashMap<u32, u64> = HashMap::new();
+
     // Extract all column chunks metadata from parquet metadata object, ordered by file position
-    let column_chunks = extract_column_chunk_metadata(&metadata, &excluded_columns);
+    let mut column_chunks = extract_column_chunk_metadata(&metadata, &excluded_columns)?;
+
+    // Row groups completed at the last checkpoint are skipped, and a checkpoint can be
+    // taken whenever all chunks of the following row groups are merged
+    let total_row_groups = metadata.num_row_groups();
+    let mut row_group_boundaries = HashMap::new();
+    if let Some(checkpoint) = &checkpoint {
+        let completed = checkpoint.completed_row_groups();
+        column_chunks.retain(|chunk| chunk.row_group as usize >= completed);
+        row_group_boundaries = completed_row_group_boundaries(&column_chunks, total_row_groups);
+    }
 
     if column_chunks.is_empty() {
-        return Ok(());
+        return Ok(rows_with_keywords);
     }
+    monitor.report(|progress| progress.total_column_chunks = column_chunks.len());
 
     // Create channel for passing column chunks from reader to processor
     //
-    // Design decision: Process columns sequentially rather than in parallel
-    // Current single-threaded approach simplifies memory management and avoids
-    // contention on the shared keyword_map HashMap. Parallelizing would require
-    // either sharded locks (DashMap) or thread-local maps with merge overhead.
-    // For most files, I/O (not CPU) is a bottleneck, so parallelization wouldn't
-    // improve throughput enough. Revisit if CPU-bound processing is identified.
+    // Design decision: Tokenize column chunks in parallel into per-chunk keyword maps
+    // and merge them into the shared keyword_map on the processor task. This keeps
+    // the Rc-based keyword_map single-owner (no locks), and merging per distinct
+    // keyword per chunk is much cheaper than splitting every value. Merging in file
+    // order keeps column IDs and row order independent of the thread count.
 
     // Use high capacity for <=250MB files, limited for large files
     let channel_capacity = if metadata_with_cache.file_size < 250 * 1024 * 1024 {
@@ -335,64 +388,155 @@ pub(crate) async fn stream_and_process_parquet(
     let metadata_for_processor = Arc::clone(&metadata_arc);
 
     // Choose path based on whether we have cached bytes
+    let reader_monitor = monitor.clone();
     let reader_handle = if cache.cached_range_start == 0 {
         // Fast path: slice directly from cached bytes (zero-copy)
+        reader_monitor.report(|progress| progress.bytes_streamed = progress.total_bytes);
         tokio::spawn(async move {
             for chunk_info in column_chunks {
+                if reader_monitor.is_cancelled() {
+                    return Ok(());
+                }
                 let start = chunk_info.start_offset as usize;
                 let end = start + chunk_info.size as usize;
 
                 // Zero-copy slice from cached bytes
                 let column_bytes = cache.cached_file_data.slice(start..end);
 
-                tx.send(ColumnChunk {
+                let sent = tx.send(ColumnChunk {
                     bytes: column_bytes,
                     column_name: chunk_info.column_name,
                     row_group: chunk_info.row_group,
                     column_index: chunk_info.column_index,
+                    leaf: chunk_info.leaf,
                     start_offset: chunk_info.start_offset,
-                })
-                    .await
-                    .expect("Failed to send column chunk to processor");
+                }).await;
+                // The processor stopped on an error or cancellation
+                if sent.is_err() {
+                    return Ok(());
+                }
             }
+            Ok::<(), Error>(())
         })
     } else {
         // Network path: stream from object store (except the end that we have already cached)
-        // We should be guaranteed by design to have path information in the metadata_with_cache object
-        let path_str: &str = match &metadata_with_cache.parquet_source {
-            ParquetSource::Path(path) => {
-                path.as_str()
-            }
-            ParquetSource::Bytes(_) => {
-                panic!("Design error, should not be possible to get bytes parquet source in this else block")
-            }
+        // In-memory sources are always fully cached, so they have a path here
+        let ParquetSource::Path(path_str) = &metadata_with_cache.parquet_source else {
+            return Err(Error::InvalidArgument("In-memory Parquet data must be fully cached".to_string()));
         };
+        // Streaming starts at the first column chunk (after the row groups of a checkpoint)
         let (store, path) = get_object_store(path_str).await?;
-        let get_result = store.get(&path).await?;
+        let stream_start = column_chunks[0].start_offset;
+        let get_options = GetOptions { range: Some(GetRange::Offset(stream_start)), ..Default::default() };
+        let get_result = store.get_opts(&path, get_options).await?;
         let stream_file = get_result.into_stream();
 
         // Spawn reader task (handles streaming and buffering)
-        tokio::spawn(async move {
-            reader_task(stream_file, column_chunks, tx).await;
-        })
+        tokio::spawn(reader_task(stream_file, column_chunks, tx, reader_monitor))
     };
 
-    // Processor task (decodes and processes columns)
-    // Runs in main task to ensure sequential processing
-    while let Some(column_chunk) = rx.recv().await {
-        // Design decision: Rely on object_store crate's built-in retry logic for transient S3 failures
-        // The object_store library already implements exponential backoff for transient errors.
-        // Additional retry logic here would add complexity without clear benefit, since we can't
-        // distinguish transient vs fatal errors at this layer. Fatal errors (corrupted Parquet,
-        // invalid schemas) should fail fast. If more sophisticated error recovery is needed
-        // (e.g., skip corrupted columns, continue with partial index), add explicit Result handling.
-        process_column_chunk(column_chunk, &metadata_for_processor, keyword_map, column_pool);
+    // Processor task (decodes and processes columns, or hands them to workers)
+    // Design decision: Rely on object_store crate's built-in retry logic for transient S3 failures
+    // The object_store library already implements exponential backoff for transient errors.
+    // Additional retry logic here would add complexity without clear benefit, since we can't
+    // distinguish transient vs fatal errors at this layer. Fatal errors (corrupted Parquet,
+    // invalid schemas) should fail fast. If more sophisticated error recovery is needed
+    // (e.g., skip corrupted columns, continue with partial index), add explicit Result handling.
+    if threads <= 1 && spill.is_none() {
+        while let Some(column_chunk) = rx.recv().await {
+            monitor.check_cancelled()?;
+            let column_reference = column_pool.intern(&column_chunk.column_name);
+            let column_tokenization = tokenization.column(&column_chunk.column_name);
+            let rows = process_column_chunk(column_chunk, &metadata_for_processor, column_reference, column_tokenization, keyword_map)?;
+            if rows > 0 {
+                *rows_with_keywords.entry(column_reference).or_default() += rows;
+            }
+            report_chunk_processed(monitor, keyword_map);
+        }
+    } else {
+        // Per-chunk keyword maps are needed to measure the memory each chunk adds, so a
+        // memory budget always takes this path, with a single worker if requested
+        let threads = threads.max(1);
+
+        // Chunks being tokenized, oldest first so they are merged in file order
+        let mut in_flight: VecDeque<tokio::task::JoinHandle<Result<(ChunkKeywords, u64), Error>>> = VecDeque::with_capacity(threads);
+
+        let mut merged_chunks = 0;
+        let mut merge = |(chunk_keywords, rows): (ChunkKeywords, u64), column_pool: &ColumnPool| {
+            if rows > 0 {
+                *rows_with_keywords.entry(chunk_keywords.column_reference).or_default() += rows;
+            }
+            let added = chunk_keywords.estimated_size();
+            chunk_keywords.merge_into(keyword_map);
+            if let Some(spill) = spill.as_deref_mut() {
+                spill.account(added, keyword_map)?;
+            }
+
+            merged_chunks += 1;
+            if let (Some(checkpoint), Some(spill), Some(&completed)) =
+                (checkpoint.as_deref_mut(), spill.as_deref_mut(), row_group_boundaries.get(&merged_chunks)) {
+                checkpoint.row_groups_completed(completed, total_row_groups, keyword_map, column_pool, &rows_with_keywords, spill)?;
+            }
+            report_chunk_processed(monitor, keyword_map);
+            Ok::<(), Error>(())
+        };
+
+        while let Some(column_chunk) = rx.recv().await {
+            monitor.check_cancelled()?;
+            if in_flight.len() == threads {
+                let oldest = in_flight.pop_front().expect("In-flight queue is full");
+                merge(oldest.await??, column_pool)?;
+            }
+
+            // Intern on the processor task so column IDs follow file order
+            let column_reference = column_pool.intern(&column_chunk.column_name);
+            let column_tokenization = tokenization.column(&column_chunk.column_name);
+            let metadata = Arc::clone(&metadata_for_processor);
+            in_flight.push_back(tokio::task::spawn_blocking(move || {
+                tokenize_column_chunk(column_chunk, &metadata, column_reference, column_tokenization)
+            }));
+        }
+
+        while let Some(handle) = in_flight.pop_front() {
+            merge(handle.await??, column_pool)?;
+        }
     }
 
-    // Ensure reader completed successfully
-    reader_handle.await.expect("Reader task panicked");
+    // Ensure reader completed successfully; it stops early when the build is cancelled
+    reader_handle.await??;
+    monitor.check_cancelled()?;
 
-    Ok(())
+    Ok(rows_with_keywords)
+}
+
+/// Numbers of merged chunks after which leading row groups are completed, with the
+/// number of row groups completed: every position where no later chunk belongs to an
+/// earlier row group, and the end of the chunks.
+fn completed_row_group_boundaries(column_chunks: &[ColumnChunkInfo], total_row_groups: usize) -> HashMap<usize, usize> {
+    let mut boundaries = HashMap::new();
+    boundaries.insert(column_chunks.len(), total_row_groups);
+
+    // Lowest row group of the chunks from each position on
+    let mut later_min = vec![usize::MAX; column_chunks.len() + 1];
+    for i in (0..column_chunks.len()).rev() {
+        later_min[i] = later_min[i + 1].min(column_chunks[i].row_group as usize);
+    }
+    let mut earlier_max = 0;
+    for merged in 1..column_chunks.len() {
+        earlier_max = earlier_max.max(column_chunks[merged - 1].row_group as usize);
+        if earlier_max < later_min[merged] {
+            boundaries.insert(merged, later_min[merged]);
+        }
+    }
+    boundaries
+}
+
+/// Reports a column chunk processed, with the distinct keywords now in `keyword_map`.
+fn report_chunk_processed(monitor: &BuildMonitor, keyword_map: &HashMap<Rc<str>, KeywordOneFile>) {
+    monitor.report(|progress| {
+        progress.column_chunks_processed += 1;
+        progress.keywords_found = keyword_map.len();
+    });
 }
 
 /// Extracts column chunk information from Parquet metadata, ordered by file position.
@@ -410,63 +554,299 @@ pub(crate) async fn stream_and_process_parquet(
 ///
 /// Returns `Vec<ColumnChunkInfo>` containing information about each column chunk:
 /// - Column name
-/// - Row group index (as u16)
+/// - Row group index (as u32)
 /// - Column index within the row group
 /// - Start offset in the file
 /// - Size in bytes
 ///
 /// The vector is sorted by `start_offset` to enable sequential streaming.
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if the file has more than `u16::MAX` (65,535) row groups, which exceeds the
-/// supported limit.
+/// Returns [`Error::LimitExceeded`] if the file has more than `u32::MAX` row groups,
+/// which exceeds the supported limit.
 fn extract_column_chunk_metadata(
     metadata: &ParquetMetaData,
     excluded_columns: &Option<HashSet<String>>
-) -> Vec<ColumnChunkInfo> {
+) -> Result<Vec<ColumnChunkInfo>, Error> {
     let num_row_groups = metadata.num_row_groups();
 
-    if num_row_groups > u16::MAX as usize {
-        panic!(
-            "Parquet file has {} row groups, but only {} row groups are supported (u16::MAX). \
+    if num_row_groups > u32::MAX as usize {
+        return Err(Error::LimitExceeded(format!(
+            "Parquet file has {} row groups, but only {} row groups are supported (u32::MAX). \
              Please split the file into smaller files.",
             num_row_groups,
-            u16::MAX
-        );
+            u32::MAX
+        )));
     }
 
     let mut column_chunks = Vec::new();
 
+    let leaves: Vec<Arc<LeafColumn>> = leaf_columns(
+        metadata.file_metadata().schema_descr(),
+        metadata.file_metadata().key_value_metadata(),
+    ).into_iter().map(Arc::new).collect();
+
+    // A blank name is reserved for the keywords of all columns in the column pool
+    if leaves.iter().any(|leaf| leaf.name.is_empty())
+        && !excluded_columns.as_ref().is_some_and(|set| set.contains("")) {
+        return Err(Error::UnsupportedSchema("Columns with a blank name cannot be indexed".to_string()));
+    }
+
     for rg_idx in 0..num_row_groups {
         let rg_metadata = metadata.row_group(rg_idx);
 
-        for col_idx in 0..rg_metadata.num_columns() {
-            let col_chunk = rg_metadata.column(col_idx);
-            let col_desc = col_chunk.column_descr();
-            let column_name = col_desc.name().to_string();
-
+        for (col_idx, leaf) in leaves.iter().enumerate() {
             // Skip excluded columns
-            if excluded_columns.as_ref().is_some_and(|set| set.contains(&column_name)) {
+            if excluded_columns.as_ref().is_some_and(|set| set.contains(&leaf.name)) {
                 continue;
             }
 
-            let (offset, size) = col_chunk.byte_range();
+            // Leaves of a map are decoded together, so their chunk spans every leaf of the map
+            let (offset, end) = leaf.projection.iter()
+                .map(|&leaf_index| {
+                    let (offset, size) = rg_metadata.column(leaf_index).byte_range();
+                    (offset, offset + size)
+                })
+                .fold((u64::MAX, 0), |(start, end), (offset, chunk_end)| (start.min(offset), end.max(chunk_end)));
 
             column_chunks.push(ColumnChunkInfo {
-                column_name,
-                row_group: rg_idx as u16,
+                column_name: leaf.name.clone(),
+                row_group: rg_idx as u32,
                 column_index: col_idx,
+                leaf: Arc::clone(leaf),
                 start_offset: offset,
-                size,
+                size: end - offset,
             });
         }
     }
 
-    // Sort by file position to process in order
+    // Sort by file position to process in order (leaves of a map share their position)
     column_chunks.sort_by_key(|c| c.start_offset);
 
-    column_chunks
+    Ok(column_chunks)
+}
+
+/// Resolves the columns left out of the index into a set of excluded column names.
+///
+/// Columns are leaf columns named by their path (see [`leaf_columns`]). A column is
+/// indexed when it is not in `exclude_columns`, matches one of
+/// [`BuildOptions::include_columns`] (exact names or glob patterns) if given, and has
+/// one of [`BuildOptions::column_types`] if given. Every other column is added to the
+/// excluded set, so that [`extract_column_chunk_metadata`] never lists its chunks and
+/// their byte ranges are not read.
+///
+/// Column types are those of the Arrow schema derived from the Parquet schema (including
+/// the Arrow schema embedded by Arrow writers); a nested column has the type of its
+/// leaf values, e.g. `Utf8` for `tags[]` in a list of strings.
+///
+/// # Returns
+///
+/// `exclude_columns` extended with the unselected columns, or `None` if every column
+/// is indexed.
+fn resolve_excluded_columns(
+    metadata: &ParquetMetaData,
+    exclude_columns: Option<HashSet<String>>,
+    options: &BuildOptions,
+) -> Result<Option<HashSet<String>>, Error> {
+    if options.include_columns.is_none() && options.column_types.is_none() {
+        return Ok(exclude_columns);
+    }
+
+    let schema_descr = metadata.file_metadata().schema_descr();
+    // Fail rather than select columns without their Arrow types
+    parquet_to_arrow_schema(schema_descr, metadata.file_metadata().key_value_metadata())?;
+
+    let leaves = leaf_columns(schema_descr, metadata.file_metadata().key_value_metadata());
+    Ok(excluded_leaf_columns(&leaves, exclude_columns, options))
+}
+
+/// Adds the leaf columns left out by [`BuildOptions::include_columns`] and
+/// [`BuildOptions::column_types`] to `exclude_columns` (see [`resolve_excluded_columns`]).
+pub(crate) fn excluded_leaf_columns(
+    leaves: &[LeafColumn],
+    exclude_columns: Option<HashSet<String>>,
+    options: &BuildOptions,
+) -> Option<HashSet<String>> {
+    if options.include_columns.is_none() && options.column_types.is_none() {
+        return exclude_columns;
+    }
+
+    let mut excluded = exclude_columns.unwrap_or_default();
+    for leaf in leaves {
+        let column_name = leaf.name.as_str();
+
+        let included = options.include_columns.as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|pattern| column_matches(pattern, column_name)));
+
+        let type_selected = options.column_types.as_ref()
+            .is_none_or(|types| types.iter().any(|selected| data_type_matches(selected, &leaf.data_type)));
+
+        if !included || !type_selected {
+            excluded.insert(column_name.to_string());
+        }
+    }
+
+    Some(excluded)
+}
+
+/// Whether a column's Arrow type matches a selected type.
+///
+/// A selected dictionary type matches dictionaries of any key type with the same value
+/// type, so `Dictionary(Int32, Utf8)` also selects `Dictionary(Int8, Utf8)` columns.
+fn data_type_matches(selected: &DataType, data_type: &DataType) -> bool {
+    match (selected, data_type) {
+        (DataType::Dictionary(_, selected_value), DataType::Dictionary(_, value)) => selected_value == value,
+        _ => selected == data_type,
+    }
+}
+
+/// Reads the offset index of the file and lists the data pages of every indexed column chunk.
+///
+/// The offset index is read from the cache when the whole file is cached, and fetched
+/// from the object store otherwise. Column chunks without an offset index are left out.
+///
+/// # Returns
+///
+/// One [`PageTable`] per column chunk of a column in `column_pool`, sorted by column
+/// ID and row group.
+pub(crate) async fn read_page_tables(
+    metadata_with_cache: &MetadataWithCache,
+    column_pool: &ColumnPool,
+) -> Result<Vec<PageTable>, Error> {
+    let mut metadata_reader = ParquetMetaDataReader::new_with_metadata(metadata_with_cache.metadata.clone())
+        .with_column_index_policy(PageIndexPolicy::Skip)
+        .with_offset_index_policy(PageIndexPolicy::Optional);
+    let cache = &metadata_with_cache.cached_file_data;
+    match &metadata_with_cache.parquet_source {
+        ParquetSource::Path(path) if cache.cached_range_start != 0 => {
+            let (store, path) = get_object_store(path).await?;
+            let mut object_reader = ParquetObjectReader::new(store, path)
+                .with_file_size(metadata_with_cache.file_size);
+            metadata_reader.load_page_index(&mut object_reader).await?;
+        }
+        _ => metadata_reader.read_page_indexes(&cache.cached_file_data)?,
+    }
+    let metadata = metadata_reader.finish()?;
+    let Some(offset_index) = metadata.offset_index() else {
+        return Ok(Vec::new());
+    };
+
+    let column_ids: HashMap<&str, u32> = column_pool.strings.iter()
+        .enumerate()
+        .map(|(id, name)| (name.as_str(), id as u32))
+        .collect();
+    let file_metadata = metadata.file_metadata();
+    let leaves = leaf_columns(file_metadata.schema_descr(), file_metadata.key_value_metadata());
+
+    let mut page_tables = Vec::new();
+    for (row_group, (row_group_metadata, chunks)) in metadata.row_groups().iter().zip(offset_index).enumerate() {
+        for (leaf, chunk) in leaves.iter().zip(chunks) {
+            let Some(&column_id) = column_ids.get(leaf.name.as_str()) else {
+                continue;
+            };
+            if chunk.page_locations().is_empty() {
+                continue;
+            }
+            page_tables.push(PageTable {
+                column_id,
+                row_group_id: row_group as u32,
+                first_rows: chunk.page_locations().iter().map(|page| page.first_row_index as u32).collect(),
+                num_rows: row_group_metadata.num_rows() as u32,
+            });
+        }
+    }
+    page_tables.sort_by_key(|table| (table.column_id, table.row_group_id));
+    Ok(page_tables)
+}
+
+/// Samples the first rows of every column and decides how each column is indexed.
+///
+/// Whole row groups are read from the start of the file until they hold at least
+/// [`CardinalityCheck::sample_rows`] rows, and the first `sample_rows` rows of each
+/// column not in `excluded_columns` are tokenized into a separate keyword map. Column
+/// chunks are sliced from the cache when the whole file is cached, and fetched with one
+/// ranged request otherwise. Values are split at the `split_levels` of the build.
+///
+/// # Returns
+///
+/// One [`ColumnDecision`] per sampled column, in file column order.
+async fn sample_column_cardinality(
+    metadata_with_cache: &MetadataWithCache,
+    excluded_columns: &Option<HashSet<String>>,
+    check: &CardinalityCheck,
+    split_levels: usize,
+) -> Result<Vec<ColumnDecision>, Error> {
+    let metadata = &metadata_with_cache.metadata;
+
+    let mut sampled_row_groups = 0;
+    let mut rows = 0usize;
+    while sampled_row_groups < metadata.num_row_groups() && rows < check.sample_rows {
+        rows += metadata.row_group(sampled_row_groups).num_rows() as usize;
+        sampled_row_groups += 1;
+    }
+
+    let chunk_infos: Vec<ColumnChunkInfo> = extract_column_chunk_metadata(metadata, excluded_columns)?
+        .into_iter()
+        .filter(|chunk| (chunk.row_group as usize) < sampled_row_groups)
+        .collect();
+    let mut column_chunks = read_column_chunks(metadata_with_cache, chunk_infos).await?;
+
+    // Each column's rows must be added in order
+    column_chunks.sort_by_key(|chunk| (chunk.row_group, chunk.column_index));
+
+    // Keyed by column index so the decisions follow the file's column order
+    let mut samples: BTreeMap<usize, (String, ColumnSample)> = BTreeMap::new();
+    let metadata = Arc::new(metadata.clone());
+    for column_chunk in column_chunks {
+        let row_group = column_chunk.row_group;
+        let (_, sample) = samples.entry(column_chunk.column_index)
+            .or_insert_with(|| (column_chunk.column_name.clone(), ColumnSample::new(split_levels)));
+        decode_column_chunk(column_chunk, &metadata, |array, row_offset, value_rows| {
+            sample.add(array, value_rows, row_group, row_offset, check.sample_rows);
+        })?;
+    }
+
+    Ok(samples.into_values()
+        .map(|(column_name, sample)| sample.decide(column_name, check))
+        .collect())
+}
+
+/// Reads the bytes of a set of column chunks, from the cache or the object store.
+async fn read_column_chunks(
+    metadata_with_cache: &MetadataWithCache,
+    chunk_infos: Vec<ColumnChunkInfo>,
+) -> Result<Vec<ColumnChunk>, Error> {
+    let cache = &metadata_with_cache.cached_file_data;
+    let chunk_bytes: Vec<Bytes> = if cache.cached_range_start == 0 {
+        chunk_infos.iter()
+            .map(|chunk| {
+                let start = chunk.start_offset as usize;
+                cache.cached_file_data.slice(start..start + chunk.size as usize)
+            })
+            .collect()
+    } else {
+        let ParquetSource::Path(path) = &metadata_with_cache.parquet_source else {
+            return Err(Error::InvalidArgument("In-memory Parquet data must be fully cached".to_string()));
+        };
+        let (store, path) = get_object_store(path).await?;
+        let ranges: Vec<_> = chunk_infos.iter()
+            .map(|chunk| chunk.start_offset..chunk.start_offset + chunk.size)
+            .collect();
+        store.get_ranges(&path, &ranges).await?
+    };
+
+    Ok(chunk_infos.into_iter()
+        .zip(chunk_bytes)
+        .map(|(chunk_info, bytes)| ColumnChunk {
+            bytes,
+            column_name: chunk_info.column_name,
+            row_group: chunk_info.row_group,
+            column_index: chunk_info.column_index,
+            leaf: chunk_info.leaf,
+            start_offset: chunk_info.start_offset,
+        })
+        .collect())
 }
 
 /// Reader task that streams file data and sends column chunks to the processor.
@@ -477,20 +857,26 @@ fn extract_column_chunk_metadata(
 /// 3. Extracts column bytes using zero-copy slicing
 /// 4. Sends complete column chunks to the processor via channel
 ///
-/// The task handles gaps between columns by advancing the buffer appropriately.
+/// The task handles gaps between columns by advancing the buffer appropriately. Chunks with
+/// the same byte range as the previous one (the leaves of a map) reuse its bytes.
 ///
 /// # Arguments
 ///
 /// * `stream` - Stream of byte chunks from the object store
 /// * `column_chunks` - Vector of column chunk metadata, sorted by file position
 /// * `tx` - Channel sender for transmitting column chunks to the processor
+/// * `monitor` - Receives the bytes streamed with every chunk sent; the task stops
+///   early (closing the channel) when the build is cancelled
 ///
-/// # Panics
+/// The task also stops when the channel is closed, as the processor stopped on an error.
 ///
-/// This function panics if:
+/// # Errors
+///
+/// Returns an error if:
 /// - Stream reading fails (network error, permission denied, etc.)
-/// - Stream ends before all expected bytes are received
-/// - Channel send fails (processor task terminated unexpectedly)
+/// - Stream ends before all expected bytes are received, as the file is truncated
+///
+/// The channel is then closed, so the processor stops and returns the error.
 ///
 /// # Implementation Details
 ///
@@ -507,30 +893,54 @@ async fn reader_task(
     mut stream: impl futures::Stream<Item = Result<Bytes, object_store::Error>> + Unpin,
     column_chunks: Vec<ColumnChunkInfo>,
     tx: mpsc::Sender<ColumnChunk>,
-) {
+    monitor: BuildMonitor,
+) -> Result<(), Error> {
     let mut buffer = BytesMut::new();
-    let mut current_file_position = 0u64;
+    let mut current_file_position = column_chunks.first().map_or(0, |chunk| chunk.start_offset);
     let mut chunk_iter = column_chunks.into_iter().peekable();
+    // The leaves of a map share one byte range, which is read once
+    let mut previous_chunk: Option<(u64, u64, Bytes)> = None;
 
     while let Some(chunk_info) = chunk_iter.next() {
+        if monitor.is_cancelled() {
+            return Ok(());
+        }
+        if let Some((start_offset, size, bytes)) = &previous_chunk
+            && *start_offset == chunk_info.start_offset
+            && *size == chunk_info.size {
+            let sent = tx.send(ColumnChunk {
+                bytes: bytes.clone(),
+                column_name: chunk_info.column_name,
+                row_group: chunk_info.row_group,
+                column_index: chunk_info.column_index,
+                leaf: chunk_info.leaf,
+                start_offset: chunk_info.start_offset,
+            }).await;
+            if sent.is_err() {
+                return Ok(());
+            }
+            continue;
+        }
+
         let chunk_end_in_file = chunk_info.start_offset + chunk_info.size;
         let bytes_needed_in_buffer = chunk_end_in_file - current_file_position;
 
         // Read from stream until we have enough bytes for this column
         while (buffer.len() as u64) < bytes_needed_in_buffer {
+            if monitor.is_cancelled() {
+                return Ok(());
+            }
             match stream.next().await {
                 Some(Ok(chunk)) => {
                     buffer.extend_from_slice(&chunk);
                 }
-                Some(Err(e)) => {
-                    panic!("Failed to read from object store stream: {}", e);
-                }
+                Some(Err(e)) => return Err(e.into()),
                 None => {
-                    panic!(
+                    return Err(ParquetError::EOF(format!(
                         "Stream ended unexpectedly. Expected {} bytes but stream ended at position {}",
                         chunk_end_in_file,
                         current_file_position + buffer.len() as u64
-                    );
+                    )).into());
                 }
             }
         }
@@ -545,18 +955,23 @@ async fn reader_task(
         // Extract column bytes (zero-copy via split_to + freeze)
         let column_bytes = buffer.split_to(chunk_info.size as usize).freeze();
         current_file_position += chunk_info.size;
+        previous_chunk = Some((chunk_info.start_offset, chunk_info.size, column_bytes.clone()));
+        monitor.report(|progress| progress.bytes_streamed = current_file_position + buffer.len() as u64);
 
-        // Send to processor
-        tx.send(ColumnChunk {
+        // Send to processor; it stopped on an error or cancellation if the channel is closed
+        let sent = tx.send(ColumnChunk {
             bytes: column_bytes,
             column_name: chunk_info.column_name,
             row_group: chunk_info.row_group,
             column_index: chunk_info.column_index,
+            leaf: chunk_info.leaf,
             start_offset: chunk_info.start_offset,
-        })
-            .await
-            .expect("Failed to send column chunk to processor");
+        }).await;
+        if sent.is_err() {
+            return Ok(());
+        }
     }
+    Ok(())
 }
 
 /// Processes a single column chunk: decodes Arrow data and extracts keywords.
@@ -568,6 +983,10 @@ async fn reader_task(
 /// 4. Converts Arrow data to StringArray
 /// 5. Calls keyword extraction for each string value
 ///
+/// Dictionary-encoded string chunks take a fast path: they are decoded as Arrow
+/// dictionaries, each dictionary entry is split once, and its keywords are added to every
+/// row referencing it (see [`process_arrow_dictionary_array`]).
+///
 /// The function correctly handles multiple batches within a row group by tracking
 /// cumulative row offsets.
 ///
@@ -575,16 +994,22 @@ async fn reader_task(
 ///
 /// * `column_chunk` - Column chunk with bytes and metadata
 /// * `metadata` - Shared reference to Parquet metadata
+/// * `column_reference` - The chunk's column, already interned in the column pool
+/// * `tokenization` - Whether values are split, and at how many levels, or kept whole
 /// * `keyword_map` - Mutable reference to keyword map for storing results
-/// * `column_pool` - Mutable reference to column pool for name interning
 ///
-/// # Panics
+/// # Returns
+///
+/// The number of rows in the chunk that produced at least one keyword.
 ///
-/// Panics if:
+/// # Errors
+///
+/// Returns an error if:
 /// - Arrow metadata creation fails
 /// - Parquet reader build fails
-/// - Record batch reading fails
-/// - Type casting fails (shouldn't happen with proper conversion)
+/// - Record batch reading fails, e.g. for a corrupt page
+/// - A batch does not have the structure of the leaf, or its values cannot be cast to
+///   strings
 ///
 /// # Multiple Batch Handling
 ///
@@ -600,22 +1025,184 @@ async fn reader_task(
 fn process_column_chunk(
     column_chunk: ColumnChunk,
     metadata: &Arc<ParquetMetaData>,
+    column_reference: u32,
+    tokenization: Tokenization,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
-    column_pool: &mut ColumnPool
-) {
+) -> Result<u64, Error> {
+    let row_group = column_chunk.row_group;
+    let mut rows_with_keywords = 0u64;
+    let mut dictionary_splits = DictionarySplits::new(tokenization);
+
+    decode_leaf_arrays(column_chunk, metadata, true, |values, row_offset, value_rows| {
+        rows_with_keywords += process_leaf_values(
+            values,
+            column_reference,
+            row_group,
+            row_offset,
+            value_rows,
+            &mut dictionary_splits,
+            keyword_map,
+        )?;
+        Ok(())
+    })?;
+
+    Ok(rows_with_keywords)
+}
+
+/// Processes one batch of a leaf column's values, whatever their Arrow type.
+///
+/// `Dictionary(Int32, Utf8)` arrays are processed with [`process_arrow_dictionary_array`],
+/// every other array is cast to strings (see [`cast_to_strings`]) and processed with
+/// [`process_arrow_string_array`].
+///
+/// # Arguments
+///
+/// * `values` - The leaf's values
+/// * `column_reference` - The column being indexed, already interned in the column pool
+/// * `row_group` - The row group number (u32)
+/// * `row_offset` - The row offset of the batch's first row within the row group
+/// * `value_rows` - For values of a nested leaf, the row of each value relative to
+///   `row_offset`; `None` when value and row indices match
+/// * `dictionary_splits` - Splits of the dictionary entries, kept across the chunk's
+///   batches (and how values are tokenized)
+/// * `keyword_map` - Mutable reference to the HashMap storing indexed keywords
+///
+/// # Returns
+///
+/// The number of rows in the batch that produced at least one keyword.
+///
+/// # Errors
+///
+/// Returns error if the values cannot be cast to strings.
+pub(crate) fn process_leaf_values(
+    values: &ArrayRef,
+    column_reference: u32,
+    row_group: u32,
+    row_offset: u32,
+    value_rows: Option<&[u32]>,
+    dictionary_splits: &mut DictionarySplits,
+    keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+) -> Result<u64, Error> {
+    if let Some(dictionary_array) = values.as_dictionary_opt::<Int32Type>()
+        && dictionary_array.values().data_type() == &DataType::Utf8 {
+        return Ok(process_arrow_dictionary_array(
+            dictionary_array,
+            column_reference,
+            row_group,
+            row_offset,
+            value_rows,
+            dictionary_splits,
+            keyword_map,
+        ));
+    }
+
+    let string_array_ref = cast_to_strings(values)?;
+    Ok(process_arrow_string_array(
+        string_array_ref.as_string::<i32>(),
+        column_reference,
+        row_group,
+        row_offset,
+        value_rows,
+        dictionary_splits.tokenization,
+        keyword_map,
+    ))
+}
+
+/// Casts a leaf's values to a `StringArray`.
+///
+/// The cast is safe: values that cannot be converted (e.g. binary data that is not valid
+/// UTF-8) become nulls and contribute no keywords.
+///
+/// # Errors
+///
+/// Returns error if values of the array's type cannot be cast to strings at all.
+fn cast_to_strings(values: &ArrayRef) -> Result<ArrayRef, Error> {
+    let options = CastOptions { safe: true, ..Default::default() };
+    cast_with_options(values, &DataType::Utf8, &options).map_err(|e| Error::UnsupportedSchema(
+        format!("Values of type {} cannot be indexed as strings: {}", values.data_type(), e)
+    ))
+}
+
+/// Decodes a single column chunk and hands each batch to `process` as strings.
+///
+/// Nested leaves are extracted from their top-level field with [`leaf_values`], and values
+/// are cast to strings with [`cast_to_strings`].
+///
+/// # Arguments
+///
+/// * `column_chunk` - Column chunk with bytes and metadata
+/// * `metadata` - Shared reference to Parquet metadata
+/// * `process` - Called with each batch of values cast to a `StringArray`, the row offset
+///   of the batch's first row within the row group, and for nested leaves the row of each
+///   value relative to that offset
+///
+/// # Errors
+///
+/// Returns an error if the chunk cannot be decoded (see [`process_column_chunk`]), or if
+/// its values cannot be cast to strings.
+fn decode_column_chunk(
+    column_chunk: ColumnChunk,
+    metadata: &Arc<ParquetMetaData>,
+    mut process: impl FnMut(&StringArray, u32, Option<&[u32]>),
+) -> Result<(), Error> {
+    decode_leaf_arrays(column_chunk, metadata, false, |values, row_offset, value_rows| {
+        // Convert Arrow array to StringArray (returns ArrayRef)
+        let string_array_ref = cast_to_strings(values)?;
+        process(string_array_ref.as_string::<i32>(), row_offset, value_rows);
+        Ok(())
+    })
+}
+
+/// Decodes a single column chunk and hands each batch of the leaf's values to `process`.
+///
+/// Nested leaves are extracted from their top-level field with [`leaf_values`].
+///
+/// # Arguments
+///
+/// * `column_chunk` - Column chunk with bytes and metadata
+/// * `metadata` - Shared reference to Parquet metadata
+/// * `read_dictionary` - Decode dictionary-encoded string chunks of top-level columns as
+///   `Dictionary(Int32, Utf8)` arrays (see [`dictionary_schema`]), which share the
+///   chunk's dictionary between batches
+/// * `process` - Called with each batch of values, the row offset of the batch's first
+///   row within the row group, and for nested leaves the row of each value relative to
+///   that offset
+///
+/// # Errors
+///
+/// Returns an error if the chunk cannot be decoded (see [`process_column_chunk`]), or
+/// the error `process` returns.
+fn decode_leaf_arrays(
+    column_chunk: ColumnChunk,
+    metadata: &Arc<ParquetMetaData>,
+    read_dictionary: bool,
+    mut process: impl FnMut(&ArrayRef, u32, Option<&[u32]>) -> Result<(), Error>,
+) -> Result<(), Error> {
     // Create a chunk reader for our column data
     let chunk_reader = ColumnBytesReader::new(column_chunk.bytes, column_chunk.start_offset);
 
-    // Build projection mask to select only this column
+    // Build projection mask to select only this column (and the other leaves of a map)
     let schema = metadata.file_metadata().schema_descr();
-    let projection = ProjectionMask::leaves(schema, vec![column_chunk.column_index]);
+    let projection = ProjectionMask::leaves(schema, column_chunk.leaf.projection.iter().copied());
 
     // Create ArrowReaderMetadata from ParquetMetaData
     // This derives the Arrow schema from the Parquet schema
-    let arrow_metadata = ArrowReaderMetadata::try_new(
+    let mut arrow_metadata = ArrowReaderMetadata::try_new(
         Arc::clone(metadata),
         ArrowReaderOptions::new(),
-    ).expect("Failed to create arrow metadata");
+    )?;
+
+    if read_dictionary {
+        let column = metadata.row_group(column_chunk.row_group as usize).column(column_chunk.column_index);
+        if column.dictionary_page_offset().is_some()
+            && let Some(schema) = dictionary_schema(arrow_metadata.schema(), &column_chunk.leaf)
+            && let Ok(dictionary_metadata) = ArrowReaderMetadata::try_new(
+                Arc::clone(metadata),
+                ArrowReaderOptions::new().with_schema(schema),
+            ) {
+            arrow_metadata = dictionary_metadata;
+        }
+    }
 
     // Create arrow reader builder with the metadata
     let builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
@@ -626,42 +1213,204 @@ fn process_column_chunk(
     let mut record_batch_reader = builder
         .with_projection(projection)
         .with_row_groups(vec![column_chunk.row_group as usize])
-        .build()
-        .expect("Failed to build parquet reader");
+        .build()?;
 
     // Track cumulative row offset across batches within this row group
     let mut cumulative_row_offset = 0u32;
 
     // Decode and process batches
     while let Some(batch_result) = record_batch_reader.next() {
-        let batch = batch_result.expect("Failed to read record batch");
+        let batch = batch_result?;
         let batch_size = batch.num_rows() as u32;
 
-        // Should only be one column due to projection
+        // Should only be one top-level column due to projection
         for array in batch.columns() {
-            // Convert Arrow array to StringArray (returns ArrayRef)
-            let string_array_ref = cast(array, &DataType::Utf8).expect("Failed to cast array to string");
-
-            // Downcast to concrete StringArray type
-            let string_array = string_array_ref
-                .as_any()
-                .downcast_ref::<StringArray>()
-                .expect("Cast to Utf8 should produce StringArray");
+            let leaf = leaf_values(array, &column_chunk.leaf).map_err(|e| Error::UnsupportedSchema(
+                format!("Column '{}' does not have the structure of its schema: {}", column_chunk.leaf.name, e)
+            ))?;
 
             // Call user callback with cumulative offset
-            process_arrow_string_array(
-                string_array,
-                &column_chunk.column_name,
-                column_chunk.row_group,
-                cumulative_row_offset,
-                keyword_map,
-                column_pool
-            );
+            process(&leaf.values, cumulative_row_offset, leaf.rows.as_deref())?;
         }
 
         // Increment offset for next batch
         cumulative_row_offset += batch_size;
     }
+    Ok(())
+}
+
+/// Arrow schema that decodes a top-level string column as `Dictionary(Int32, Utf8)`.
+///
+/// Returns `None` for nested leaves and columns of other types, which are decoded as
+/// usual.
+fn dictionary_schema(schema: &SchemaRef, leaf: &LeafColumn) -> Option<SchemaRef> {
+    let field = schema.fields().get(leaf.root)?;
+    let is_string = match field.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 => true,
+        DataType::Dictionary(_, value) => matches!(value.as_ref(), DataType::Utf8 | DataType::LargeUtf8),
+        _ => false,
+    };
+    if !is_string || field.name() != &leaf.name {
+        return None;
+    }
+
+    let mut fields: Vec<FieldRef> = schema.fields().iter().cloned().collect();
+    let dictionary_type = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    fields[leaf.root] = Arc::new(field.as_ref().clone().with_data_type(dictionary_type));
+    Some(Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())))
+}
+
+/// Split dictionary entries of a column chunk, shared by every row referencing them.
+///
+/// Entries are split the first time a row references them. Batches of a dictionary-encoded
+/// chunk share one dictionary; should a batch bring a different one (e.g. after the
+/// writer fell back to plain encoding), the splits start over.
+pub(crate) struct DictionarySplits {
+    /// Split entries, or keep them whole
+    tokenization: Tokenization,
+    /// The dictionary the splits belong to
+    dictionary: Option<ArrayData>,
+    /// Split of each dictionary entry, once referenced
+    splits: Vec<Option<SplitValue>>,
+}
+
+impl DictionarySplits {
+    /// Creates empty splits; with [`Tokenization::ExactValues`], entries are kept whole
+    /// (see [`SplitValue::exact`]).
+    pub(crate) fn new(tokenization: Tokenization) -> Self {
+        DictionarySplits { tokenization, dictionary: None, splits: Vec::new() }
+    }
+}
+
+/// How the values of every column of a build are turned into keywords.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnTokenization {
+    /// Columns whose values are indexed whole, without splitting (see [`add_exact_value`])
+    pub(crate) exact_value_columns: HashSet<String>,
+    /// Delimiter levels the values of other columns are split at (see
+    /// [`perform_split_levels`])
+    pub(crate) split_levels: usize,
+}
+
+impl ColumnTokenization {
+    /// Splits every column at `split_levels` levels.
+    pub(crate) fn new(split_levels: usize) -> Self {
+        ColumnTokenization { exact_value_columns: HashSet::new(), split_levels }
+    }
+
+    /// How the values of `column_name` are turned into keywords.
+    pub(crate) fn column(&self, column_name: &str) -> Tokenization {
+        if self.exact_value_columns.contains(column_name) {
+            Tokenization::ExactValues
+        } else {
+            Tokenization::Split { levels: self.split_levels }
+        }
+    }
+}
+
+impl Default for ColumnTokenization {
+    /// Splits every column at every level.
+    fn default() -> Self {
+        Self::new(SPLIT_CHARS_COUNT)
+    }
+}
+
+/// Processes a dictionary-encoded Arrow array by splitting each dictionary entry once.
+///
+/// Equivalent to [`process_arrow_string_array`] on the decoded strings, but each
+/// distinct value is only split once per dictionary and its keywords are then added to
+/// every row referencing it, which saves most of the tokenization work for repetitive
+/// columns such as status codes or country names.
+///
+/// # Arguments
+///
+/// * `array` - The dictionary array to process; its values must be a `StringArray`
+/// * `column_reference` - The column being indexed, already interned in the column pool
+/// * `row_group` - The row group number (u32)
+/// * `row_offset` - The cumulative row offset within the row group (for batch handling)
+/// * `value_rows` - For values of a nested leaf, the row of each value relative to
+///   `row_offset` (ascending); `None` when value and row indices match
+/// * `dictionary_splits` - Splits of the dictionary entries, kept across the chunk's batches
+/// * `keyword_map` - Mutable reference to the HashMap storing indexed keywords
+///
+/// # Returns
+///
+/// The number of rows in the array that produced at least one keyword.
+pub(crate) fn process_arrow_dictionary_array(
+    array: &DictionaryArray<Int32Type>,
+    column_reference: u32,
+    row_group: u32,
+    row_offset: u32,
+    value_rows: Option<&[u32]>,
+    dictionary_splits: &mut DictionarySplits,
+    keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+) -> u64 {
+    let dictionary = array.values().as_string::<i32>();
+    let dictionary_data = dictionary.to_data();
+    if dictionary_splits.dictionary.as_ref().is_none_or(|current| !current.ptr_eq(&dictionary_data)) {
+        dictionary_splits.dictionary = Some(dictionary_data);
+        dictionary_splits.splits = vec![None; dictionary.len()];
+    }
+
+    let mut rows_with_keywords = 0u64;
+    let mut last_row_with_keywords = None;
+    for (row_idx, key) in array.keys().iter().enumerate() {
+        // Skip null values, whether null keys or null dictionary entries
+        let Some(key) = key.map(|key| key as usize).filter(|&key| dictionary.is_valid(key)) else {
+            continue;
+        };
+
+        let split = dictionary_splits.splits[key].get_or_insert_with(|| {
+            let value = dictionary.value(key);
+            match dictionary_splits.tokenization {
+                _ if value.is_empty() => SplitValue::default(),
+                Tokenization::ExactValues => SplitValue::exact(value),
+                Tokenization::Split { levels } => SplitValue::split(value, levels),
+            }
+        });
+        if split.is_empty() {
+            continue;
+        }
+
+        let row = row_offset + value_rows.map_or(row_idx as u32, |rows| rows[row_idx]);
+        if last_row_with_keywords != Some(row) {
+            rows_with_keywords += 1;
+            last_row_with_keywords = Some(row);
+        }
+        split.add_to_row(column_reference, row_group, row, keyword_map);
+    }
+    rows_with_keywords
+}
+
+/// Processes a single column chunk into its own keyword map, for use on a worker thread.
+///
+/// The chunk's keywords are returned detached from `Rc` so they can be sent back to the
+/// processor task and merged with [`ChunkKeywords::merge_into`].
+///
+/// # Arguments
+///
+/// * `column_chunk` - Column chunk with bytes and metadata
+/// * `metadata` - Shared reference to Parquet metadata
+/// * `column_reference` - The chunk's column, already interned in the column pool
+/// * `tokenization` - Whether values are split, and at how many levels, or kept whole
+///
+/// # Returns
+///
+/// The chunk's keywords and the number of rows that produced at least one keyword.
+///
+/// # Errors
+///
+/// Returns an error if the chunk cannot be decoded (see [`process_column_chunk`]).
+fn tokenize_column_chunk(
+    column_chunk: ColumnChunk,
+    metadata: &Arc<ParquetMetaData>,
+    column_reference: u32,
+    tokenization: Tokenization,
+) -> Result<(ChunkKeywords, u64), Error> {
+    let row_group = column_chunk.row_group;
+    let mut keyword_map = HashMap::new();
+    let rows_with_keywords = process_column_chunk(column_chunk, metadata, column_reference, tokenization, &mut keyword_map)?;
+    Ok((ChunkKeywords::from_keyword_map(keyword_map, column_reference, row_group), rows_with_keywords))
 }
 
 /// ChunkReader implementation that wraps a Bytes buffer with offset translation.
@@ -788,11 +1537,19 @@ impl ChunkReader for ColumnBytesReader {
 /// # Arguments
 ///
 /// * `array` - The Arrow StringArray to process
-/// * `column_name` - The name of the column being indexed
-/// * `row_group` - The row group number (u16)
+/// * `column_reference` - The column being indexed, already interned in the column pool
+///   (interning happens on the processor task so this can run on a worker thread)
+/// * `row_group` - The row group number (u32)
 /// * `row_offset` - The cumulative row offset within the row group (for batch handling)
+/// * `value_rows` - For values of a nested leaf, the row of each value relative to
+///   `row_offset` (ascending); `None` when value and row indices match
+/// * `tokenization` - Split each value (see [`perform_split_levels`]), or add it as a
+///   single keyword (see [`add_exact_value`])
 /// * `keyword_map` - Mutable reference to the HashMap storing indexed keywords
-/// * `column_pool` - Mutable reference to the column pool for interning column names
+///
+/// # Returns
+///
+/// The number of rows in the array that produced at least one keyword.
 ///
 /// # Performance Considerations
 ///
@@ -809,34 +1566,57 @@ impl ChunkReader for ColumnBytesReader {
 ///   Batch 1 (offset=100):   rows 100-199 -> row_offset + 0, row_offset + 1, ...
 /// ```
 ///
-/// Actual row number = `row_offset + row_idx`
+/// Actual row number = `row_offset + row_idx`, or `row_offset + value_rows[row_idx]` for
+/// nested leaves, where several values (list elements) can belong to the same row.
 pub(crate) fn process_arrow_string_array(
     array: &StringArray,
-    column_name: &str,
-    row_group: u16,
+    column_reference: u32,
+    row_group: u32,
     row_offset: u32,
+    value_rows: Option<&[u32]>,
+    tokenization: Tokenization,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
-    column_pool: &mut ColumnPool
-) {
-    let column_reference: u32 = column_pool.intern(column_name);
+) -> u64 {
+    let mut rows_with_keywords = 0u64;
+    let mut last_row_with_keywords = None;
     for row_idx in 0..array.len() {
         // Skip null values efficiently
         if array.is_valid(row_idx) {
             // Get string slice directly from Arrow array (zero-copy)
             let value = array.value(row_idx);
+            let row = row_offset + value_rows.map_or(row_idx as u32, |rows| rows[row_idx]);
 
             // Only process non-empty strings
-            if !value.is_empty() {
-                perform_split(
-                    value,
-                    column_reference,
-                    row_group,
-                    row_offset + row_idx as u32,  // Add offset to handle multiple batches
-                    keyword_map
-                );
+            if value.is_empty() {
+                continue;
+            }
+            match tokenization {
+                Tokenization::ExactValues => {
+                    if last_row_with_keywords != Some(row) {
+                        rows_with_keywords += 1;
+                        last_row_with_keywords = Some(row);
+                    }
+                    add_exact_value(value, column_reference, row_group, row, keyword_map);
+                }
+                Tokenization::Split { levels } => {
+                    // A value yields keywords unless it consists only of top-level delimiters
+                    if last_row_with_keywords != Some(row) && value.chars().any(|c| !is_delimiter(c, 0)) {
+                        rows_with_keywords += 1;
+                        last_row_with_keywords = Some(row);
+                    }
+                    perform_split_levels(
+                        value,
+                        column_reference,
+                        row_group,
+                        row,  // Includes the offset to handle multiple batches
+                        keyword_map,
+                        levels,
+                    );
+                }
             }
         }
     }
+    rows_with_keywords
 }
 
 /// Processes a Parquet file and returns a complete keyword index with filters.
@@ -927,12 +1707,54 @@ pub async fn process_parquet_file(
     source: ParquetSource,
     exclude_columns: Option<HashSet<String>>,
     error_rate: Option<f64>,
-) -> Result<ProcessResult, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<ProcessResult, Error> {
+    process_parquet_file_with_options(source, exclude_columns, error_rate, &BuildOptions::default()).await
+}
+
+/// Processes a Parquet file with explicit build options.
+///
+/// Same as [`process_parquet_file`], with `options` controlling how the file is processed
+/// (the columns to index, see [`BuildOptions::include_columns`] and
+/// [`BuildOptions::column_types`], the number of tokenizer threads, see
+/// [`BuildOptions::threads`], the memory budget, see [`BuildOptions::memory_budget`],
+/// and the cardinality sampling pass, see [`BuildOptions::cardinality_check`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::column_parquet_reader::process_parquet_file_with_options;
+/// use keywords::{BuildOptions, ParquetSource};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let options = BuildOptions { threads: 4, ..Default::default() };
+/// let result = process_parquet_file_with_options(
+///     ParquetSource::Path("data.parquet".to_string()), None, None, &options,
+/// ).await?;
+/// println!("Indexed {} unique keywords", result.keyword_map.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn process_parquet_file_with_options(
+    source: ParquetSource,
+    exclude_columns: Option<HashSet<String>>,
+    error_rate: Option<f64>,
+    options: &BuildOptions,
+) -> Result<ProcessResult, Error> {
     // Default to 1% error rate if not specified
     let error_rate = error_rate.unwrap_or(0.01);
+    options.validate()?;
 
     // Read metadata and determine file path for streaming
     let metadata_with_cache: MetadataWithCache = read_metadata(source, None).await?;
+    let monitor = options.monitor();
+    monitor.report(|progress| *progress = BuildProgress {
+        total_bytes: metadata_with_cache.file_size,
+        ..Default::default()
+    });
+
+    // Columns outside the include list or selected types are excluded like any other
+    let exclude_columns = resolve_excluded_columns(&metadata_with_cache.metadata, exclude_columns, options)?;
 
     let num_rows: i64 = metadata_with_cache.metadata.file_metadata().num_rows();
     let num_cols: usize = metadata_with_cache.metadata.file_metadata().schema_descr().num_columns();
@@ -946,23 +1768,133 @@ pub async fn process_parquet_file(
     ) as usize;
 
     // Create keyword map, column pool, and column keywords map
-    let mut keyword_map = HashMap::with_capacity(estimated);
-    let mut column_pool = ColumnPool::new();
+    // With a memory budget or checkpoints, the map is flushed long before reaching the estimate
+    let mut keyword_map = match (options.memory_budget, &options.checkpoint_directory) {
+        (None, None) => HashMap::with_capacity(estimated),
+        _ => HashMap::new(),
+    };
+
+    // A checkpoint brings the column pool and runs of the row groups it completed, and
+    // writes its own runs
+    let mut checkpoint = None;
+    let (mut column_pool, mut spill, resumed_decisions) = match &options.checkpoint_directory {
+        Some(directory) => {
+            let opened = BuildCheckpoint::open(
+                directory,
+                options,
+                error_rate,
+                &metadata_with_cache.parquet_source,
+                &exclude_columns,
+            ).await?;
+            monitor.report(|progress| progress.checkpoint = opened.status.clone());
+            checkpoint = Some(opened.checkpoint);
+            (opened.column_pool, Some(opened.spill), opened.column_decisions)
+        }
+        None => {
+            let spill = options.memory_budget
+                .map(|budget| KeywordSpill::new(budget, options.spill_directory()));
+            (ColumnPool::new(), spill, None)
+        }
+    };
+
+    // Sample the columns first, so that high-cardinality ones are left out of the full
+    // pass or indexed as whole values. A resumed build keeps the decisions it started with.
+    monitor.check_cancelled()?;
+    let column_decisions = match (resumed_decisions, &options.cardinality_check) {
+        (Some(column_decisions), _) => column_decisions,
+        (None, Some(check)) => sample_column_cardinality(&metadata_with_cache, &exclude_columns, check, options.split_levels()).await?,
+        (None, None) => Vec::new(),
+    };
+    if let Some(checkpoint) = &mut checkpoint {
+        checkpoint.set_column_decisions(column_decisions.clone());
+    }
+    let mut exclude_columns = exclude_columns;
+    let mut tokenization = ColumnTokenization::new(options.split_levels());
+    if !options.exact_value_columns.is_empty() {
+        let file_metadata = metadata_with_cache.metadata.file_metadata();
+        for leaf in leaf_columns(file_metadata.schema_descr(), file_metadata.key_value_metadata()) {
+            if options.is_exact_value_column(&leaf.name) {
+                tokenization.exact_value_columns.insert(leaf.name);
+            }
+        }
+    }
+    for decision in &column_decisions {
+        match decision.action {
+            ColumnAction::Indexed => {}
+            ColumnAction::ExactValues => {
+                tokenization.exact_value_columns.insert(decision.column_name.clone());
+            }
+            ColumnAction::Excluded => {
+                exclude_columns.get_or_insert_with(HashSet::new).insert(decision.column_name.clone());
+            }
+        }
+    }
 
-    stream_and_process_parquet(
+    let mut column_rows_with_keywords = stream_and_process_parquet(
         exclude_columns,
+        &tokenization,
         &metadata_with_cache,
         &mut keyword_map,
-        &mut column_pool
+        &mut column_pool,
+        options.thread_count(),
+        spill.as_mut(),
+        checkpoint.as_mut(),
+        &monitor,
     ).await?;
+    if let Some(checkpoint) = &checkpoint {
+        checkpoint.add_resumed_rows(&mut column_rows_with_keywords);
+    }
+
+    let page_tables = if options.page_locations && options.granularity == IndexGranularity::Rows {
+        read_page_tables(&metadata_with_cache, &column_pool).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(finish_process_result(
+        keyword_map,
+        column_pool,
+        column_rows_with_keywords,
+        spill,
+        column_decisions,
+        page_tables,
+        error_rate,
+        options,
+    ))
+}
+
+/// Builds the column keywords map and Bloom filters of the processed keywords.
+///
+/// Shared by every way of processing a file: the per-column and global filters are
+/// created with `error_rate`, or the rate of a column in
+/// [`BuildOptions::column_error_rates`]. When keywords were spilled to disk, the map and
+/// filters are left empty, as the keywords of the runs are no longer in memory;
+/// [`build_distributed_index`](crate::index_data::build_distributed_index) builds the
+/// filters while merging the runs instead.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn finish_process_result(
+    keyword_map: HashMap<Rc<str>, KeywordOneFile>,
+    column_pool: ColumnPool,
+    column_rows_with_keywords: HashMap<u32, u64>,
+    spill: Option<KeywordSpill>,
+    column_decisions: Vec<ColumnDecision>,
+    page_tables: Vec<PageTable>,
+    error_rate: f64,
+    options: &BuildOptions,
+) -> ProcessResult {
+    let spilled_runs = spill.map(KeywordSpill::finish).unwrap_or_default();
 
     // Process columns (will reuse cached data if available)
-    let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
+    let column_keywords_map = if spilled_runs.is_empty() {
+        build_column_keywords_map(&keyword_map, &column_pool)
+    } else {
+        HashMap::new()
+    };
 
     // Build column filters
     let mut column_filters = HashMap::new();
     for (column_name, keywords) in &column_keywords_map {
-        let filter = ColumnFilter::create_column_filter(keywords, error_rate);
+        let filter = ColumnFilter::create_column_filter(keywords, options.column_error_rate(column_name, error_rate));
         column_filters.insert(column_name.clone(), filter);
     }
 
@@ -974,13 +1906,18 @@ pub async fn process_parquet_file(
     let global_filter = ColumnFilter::create_column_filter(&all_keywords, error_rate);
 
     // Return the populated map, pool, column keywords map, filters, and global filter
-    Ok(ProcessResult {
+    ProcessResult {
         keyword_map,
         column_pool,
         column_keywords_map,
         column_filters,
         global_filter,
-    })
+        column_rows_with_keywords,
+        spilled_runs,
+        column_decisions,
+        page_tables,
+        text_layout: None,
+    }
 }
 
 #[cfg(test)]
@@ -991,7 +1928,8 @@ mod tests {
     use arrow::record_batch::RecordBatch;
     use parquet::arrow::ArrowWriter;
     use parquet::basic::Compression;
-    use parquet::file::properties::WriterProperties;
+    use parquet::file::metadata::ParquetMetaDataReader;
+    use parquet::file::properties::{WriterProperties, WriterPropertiesBuilder};
     use std::sync::Arc;
 
     /// Helper function to write a RecordBatch to in-memory parquet bytes
@@ -1043,9 +1981,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Verify that we have all 1000 unique keywords
@@ -1101,9 +2044,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Verify row group 0, row 0
@@ -1165,9 +2113,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Should only have 3 keywords (nulls skipped)
@@ -1186,6 +2139,32 @@ mod tests {
         assert_eq!(data.row_group_to_rows[1][0][0].row, 4);
     }
 
+    /// Test that binary values that are not valid UTF-8 are skipped, not their whole batch
+    #[tokio::test]
+    async fn test_invalid_utf8_values_skipped() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("binary_col", DataType::Binary, false),
+        ]));
+        let array: ArrayRef = Arc::new(BinaryArray::from(vec![
+            b"value0".as_ref(),
+            &[0xff, 0xfe],
+            b"value2".as_ref(),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![array]).unwrap();
+        let parquet_bytes = write_parquet_to_bytes(schema, batch, WriterProperties::builder().build());
+
+        let result = process_parquet_file_with_options(
+            ParquetSource::Bytes(bytes::Bytes::from(parquet_bytes)),
+            None,
+            None,
+            &BuildOptions::default(),
+        ).await.unwrap();
+
+        assert_eq!(result.keyword_map.len(), 2);
+        let data = result.keyword_map.get("value2").unwrap();
+        assert_eq!(data.row_group_to_rows[1][0][0].row, 2);
+    }
+
     /// Test with empty strings (should be skipped)
     #[tokio::test]
     async fn test_empty_strings_skipped() {
@@ -1214,9 +2193,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Should only have 2 keywords (empty string skipped)
@@ -1224,6 +2208,49 @@ mod tests {
         assert!(keyword_map.contains_key("another"));
     }
 
+    /// Test that rows producing keywords are counted per column
+    #[tokio::test]
+    async fn test_rows_with_keywords_counted() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("col", DataType::Utf8, true),
+        ]));
+
+        let props = WriterProperties::builder().build();
+
+        let array: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("value"),
+            None,          // null
+            Some(""),      // empty
+            Some("   "),   // only delimiters
+            Some("a b"),
+            Some("@@"),    // only lower-level delimiters, still indexed
+        ]));
+
+        let batch = RecordBatch::try_new(schema.clone(), vec![array]).unwrap();
+        let parquet_bytes = write_parquet_to_bytes(schema.clone(), batch, props);
+
+        let mut keyword_map = HashMap::new();
+        let mut column_pool = ColumnPool::new();
+        let bytes = bytes::Bytes::from(parquet_bytes.clone());
+        let metadata_with_cache = read_metadata(ParquetSource::Bytes(bytes.clone()), None).await.unwrap();
+
+        let rows_with_keywords = stream_and_process_parquet(
+            None,
+            &ColumnTokenization::default(),
+            &metadata_with_cache,
+            &mut keyword_map,
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
+        ).await.unwrap();
+
+        let column_id = column_pool.intern("col");
+        assert_eq!(rows_with_keywords.get(&column_id), Some(&3));
+        assert!(keyword_map.contains_key("@@"));
+    }
+
     /// Test excluded columns functionality
     #[tokio::test]
     async fn test_excluded_columns() {
@@ -1253,9 +2280,14 @@ mod tests {
 
         stream_and_process_parquet(
             Some(excluded),
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Should only have keyword from included column
@@ -1305,9 +2337,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // Verify split keywords exist
@@ -1372,9 +2409,14 @@ mod tests {
 
         stream_and_process_parquet(
             None,
+            &ColumnTokenization::default(),
             &metadata_with_cache,
             &mut keyword_map,
-            &mut column_pool
+            &mut column_pool,
+            1,
+            None,
+            None,
+            &BuildMonitor::default(),
         ).await.unwrap();
 
         // THE BUG: Without the fix, all these would be at row 0, 1, or 2
@@ -1406,6 +2448,260 @@ mod tests {
                    "BUG: unique_9 should be at row 9, not 0");
     }
 
+    /// Three columns over several row groups, with keywords shared between columns and
+    /// runs that end in one column right where they continue in the next
+    fn multi_column_parquet() -> Vec<u8> {
+        multi_column_parquet_with(WriterProperties::builder())
+    }
+
+    fn multi_column_parquet_with(props: WriterPropertiesBuilder) -> Vec<u8> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Utf8, true),
+        ]));
+
+        let props = props
+            .set_max_row_group_size(100)
+            .build();
+
+        let a: Vec<Option<String>> = (0..350)
+            .map(|i| if i % 100 < 50 { Some(format!("shared-{} user@example.com", i % 7)) } else { None })
+            .collect();
+        let b: Vec<Option<String>> = (0..350)
+            .map(|i| match i % 100 {
+                49 => Some("shared shared-x".to_string()),
+                50..=80 => Some(format!("shared-{} id_{}", i % 7, i % 3)),
+                _ => None,
+            })
+            .collect();
+        let c: Vec<Option<String>> = (0..350)
+            .map(|i| if i % 11 == 0 { None } else { Some(format!("example.com/{}", i % 5)) })
+            .collect();
+
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(a)) as ArrayRef,
+            Arc::new(StringArray::from(b)) as ArrayRef,
+            Arc::new(StringArray::from(c)) as ArrayRef,
+        ]).unwrap();
+
+        write_parquet_to_bytes(schema, batch, props)
+    }
+
+    async fn process_with_threads(
+        parquet_bytes: &[u8],
+        threads: usize,
+    ) -> (HashMap<Rc<str>, KeywordOneFile>, ColumnPool, HashMap<u32, u64>) {
+        let mut keyword_map = HashMap::new();
+        let mut column_pool = ColumnPool::new();
+        let bytes = bytes::Bytes::from(parquet_bytes.to_vec());
+        let metadata_with_cache = read_metadata(ParquetSource::Bytes(bytes), None).await.unwrap();
+
+        let rows_with_keywords = stream_and_process_parquet(
+            None,
+            &ColumnTokenization::default(),
+            &metadata_with_cache,
+            &mut keyword_map,
+            &mut column_pool,
+            threads,
+            None,
+            None,
+            &BuildMonitor::default(),
+        ).await.unwrap();
+
+        (keyword_map, column_pool, rows_with_keywords)
+    }
+
+    fn assert_same_keywords(actual: &HashMap<Rc<str>, KeywordOneFile>, expected: &HashMap<Rc<str>, KeywordOneFile>) {
+        assert_eq!(actual.len(), expected.len());
+        for (keyword, expected) in expected {
+            let actual = actual.get(keyword).unwrap_or_else(|| panic!("Missing keyword {}", keyword));
+            assert_eq!(actual.splits_matched, expected.splits_matched, "splits of {}", keyword);
+            assert_eq!(actual.column_references, expected.column_references, "columns of {}", keyword);
+            assert_eq!(actual.row_groups, expected.row_groups, "row groups of {}", keyword);
+            assert_eq!(actual.row_group_to_rows, expected.row_group_to_rows, "rows of {}", keyword);
+        }
+    }
+
+    /// Parallel tokenization must produce the sequential keyword map
+    #[tokio::test]
+    async fn test_parallel_matches_sequential() {
+        let parquet_bytes = multi_column_parquet();
+        let (sequential, sequential_pool, sequential_rows) = process_with_threads(&parquet_bytes, 1).await;
+
+        for threads in [2, 4, 16] {
+            let (parallel, parallel_pool, parallel_rows) = process_with_threads(&parquet_bytes, threads).await;
+
+            assert_eq!(parallel_pool.strings, sequential_pool.strings);
+            assert_eq!(parallel_rows, sequential_rows);
+            assert_same_keywords(&parallel, &sequential);
+        }
+    }
+
+    /// Splitting dictionary entries once must produce the keyword map of splitting every
+    /// row, including chunks whose dictionary overflowed into plain-encoded pages
+    #[tokio::test]
+    async fn test_dictionary_matches_plain() {
+        let plain = multi_column_parquet_with(WriterProperties::builder().set_dictionary_enabled(false));
+        let (expected, expected_pool, expected_rows) = process_with_threads(&plain, 1).await;
+
+        let dictionary = multi_column_parquet();
+        let overflowing = multi_column_parquet_with(WriterProperties::builder()
+            .set_dictionary_page_size_limit(64)
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10));
+        for parquet_bytes in [dictionary, overflowing] {
+            let (actual, actual_pool, actual_rows) = process_with_threads(&parquet_bytes, 1).await;
+            assert_eq!(actual_pool.strings, expected_pool.strings);
+            assert_eq!(actual_rows, expected_rows);
+            assert_same_keywords(&actual, &expected);
+        }
+    }
+
+    /// Dictionary-encoded string chunks are decoded as dictionaries sharing one set of values
+    #[test]
+    fn test_dictionary_chunks_decoded_as_dictionaries() {
+        let parquet_bytes = bytes::Bytes::from(multi_column_parquet());
+        let metadata = Arc::new(ParquetMetaDataReader::new().parse_and_finish(&parquet_bytes).unwrap());
+        let leaf = Arc::new(leaf_columns(metadata.file_metadata().schema_descr(), None).remove(2));
+        assert!(metadata.row_group(0).column(2).dictionary_page_offset().is_some());
+
+        let chunk = || ColumnChunk {
+            bytes: parquet_bytes.clone(),
+            column_name: leaf.name.clone(),
+            row_group: 0,
+            column_index: 2,
+            leaf: Arc::clone(&leaf),
+            start_offset: 0,
+        };
+
+        let mut dictionaries = Vec::new();
+        decode_leaf_arrays(chunk(), &metadata, true, |values, _, _| {
+            dictionaries.push(values.as_dictionary::<Int32Type>().values().to_data());
+            Ok(())
+        }).unwrap();
+        assert!(!dictionaries.is_empty());
+        assert!(dictionaries.iter().all(|dictionary| dictionary.ptr_eq(&dictionaries[0])));
+        assert_eq!(dictionaries[0].len(), 5);
+
+        decode_leaf_arrays(chunk(), &metadata, false, |values, _, _| {
+            assert_eq!(values.data_type(), &DataType::Utf8);
+            Ok(())
+        }).unwrap();
+    }
+
+    #[test]
+    fn test_dictionary_exact_values() {
+        let array: DictionaryArray<Int32Type> = vec![Some("a-b"), None, Some(""), Some("a-b"), Some("c")]
+            .into_iter()
+            .collect();
+        let mut keyword_map = HashMap::new();
+        let rows = process_arrow_dictionary_array(
+            &array, 1, 0, 10, None, &mut DictionarySplits::new(Tokenization::ExactValues), &mut keyword_map,
+        );
+
+        assert_eq!(rows, 3);
+        assert_eq!(keyword_map.len(), 2);
+        let rows: Vec<u32> = keyword_map["a-b"].row_group_to_rows[1][0].iter().map(|row| row.row).collect();
+        assert_eq!(rows, vec![10, 13]);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_build_options() {
+        let source = ParquetSource::Bytes(bytes::Bytes::from(multi_column_parquet()));
+        let options = BuildOptions { threads: 3, ..Default::default() };
+        let result = process_parquet_file_with_options(source, None, None, &options).await.unwrap();
+
+        assert!(result.keyword_map.contains_key("shared-x"));
+        assert!(result.global_filter.might_contain("example.com"));
+        assert_eq!(result.column_pool.strings.len(), 4);
+    }
+
+    fn wide_parquet() -> Vec<u8> {
+        let tag_type = DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("http_status", DataType::Utf8, false),
+            Field::new("http_method", DataType::Utf8, false),
+            Field::new("body", DataType::LargeUtf8, false),
+            Field::new("count", DataType::Int64, false),
+            Field::new("tag", tag_type, false),
+        ]));
+
+        let tags: DictionaryArray<Int8Type> = vec!["tag-a", "tag-b"].into_iter().collect();
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(vec!["status-200", "status-404"])) as ArrayRef,
+            Arc::new(StringArray::from(vec!["method-get", "method-post"])) as ArrayRef,
+            Arc::new(LargeStringArray::from(vec!["body-one", "body-two"])) as ArrayRef,
+            Arc::new(Int64Array::from(vec![1234, 5678])) as ArrayRef,
+            Arc::new(tags) as ArrayRef,
+        ]).unwrap();
+
+        write_parquet_to_bytes(schema, batch, WriterProperties::builder().build())
+    }
+
+    fn column_names(result: &ProcessResult) -> Vec<&str> {
+        result.column_pool.strings.iter().skip(1).map(|s| s.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_include_columns() {
+        let source = ParquetSource::Bytes(bytes::Bytes::from(wide_parquet()));
+        let options = BuildOptions {
+            include_columns: Some(vec!["http_*".to_string(), "count".to_string()]),
+            ..Default::default()
+        };
+
+        // Exclusions still apply to included columns
+        let excluded = HashSet::from(["http_method".to_string()]);
+        let result = process_parquet_file_with_options(source, Some(excluded), None, &options).await.unwrap();
+
+        assert_eq!(column_names(&result), vec!["http_status", "count"]);
+        assert!(result.keyword_map.contains_key("status-404"));
+        assert!(result.keyword_map.contains_key("5678"));
+        assert!(!result.keyword_map.contains_key("method-get"));
+        assert!(!result.keyword_map.contains_key("body-one"));
+    }
+
+    #[tokio::test]
+    async fn test_column_types() {
+        let parquet_bytes = bytes::Bytes::from(wide_parquet());
+        let options = BuildOptions {
+            column_types: Some(vec![
+                DataType::Utf8,
+                DataType::LargeUtf8,
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            ]),
+            ..Default::default()
+        };
+
+        let metadata_with_cache = read_metadata(ParquetSource::Bytes(parquet_bytes.clone()), None).await.unwrap();
+        let excluded = resolve_excluded_columns(&metadata_with_cache.metadata, None, &options).unwrap();
+        assert_eq!(excluded, Some(HashSet::from(["count".to_string()])));
+
+        // The chunks of unselected columns are never read
+        let chunks = extract_column_chunk_metadata(&metadata_with_cache.metadata, &excluded).unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.column_name != "count"));
+        assert_eq!(chunks.len(), 4);
+
+        let result = process_parquet_file_with_options(ParquetSource::Bytes(parquet_bytes), None, None, &options)
+            .await
+            .unwrap();
+        assert_eq!(column_names(&result), vec!["http_status", "http_method", "body", "tag"]);
+        assert!(result.keyword_map.contains_key("body-two"));
+        assert!(result.keyword_map.contains_key("tag-b"));
+        assert!(!result.keyword_map.contains_key("1234"));
+    }
+
+    #[tokio::test]
+    async fn test_no_column_selection() {
+        let metadata_with_cache = read_metadata(ParquetSource::Bytes(bytes::Bytes::from(wide_parquet())), None)
+            .await
+            .unwrap();
+        let excluded = resolve_excluded_columns(&metadata_with_cache.metadata, None, &BuildOptions::default()).unwrap();
+        assert_eq!(excluded, None);
+        assert_eq!(extract_column_chunk_metadata(&metadata_with_cache.metadata, &excluded).unwrap().len(), 5);
+    }
+
     /// Test metadata length extraction
     #[test]
     fn test_read_metadata_length() {
@@ -0,0 +1,203 @@
+//! Optional sampling pass that detects columns unsuitable for keyword indexing.
+//!
+//! Columns such as UUIDs, hashes or numeric IDs hold a different value in almost every
+//! row, and splitting them yields keywords that each occur in a single row. They inflate
+//! the index without helping searches. With [`BuildOptions::cardinality_check`] set, the
+//! first rows of every column are tokenized before the full pass, and columns that look
+//! like this are either excluded or indexed as whole values (see [`HighCardinalityAction`]).
+//!
+//! The decision for every sampled column is stored in the index as a [`ColumnDecision`].
+//!
+//! [`BuildOptions::cardinality_check`]: crate::BuildOptions::cardinality_check
+
+use std::collections::HashSet;
+use std::rc::Rc;
+use arrow::array::{Array, StringArray};
+use hashbrown::HashMap;
+use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize};
+use crate::column_parquet_reader::process_arrow_string_array;
+use crate::index_data::{ColumnAction, ColumnDecision};
+use crate::keyword_shred::{KeywordOneFile, Tokenization};
+
+/// What to do with a column the sampling pass finds to be high-cardinality.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HighCardinalityAction {
+    /// Leave the column out of the index
+    #[default]
+    Exclude,
+    /// Index each whole value as a single keyword, without splitting it, so that exact
+    /// lookups of a value still work
+    ExactValues,
+}
+
+/// Thresholds of the cardinality sampling pass.
+///
+/// A column is high-cardinality when both the share of distinct values and the share of
+/// keyword occurrences unique to one row exceed their maximum. The second condition keeps
+/// columns such as email addresses, whose values are distinct but whose parts (domains,
+/// words) repeat across rows.
+///
+/// # Examples
+///
+/// ```
+/// use keywords::BuildOptions;
+/// use keywords::column_sampling::{CardinalityCheck, HighCardinalityAction};
+///
+/// let options = BuildOptions {
+///     cardinality_check: Some(CardinalityCheck {
+///         action: HighCardinalityAction::ExactValues,
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq)]
+pub struct CardinalityCheck {
+    /// Number of rows sampled per column, from the start of the file
+    pub sample_rows: usize,
+
+    /// Columns with fewer non-empty sampled values are always indexed
+    pub min_sampled_values: u64,
+
+    /// Maximum share of distinct values among the sampled values
+    pub max_distinct_value_ratio: f64,
+
+    /// Maximum share of keyword occurrences whose keyword occurs in no other sampled row
+    pub max_unique_keyword_ratio: f64,
+
+    /// What to do with a column above both maximums
+    pub action: HighCardinalityAction,
+}
+
+impl Default for CardinalityCheck {
+    fn default() -> Self {
+        Self {
+            sample_rows: 10_000,
+            min_sampled_values: 100,
+            max_distinct_value_ratio: 0.9,
+            max_unique_keyword_ratio: 0.6,
+            action: HighCardinalityAction::default(),
+        }
+    }
+}
+
+/// Column reference used for the keywords of a sample, which holds a single column.
+const SAMPLE_COLUMN: u32 = 1;
+
+/// Values and keywords sampled from one column.
+pub(crate) struct ColumnSample {
+    rows: usize,
+    values: u64,
+    distinct_values: HashSet<String>,
+    keyword_map: HashMap<Rc<str>, KeywordOneFile>,
+    /// Delimiter levels values are split at, as in the build
+    split_levels: usize,
+}
+
+impl ColumnSample {
+    pub(crate) fn new(split_levels: usize) -> Self {
+        Self {
+            rows: 0,
+            values: 0,
+            distinct_values: HashSet::new(),
+            keyword_map: HashMap::new(),
+            split_levels,
+        }
+    }
+
+    /// Adds a batch of the column's values, until `sample_rows` rows have been added.
+    ///
+    /// Batches must be added in row order. For nested leaves, `value_rows` holds the row of
+    /// each value and `sample_rows` limits the number of values (list elements) instead.
+    pub(crate) fn add(
+        &mut self,
+        array: &StringArray,
+        value_rows: Option<&[u32]>,
+        row_group: u32,
+        row_offset: u32,
+        sample_rows: usize,
+    ) {
+        let take = array.len().min(sample_rows.saturating_sub(self.rows));
+        if take == 0 {
+            return;
+        }
+        let array = array.slice(0, take);
+        let value_rows = value_rows.map(|rows| &rows[..take]);
+        self.rows += take;
+
+        for value in array.iter().flatten().filter(|value| !value.is_empty()) {
+            self.values += 1;
+            if !self.distinct_values.contains(value) {
+                self.distinct_values.insert(value.to_string());
+            }
+        }
+        let tokenization = Tokenization::Split { levels: self.split_levels };
+        process_arrow_string_array(&array, SAMPLE_COLUMN, row_group, row_offset, value_rows, tokenization, &mut self.keyword_map);
+    }
+
+    /// Share of keyword occurrences (distinct keyword and row pairs) whose keyword occurs
+    /// in a single row of the sample.
+    fn unique_keyword_ratio(&self) -> f64 {
+        let mut occurrences = 0u64;
+        let mut unique = 0u64;
+        for keyword in self.keyword_map.values() {
+            // The global bucket (index 0) holds every row of the sampled column
+            let rows: HashSet<(u32, u32)> = keyword.row_groups[0].iter()
+                .zip(&keyword.row_group_to_rows[0])
+                .flat_map(|(&row_group, rows)| {
+                    rows.iter().flat_map(move |run| {
+                        (run.row..=run.row + run.additional_rows as u32).map(move |row| (row_group, row))
+                    })
+                })
+                .collect();
+            occurrences += rows.len() as u64;
+            if rows.len() == 1 {
+                unique += 1;
+            }
+        }
+        if occurrences == 0 { 0.0 } else { unique as f64 / occurrences as f64 }
+    }
+
+    /// Decides how the column is indexed.
+    pub(crate) fn decide(&self, column_name: String, check: &CardinalityCheck) -> ColumnDecision {
+        let distinct_value_ratio = if self.values == 0 {
+            0.0
+        } else {
+            self.distinct_values.len() as f64 / self.values as f64
+        };
+        let unique_keyword_ratio = self.unique_keyword_ratio();
+        let statistics = format!(
+            "{:.0}% of sampled values distinct, {:.0}% of keyword occurrences unique to one row",
+            distinct_value_ratio * 100.0,
+            unique_keyword_ratio * 100.0,
+        );
+
+        let (action, reason) = if self.values < check.min_sampled_values {
+            (ColumnAction::Indexed, format!(
+                "Too few sampled values to decide ({} of {} required)",
+                self.values, check.min_sampled_values,
+            ))
+        } else if distinct_value_ratio > check.max_distinct_value_ratio
+            && unique_keyword_ratio > check.max_unique_keyword_ratio {
+            match check.action {
+                HighCardinalityAction::Exclude => {
+                    (ColumnAction::Excluded, format!("High cardinality: {}", statistics))
+                }
+                HighCardinalityAction::ExactValues => {
+                    (ColumnAction::ExactValues, format!("High cardinality, values indexed whole: {}", statistics))
+                }
+            }
+        } else {
+            (ColumnAction::Indexed, format!("Within limits: {}", statistics))
+        };
+
+        ColumnDecision {
+            column_name,
+            sampled_values: self.values,
+            distinct_value_ratio,
+            unique_keyword_ratio,
+            action,
+            reason,
+        }
+    }
+}
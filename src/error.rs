@@ -0,0 +1,245 @@
+//! Error type of the library.
+//!
+//! Every fallible function of the library returns an [`Error`], whose variants tell
+//! apart failures a service embedding the library may want to handle differently: a
+//! missing or unreadable file ([`Io`](Error::Io), [`ObjectStore`](Error::ObjectStore)),
+//! an invalid Parquet file ([`Parquet`](Error::Parquet)), an index that is damaged
+//! ([`CorruptIndex`](Error::CorruptIndex)) or built for another version of the data
+//! ([`StaleIndex`](Error::StaleIndex)), or a file the index cannot describe
+//! ([`UnsupportedSchema`](Error::UnsupportedSchema), [`LimitExceeded`](Error::LimitExceeded)).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::error::Error;
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! # async fn example() -> Result<(), Error> {
+//! let searcher = KeywordSearcher::load("s3://bucket/data.parquet", None).await?;
+//! match searcher.search_verified("s3://bucket/data.parquet", "john-doe", None).await {
+//!     Ok(verified) => println!("{} candidates rejected", verified.candidates_rejected),
+//!     // The data file changed since the index was built
+//!     Err(Error::StaleIndex(reason)) => println!("rebuild needed: {}", reason),
+//!     Err(error) => return Err(error),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use arrow::error::ArrowError;
+use parquet::errors::ParquetError;
+
+/// Errors returned by indexing and searching.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing a local file or spill file failed.
+    Io(std::io::Error),
+    /// Reading or writing through the object store failed, including files not found.
+    ObjectStore(object_store::Error),
+    /// The data file is not valid Parquet, or is truncated.
+    Parquet(ParquetError),
+    /// Decoding data into Arrow arrays failed.
+    Arrow(ArrowError),
+    /// An index file could not be decoded.
+    CorruptIndex(String),
+    /// The index was built for another version of the data file.
+    StaleIndex(String),
+    /// The data has a column or type the index does not support.
+    UnsupportedSchema(String),
+    /// The data exceeds a limit of the index format, such as the number of row groups.
+    LimitExceeded(String),
+    /// An argument or option is invalid, such as an unknown column or a malformed query.
+    InvalidArgument(String),
+    /// Encoding index data failed.
+    Serialization(String),
+    /// The build was cancelled with a [`CancellationToken`](crate::build_progress::CancellationToken).
+    Cancelled,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::ObjectStore(e) => write!(f, "Object store error: {}", e),
+            Error::Parquet(e) => write!(f, "Parquet error: {}", e),
+            Error::Arrow(e) => write!(f, "Arrow error: {}", e),
+            Error::CorruptIndex(message) => write!(f, "Corrupt index: {}", message),
+            Error::StaleIndex(message) => write!(f, "Stale index: {}", message),
+            Error::UnsupportedSchema(message) => write!(f, "Unsupported schema: {}", message),
+            Error::LimitExceeded(message) => write!(f, "Limit exceeded: {}", message),
+            Error::InvalidArgument(message) => write!(f, "Invalid argument: {}", message),
+            Error::Serialization(message) => write!(f, "Serialization error: {}", message),
+            Error::Cancelled => write!(f, "Index build cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::ObjectStore(e) => Some(e),
+            Error::Parquet(e) => Some(e),
+            Error::Arrow(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<object_store::Error> for Error {
+    fn from(e: object_store::Error) -> Self {
+        Error::ObjectStore(e)
+    }
+}
+
+impl From<ParquetError> for Error {
+    fn from(e: ParquetError) -> Self {
+        Error::Parquet(e)
+    }
+}
+
+/// Parquet errors raised while decoding record batches are reported as Parquet errors.
+impl From<ArrowError> for Error {
+    fn from(e: ArrowError) -> Self {
+        match e {
+            ArrowError::ParquetError(message) => Error::Parquet(ParquetError::General(message)),
+            e => Error::Arrow(e),
+        }
+    }
+}
+
+/// A task of the build that panicked or was aborted.
+impl From<tokio::task::JoinError> for Error {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Error::Io(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+    use std::sync::Arc;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use bytes::Bytes;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use crate::{build_and_save_index, build_index_in_memory_with_options, BuildOptions, ParquetSource};
+    use crate::build_progress::CancellationToken;
+    use crate::index_data::build_distributed_index;
+    use crate::column_parquet_reader::process_parquet_file;
+    use crate::record_batch_indexer::RecordBatchIndexer;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{string_batch, write_parquet};
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS: usize = 400;
+    const ROWS_PER_GROUP: usize = 100;
+
+    fn create_parquet(column: &str, variant: &str) -> Bytes {
+        let values: Vec<String> = (0..ROWS).map(|row| format!("{}-{} host-{}", variant, row, row % 7)).collect();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(ROWS_PER_GROUP)
+            .set_compression(Compression::UNCOMPRESSED)
+            .set_dictionary_enabled(false)
+            .build();
+        write_parquet(&string_batch(vec![(column, values.into())]), props)
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_column_chunk() {
+        // Page headers and values are overwritten, the footer is intact
+        let mut bytes = create_parquet("message", "a").to_vec();
+        let end = bytes.len() / 2;
+        bytes[4..end].fill(0xFF);
+        let bytes = Bytes::from(bytes);
+
+        for threads in [1, 2] {
+            let options = BuildOptions { threads, ..Default::default() };
+            let result = build_index_in_memory_with_options(ParquetSource::Bytes(bytes.clone()), None, None, &options).await;
+            assert!(result.is_err_and(|error| matches!(error, Error::Parquet(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_parquet() {
+        let source = ParquetSource::Bytes(Bytes::from_static(b"message\nnot a parquet file"));
+        assert!(process_parquet_file(source, None, None).await.is_err_and(|error| matches!(error, Error::Parquet(_))));
+
+        let source = ParquetSource::Path("memory://error-tests-missing.parquet".to_string());
+        assert!(process_parquet_file(source, None, None).await.is_err_and(|error| matches!(error, Error::ObjectStore(_))));
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_filters() {
+        let source = ParquetSource::Bytes(create_parquet("message", "b"));
+        let result = process_parquet_file(source.clone(), None, None).await.unwrap();
+        let mut files = build_distributed_index(&result, &source, 0.01, &BuildOptions::default()).await.unwrap();
+        assert!(KeywordSearcher::from_serialized(&files, String::new(), None).is_ok());
+
+        let middle = files.filters.len() / 2;
+        files.filters.truncate(middle);
+        let searcher = KeywordSearcher::from_serialized(&files, String::new(), None);
+        assert!(searcher.is_err_and(|error| matches!(error, Error::CorruptIndex(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stale_index() {
+        let path = "memory://error-tests-stale.parquet";
+        register_memory_file(path, create_parquet("message", "c")).await.unwrap();
+        build_and_save_index(path, None, None, None).await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        assert!(searcher.search_highlighted(path, "host-3", None).await.is_ok());
+
+        // The keyword lookup only reads the index, rows are read from the new file
+        register_memory_file(path, create_parquet("message", "d")).await.unwrap();
+        assert!(searcher.search("host-3", None, true).await.unwrap().found);
+        let highlighted = searcher.search_highlighted(path, "host-3", None).await;
+        assert!(highlighted.is_err_and(|error| matches!(error, Error::StaleIndex(_))));
+    }
+
+    #[tokio::test]
+    async fn test_blank_column_name() {
+        let bytes = create_parquet("", "e");
+        let result = process_parquet_file(ParquetSource::Bytes(bytes.clone()), None, None).await;
+        assert!(result.is_err_and(|error| matches!(error, Error::UnsupportedSchema(_))));
+
+        // Unless the column is excluded
+        let exclude = Some(["".to_string()].into_iter().collect());
+        assert!(process_parquet_file(ParquetSource::Bytes(bytes), exclude, None).await.is_ok());
+
+        let schema = Arc::new(Schema::new(vec![Field::new("", DataType::Utf8, false)]));
+        let indexer = RecordBatchIndexer::try_new(schema, None, None, &BuildOptions::default());
+        assert!(indexer.is_err_and(|error| matches!(error, Error::UnsupportedSchema(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled() {
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let options = BuildOptions { cancellation: Some(cancellation), ..Default::default() };
+        let source = ParquetSource::Bytes(create_parquet("message", "f"));
+        let result = build_index_in_memory_with_options(source, None, None, &options).await;
+        assert!(result.is_err_and(|error| matches!(error, Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_source_of_wrapped_errors() {
+        let error = Error::from(std::io::Error::new(std::io::ErrorKind::NotFound, "missing"));
+        assert!(matches!(error, Error::Io(_)));
+        assert_eq!(error.to_string(), "I/O error: missing");
+        assert!(error.source().is_some());
+
+        let error = Error::CorruptIndex("bad filter".to_string());
+        assert_eq!(error.to_string(), "Corrupt index: bad filter");
+        assert!(error.source().is_none());
+    }
+}
@@ -0,0 +1,1233 @@
+//! Building indexes from a reproducible configuration.
+//!
+//! [`IndexBuilder`] gathers every setting of a build (column selection, error rates,
+//! chunk size, whole-value columns, cardinality check, parallelism and memory budget) in
+//! a [`BuildConfig`], which is stored in the index. The configuration of an existing index
+//! can be read back with [`KeywordSearcher::build_config`], written to a configuration
+//! file (see [`BuildConfig`]'s `Display` and `FromStr` implementations) and used to
+//! rebuild the index the same way, e.g. with `keywords index <file> --config <file>`.
+//!
+//! Settings that depend on the machine or the run rather than the index (output path,
+//! spill and checkpoint directories, progress reporting and cancellation) are set on the
+//! builder only and are not stored.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::index_builder::IndexBuilder;
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! IndexBuilder::new("data.parquet")
+//!     .with_exclude_columns(["id"])
+//!     .with_column_error_rate("email", 0.001)
+//!     .with_chunk_size_bytes(256 * 1024)
+//!     .with_exact_value_columns(["request_id"])
+//!     .with_threads(4)
+//!     .build_and_save()
+//!     .await?;
+//!
+//! // Rebuild another file the same way
+//! let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//! let config = searcher.build_config().ok_or("Index has no build configuration")?;
+//! IndexBuilder::from_config("data-2.parquet", config.clone()).build_and_save().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`KeywordSearcher::build_config`]: crate::searching::keyword_search::KeywordSearcher::build_config
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use arrow::datatypes::DataType;
+use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize};
+use crate::build_progress::{CancellationToken, ProgressCallback};
+use crate::checkpoint::remove_checkpoint;
+use crate::column_parquet_reader::process_parquet_file_with_options;
+use crate::column_sampling::{CardinalityCheck, HighCardinalityAction};
+use crate::error::Error;
+use crate::keyword_shred::SPLIT_CHARS_COUNT;
+use crate::index_data::{build_distributed_index, save_distributed_index_with_options, DistributedIndexFiles, IndexGranularity, TextFormat};
+use crate::text_source::{process_text_file, TextOptions, DEFAULT_ROWS_PER_BATCH};
+use crate::{validate_error_rate, BuildOptions, ParquetSource, DEFAULT_CHUNK_SIZE_BYTES};
+
+/// Settings of an index build, stored in the index (see [`IndexBuilder`]).
+///
+/// A configuration is written and read as a configuration file of `key = value` lines,
+/// with `#` starting a comment line. Lists are separated by commas, and settings that are
+/// not given keep their default:
+///
+/// ```text
+/// error_rate = 0.01
+/// column_error_rate.email = 0.001
+/// chunk_size_bytes = 262144
+/// split_levels = 3
+/// include_columns = http_*, email, request_id
+/// exclude_columns = http_debug
+/// column_types = Utf8, Dictionary(Int32, Utf8)
+/// exact_value_columns = request_id
+/// threads = 4
+/// memory_budget = 536870912
+/// granularity = rows
+/// ```
+///
+/// A list item or column name that would not read back as written (one with a comma
+/// outside parentheses, an `=` in a key, leading or trailing spaces, or a line break) is
+/// written in double quotes, with `\` escaping `"`, `\` and line breaks (`\n`, `\r`):
+///
+/// ```text
+/// column_error_rate."a=b" = 0.001
+/// exclude_columns = "last, first", " padded "
+/// ```
+///
+/// Text sources add `text_format` (`csv`, `json_lines`), `csv_delimiter` (a single
+/// character, or `\t`), `csv_has_header` and `rows_per_batch`. The other keys are
+/// `index_file_prefix`, `column_sketches`, `row_group_sketches`, `trigram_index`,
+/// `prefix_suggestions`, `page_locations` and `keyword_detail_limit`, named like the [`BuildOptions`] fields
+/// they set.
+///
+/// `cardinality_check` (`exclude`, `exact_values`) turns on the sampling pass of
+/// [`BuildOptions::cardinality_check`] with the given [`HighCardinalityAction`]. Its
+/// thresholds are set with `cardinality_sample_rows`, `cardinality_min_sampled_values`,
+/// `cardinality_max_distinct_value_ratio` and `cardinality_max_unique_keyword_ratio`,
+/// named like the [`CardinalityCheck`] fields, which also turn on the pass.
+///
+/// # Examples
+///
+/// ```
+/// use keywords::index_builder::BuildConfig;
+///
+/// let config: BuildConfig = "error_rate = 0.001\nexclude_columns = id, uuid".parse().unwrap();
+/// assert_eq!(config.exclude_columns, vec!["id", "uuid"]);
+/// assert_eq!(config.to_string().parse::<BuildConfig>().unwrap(), config);
+/// ```
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq)]
+pub struct BuildConfig {
+    /// Index only these columns (names or glob patterns), see [`BuildOptions::include_columns`]
+    pub include_columns: Option<Vec<String>>,
+
+    /// Columns left out of the index, by name
+    pub exclude_columns: Vec<String>,
+
+    /// Index only columns of these Arrow data types, written like `Utf8` or
+    /// `Dictionary(Int32, Utf8)`, see [`BuildOptions::column_types`]
+    pub column_types: Option<Vec<String>>,
+
+    /// Bloom filter false positive rate of the global filter and every column
+    pub error_rate: f64,
+
+    /// Error rates of single columns (names or glob patterns), see
+    /// [`BuildOptions::column_error_rates`]
+    pub column_error_rates: Vec<(String, f64)>,
+
+    /// Target size in bytes of the keyword chunks of `data.bin`
+    pub chunk_size_bytes: u64,
+
+    /// Delimiter levels values are split at, see [`BuildOptions::split_levels`]
+    pub split_levels: u64,
+
+    /// Columns (names or glob patterns) whose values are indexed whole, see
+    /// [`BuildOptions::exact_value_columns`]
+    pub exact_value_columns: Vec<String>,
+
+    /// Format of a CSV or JSON lines source; `None` for Parquet files, or to recognize
+    /// the format by the file extension
+    pub text_format: Option<TextFormat>,
+
+    /// Rows per batch of a text source, see [`TextOptions::rows_per_batch`]
+    pub rows_per_batch: u64,
+
+    /// Prefix of the index file names, e.g. `v2_` for `v2_filters.rkyv`
+    pub index_file_prefix: Option<String>,
+
+    /// Number of tokenizer threads, see [`BuildOptions::threads`]
+    pub threads: u64,
+
+    /// Memory budget in bytes, see [`BuildOptions::memory_budget`]
+    pub memory_budget: Option<u64>,
+
+    /// See [`BuildOptions::granularity`]
+    pub granularity: IndexGranularity,
+
+    /// See [`BuildOptions::column_sketches`]
+    pub column_sketches: bool,
+
+    /// See [`BuildOptions::row_group_sketches`]
+    pub row_group_sketches: bool,
+
+    /// See [`BuildOptions::trigram_index`]
+    pub trigram_index: bool,
+
+    /// See [`BuildOptions::prefix_suggestions`]
+    pub prefix_suggestions: bool,
+
+    /// See [`BuildOptions::page_locations`]
+    pub page_locations: bool,
+
+    /// See [`BuildOptions::keyword_detail_limit`]
+    pub keyword_detail_limit: Option<u64>,
+
+    /// See [`BuildOptions::cardinality_check`]
+    pub cardinality_check: Option<CardinalityCheck>,
+}
+
+impl Default for BuildConfig {
+    fn default() -> Self {
+        BuildConfig {
+            include_columns: None,
+            exclude_columns: Vec::new(),
+            column_types: None,
+            error_rate: 0.01,
+            column_error_rates: Vec::new(),
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES as u64,
+            split_levels: SPLIT_CHARS_COUNT as u64,
+            exact_value_columns: Vec::new(),
+            text_format: None,
+            rows_per_batch: DEFAULT_ROWS_PER_BATCH as u64,
+            index_file_prefix: None,
+            threads: 0,
+            memory_budget: None,
+            granularity: IndexGranularity::default(),
+            column_sketches: false,
+            row_group_sketches: false,
+            trigram_index: false,
+            prefix_suggestions: false,
+            page_locations: false,
+            keyword_detail_limit: None,
+            cardinality_check: None,
+        }
+    }
+}
+
+impl BuildConfig {
+    /// The Arrow data types of [`column_types`](Self::column_types).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if a type name is not an Arrow data type.
+    pub fn data_types(&self) -> Result<Option<Vec<DataType>>, Error> {
+        self.column_types.as_ref()
+            .map(|types| {
+                types.iter()
+                    .map(|name| DataType::from_str(name)
+                        .map_err(|e| Error::InvalidArgument(format!("Invalid column type '{}': {}", name, e))))
+                    .collect()
+            })
+            .transpose()
+    }
+}
+
+impl fmt::Display for BuildConfig {
+    /// Writes the configuration file of the configuration, with every setting that is
+    /// not `None`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error_rate = {}", self.error_rate)?;
+        for (pattern, error_rate) in &self.column_error_rates {
+            writeln!(f, "column_error_rate.{} = {}", quote_key(pattern), error_rate)?;
+        }
+        writeln!(f, "chunk_size_bytes = {}", self.chunk_size_bytes)?;
+        writeln!(f, "split_levels = {}", self.split_levels)?;
+        if let Some(patterns) = &self.include_columns {
+            writeln!(f, "include_columns = {}", join_list(patterns))?;
+        }
+        writeln!(f, "exclude_columns = {}", join_list(&self.exclude_columns))?;
+        if let Some(types) = &self.column_types {
+            writeln!(f, "column_types = {}", join_list(types))?;
+        }
+        writeln!(f, "exact_value_columns = {}", join_list(&self.exact_value_columns))?;
+        match self.text_format {
+            Some(TextFormat::Csv { delimiter, has_header }) => {
+                writeln!(f, "text_format = csv")?;
+                match delimiter {
+                    b'\t' => writeln!(f, "csv_delimiter = \\t")?,
+                    delimiter => writeln!(f, "csv_delimiter = {}", delimiter as char)?,
+                }
+                writeln!(f, "csv_has_header = {}", has_header)?;
+            }
+            Some(TextFormat::JsonLines) => writeln!(f, "text_format = json_lines")?,
+            None => {}
+        }
+        writeln!(f, "rows_per_batch = {}", self.rows_per_batch)?;
+        if let Some(prefix) = &self.index_file_prefix {
+            writeln!(f, "index_file_prefix = {}", quote_key(prefix))?;
+        }
+        writeln!(f, "threads = {}", self.threads)?;
+        if let Some(memory_budget) = self.memory_budget {
+            writeln!(f, "memory_budget = {}", memory_budget)?;
+        }
+        let granularity = match self.granularity {
+            IndexGranularity::Rows => "rows",
+            IndexGranularity::RowGroups => "row_groups",
+        };
+        writeln!(f, "granularity = {}", granularity)?;
+        writeln!(f, "column_sketches = {}", self.column_sketches)?;
+        writeln!(f, "row_group_sketches = {}", self.row_group_sketches)?;
+        writeln!(f, "trigram_index = {}", self.trigram_index)?;
+        writeln!(f, "prefix_suggestions = {}", self.prefix_suggestions)?;
+        writeln!(f, "page_locations = {}", self.page_locations)?;
+        if let Some(limit) = self.keyword_detail_limit {
+            writeln!(f, "keyword_detail_limit = {}", limit)?;
+        }
+        if let Some(check) = &self.cardinality_check {
+            let action = match check.action {
+                HighCardinalityAction::Exclude => "exclude",
+                HighCardinalityAction::ExactValues => "exact_values",
+            };
+            writeln!(f, "cardinality_check = {}", action)?;
+            writeln!(f, "cardinality_sample_rows = {}", check.sample_rows)?;
+            writeln!(f, "cardinality_min_sampled_values = {}", check.min_sampled_values)?;
+            writeln!(f, "cardinality_max_distinct_value_ratio = {}", check.max_distinct_value_ratio)?;
+            writeln!(f, "cardinality_max_unique_keyword_ratio = {}", check.max_unique_keyword_ratio)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BuildConfig {
+    type Err = Error;
+
+    /// Reads a configuration file (see [`BuildConfig`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] for a line without `=`, an unknown key, an
+    /// invalid value or an unterminated quote, naming the line.
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut config = BuildConfig::default();
+        let mut csv_delimiter = b',';
+        let mut csv_has_header = true;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| Error::InvalidArgument(format!("Line {} of build configuration: {}", number + 1, message));
+            let (key, value) = split_key_value(line)
+                .ok_or_else(|| invalid(format!("expected 'key = value', got '{}'", line)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let list_value = |value: &str| split_list(value).map_err(&invalid);
+
+            let number_value = |value: &str| value.parse::<u64>()
+                .map_err(|_| invalid(format!("'{}' is not a number for '{}'", value, key)));
+            let float_value = |value: &str| value.parse::<f64>()
+                .map_err(|_| invalid(format!("'{}' is not a number for '{}'", value, key)));
+            let bool_value = |value: &str| value.parse::<bool>()
+                .map_err(|_| invalid(format!("'{}' is not true or false for '{}'", value, key)));
+
+            if let Some(pattern) = key.strip_prefix("column_error_rate.") {
+                config.column_error_rates.push((unquote_key(pattern).map_err(&invalid)?, float_value(value)?));
+                continue;
+            }
+            match key {
+                "error_rate" => config.error_rate = float_value(value)?,
+                "chunk_size_bytes" => config.chunk_size_bytes = number_value(value)?,
+                "split_levels" => config.split_levels = number_value(value)?,
+                "include_columns" => config.include_columns = Some(list_value(value)?),
+                "exclude_columns" => config.exclude_columns = list_value(value)?,
+                "column_types" => config.column_types = Some(list_value(value)?),
+                "exact_value_columns" => config.exact_value_columns = list_value(value)?,
+                "text_format" => {
+                    config.text_format = match value {
+                        "csv" => Some(TextFormat::CSV),
+                        "json_lines" => Some(TextFormat::JsonLines),
+                        _ => return Err(invalid(format!("unknown text format '{}' (csv or json_lines)", value))),
+                    }
+                }
+                "csv_delimiter" => {
+                    csv_delimiter = match value {
+                        "\\t" => b'\t',
+                        _ if value.len() == 1 => value.as_bytes()[0],
+                        _ => return Err(invalid(format!("'{}' is not a single character delimiter", value))),
+                    }
+                }
+                "csv_has_header" => csv_has_header = bool_value(value)?,
+                "rows_per_batch" => config.rows_per_batch = number_value(value)?,
+                "index_file_prefix" => config.index_file_prefix = Some(unquote_key(value).map_err(&invalid)?),
+                "threads" => config.threads = number_value(value)?,
+                "memory_budget" => config.memory_budget = Some(number_value(value)?),
+                "granularity" => {
+                    config.granularity = match value {
+                        "rows" => IndexGranularity::Rows,
+                        "row_groups" => IndexGranularity::RowGroups,
+                        _ => return Err(invalid(format!("unknown granularity '{}' (rows or row_groups)", value))),
+                    }
+                }
+                "column_sketches" => config.column_sketches = bool_value(value)?,
+                "row_group_sketches" => config.row_group_sketches = bool_value(value)?,
+                "trigram_index" => config.trigram_index = bool_value(value)?,
+                "prefix_suggestions" => config.prefix_suggestions = bool_value(value)?,
+                "page_locations" => config.page_locations = bool_value(value)?,
+                "keyword_detail_limit" => config.keyword_detail_limit = Some(number_value(value)?),
+                "cardinality_check" => {
+                    config.cardinality_check.get_or_insert_with(CardinalityCheck::default).action = match value {
+                        "exclude" => HighCardinalityAction::Exclude,
+                        "exact_values" => HighCardinalityAction::ExactValues,
+                        _ => return Err(invalid(format!("unknown cardinality check action '{}' (exclude or exact_values)", value))),
+                    }
+                }
+                "cardinality_sample_rows" => {
+                    config.cardinality_check.get_or_insert_with(CardinalityCheck::default).sample_rows = number_value(value)? as usize;
+                }
+                "cardinality_min_sampled_values" => {
+                    config.cardinality_check.get_or_insert_with(CardinalityCheck::default).min_sampled_values = number_value(value)?;
+                }
+                "cardinality_max_distinct_value_ratio" => {
+                    config.cardinality_check.get_or_insert_with(CardinalityCheck::default).max_distinct_value_ratio = float_value(value)?;
+                }
+                "cardinality_max_unique_keyword_ratio" => {
+                    config.cardinality_check.get_or_insert_with(CardinalityCheck::default).max_unique_keyword_ratio = float_value(value)?;
+                }
+                _ => return Err(invalid(format!("unknown key '{}'", key))),
+            }
+        }
+
+        if let Some(TextFormat::Csv { .. }) = config.text_format {
+            config.text_format = Some(TextFormat::Csv { delimiter: csv_delimiter, has_header: csv_has_header });
+        }
+        Ok(config)
+    }
+}
+
+/// Splits a configuration line at its first `=` outside double quotes.
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '=' if !quoted => return Some((&line[..i], &line[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a list item would not read back as written: it is empty, has leading or
+/// trailing whitespace, starts with a quote, breaks the line, or has a comma outside
+/// parentheses or unbalanced parentheses.
+fn list_item_needs_quotes(item: &str) -> bool {
+    if item.is_empty() || item.trim() != item || item.starts_with('"') || item.contains(['\n', '\r']) {
+        return true;
+    }
+    let mut depth = 0usize;
+    for c in item.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return true,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+    depth != 0
+}
+
+/// Writes `item` in double quotes, escaping quotes, backslashes and line breaks.
+fn quote(item: &str) -> String {
+    let mut quoted = String::with_capacity(item.len() + 2);
+    quoted.push('"');
+    for c in item.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reads the quoted item at the start of `text` (see [`quote`]), returning it and the
+/// text after the closing quote.
+fn unquote(text: &str) -> Result<(String, &str), String> {
+    let mut item = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((item, &text[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => item.push('\n'),
+                Some((_, 'r')) => item.push('\r'),
+                Some((_, escaped)) => item.push(escaped),
+                None => break,
+            },
+            _ => item.push(c),
+        }
+    }
+    Err(format!("unterminated quote in '{}'", text))
+}
+
+/// Joins list items with commas, quoting those that would not read back as written.
+fn join_list(items: &[String]) -> String {
+    items.iter()
+        .map(|item| if list_item_needs_quotes(item) { quote(item) } else { item.clone() })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Writes a column name within a key (or a single value), quoted if it would not read
+/// back as written.
+fn quote_key(name: &str) -> String {
+    if name.is_empty() || name.trim() != name || name.starts_with('"') || name.contains(['=', '\n', '\r']) {
+        quote(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Reads a column name written by [`quote_key`].
+fn unquote_key(text: &str) -> Result<String, String> {
+    if !text.starts_with('"') {
+        return Ok(text.to_string());
+    }
+    match unquote(text)? {
+        (name, "") => Ok(name),
+        (_, rest) => Err(format!("unexpected '{}' after quoted name", rest)),
+    }
+}
+
+/// Splits a comma-separated list, keeping commas within parentheses (as in
+/// `Dictionary(Int32, Utf8)`) and reading quoted items (see [`join_list`]). An empty
+/// value is an empty list.
+fn split_list(value: &str) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+    let mut rest = value.trim_start();
+    while !rest.is_empty() {
+        let after_item = if rest.starts_with('"') {
+            let (item, after) = unquote(rest)?;
+            items.push(item);
+            after.trim_start()
+        } else {
+            let mut depth = 0usize;
+            let end = rest.char_indices()
+                .find(|&(_, c)| match c {
+                    '(' => {
+                        depth += 1;
+                        false
+                    }
+                    ')' => {
+                        depth = depth.saturating_sub(1);
+                        false
+                    }
+                    ',' => depth == 0,
+                    _ => false,
+                })
+                .map_or(rest.len(), |(i, _)| i);
+            let item = rest[..end].trim();
+            if !item.is_empty() {
+                items.push(item.to_string());
+            }
+            &rest[end..]
+        };
+        rest = match after_item.strip_prefix(',') {
+            Some(after_comma) => after_comma.trim_start(),
+            None if after_item.is_empty() => after_item,
+            None => return Err(format!("expected ',' before '{}'", after_item)),
+        };
+    }
+    Ok(items)
+}
+
+/// Builds and saves the index of a Parquet, CSV or JSON lines file from a
+/// [`BuildConfig`], which is stored in the index.
+///
+/// Replaces the positional arguments of
+/// [`build_and_save_index_with_options`](crate::build_and_save_index_with_options) and
+/// [`build_and_save_text_index`](crate::build_and_save_text_index): every setting has a
+/// `with_` method, and settings not given keep their defaults. Files ending in `.csv`,
+/// `.tsv`, `.ndjson` or `.jsonl` are indexed as text files unless a
+/// [text format](Self::with_text_format) is given.
+#[derive(Debug, Clone)]
+pub struct IndexBuilder {
+    source: String,
+    config: BuildConfig,
+    output_path: Option<String>,
+    spill_directory: Option<PathBuf>,
+    progress: Option<ProgressCallback>,
+    cancellation: Option<CancellationToken>,
+    checkpoint_directory: Option<PathBuf>,
+    checkpoint_interval: usize,
+}
+
+impl IndexBuilder {
+    /// A builder for the index of `source` (a local, `s3://` or `memory://` path), with
+    /// the default configuration.
+    pub fn new(source: impl Into<String>) -> Self {
+        Self::from_config(source, BuildConfig::default())
+    }
+
+    /// A builder for the index of `source` with `config`, e.g. the configuration of an
+    /// existing index or one read from a configuration file.
+    pub fn from_config(source: impl Into<String>, config: BuildConfig) -> Self {
+        IndexBuilder {
+            source: source.into(),
+            config,
+            output_path: None,
+            spill_directory: None,
+            progress: None,
+            cancellation: None,
+            checkpoint_directory: None,
+            checkpoint_interval: 0,
+        }
+    }
+
+    /// The configuration of the build.
+    pub fn config(&self) -> &BuildConfig {
+        &self.config
+    }
+
+    /// Index only the columns matching one of these names or glob patterns.
+    pub fn with_include_columns(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.config.include_columns = Some(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Leave these columns out of the index.
+    pub fn with_exclude_columns(mut self, columns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.config.exclude_columns = columns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Index only the columns of these Arrow data types.
+    pub fn with_column_types(mut self, types: impl IntoIterator<Item = DataType>) -> Self {
+        self.config.column_types = Some(types.into_iter().map(|data_type| data_type.to_string()).collect());
+        self
+    }
+
+    /// Bloom filter false positive rate (default: 0.01 = 1%).
+    pub fn with_error_rate(mut self, error_rate: f64) -> Self {
+        self.config.error_rate = error_rate;
+        self
+    }
+
+    /// Bloom filter false positive rate of the columns matching `pattern`, a name or glob
+    /// pattern. Patterns added first take precedence.
+    pub fn with_column_error_rate(mut self, pattern: impl Into<String>, error_rate: f64) -> Self {
+        self.config.column_error_rates.push((pattern.into(), error_rate));
+        self
+    }
+
+    /// Target size in bytes of the keyword chunks of `data.bin` (default:
+    /// [`DEFAULT_CHUNK_SIZE_BYTES`]).
+    pub fn with_chunk_size_bytes(mut self, chunk_size_bytes: usize) -> Self {
+        self.config.chunk_size_bytes = chunk_size_bytes as u64;
+        self
+    }
+
+    /// Number of delimiter levels values are split at, from 1 to [`SPLIT_CHARS_COUNT`]
+    /// (default: all), see [`BuildOptions::split_levels`].
+    pub fn with_split_levels(mut self, split_levels: usize) -> Self {
+        self.config.split_levels = split_levels as u64;
+        self
+    }
+
+    /// Index the values of the columns matching these names or glob patterns whole,
+    /// without splitting them.
+    pub fn with_exact_value_columns(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.config.exact_value_columns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Index the source as a CSV or JSON lines file of `format`.
+    pub fn with_text_format(mut self, format: TextFormat) -> Self {
+        self.config.text_format = Some(format);
+        self
+    }
+
+    /// Rows per batch of a text source (default: [`DEFAULT_ROWS_PER_BATCH`]).
+    pub fn with_rows_per_batch(mut self, rows_per_batch: usize) -> Self {
+        self.config.rows_per_batch = rows_per_batch as u64;
+        self
+    }
+
+    /// Save the index to `{output_path}.index/` instead of next to the source file (not
+    /// stored in the index).
+    ///
+    /// Load it with [`KeywordSearcher::load`](crate::searching::keyword_search::KeywordSearcher::load)
+    /// of `output_path`.
+    pub fn with_output_path(mut self, output_path: impl Into<String>) -> Self {
+        self.output_path = Some(output_path.into());
+        self
+    }
+
+    /// Prefix of the index file names.
+    pub fn with_index_file_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.config.index_file_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Number of tokenizer threads (default: `0`, all available cores).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads as u64;
+        self
+    }
+
+    /// Approximate limit in bytes for the keyword data held in memory.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.config.memory_budget = Some(memory_budget as u64);
+        self
+    }
+
+    /// Store row ranges or only row group membership for every keyword.
+    pub fn with_granularity(mut self, granularity: IndexGranularity) -> Self {
+        self.config.granularity = granularity;
+        self
+    }
+
+    /// Store a distinct-keyword sketch per column.
+    pub fn with_column_sketches(mut self, column_sketches: bool) -> Self {
+        self.config.column_sketches = column_sketches;
+        self
+    }
+
+    /// Store a distinct-keyword sketch per column and row group.
+    pub fn with_row_group_sketches(mut self, row_group_sketches: bool) -> Self {
+        self.config.row_group_sketches = row_group_sketches;
+        self
+    }
+
+    /// Store a trigram index of all keywords for substring search.
+    pub fn with_trigram_index(mut self, trigram_index: bool) -> Self {
+        self.config.trigram_index = trigram_index;
+        self
+    }
+
+    /// Store the most frequent keywords of short prefixes for autocomplete.
+    pub fn with_prefix_suggestions(mut self, prefix_suggestions: bool) -> Self {
+        self.config.prefix_suggestions = prefix_suggestions;
+        self
+    }
+
+    /// Record the data page of every keyword occurrence.
+    pub fn with_page_locations(mut self, page_locations: bool) -> Self {
+        self.config.page_locations = page_locations;
+        self
+    }
+
+    /// Maximum number of row entries stored for a single keyword.
+    pub fn with_keyword_detail_limit(mut self, limit: usize) -> Self {
+        self.config.keyword_detail_limit = Some(limit as u64);
+        self
+    }
+
+    /// Run the cardinality sampling pass before the build, excluding or indexing whole the
+    /// values of high-cardinality columns. Parquet sources only, see
+    /// [`BuildOptions::cardinality_check`].
+    pub fn with_cardinality_check(mut self, check: CardinalityCheck) -> Self {
+        self.config.cardinality_check = Some(check);
+        self
+    }
+
+    /// Directory for the run files written under a memory budget (not stored in the index).
+    pub fn with_spill_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.spill_directory = Some(directory.into());
+        self
+    }
+
+    /// Receive the progress of the build (not stored in the index).
+    pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stop the build when `cancellation` is cancelled (not stored in the index).
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Checkpoint the build in `directory` every `interval` row groups, so that a failed
+    /// build resumes (not stored in the index, see [`BuildOptions::checkpoint_directory`]).
+    pub fn with_checkpoint_directory(mut self, directory: impl Into<PathBuf>, interval: usize) -> Self {
+        self.checkpoint_directory = Some(directory.into());
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// The path the index is saved next to: the output path, or the source file.
+    pub fn index_path(&self) -> &str {
+        self.output_path.as_deref().unwrap_or(&self.source)
+    }
+
+    /// The format of a text source: the configured format, or the format of the file
+    /// extension. `None` for Parquet files.
+    fn text_format(&self) -> Option<TextFormat> {
+        self.config.text_format.or_else(|| TextFormat::from_path(&self.source))
+    }
+
+    /// The [`BuildOptions`] of the build, storing the configuration in the index.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if the error rate is out of range or a column
+    /// type is not an Arrow data type.
+    pub fn build_options(&self) -> Result<BuildOptions, Error> {
+        let config = &self.config;
+        validate_error_rate(config.error_rate)?;
+
+        // The stored configuration names the text format, so that it does not depend on
+        // the file name
+        let stored = BuildConfig { text_format: self.text_format(), ..config.clone() };
+
+        Ok(BuildOptions {
+            column_sketches: config.column_sketches,
+            row_group_sketches: config.row_group_sketches,
+            trigram_index: config.trigram_index,
+            prefix_suggestions: config.prefix_suggestions,
+            threads: config.threads as usize,
+            memory_budget: config.memory_budget.map(|budget| budget as usize),
+            spill_directory: self.spill_directory.clone(),
+            keyword_detail_limit: config.keyword_detail_limit.map(|limit| limit as usize),
+            granularity: config.granularity,
+            page_locations: config.page_locations,
+            cardinality_check: config.cardinality_check.clone(),
+            include_columns: config.include_columns.clone(),
+            column_types: config.data_types()?,
+            progress: self.progress.clone(),
+            cancellation: self.cancellation.clone(),
+            checkpoint_directory: self.checkpoint_directory.clone(),
+            checkpoint_interval: self.checkpoint_interval,
+            chunk_size_bytes: Some(config.chunk_size_bytes as usize),
+            split_levels: Some(config.split_levels as usize),
+            column_error_rates: config.column_error_rates.clone(),
+            exact_value_columns: config.exact_value_columns.clone(),
+            build_config: Some(stored),
+        })
+    }
+
+    /// Builds the index files without saving them.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the configuration is invalid (see [`build_options`](Self::build_options)),
+    /// or the source cannot be read or indexed.
+    pub async fn build(&self) -> Result<DistributedIndexFiles, Error> {
+        let options = self.build_options()?;
+        self.build_files(&options).await
+    }
+
+    /// Builds the index files and saves them to `{index_path}.index/` (see
+    /// [`index_path`](Self::index_path)), removing the checkpoint of the build.
+    ///
+    /// # Returns
+    ///
+    /// The saved index files.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the index cannot be built (see [`build`](Self::build)) or saved.
+    pub async fn build_and_save(&self) -> Result<DistributedIndexFiles, Error> {
+        let options = self.build_options()?;
+        let files = self.build_files(&options).await?;
+        save_distributed_index_with_options(&files, self.index_path(), self.config.index_file_prefix.as_deref(), &options).await?;
+
+        // The index is saved, so the build no longer needs its checkpoint
+        if let Some(directory) = &options.checkpoint_directory {
+            remove_checkpoint(directory)?;
+        }
+        Ok(files)
+    }
+
+    /// Processes the source and lays out the index files.
+    async fn build_files(&self, options: &BuildOptions) -> Result<DistributedIndexFiles, Error> {
+        let exclude_columns: Option<HashSet<String>> = (!self.config.exclude_columns.is_empty())
+            .then(|| self.config.exclude_columns.iter().cloned().collect());
+        let error_rate = self.config.error_rate;
+        let source = ParquetSource::Path(self.source.clone());
+
+        let result = match self.text_format() {
+            Some(format) => {
+                let text_options = TextOptions {
+                    rows_per_batch: self.config.rows_per_batch as usize,
+                    ..TextOptions::new(format)
+                };
+                process_text_file(source.clone(), &text_options, exclude_columns, Some(error_rate), options).await?
+            }
+            None => process_parquet_file_with_options(source.clone(), exclude_columns, Some(error_rate), options).await?,
+        };
+        build_distributed_index(&result, &source, error_rate, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::DataType;
+    use bytes::Bytes;
+    use crate::{get_index_info, ParquetSource, DEFAULT_CHUNK_SIZE_BYTES};
+    use crate::column_sampling::{CardinalityCheck, HighCardinalityAction};
+    use crate::error::Error;
+    use crate::index_data::{ColumnAction, TextFormat};
+    use crate::index_structure::column_filter::ColumnFilter;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::string_parquet;
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS: usize = 600;
+    const ROWS_PER_GROUP: usize = 200;
+
+    fn request_id_at(row: usize) -> String {
+        format!("{:08x}-{:04x}-req", row * 7919, row % 97)
+    }
+
+    fn create_parquet() -> Bytes {
+        let message: Vec<String> = (0..ROWS).map(|row| format!("GET /items/{} user{}@example.com", row, row % 50)).collect();
+        let host: Vec<String> = (0..ROWS).map(|row| format!("web-{}.example.com", row % 13)).collect();
+        let request_id: Vec<String> = (0..ROWS).map(request_id_at).collect();
+        string_parquet(vec![
+            ("message", message.into()),
+            ("host", host.into()),
+            ("request_id", request_id.into()),
+        ], ROWS_PER_GROUP)
+    }
+
+    async fn register(path: &str) {
+        register_memory_file(path, create_parquet()).await.unwrap();
+    }
+
+    fn bloom_bits(searcher: &KeywordSearcher, column: &str) -> u64 {
+        match &searcher.filters.column_filters[column] {
+            ColumnFilter::BloomFilter { num_bits, .. } => *num_bits,
+            ColumnFilter::RkyvHashSet(_) => panic!("Expected a Bloom filter for {}", column),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_config_stored_in_index() {
+        let path = "memory://index-builder-stored.parquet";
+        register(path).await;
+
+        let builder = IndexBuilder::new(path)
+            .with_exclude_columns(["host"])
+            .with_error_rate(0.001)
+            .with_threads(2)
+            .with_trigram_index(true);
+        builder.build_and_save().await.unwrap();
+
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        assert_eq!(searcher.build_config(), Some(builder.config()));
+        assert_eq!(searcher.filters.error_rate, 0.001);
+        assert!(!searcher.filters.column_filters.contains_key("host"));
+
+        let info = get_index_info(path, None).await.unwrap();
+        assert_eq!(info.build_config.as_ref(), Some(builder.config()));
+        assert_eq!(info.max_chunk_size_bytes, DEFAULT_CHUNK_SIZE_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_from_stored_config() {
+        let path = "memory://index-builder-original.parquet";
+        let rebuilt_path = "memory://index-builder-rebuilt.parquet";
+        register(path).await;
+        register(rebuilt_path).await;
+
+        IndexBuilder::new(path)
+            .with_include_columns(["mess*", "request_id"])
+            .with_column_types([DataType::Utf8])
+            .with_exact_value_columns(["request_*"])
+            .with_chunk_size_bytes(2048)
+            .build_and_save()
+            .await
+            .unwrap();
+        let original = KeywordSearcher::load(path, None).await.unwrap();
+
+        // The configuration survives a round trip through a configuration file
+        let config: BuildConfig = original.build_config().unwrap().to_string().parse().unwrap();
+        IndexBuilder::from_config(rebuilt_path, config).build_and_save().await.unwrap();
+        let rebuilt = KeywordSearcher::load(rebuilt_path, None).await.unwrap();
+
+        assert_eq!(rebuilt.build_config(), original.build_config());
+        assert_eq!(rebuilt.filters.chunk_index.len(), original.filters.chunk_index.len());
+        assert_eq!(rebuilt.filters.column_pool.strings, original.filters.column_pool.strings);
+        for keyword in ["items", "user7@example.com", &request_id_at(321)] {
+            let expected = original.search(keyword, None, true).await.unwrap();
+            let result = rebuilt.search(keyword, None, true).await.unwrap();
+            assert!(result.found, "{}", keyword);
+            assert_eq!(
+                result.verified_matches.unwrap().total_occurrences,
+                expected.verified_matches.unwrap().total_occurrences,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunk_size() {
+        let path = "memory://index-builder-chunks.parquet";
+        register(path).await;
+
+        let default = IndexBuilder::new(path).build().await.unwrap();
+        let small = IndexBuilder::new(path).with_chunk_size_bytes(1024).build().await.unwrap();
+        let default = KeywordSearcher::from_serialized(&default, format!("{}.index", path), None).unwrap();
+        let small = KeywordSearcher::from_serialized(&small, format!("{}.index", path), None).unwrap();
+
+        assert!(small.filters.chunk_index.len() > 5 * default.filters.chunk_index.len());
+        let keywords = |searcher: &KeywordSearcher| searcher.filters.chunk_index.iter().map(|chunk| chunk.count).sum::<u32>();
+        assert_eq!(keywords(&small), keywords(&default));
+        assert_eq!(default.filters.chunk_size_bytes, DEFAULT_CHUNK_SIZE_BYTES as u64);
+        assert_eq!(small.filters.chunk_size_bytes, 1024);
+    }
+
+    #[tokio::test]
+    async fn test_column_error_rates() {
+        let path = "memory://index-builder-error-rates.parquet";
+        register(path).await;
+
+        let files = IndexBuilder::new(path).build().await.unwrap();
+        let default = KeywordSearcher::from_serialized(&files, format!("{}.index", path), None).unwrap();
+        let files = IndexBuilder::new(path)
+            .with_column_error_rate("message", 0.0001)
+            .with_column_error_rate("*", 0.1)
+            .build()
+            .await
+            .unwrap();
+        let overridden = KeywordSearcher::from_serialized(&files, format!("{}.index", path), None).unwrap();
+
+        // The first matching pattern applies, and the global filter keeps the build's rate
+        assert!(bloom_bits(&overridden, "message") > bloom_bits(&default, "message"));
+        assert!(bloom_bits(&overridden, "request_id") < bloom_bits(&default, "request_id"));
+        match (&overridden.filters.global_filter, &default.filters.global_filter) {
+            (ColumnFilter::BloomFilter { num_bits, .. }, ColumnFilter::BloomFilter { num_bits: default_bits, .. }) => {
+                assert_eq!(num_bits, default_bits);
+            }
+            _ => panic!("Expected Bloom filters"),
+        }
+        assert!(overridden.filters.column_filters["message"].might_contain("user7@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_exact_value_columns() {
+        let path = "memory://index-builder-exact.parquet";
+        register(path).await;
+
+        IndexBuilder::new(path).with_exact_value_columns(["request_*"]).build_and_save().await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+
+        let result = searcher.search(&request_id_at(321), Some("request_id"), true).await.unwrap();
+        let data = result.verified_matches.unwrap();
+        assert_eq!(data.total_occurrences, 1);
+        assert_eq!(data.column_details[0].row_groups[0].row_group_id, (321 / ROWS_PER_GROUP) as u32);
+
+        // Parts of whole values are not indexed, other columns are still split
+        let first_part = request_id_at(321).split('-').next().unwrap().to_string();
+        assert!(!searcher.search(&first_part, Some("request_id"), true).await.unwrap().found);
+        assert!(searcher.search("items", Some("message"), true).await.unwrap().found);
+    }
+
+    #[tokio::test]
+    async fn test_split_levels() {
+        let path = "memory://index-builder-split-levels.parquet";
+        register(path).await;
+
+        IndexBuilder::new(path).with_split_levels(3).build_and_save().await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        assert_eq!(searcher.build_config().unwrap().split_levels, 3);
+        assert_eq!(searcher.filters.split_chars_inclusive.len(), 3);
+
+        // Hyphenated tokens are kept whole, dots still split them from the domain
+        assert!(searcher.search("web-3", Some("host"), true).await.unwrap().found);
+        assert!(searcher.search("web-3.example.com", Some("host"), true).await.unwrap().found);
+        assert!(!searcher.search("web", Some("host"), true).await.unwrap().found);
+
+        // Highlights stop at the same boundaries
+        let highlighted = searcher.search_highlighted(path, "web-3", Some("host")).await.unwrap();
+        assert_eq!(highlighted.cells.len(), (0..ROWS).filter(|row| row % 13 == 3).count());
+        assert!(highlighted.cells.iter().all(|cell| cell.highlights.len() == 1 && cell.highlights[0].offset == 0));
+    }
+
+    #[tokio::test]
+    async fn test_cardinality_check() {
+        let path = "memory://index-builder-cardinality.parquet";
+        register(path).await;
+
+        let check = CardinalityCheck {
+            max_unique_keyword_ratio: 0.3,
+            action: HighCardinalityAction::ExactValues,
+            ..Default::default()
+        };
+        let builder = IndexBuilder::new(path).with_cardinality_check(check.clone());
+        builder.build_and_save().await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+
+        assert_eq!(searcher.build_config().unwrap().cardinality_check, Some(check));
+        let actions: Vec<_> = searcher.column_decisions().iter()
+            .map(|decision| (decision.column_name.as_str(), decision.action))
+            .collect();
+        assert!(actions.contains(&("request_id", ColumnAction::ExactValues)), "{:?}", actions);
+        assert!(actions.contains(&("host", ColumnAction::Indexed)), "{:?}", actions);
+        assert!(searcher.search(&request_id_at(321), Some("request_id"), true).await.unwrap().found);
+    }
+
+    #[tokio::test]
+    async fn test_output_path_and_prefix() {
+        let path = "memory://index-builder-source.parquet";
+        let output_path = "memory://indexes/index-builder-output";
+        register(path).await;
+
+        let builder = IndexBuilder::new(path).with_output_path(output_path).with_index_file_prefix("v2_");
+        assert_eq!(builder.index_path(), output_path);
+        builder.build_and_save().await.unwrap();
+
+        assert!(KeywordSearcher::load(path, Some("v2_")).await.is_err());
+        let searcher = KeywordSearcher::load(output_path, Some("v2_")).await.unwrap();
+        assert!(searcher.validate_index(&ParquetSource::Path(path.to_string())).await.unwrap());
+        assert!(searcher.search("items", None, true).await.unwrap().found);
+        assert_eq!(searcher.build_config().unwrap().index_file_prefix.as_deref(), Some("v2_"));
+
+        // The output path is not stored, and cells are read from the source file
+        let config = searcher.build_config().unwrap().clone();
+        assert_eq!(IndexBuilder::from_config(path, config).index_path(), path);
+        let highlighted = searcher.search_highlighted(path, "items", None).await.unwrap();
+        assert_eq!(highlighted.cells.len(), ROWS);
+    }
+
+    #[tokio::test]
+    async fn test_text_source() {
+        let path = "memory://index-builder-text.tsv";
+        let tsv: String = std::iter::once("id\tmessage\n".to_string())
+            .chain((0..100).map(|row| format!("{}\tGET /items/{}\n", row, row)))
+            .collect();
+        register_memory_file(path, Bytes::from(tsv)).await.unwrap();
+
+        IndexBuilder::new(path).with_rows_per_batch(25).with_exclude_columns(["id"]).build_and_save().await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+
+        // The format recognized by the extension is stored with the configuration
+        assert_eq!(searcher.build_config().unwrap().text_format, Some(TextFormat::TSV));
+        assert_eq!(searcher.text_layout().unwrap().num_batches(), 4);
+        let result = searcher.search("items", None, true).await.unwrap();
+        assert_eq!(result.verified_matches.unwrap().total_occurrences, 100);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_settings() {
+        let path = "memory://index-builder-invalid.parquet";
+        register(path).await;
+
+        for builder in [
+            IndexBuilder::new(path).with_error_rate(0.9),
+            IndexBuilder::new(path).with_column_error_rate("message", 0.0),
+            IndexBuilder::new(path).with_chunk_size_bytes(0),
+            IndexBuilder::new(path).with_split_levels(0),
+            IndexBuilder::from_config(path, BuildConfig { column_types: Some(vec!["Text".to_string()]), ..Default::default() }),
+        ] {
+            let error = builder.build_and_save().await.err();
+            assert!(matches!(error, Some(Error::InvalidArgument(_))), "{:?}", error);
+        }
+        assert!(!crate::index_exists(path).await);
+    }
+
+    #[test]
+    fn test_config_file_round_trip() {
+        let config = BuildConfig {
+            include_columns: Some(vec!["http_*".to_string(), "email".to_string()]),
+            exclude_columns: vec!["id".to_string()],
+            column_types: Some(vec!["Utf8".to_string(), "Dictionary(Int32, Utf8)".to_string()]),
+            error_rate: 0.001,
+            column_error_rates: vec![("email".to_string(), 0.0001), ("http_*".to_string(), 0.05)],
+            chunk_size_bytes: 4096,
+            split_levels: 3,
+            exact_value_columns: vec!["request_id".to_string()],
+            text_format: Some(TextFormat::TSV),
+            rows_per_batch: 500,
+            index_file_prefix: Some("v2_".to_string()),
+            threads: 4,
+            memory_budget: Some(1 << 20),
+            granularity: IndexGranularity::RowGroups,
+            column_sketches: true,
+            row_group_sketches: true,
+            trigram_index: true,
+            prefix_suggestions: true,
+            page_locations: true,
+            keyword_detail_limit: Some(1000),
+            cardinality_check: Some(CardinalityCheck {
+                sample_rows: 500,
+                max_distinct_value_ratio: 0.75,
+                action: HighCardinalityAction::ExactValues,
+                ..Default::default()
+            }),
+        };
+        let text = config.to_string();
+        assert_eq!(text.parse::<BuildConfig>().unwrap(), config);
+        assert!(text.contains("csv_delimiter = \\t"));
+
+        assert_eq!(BuildConfig::default().to_string().parse::<BuildConfig>().unwrap(), BuildConfig::default());
+    }
+
+    #[test]
+    fn test_config_file_quoted_names() {
+        let names = ["last, first", "a=b", " padded ", "\"quoted\"", "back\\slash", "two\nlines", "open(", ""];
+        let config = BuildConfig {
+            include_columns: Some(names.iter().map(|name| name.to_string()).collect()),
+            exclude_columns: vec!["plain".to_string(), "x)".to_string()],
+            column_types: Some(vec!["Dictionary(Int32, Utf8)".to_string()]),
+            column_error_rates: names.iter().map(|name| (name.to_string(), 0.001)).collect(),
+            index_file_prefix: Some(" v2 ".to_string()),
+            ..Default::default()
+        };
+        let text = config.to_string();
+        assert_eq!(text.parse::<BuildConfig>().unwrap(), config);
+        assert!(text.contains("column_error_rate.\"a=b\" = 0.001"));
+        assert!(text.contains("exclude_columns = plain, \"x)\""));
+        assert!(text.contains("column_types = Dictionary(Int32, Utf8)"));
+
+        for (text, message) in [
+            ("exclude_columns = \"open", "unterminated quote"),
+            ("exclude_columns = \"a\" b", "expected ','"),
+            ("column_error_rate.\"a\"b = 0.1", "after quoted name"),
+        ] {
+            let error = text.parse::<BuildConfig>().unwrap_err();
+            assert!(error.to_string().contains(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_config_file_syntax() {
+        let config: BuildConfig = "\
+            # Comments and blank lines are skipped\n\
+            \n\
+            include_columns =\n\
+            column_types = Dictionary(Int32, Utf8), LargeUtf8\n\
+            text_format = csv\n\
+            csv_delimiter = ;\n\
+            csv_has_header = false\n\
+            cardinality_max_distinct_value_ratio = 0.5\n"
+            .parse()
+            .unwrap();
+        assert_eq!(config.include_columns, Some(Vec::new()));
+        assert_eq!(
+            config.data_types().unwrap(),
+            Some(vec![DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)), DataType::LargeUtf8]),
+        );
+        assert_eq!(config.text_format, Some(TextFormat::Csv { delimiter: b';', has_header: false }));
+        assert_eq!(config.error_rate, 0.01);
+        // A threshold alone turns on the cardinality check with the default action
+        assert_eq!(
+            config.cardinality_check,
+            Some(CardinalityCheck { max_distinct_value_ratio: 0.5, ..Default::default() }),
+        );
+
+        for (text, message) in [
+            ("error_rate 0.1", "Line 1"),
+            ("threads = 2\nunknown = 1", "unknown key 'unknown'"),
+            ("threads = many", "'many' is not a number"),
+            ("granularity = pages", "unknown granularity"),
+            ("cardinality_check = keep", "unknown cardinality check action"),
+        ] {
+            let error = text.parse::<BuildConfig>().unwrap_err();
+            assert!(matches!(error, Error::InvalidArgument(_)));
+            assert!(error.to_string().contains(message), "{}", error);
+        }
+
+        let invalid_type = BuildConfig { column_types: Some(vec!["NotAType".to_string()]), ..Default::default() };
+        assert!(matches!(invalid_type.data_types(), Err(Error::InvalidArgument(_))));
+    }
+}
@@ -1,14 +1,61 @@
 use hashbrown::HashMap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::collections::HashMap as StdHashMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::rc::Rc;
+use crate::error::Error;
+use crate::build_progress::BuildPhase;
 use crate::utils::column_pool::ColumnPool;
-use crate::index_structure::column_filter::ColumnFilter;
+use crate::index_structure::column_filter::{ColumnFilter, ColumnFilterBuilder};
+use crate::index_structure::hyperloglog::HyperLogLog;
+use crate::index_structure::trigram_index::{TrigramIndexBuilder, TrigramPostings};
 use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, to_bytes};
 use rkyv::rancor::Error as RkyvError;
-use crate::keyword_shred::SPLIT_CHARS_INCLUSIVE;
+use rkyv::util::AlignedVec;
+use crate::keyword_shred::{Row, SPLIT_CHARS_INCLUSIVE};
+use crate::keyword_spill::{for_each_sorted_keyword, TempFile};
 use crate::utils::file_interaction_local_and_cloud::get_object_store;
-use crate::{KeywordOneFile, ParquetSource, ProcessResult, MAX_CHUNK_SIZE_BYTES};
+use crate::{BuildOptions, KeywordOneFile, ParquetSource, ProcessResult};
+use crate::index_builder::BuildConfig;
 use crate::index_structure::index_files::{index_filename, IndexFile};
 
+/// Version of the index format written by this library, stored in the header of
+/// `filters.rkyv` (see [`FILTERS_MAGIC`]) and in [`IndexFilters::version`].
+///
+/// Version 1 stored row group IDs, chunk keyword counts and parent keyword references as
+/// `u16`, limiting files to 65,535 row groups. Version 2 stores them as varints (row
+/// entries) or `u32`. Indexes of both versions can be searched.
+pub const INDEX_FORMAT_VERSION: u32 = 2;
+
+/// Magic bytes starting `filters.rkyv` since version 2, followed by the format version
+/// (`u32`, little endian) and the archived [`IndexFilters`]. Version 1 files are the bare
+/// archive, so the version is known before the archive is validated.
+pub const FILTERS_MAGIC: &[u8; 4] = b"KWIX";
+
+/// Splits the bytes of `filters.rkyv` into the index format version and the archive.
+///
+/// # Examples
+///
+/// ```
+/// use keywords::index_data::{filters_file_version, FILTERS_MAGIC};
+///
+/// let mut bytes = FILTERS_MAGIC.to_vec();
+/// bytes.extend_from_slice(&7u32.to_le_bytes());
+/// bytes.extend_from_slice(b"archive");
+/// assert_eq!(filters_file_version(&bytes), (7, &b"archive"[..]));
+/// assert_eq!(filters_file_version(b"version 1 archive"), (1, &b"version 1 archive"[..]));
+/// ```
+pub fn filters_file_version(bytes: &[u8]) -> (u32, &[u8]) {
+    match bytes.strip_prefix(FILTERS_MAGIC).and_then(|rest| rest.split_first_chunk::<4>()) {
+        Some((version, archive)) => (u32::from_le_bytes(*version), archive),
+        None => (1, bytes),
+    }
+}
+
 #[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug)]
 pub struct IndexFilters {
     // Version & validation
@@ -25,6 +72,8 @@ pub struct IndexFilters {
 
     // Configuration
     pub error_rate: f64,
+    // Target size of the chunks of data.bin
+    pub chunk_size_bytes: u64,
     pub split_chars_inclusive: Vec<Vec<char>>,
 
     // Data structures
@@ -32,6 +81,174 @@ pub struct IndexFilters {
     pub column_filters: StdHashMap<String, ColumnFilter>,
     pub global_filter: ColumnFilter,
     pub chunk_index: Vec<ChunkInfo>,
+
+    // Autocomplete: most frequent keywords for short prefixes, per column
+    // (empty unless built with prefix suggestions)
+    pub prefix_suggestions: Vec<PrefixSuggestions>,
+
+    // Per-column statistics (top keywords, distinct keywords, rows with keywords)
+    pub column_statistics: Vec<ColumnStatistics>,
+
+    // Distinct keyword sketches per column, optionally per row group
+    // (empty unless built with column or row group sketches)
+    pub column_sketches: Vec<ColumnSketch>,
+
+    // Optional trigram directory for substring search (empty unless built)
+    pub trigram_directory: Vec<TrigramPostings>,
+
+    // Outcome of the optional cardinality sampling pass, per sampled column
+    pub column_decisions: Vec<ColumnDecision>,
+
+    // Whether data.bin stores rows or only row groups per keyword and column
+    pub granularity: IndexGranularity,
+
+    // Data pages of every indexed column chunk, sorted by column and row group
+    // (empty unless built with page locations)
+    pub page_tables: Vec<PageTable>,
+
+    // Batches of rows the file was split into, for CSV and JSON lines sources
+    // (None for Parquet files)
+    pub text_layout: Option<TextLayout>,
+
+    // Configuration the index was built with, to rebuild it the same way
+    // (None unless built with an IndexBuilder)
+    pub build_config: Option<BuildConfig>,
+}
+
+/// How precisely keyword locations are stored in `data.bin`.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexGranularity {
+    /// Row ranges of every keyword, with parent keywords for phrase search
+    #[default]
+    Rows,
+    /// Only the row groups each keyword occurs in, per column, as a [`RowGroupBitmap`].
+    ///
+    /// Much smaller for large files, but every search result is
+    /// [`coarse`](crate::searching::search_results::RowGroupLocation::coarse): whole row
+    /// groups must be scanned, and phrases always need verification.
+    RowGroups,
+}
+
+/// Precision of the per-column keyword sketches (4096 registers, ~1.6% error).
+pub const COLUMN_SKETCH_PRECISION: u8 = 12;
+
+/// Precision of the optional per-row-group keyword sketches (1024 registers, ~3.3% error).
+///
+/// Lower than the column precision because there is one sketch per column and row group.
+pub const ROW_GROUP_SKETCH_PRECISION: u8 = 10;
+
+/// HyperLogLog sketch of the distinct keywords in one column.
+///
+/// Sketches of the same column from different indexes can be merged to estimate the
+/// distinct keywords across files.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub struct ColumnSketch {
+    /// Column ID from the column pool (0 = all columns)
+    pub column_id: u32,
+
+    /// Sketch of every keyword in the column, at [`COLUMN_SKETCH_PRECISION`]
+    pub sketch: HyperLogLog,
+
+    /// Per-row-group sketches at [`ROW_GROUP_SKETCH_PRECISION`], ordered by row group ID.
+    /// Empty unless [`BuildOptions::row_group_sketches`] was enabled.
+    pub row_groups: Vec<RowGroupSketch>,
+}
+
+/// HyperLogLog sketch of the distinct keywords of a column within one row group.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub struct RowGroupSketch {
+    pub row_group_id: u32,
+    pub sketch: HyperLogLog,
+}
+
+/// Number of most frequent keywords stored in each column's statistics.
+pub const COLUMN_STATS_TOP_N: usize = 10;
+
+/// Keyword statistics for one indexed column, computed at build time.
+///
+/// Useful for spotting junk columns (few rows with keywords, or one keyword dominating)
+/// and skewed values without scanning the data file.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub struct ColumnStatistics {
+    /// Column ID from the column pool
+    pub column_id: u32,
+
+    /// Number of distinct keywords in the column
+    pub distinct_keywords: u64,
+
+    /// Number of rows with at least one keyword in the column
+    pub rows_with_keywords: u64,
+
+    /// The [`COLUMN_STATS_TOP_N`] most frequent keywords, by descending occurrences
+    pub top_keywords: Vec<KeywordCount>,
+}
+
+/// How a column was indexed after the cardinality sampling pass.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnAction {
+    /// Split into keywords like any other column
+    Indexed,
+    /// Each whole value indexed as a single keyword, without splitting
+    ExactValues,
+    /// Left out of the index
+    Excluded,
+}
+
+/// Decision taken for one column by the cardinality sampling pass, with the sampled
+/// statistics it was based on.
+///
+/// Only present when the index was built with
+/// [`BuildOptions::cardinality_check`](crate::BuildOptions::cardinality_check).
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq)]
+pub struct ColumnDecision {
+    /// Column name (excluded columns are not in the column pool)
+    pub column_name: String,
+
+    /// Number of non-empty values sampled
+    pub sampled_values: u64,
+
+    /// Share of sampled values that are distinct
+    pub distinct_value_ratio: f64,
+
+    /// Share of sampled keyword occurrences whose keyword occurs in no other sampled row
+    pub unique_keyword_ratio: f64,
+
+    pub action: ColumnAction,
+
+    /// Human-readable explanation of the decision
+    pub reason: String,
+}
+
+/// Longest prefix (in characters) with precomputed suggestions.
+///
+/// Longer prefixes narrow the keyword range enough that scanning the matching chunks
+/// is cheap, so they are answered from the data file instead.
+pub const SUGGESTION_PREFIX_MAX_CHARS: usize = 2;
+
+/// Number of suggestions stored per precomputed prefix.
+pub const SUGGESTION_TOP_K: usize = 10;
+
+/// Precomputed autocomplete suggestions for one column.
+///
+/// Maps every prefix of up to [`SUGGESTION_PREFIX_MAX_CHARS`] characters (including the
+/// empty prefix) that occurs in the column to its [`SUGGESTION_TOP_K`] most frequent
+/// keywords. A prefix missing from the map has no matching keywords in the column.
+///
+/// Only stored when the index was built with [`BuildOptions::prefix_suggestions`].
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub struct PrefixSuggestions {
+    /// Column ID from the column pool (0 = all columns)
+    pub column_id: u32,
+
+    /// Prefix → keywords ordered by descending occurrences, then keyword
+    pub prefixes: StdHashMap<String, Vec<KeywordCount>>,
+}
+
+/// A keyword together with the number of rows it occurs in.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeywordCount {
+    pub keyword: String,
+    pub occurrences: u64,
 }
 
 /// Information about a chunk in the data file.
@@ -56,7 +273,7 @@ pub struct ChunkInfo {
     pub total_length: u32,
 
     /// Number of keywords in this chunk (dynamic, based on ~1MB serialized size target)
-    pub count: u16,
+    pub count: u32,
 }
 
 /// Flattened keyword data (stored in data.bin)
@@ -64,6 +281,26 @@ pub struct ChunkInfo {
 pub struct KeywordDataFlat {
     pub columns: Vec<ColumnDataFlat>,
     pub splits_matched: u16,
+
+    /// The keyword exceeded the per-keyword detail limit, so only the row groups it
+    /// occurs in are stored: every `RowGroupDataFlat::rows` is empty.
+    pub coarse: bool,
+}
+
+impl ArchivedKeywordDataFlat {
+    /// Checks the row entries of every row group (see [`FlatRow::validate`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CorruptIndex`] if the entries of a row group are corrupt.
+    pub fn validate_rows(&self) -> Result<(), Error> {
+        for column in self.columns.iter() {
+            for row_group in column.row_groups.iter() {
+                FlatRow::validate(&row_group.rows)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Per-column data for a keyword
@@ -71,13 +308,241 @@ pub struct KeywordDataFlat {
 pub struct ColumnDataFlat {
     pub column_id: u32,
     pub row_groups: Vec<RowGroupDataFlat>,
+
+    /// Row groups of an [`IndexGranularity::RowGroups`] index, which leaves `row_groups`
+    /// empty
+    pub row_group_bitmap: Option<Box<RowGroupBitmap>>,
+}
+
+/// Row groups containing a keyword in one column, one bit per row group.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RowGroupBitmap {
+    /// Row group `n` is bit `n % 32` of word `n / 32` (32-bit words keep the chunk
+    /// data 4-byte aligned)
+    pub words: Vec<u32>,
+
+    /// Number of rows in the column containing the keyword
+    pub occurrences: u32,
+}
+
+impl RowGroupBitmap {
+    /// Builds the bitmap of a set of row groups.
+    pub fn new(row_groups: impl IntoIterator<Item = u32>, occurrences: u32) -> Self {
+        let mut words = Vec::new();
+        for row_group in row_groups {
+            let word = row_group as usize / 32;
+            if words.len() <= word {
+                words.resize(word + 1, 0);
+            }
+            words[word] |= 1 << (row_group % 32);
+        }
+        RowGroupBitmap { words, occurrences }
+    }
+
+    /// The row groups in the bitmap, ascending.
+    pub fn row_groups(&self) -> impl Iterator<Item = u32> + '_ {
+        bitmap_row_groups(self.words.iter().copied())
+    }
+}
+
+impl ArchivedRowGroupBitmap {
+    /// The row groups in the bitmap, ascending.
+    pub fn row_groups(&self) -> impl Iterator<Item = u32> + '_ {
+        bitmap_row_groups(self.words.iter().map(|word| word.to_native()))
+    }
+}
+
+/// Row groups of the set bits of bitmap words.
+fn bitmap_row_groups(words: impl Iterator<Item = u32>) -> impl Iterator<Item = u32> {
+    words.enumerate().flat_map(|(index, mut word)| {
+        std::iter::from_fn(move || {
+            (word != 0).then(|| {
+                let bit = word.trailing_zeros();
+                word &= word - 1;
+                (index * 32) as u32 + bit
+            })
+        })
+    })
+}
+
+/// Data pages of one column chunk, from the Parquet offset index.
+///
+/// Recorded for every indexed column chunk with an offset index when
+/// [`BuildOptions::page_locations`] is enabled.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PageTable {
+    /// Column ID from the column pool
+    pub column_id: u32,
+    pub row_group_id: u32,
+
+    /// First row of each data page, ascending (pages start on row boundaries)
+    pub first_rows: Vec<u32>,
+
+    /// Number of rows in the row group, where the last page ends
+    pub num_rows: u32,
+}
+
+impl PageTable {
+    /// The page containing a row.
+    pub fn page_of(&self, row: u32) -> u32 {
+        self.first_rows.partition_point(|&first_row| first_row <= row).saturating_sub(1) as u32
+    }
+
+    /// First and last row (inclusive) of a page, or `None` past the last page.
+    pub fn page_rows(&self, page: u32) -> Option<(u32, u32)> {
+        let start = *self.first_rows.get(page as usize)?;
+        let end = self.first_rows.get(page as usize + 1).copied().unwrap_or(self.num_rows);
+        Some((start, end.saturating_sub(1).max(start)))
+    }
+}
+
+/// Finds the page table of a column chunk in page tables sorted by column and row group.
+pub fn find_page_table(page_tables: &[PageTable], column_id: u32, row_group_id: u32) -> Option<&PageTable> {
+    page_tables.binary_search_by_key(&(column_id, row_group_id), |table| (table.column_id, table.row_group_id))
+        .ok()
+        .map(|index| &page_tables[index])
+}
+
+/// Format of a text source file indexed in batches of rows (see [`TextLayout`]).
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Delimited values, one record per line (quoted values may span lines)
+    Csv {
+        /// Field delimiter, e.g. `b','` or `b'\t'`
+        delimiter: u8,
+        /// Whether the first record holds the column names
+        has_header: bool,
+    },
+    /// One JSON object per line (NDJSON)
+    JsonLines,
+}
+
+impl TextFormat {
+    /// Comma-separated values with a header record.
+    pub const CSV: TextFormat = TextFormat::Csv { delimiter: b',', has_header: true };
+
+    /// Tab-separated values with a header record.
+    pub const TSV: TextFormat = TextFormat::Csv { delimiter: b'\t', has_header: true };
+
+    /// The format of a file by its extension: `.csv`, `.tsv`, `.ndjson` or `.jsonl`
+    /// (case-insensitive). `None` for other files, such as Parquet files.
+    pub fn from_path(path: &str) -> Option<TextFormat> {
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "csv" => Some(TextFormat::CSV),
+            "tsv" => Some(TextFormat::TSV),
+            "ndjson" | "jsonl" => Some(TextFormat::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// How a CSV or JSON lines file was split into pseudo row groups.
+///
+/// Text files have no row groups, so their rows are indexed in consecutive batches of
+/// `rows_per_batch` rows, each batch taking the place of a row group: row group `i` of
+/// a search result is batch `i`, and row numbers are relative to the batch. The byte
+/// range of each batch is recorded so that a reader can fetch and decode only the
+/// batches holding matches (see
+/// [`PrunedTextReader`](crate::searching::pruned_text_reader::PrunedTextReader)).
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    pub format: TextFormat,
+
+    /// Arrow schema the rows were decoded with, as an Arrow IPC schema message
+    pub schema: Vec<u8>,
+
+    /// Number of rows per batch (the last batch may have fewer)
+    pub rows_per_batch: u32,
+
+    /// Byte offset of the start of every batch in the file, followed by the end of the
+    /// last batch: batch `i` spans `batch_offsets[i]..batch_offsets[i + 1]`. The first
+    /// batch includes the CSV header record, if any.
+    pub batch_offsets: Vec<u64>,
+}
+
+impl TextLayout {
+    /// Number of batches (pseudo row groups).
+    pub fn num_batches(&self) -> usize {
+        self.batch_offsets.len().saturating_sub(1)
+    }
+
+    /// Byte range of a batch in the file, or `None` past the last batch.
+    pub fn batch_range(&self, batch: usize) -> Option<std::ops::Range<u64>> {
+        Some(*self.batch_offsets.get(batch)?..*self.batch_offsets.get(batch + 1)?)
+    }
+
+    /// Decodes the Arrow schema the rows were decoded with.
+    pub fn arrow_schema(&self) -> Result<arrow::datatypes::Schema, arrow::error::ArrowError> {
+        arrow::ipc::convert::try_schema_from_flatbuffer_bytes(&self.schema)
+    }
 }
 
 /// Per-row-group data for a column
 #[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
 pub struct RowGroupDataFlat {
-    pub row_group_id: u16,
-    pub rows: Vec<FlatRow>,
+    pub row_group_id: u32,
+
+    /// Row entries, encoded with [`FlatRow::encode`]; read them with
+    /// [`rows`](Self::rows)
+    pub rows: Vec<u8>,
+
+    /// Only written for coarse keywords, which store no rows, and for indexes built
+    /// with page locations
+    pub summary: Option<Box<RowGroupSummary>>,
+}
+
+/// Occurrences and pages of a keyword in one row group of a column.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RowGroupSummary {
+    /// Number of rows in this row group containing the keyword
+    pub occurrences: u32,
+
+    /// Data pages of the column chunk containing the keyword (see [`PageTable`]),
+    /// ascending. Empty unless built with page locations.
+    pub pages: Vec<u32>,
+}
+
+impl RowGroupDataFlat {
+    /// The row entries of this row group.
+    pub fn rows(&self) -> FlatRows<'_> {
+        FlatRow::decode(&self.rows)
+    }
+
+    /// Number of rows in this row group containing the keyword.
+    pub fn occurrences(&self) -> u32 {
+        match &self.summary {
+            Some(summary) => summary.occurrences,
+            None => count_distinct_rows(self.rows().map(|row| (row.row, row.row.saturating_add(row.additional_rows))))
+                .min(u32::MAX as u64) as u32,
+        }
+    }
+
+    /// Data pages of the column chunk containing the keyword, ascending.
+    pub fn pages(&self) -> &[u32] {
+        self.summary.as_ref().map_or(&[], |summary| &summary.pages)
+    }
+}
+
+impl ArchivedRowGroupDataFlat {
+    /// The row entries of this row group.
+    pub fn rows(&self) -> FlatRows<'_> {
+        FlatRow::decode(&self.rows)
+    }
+
+    /// Number of rows in this row group containing the keyword.
+    pub fn occurrences(&self) -> u32 {
+        match self.summary.as_ref() {
+            Some(summary) => summary.occurrences.to_native(),
+            None => count_distinct_rows(self.rows().map(|row| (row.row, row.row.saturating_add(row.additional_rows))))
+                .min(u32::MAX as u64) as u32,
+        }
+    }
+
+    /// Data pages of the column chunk containing the keyword, ascending.
+    pub fn pages(&self) -> impl Iterator<Item = u32> + '_ {
+        self.summary.as_ref().into_iter().flat_map(|summary| summary.pages.iter().map(|page| page.to_native()))
+    }
 }
 
 /// Flattened row information with chunk-based parent tracking.
@@ -85,7 +550,10 @@ pub struct RowGroupDataFlat {
 /// Parent tracking uses chunk number + position within chunk instead of global offset.
 /// This eliminates the need for a separate global keyword array and supports the
 /// 2-file index structure where keywords are organized in chunks.
-#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone, PartialEq, Eq)]
+///
+/// Row entries are stored as varints (see [`encode`](Self::encode)), so that small
+/// values take a byte whatever the widths of the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FlatRow {
     pub row: u32,
     pub additional_rows: u32,
@@ -93,19 +561,414 @@ pub struct FlatRow {
 
     /// Parent keyword chunk number (which chunk contains the parent)
     /// For parent lookup: chunk_index[parent_chunk] gives the chunk location
-    pub parent_chunk: Option<u16>,
+    pub parent_chunk: Option<u32>,
 
     /// Position within the parent chunk (0 to chunk size-1)
     /// Combined with parent_chunk: keywords[parent_position] in that chunk
-    pub parent_position: Option<u16>,
+    pub parent_position: Option<u32>,
+}
+
+impl FlatRow {
+    /// Encodes row entries as LEB128 varints, per entry: the difference to the previous
+    /// entry's row (zigzag-encoded, the first entry's to 0), `additional_rows`,
+    /// `splits_matched`, then `0` without a parent or the parent chunk plus one followed
+    /// by the parent position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use keywords::index_data::FlatRow;
+    ///
+    /// let rows = vec![
+    ///     FlatRow { row: 7, additional_rows: 2, splits_matched: 1, parent_chunk: None, parent_position: None },
+    ///     FlatRow { row: 300, additional_rows: 0, splits_matched: 2, parent_chunk: Some(4), parent_position: Some(17) },
+    /// ];
+    /// let bytes = FlatRow::encode(&rows);
+    /// assert_eq!(bytes.len(), 10);
+    /// assert_eq!(FlatRow::decode(&bytes).collect::<Vec<_>>(), rows);
+    /// ```
+    pub fn encode(rows: &[FlatRow]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut previous_row = 0u32;
+        for row in rows {
+            let delta = row.row as i64 - previous_row as i64;
+            write_varint(&mut bytes, ((delta << 1) ^ (delta >> 63)) as u64);
+            write_varint(&mut bytes, row.additional_rows as u64);
+            write_varint(&mut bytes, row.splits_matched as u64);
+            match row.parent_chunk.zip(row.parent_position) {
+                Some((chunk, position)) => {
+                    write_varint(&mut bytes, chunk as u64 + 1);
+                    write_varint(&mut bytes, position as u64);
+                }
+                None => write_varint(&mut bytes, 0),
+            }
+            previous_row = row.row;
+        }
+        bytes
+    }
+
+    /// Decodes row entries written by [`encode`](Self::encode).
+    pub fn decode(bytes: &[u8]) -> FlatRows<'_> {
+        FlatRows { bytes, previous_row: 0 }
+    }
+
+    /// Checks that `bytes` decode to the end as row entries written by
+    /// [`encode`](Self::encode).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CorruptIndex`] if an entry is truncated or out of range.
+    pub fn validate(bytes: &[u8]) -> Result<(), Error> {
+        let mut rows = Self::decode(bytes);
+        while !rows.bytes.is_empty() {
+            if rows.next().is_none() {
+                return Err(Error::CorruptIndex(format!(
+                    "Truncated or out-of-range row entry at byte {}",
+                    bytes.len() - rows.bytes.len(),
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the row entries of a row group (see [`FlatRow::decode`]).
+///
+/// Searches check the entries of a chunk with [`FlatRow::validate`] when they read it; on
+/// unchecked bytes, the iterator ends early at a truncated or out-of-range entry.
+#[derive(Debug, Clone)]
+pub struct FlatRows<'a> {
+    bytes: &'a [u8],
+    previous_row: u32,
+}
+
+impl FlatRows<'_> {
+    fn read_varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for (index, &byte) in self.bytes.iter().enumerate().take(10) {
+            value |= ((byte & 0x7f) as u64) << (7 * index);
+            if byte & 0x80 == 0 {
+                self.bytes = &self.bytes[index + 1..];
+                return Some(value);
+            }
+        }
+        self.bytes = &[];
+        None
+    }
+}
+
+impl Iterator for FlatRows<'_> {
+    type Item = FlatRow;
+
+    fn next(&mut self) -> Option<FlatRow> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let zigzag = self.read_varint()?;
+        let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        let row = u32::try_from(self.previous_row as i64 + delta).ok()?;
+        let additional_rows = u32::try_from(self.read_varint()?).ok()?;
+        let splits_matched = u16::try_from(self.read_varint()?).ok()?;
+        let (parent_chunk, parent_position) = match self.read_varint()? {
+            0 => (None, None),
+            chunk => (Some(u32::try_from(chunk - 1).ok()?), Some(u32::try_from(self.read_varint()?).ok()?)),
+        };
+        self.previous_row = row;
+        Some(FlatRow { row, additional_rows, splits_matched, parent_chunk, parent_position })
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
 }
 
 /// Location of a keyword within the chunked structure.
 /// Used during index building to track where each keyword ends up.
 #[derive(Debug, Clone, Copy)]
 struct KeywordLocation {
-    chunk_number: u16,
-    position_in_chunk: u16,
+    chunk_number: u32,
+    position_in_chunk: u32,
+}
+
+/// Number of recently read keyword lists kept in memory while resolving the parent
+/// keywords of a spilled build.
+const CACHED_KEYWORD_LISTS: usize = 8;
+
+/// Keyword lists of the chunks laid out by [`build_distributed_index`], used to resolve
+/// parent keywords to chunk+position pairs and written to `data.bin` with each chunk.
+///
+/// Builds whose keywords all fit in memory keep the lists (sharing the strings of the
+/// keyword map) with a keyword → location map. Builds that spilled keywords to disk
+/// write the serialized lists to a temporary file instead and keep only the first
+/// keyword of every chunk: a parent is found by binary search over the chunks, then
+/// within the chunk's list, read back from the file.
+enum ChunkKeywordLists {
+    Memory {
+        chunks: Vec<Vec<Rc<str>>>,
+        locations: HashMap<Rc<str>, KeywordLocation>,
+    },
+    File {
+        /// Removes the file when the build is done
+        _file: TempFile,
+        handle: File,
+        chunks: Vec<StoredKeywordList>,
+        /// Recently read lists, most recent last
+        cache: VecDeque<(u32, Vec<String>)>,
+        /// Last parent looked up, as consecutive rows often share it
+        last_lookup: Option<(Rc<str>, Option<KeywordLocation>)>,
+    },
+}
+
+/// A chunk's keyword list in the temporary file of a spilled build.
+struct StoredKeywordList {
+    start_keyword: String,
+    count: u32,
+    offset: u64,
+    length: u32,
+}
+
+impl ChunkKeywordLists {
+    /// Keeps the lists in memory, or in a temporary file in `spill_directory` if given.
+    fn new(spill_directory: Option<&Path>) -> Result<Self, Error> {
+        Ok(match spill_directory {
+            None => ChunkKeywordLists::Memory { chunks: Vec::new(), locations: HashMap::new() },
+            Some(directory) => {
+                let (file, handle) = TempFile::create(directory, "keyword-lists")?;
+                ChunkKeywordLists::File { _file: file, handle, chunks: Vec::new(), cache: VecDeque::new(), last_lookup: None }
+            }
+        })
+    }
+
+    /// Number of chunks.
+    fn len(&self) -> usize {
+        match self {
+            ChunkKeywordLists::Memory { chunks, .. } => chunks.len(),
+            ChunkKeywordLists::File { chunks, .. } => chunks.len(),
+        }
+    }
+
+    /// Number of keywords in a chunk.
+    fn chunk_len(&self, chunk: usize) -> usize {
+        match self {
+            ChunkKeywordLists::Memory { chunks, .. } => chunks[chunk].len(),
+            ChunkKeywordLists::File { chunks, .. } => chunks[chunk].count as usize,
+        }
+    }
+
+    /// Adds the sorted keywords of the next chunk.
+    fn push(&mut self, keywords: Vec<Rc<str>>) -> Result<(), Error> {
+        match self {
+            ChunkKeywordLists::Memory { chunks, locations } => {
+                let chunk_number = chunks.len() as u32;
+                for (position, keyword) in keywords.iter().enumerate() {
+                    locations.insert(keyword.clone(), KeywordLocation { chunk_number, position_in_chunk: position as u32 });
+                }
+                chunks.push(keywords);
+            }
+            ChunkKeywordLists::File { handle, chunks, .. } => {
+                let bytes = serialize_keyword_list(&keywords)?;
+                let offset = chunks.last().map_or(0, |list| list.offset + list.length as u64);
+                handle.seek(SeekFrom::Start(offset))?;
+                handle.write_all(&bytes)?;
+                chunks.push(StoredKeywordList {
+                    start_keyword: keywords.first().map(|keyword| keyword.to_string()).unwrap_or_default(),
+                    count: keywords.len() as u32,
+                    offset,
+                    length: bytes.len() as u32,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// First keyword and serialized keyword list of a chunk, as stored in `data.bin`.
+    fn serialized_list(&mut self, chunk: usize) -> Result<(String, Vec<u8>), Error> {
+        match self {
+            ChunkKeywordLists::Memory { chunks, .. } => {
+                let keywords = &chunks[chunk];
+                let start_keyword = keywords.first().map(|keyword| keyword.to_string()).unwrap_or_default();
+                Ok((start_keyword, serialize_keyword_list(keywords)?))
+            }
+            ChunkKeywordLists::File { handle, chunks, .. } => {
+                let list = &chunks[chunk];
+                let mut bytes = vec![0u8; list.length as usize];
+                handle.seek(SeekFrom::Start(list.offset))?;
+                handle.read_exact(&mut bytes)?;
+                Ok((list.start_keyword.clone(), bytes))
+            }
+        }
+    }
+
+    /// Chunk and position of a keyword, or `None` if it was not laid out.
+    fn locate(&mut self, keyword: &Rc<str>) -> Result<Option<KeywordLocation>, Error> {
+        match self {
+            ChunkKeywordLists::Memory { locations, .. } => Ok(locations.get(keyword).copied()),
+            ChunkKeywordLists::File { handle, chunks, cache, last_lookup, .. } => {
+                if let Some((last, location)) = last_lookup.as_ref()
+                    && last == keyword
+                {
+                    return Ok(*location);
+                }
+
+                let location = match chunks.partition_point(|list| list.start_keyword.as_str() <= keyword.as_ref()).checked_sub(1) {
+                    None => None,
+                    Some(chunk) => {
+                        let chunk_number = chunk as u32;
+                        let cached = cache.iter().position(|(cached, _)| *cached == chunk_number);
+                        let keywords = match cached {
+                            Some(index) => {
+                                let entry = cache.remove(index).expect("Cached list found above");
+                                cache.push_back(entry);
+                                &cache.back().expect("List pushed above").1
+                            }
+                            None => {
+                                let list = &chunks[chunk];
+                                let mut bytes = AlignedVec::<16>::with_capacity(list.length as usize);
+                                bytes.resize(list.length as usize, 0);
+                                handle.seek(SeekFrom::Start(list.offset))?;
+                                handle.read_exact(&mut bytes)?;
+                                let keywords = rkyv::from_bytes::<Vec<String>, RkyvError>(&bytes)
+                                    .map_err(|e| Error::Serialization(format!("Failed to read back keyword list: {}", e)))?;
+                                if cache.len() == CACHED_KEYWORD_LISTS {
+                                    cache.pop_front();
+                                }
+                                cache.push_back((chunk_number, keywords));
+                                &cache.back().expect("List pushed above").1
+                            }
+                        };
+                        keywords.binary_search_by(|candidate| candidate.as_str().cmp(keyword.as_ref()))
+                            .ok()
+                            .map(|position| KeywordLocation { chunk_number, position_in_chunk: position as u32 })
+                    }
+                };
+
+                *last_lookup = Some((keyword.clone(), location));
+                Ok(location)
+            }
+        }
+    }
+}
+
+/// Serializes the keyword list section of a chunk.
+fn serialize_keyword_list(keywords: &[Rc<str>]) -> Result<Vec<u8>, Error> {
+    let keywords: Vec<String> = keywords.iter().map(|keyword| keyword.to_string()).collect();
+    let bytes = to_bytes::<RkyvError>(&keywords)
+        .map_err(|e| Error::Serialization(format!("Failed to serialize keyword list: {}", e)))?;
+    Ok(bytes.to_vec())
+}
+
+/// Bloom filters of a build whose keywords were spilled, built while merging: the
+/// layout pass counts the distinct keywords of every column, so that the data pass can
+/// add them to filters of the right size.
+#[derive(Default)]
+struct SpilledFilters {
+    counts: StdHashMap<u32, usize>,
+    all: usize,
+    builders: StdHashMap<u32, ColumnFilterBuilder>,
+    global: Option<ColumnFilterBuilder>,
+}
+
+impl SpilledFilters {
+    /// Counts a keyword (layout pass).
+    fn count(&mut self, keyword_data: &KeywordOneFile) {
+        let mut in_any_column = false;
+        for &column_id in keyword_data.column_references.iter().filter(|&&column_id| column_id != 0) {
+            *self.counts.entry(column_id).or_default() += 1;
+            in_any_column = true;
+        }
+        if in_any_column {
+            self.all += 1;
+        }
+    }
+
+    /// Creates the filters for the counted keywords.
+    fn start(&mut self, column_pool: &ColumnPool, options: &BuildOptions, error_rate: f64) {
+        self.builders = self.counts.iter()
+            .filter_map(|(&column_id, &count)| {
+                let column_name = column_pool.get(column_id)?;
+                Some((column_id, ColumnFilterBuilder::new(count, options.column_error_rate(column_name, error_rate))))
+            })
+            .collect();
+        self.global = Some(ColumnFilterBuilder::new(self.all, error_rate));
+    }
+
+    /// Adds a keyword to the filters of its columns (data pass).
+    fn add(&mut self, keyword: &Rc<str>, keyword_data: &KeywordOneFile) {
+        let mut in_any_column = false;
+        for column_id in &keyword_data.column_references {
+            if let Some(builder) = self.builders.get_mut(column_id) {
+                builder.insert(keyword);
+                in_any_column = true;
+            }
+        }
+        if in_any_column && let Some(global) = &mut self.global {
+            global.insert(keyword);
+        }
+    }
+
+    /// Returns the filters by column name, and the filter of all keywords.
+    fn finish(self, column_pool: &ColumnPool, error_rate: f64) -> (StdHashMap<String, ColumnFilter>, ColumnFilter) {
+        let column_filters = self.builders.into_iter()
+            .filter_map(|(column_id, builder)| Some((column_pool.get(column_id)?.to_string(), builder.finish())))
+            .collect();
+        let global_filter = self.global.unwrap_or_else(|| ColumnFilterBuilder::new(0, error_rate)).finish();
+        (column_filters, global_filter)
+    }
+}
+
+/// Where `data.bin` is written while chunks are laid out.
+enum DataOutput {
+    Memory(Vec<u8>),
+    File {
+        file: TempFile,
+        writer: BufWriter<File>,
+        length: u64,
+    },
+}
+
+impl DataOutput {
+    /// Writes to memory, or to a temporary file in `spill_directory` if given.
+    fn new(spill_directory: Option<&Path>) -> Result<Self, Error> {
+        Ok(match spill_directory {
+            None => DataOutput::Memory(Vec::new()),
+            Some(directory) => {
+                let (file, handle) = TempFile::create(directory, "data")?;
+                DataOutput::File { file, writer: BufWriter::new(handle), length: 0 }
+            }
+        })
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            DataOutput::Memory(bytes) => bytes.len() as u64,
+            DataOutput::File { length, .. } => *length,
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        match self {
+            DataOutput::Memory(data) => data.extend_from_slice(bytes),
+            DataOutput::File { writer, length, .. } => {
+                writer.write_all(bytes)?;
+                *length += bytes.len() as u64;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<IndexData, Error> {
+        match self {
+            DataOutput::Memory(bytes) => Ok(IndexData::Memory(bytes)),
+            DataOutput::File { file, mut writer, length } => {
+                writer.flush()?;
+                Ok(IndexData::File { file, length })
+            }
+        }
+    }
 }
 
 /// Converts a KeywordOneFile to a flattened KeywordDataFlat structure for serialization.
@@ -124,8 +987,11 @@ struct KeywordLocation {
 /// # Arguments
 ///
 /// * `keyword_data` - Reference to the KeywordOneFile containing the hierarchical keyword data
-/// * `keyword_to_location` - HashMap mapping keyword strings to their chunk locations,
-///   used for converting parent keyword references to chunk+position pairs
+/// * `chunk_lists` - Keyword lists of the chunks, used for converting parent keyword
+///   references to chunk+position pairs
+/// * `coarse` - Store only the row groups, with their occurrence counts
+/// * `granularity` - With [`IndexGranularity::RowGroups`], each column stores a
+///   [`RowGroupBitmap`] instead of row groups
 ///
 /// # Returns
 ///
@@ -133,6 +999,8 @@ struct KeywordLocation {
 /// - Flattened column data with row groups and rows
 /// - The splits_matched bitmask from the original keyword data
 ///
+/// or an error if the keyword list of a parent cannot be read back.
+///
 /// # Notes
 ///
 /// - Skips column index 0 (the aggregate bucket) during iteration
@@ -140,10 +1008,35 @@ struct KeywordLocation {
 /// - Handles missing data gracefully (uses Option for parent tracking)
 fn convert_to_flat(
     keyword_data: &KeywordOneFile,
-    keyword_to_location: &HashMap<&str, KeywordLocation>,
-) -> KeywordDataFlat {
+    chunk_lists: &mut ChunkKeywordLists,
+    coarse: bool,
+    granularity: IndexGranularity,
+    page_tables: &[PageTable],
+) -> Result<KeywordDataFlat, Error> {
     let mut columns = Vec::new();
 
+    if granularity == IndexGranularity::RowGroups {
+        for ((&column_id, row_group_ids), rows) in keyword_data.column_references.iter()
+            .zip(&keyword_data.row_groups)
+            .zip(&keyword_data.row_group_to_rows) {
+            let occurrences = rows.iter()
+                .map(|rows| count_distinct_rows(row_ranges(rows)))
+                .sum::<u64>()
+                .min(u32::MAX as u64) as u32;
+            columns.push(ColumnDataFlat {
+                column_id,
+                row_groups: Vec::new(),
+                row_group_bitmap: Some(Box::new(RowGroupBitmap::new(row_group_ids.iter().copied(), occurrences))),
+            });
+        }
+
+        return Ok(KeywordDataFlat {
+            columns,
+            splits_matched: keyword_data.splits_matched,
+            coarse: true,
+        });
+    }
+
     // Iterate through columns (skip index 0 which is the aggregate)
     for (col_idx, &column_id) in keyword_data.column_references.iter().enumerate() {
         let mut row_groups = Vec::new();
@@ -152,17 +1045,33 @@ fn convert_to_flat(
         if let Some(rg_set) = keyword_data.row_groups.get(col_idx) {
             for (rg_idx, &row_group_id) in rg_set.iter().enumerate() {
                 let mut rows = Vec::new();
+                let mut occurrences = 0u32;
+                let mut pages = Vec::new();
+                let page_table = find_page_table(page_tables, column_id, row_group_id);
 
                 // Get rows for this row group
                 if let Some(row_data) = keyword_data.row_group_to_rows
                     .get(col_idx)
                     .and_then(|rgs| rgs.get(rg_idx))
                 {
+                    occurrences = count_distinct_rows(row_ranges(row_data)).min(u32::MAX as u64) as u32;
                     for row in row_data {
+                        if let Some(page_table) = page_table {
+                            let last_row = row.row + row.additional_rows as u32;
+                            let first_page = page_table.page_of(row.row).max(pages.last().copied().unwrap_or(0));
+                            pages.extend(first_page..=page_table.page_of(last_row));
+                            pages.dedup();
+                        }
+                        if coarse {
+                            continue;
+                        }
+
                         // Convert parent keyword string to chunk+position
-                        let (parent_chunk, parent_position) = row.parent_keyword
-                            .as_ref()
-                            .and_then(|parent_str| keyword_to_location.get(parent_str.as_ref()))
+                        let parent_location = match &row.parent_keyword {
+                            Some(parent) => chunk_lists.locate(parent)?,
+                            None => None,
+                        };
+                        let (parent_chunk, parent_position) = parent_location
                             .map(|loc| (Some(loc.chunk_number), Some(loc.position_in_chunk)))
                             .unwrap_or((None, None));
 
@@ -178,7 +1087,8 @@ fn convert_to_flat(
 
                 row_groups.push(RowGroupDataFlat {
                     row_group_id,
-                    rows,
+                    rows: FlatRow::encode(&rows),
+                    summary: (coarse || page_table.is_some()).then(|| Box::new(RowGroupSummary { occurrences, pages })),
                 });
             }
         }
@@ -186,13 +1096,277 @@ fn convert_to_flat(
         columns.push(ColumnDataFlat {
             column_id,
             row_groups,
+            row_group_bitmap: None,
         });
     }
 
-    KeywordDataFlat {
+    Ok(KeywordDataFlat {
         columns,
         splits_matched: keyword_data.splits_matched,
+        coarse,
+    })
+}
+
+/// Whether a keyword stores more row entries than the per-keyword detail limit allows.
+///
+/// Such keywords are written with row-group granularity only
+/// (see [`BuildOptions::keyword_detail_limit`]).
+fn exceeds_detail_limit(keyword_data: &KeywordOneFile, keyword_detail_limit: Option<usize>) -> bool {
+    keyword_detail_limit.is_some_and(|limit| {
+        keyword_data.row_group_to_rows.iter()
+            .flatten()
+            .map(|rows| rows.len())
+            .sum::<usize>() > limit
+    })
+}
+
+/// Counts the rows each column of a keyword occurs in.
+///
+/// Yields `(column_id, occurrences)` for every column reference, including the
+/// aggregate (column ID 0), which counts each row once across all columns.
+fn keyword_column_occurrences(keyword_data: &KeywordOneFile) -> impl Iterator<Item = (u32, u64)> + '_ {
+    keyword_data.column_references.iter()
+        .zip(&keyword_data.row_group_to_rows)
+        .map(|(&column_id, row_groups)| {
+            let occurrences = row_groups.iter()
+                .map(|rows| count_distinct_rows(row_ranges(rows)))
+                .sum();
+            (column_id, occurrences)
+        })
+}
+
+/// The inclusive row ranges of runs of rows.
+fn row_ranges(rows: &[Row]) -> impl Iterator<Item = (u32, u32)> + '_ {
+    rows.iter().map(|row| (row.row, row.row.saturating_add(row.additional_rows as u32)))
+}
+
+/// Counts the distinct rows covered by inclusive row ranges, given in any order.
+///
+/// The aggregate (column ID 0) receives the rows of every column in turn, so a row
+/// holding a keyword in several columns is in several of its ranges; it counts once.
+pub(crate) fn count_distinct_rows(ranges: impl IntoIterator<Item = (u32, u32)>) -> u64 {
+    let mut ranges: Vec<(u32, u32)> = ranges.into_iter().collect();
+    ranges.sort_unstable();
+
+    let mut rows = 0u64;
+    let mut covered_until: Option<u32> = None;
+    for (start, end) in ranges {
+        if covered_until.is_some_and(|until| end <= until) {
+            continue;
+        }
+        let start = covered_until.map_or(start, |until| start.max(until + 1));
+        rows += (end - start) as u64 + 1;
+        covered_until = Some(end);
     }
+    rows
+}
+
+/// Bounded collection of the most frequent keywords seen so far.
+///
+/// Keeps a min-heap of at most `limit` entries with the worst candidate (fewest
+/// occurrences, then alphabetically last) on top, so each insertion is O(log limit).
+struct TopKeywords {
+    limit: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<Rc<str>>)>>,
+}
+
+impl TopKeywords {
+    fn new(limit: usize) -> Self {
+        Self { limit, heap: BinaryHeap::with_capacity(limit + 1) }
+    }
+
+    fn push(&mut self, keyword: &Rc<str>, occurrences: u64) {
+        // Skip the Rc clone when the keyword would be evicted right away
+        if self.heap.len() == self.limit
+            && self.heap.peek().is_some_and(|Reverse((worst, Reverse(worst_keyword)))| {
+                (occurrences, Reverse(keyword)) <= (*worst, Reverse(worst_keyword))
+            }) {
+            return;
+        }
+
+        self.heap.push(Reverse((occurrences, Reverse(keyword.clone()))));
+        if self.heap.len() > self.limit {
+            self.heap.pop();
+        }
+    }
+
+    /// Returns the kept keywords ordered by descending occurrences, then keyword.
+    fn into_sorted(self) -> Vec<KeywordCount> {
+        // into_sorted_vec is ascending by Reverse, i.e. best entry first
+        self.heap.into_sorted_vec().into_iter()
+            .map(|Reverse((occurrences, Reverse(keyword)))| KeywordCount {
+                keyword: keyword.to_string(),
+                occurrences,
+            })
+            .collect()
+    }
+}
+
+/// Builds the per-column autocomplete tables stored in the filters file.
+///
+/// For every column (and the aggregate), keeps a bounded top-k of the most frequent
+/// keywords for each prefix of up to [`SUGGESTION_PREFIX_MAX_CHARS`] characters, so
+/// memory stays proportional to the number of distinct short prefixes rather than the
+/// number of keywords.
+#[derive(Default)]
+struct PrefixSuggestionsBuilder {
+    tables: StdHashMap<u32, HashMap<String, TopKeywords>>,
+}
+
+impl PrefixSuggestionsBuilder {
+    fn add(&mut self, keyword: &Rc<str>, keyword_data: &KeywordOneFile) {
+        let prefix_ends: Vec<usize> = std::iter::once(0)
+            .chain(keyword.char_indices().map(|(i, c)| i + c.len_utf8()))
+            .take(SUGGESTION_PREFIX_MAX_CHARS + 1)
+            .collect();
+
+        for (column_id, occurrences) in keyword_column_occurrences(keyword_data) {
+            let table = self.tables.entry(column_id).or_default();
+            for &end in &prefix_ends {
+                table.entry_ref(&keyword[..end])
+                    .or_insert_with(|| TopKeywords::new(SUGGESTION_TOP_K))
+                    .push(keyword, occurrences);
+            }
+        }
+    }
+
+    /// Returns one [`PrefixSuggestions`] per column, ordered by column ID.
+    fn finish(self) -> Vec<PrefixSuggestions> {
+        let mut suggestions: Vec<PrefixSuggestions> = self.tables.into_iter()
+            .map(|(column_id, table)| PrefixSuggestions {
+                column_id,
+                prefixes: table.into_iter()
+                    .map(|(prefix, top)| (prefix, top.into_sorted()))
+                    .collect(),
+            })
+            .collect();
+
+        suggestions.sort_by_key(|table| table.column_id);
+        suggestions
+    }
+}
+
+/// Builds the per-column statistics stored in the filters file.
+#[derive(Default)]
+struct ColumnStatisticsBuilder {
+    per_column: StdHashMap<u32, (u64, TopKeywords)>,
+}
+
+impl ColumnStatisticsBuilder {
+    fn add(&mut self, keyword: &Rc<str>, keyword_data: &KeywordOneFile) {
+        for (column_id, occurrences) in keyword_column_occurrences(keyword_data) {
+            if column_id == 0 {
+                continue;
+            }
+            let (distinct, top) = self.per_column.entry(column_id)
+                .or_insert_with(|| (0, TopKeywords::new(COLUMN_STATS_TOP_N)));
+            *distinct += 1;
+            top.push(keyword, occurrences);
+        }
+    }
+
+    /// Returns one [`ColumnStatistics`] per indexed column (excluding the aggregate),
+    /// ordered by column ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows_with_keywords` - Rows per column ID that produced at least one keyword,
+    ///   counted while processing the Parquet file
+    fn finish(self, rows_with_keywords: &HashMap<u32, u64>) -> Vec<ColumnStatistics> {
+        let mut statistics: Vec<ColumnStatistics> = self.per_column.into_iter()
+            .map(|(column_id, (distinct_keywords, top))| ColumnStatistics {
+                column_id,
+                distinct_keywords,
+                rows_with_keywords: rows_with_keywords.get(&column_id).copied().unwrap_or(0),
+                top_keywords: top.into_sorted(),
+            })
+            .collect();
+
+        statistics.sort_by_key(|stats| stats.column_id);
+        statistics
+    }
+}
+
+/// Builds the per-column keyword sketches stored in the filters file.
+struct ColumnSketchesBuilder {
+    /// Empty sketches cloned for every new column and row group
+    column_template: HyperLogLog,
+    row_group_template: Option<HyperLogLog>,
+    per_column: StdHashMap<u32, (HyperLogLog, StdHashMap<u32, HyperLogLog>)>,
+}
+
+impl ColumnSketchesBuilder {
+    /// Also builds one sketch per column and row group when `row_group_sketches` is set.
+    fn new(row_group_sketches: bool) -> Result<Self, Error> {
+        Ok(Self {
+            column_template: HyperLogLog::new(COLUMN_SKETCH_PRECISION)?,
+            row_group_template: row_group_sketches.then(|| HyperLogLog::new(ROW_GROUP_SKETCH_PRECISION)).transpose()?,
+            per_column: StdHashMap::new(),
+        })
+    }
+
+    fn add(&mut self, keyword: &Rc<str>, keyword_data: &KeywordOneFile) {
+        for (&column_id, row_group_ids) in keyword_data.column_references.iter().zip(&keyword_data.row_groups) {
+            let (sketch, row_groups) = self.per_column.entry(column_id)
+                .or_insert_with(|| (self.column_template.clone(), StdHashMap::new()));
+            sketch.insert(keyword);
+
+            if let Some(template) = &self.row_group_template {
+                for &row_group_id in row_group_ids {
+                    row_groups.entry(row_group_id)
+                        .or_insert_with(|| template.clone())
+                        .insert(keyword);
+                }
+            }
+        }
+    }
+
+    /// Returns one [`ColumnSketch`] per column (including the aggregate), ordered by column ID.
+    fn finish(self) -> Vec<ColumnSketch> {
+        let mut sketches: Vec<ColumnSketch> = self.per_column.into_iter()
+            .map(|(column_id, (sketch, row_groups))| {
+                let mut row_groups: Vec<RowGroupSketch> = row_groups.into_iter()
+                    .map(|(row_group_id, sketch)| RowGroupSketch { row_group_id, sketch })
+                    .collect();
+                row_groups.sort_by_key(|rg| rg.row_group_id);
+                ColumnSketch { column_id, sketch, row_groups }
+            })
+            .collect();
+
+        sketches.sort_by_key(|sketch| sketch.column_id);
+        sketches
+    }
+}
+
+/// Rough serialized size of a keyword's data, used to decide chunk boundaries.
+fn estimated_keyword_size(keyword_data: &KeywordOneFile, coarse: bool, granularity: IndexGranularity, pages: bool) -> usize {
+    // KeywordOneFile structure: column_references, row_groups, row_group_to_rows
+    let mut estimated_size = 100; // Base overhead per keyword
+
+    estimated_size += keyword_data.column_references.len() * 20; // Column references
+
+    if granularity == IndexGranularity::RowGroups {
+        // Bitmap words up to the last row group, plus the occurrence count
+        return estimated_size + keyword_data.row_groups.iter()
+            .map(|row_groups| 24 + row_groups.iter().max().map_or(0, |&last| (last as usize / 32 + 1) * 4))
+            .sum::<usize>();
+    }
+
+    // Estimate row group data: row_group_to_rows is Vec<Vec<Vec<Row>>>
+    // Outer Vec = columns, Middle Vec = row groups, Inner Vec = rows
+    for col_rgs in &keyword_data.row_group_to_rows {
+        for rg_rows in col_rgs {
+            estimated_size += 10; // Row group overhead
+            if !coarse {
+                estimated_size += rg_rows.len() * 8; // ~8 bytes per varint row entry
+            }
+            if pages {
+                estimated_size += rg_rows.len() * 4; // At most one page per row entry
+            }
+        }
+    }
+
+    estimated_size
 }
 
 /// Builds distributed index files from a ProcessResult.
@@ -200,7 +1374,8 @@ fn convert_to_flat(
 /// This function creates a 2-file distributed index structure from the processed keyword data.
 /// It performs several key operations:
 /// 1. Retrieves and stores Parquet metadata for validation
-/// 2. Sorts keywords deterministically for consistent layout
+/// 2. Sorts keywords deterministically for consistent layout, merging in any runs
+///    spilled under a memory budget (see [`crate::keyword_spill`])
 /// 3. Assigns keywords to chunks and creates keyword location mapping
 /// 4. For each chunk:
 ///    - Serializes keyword list (Vec<String>)
@@ -227,6 +1402,7 @@ fn convert_to_flat(
 /// * `result` - Reference to the ProcessResult containing keyword maps, filters, and column data
 /// * `parquet_path` - Path to the source Parquet file for metadata retrieval
 /// * `error_rate` - Bloom filter error rate used during index creation
+/// * `options` - Optional index structures to build (see [`BuildOptions`])
 ///
 /// # Returns
 ///
@@ -244,16 +1420,21 @@ fn convert_to_flat(
 /// # Performance Considerations
 ///
 /// - Keywords are sorted once for deterministic layout
-/// - Data is chunked (size defined by METADATA_CHUNK_SIZE) to enable efficient partial loading
+/// - Keywords are visited twice (layout, then data); spilled runs are streamed from disk
+///   on each visit, so only one keyword's occurrence data is materialized at a time
+/// - Data is chunked (size defined by [`BuildOptions::chunk_size_bytes`]) to enable efficient partial loading
 /// - Uses rkyv for zero-copy deserialization support
 /// - Parent keyword references converted to chunk+position pairs for efficient lookup
 /// - Keyword lists stored separately from data for lightweight parent resolution
+/// - When keywords were spilled, keyword lists and `data.bin` are written to temporary
+///   files and Bloom filters are built while merging, so no keyword strings are held
+///   beyond the first keyword of every chunk
 ///
 /// # Examples
 ///
 /// ```no_run
 /// # use keywords::index_data::build_distributed_index;
-/// use keywords::ParquetSource;
+/// use keywords::{BuildOptions, ParquetSource};
 /// use keywords::column_parquet_reader::process_parquet_file;
 /// use keywords::index_data::DistributedIndexFiles;
 /// # async fn example() -> () {
@@ -263,7 +1444,8 @@ fn convert_to_flat(
 ///     build_distributed_index(
 ///         &result,
 ///         &ParquetSource::from(parquet_bytes),
-///         0.01
+///         0.01,
+///         &BuildOptions::default()
 ///     ).await.unwrap();
 /// # }
 /// // index_files now contains filters and data ready to be written
@@ -272,9 +1454,17 @@ pub async fn build_distributed_index(
     result: &ProcessResult,
     source: &ParquetSource,
     error_rate: f64,
-) -> Result<DistributedIndexFiles, Box<dyn std::error::Error + Send + Sync>> {
-    // Get parquet metadata for validation and to cache metadata location
+    options: &BuildOptions,
+) -> Result<DistributedIndexFiles, Error> {
+    // Get parquet metadata for validation and to cache metadata location. Text sources
+    // have no Parquet metadata, only validation data.
     let (parquet_etag, parquet_size, parquet_last_modified, parquet_metadata_offset, parquet_metadata_length) = match source {
+        ParquetSource::Path(path) if result.text_layout.is_some() => {
+            let (store, obj_path) = get_object_store(path).await?;
+            let head = store.head(&obj_path).await?;
+            (head.e_tag.unwrap_or_else(|| "unknown".to_string()), head.size, head.last_modified.timestamp() as u64, 0, 0)
+        }
+        ParquetSource::Bytes(bytes) if result.text_layout.is_some() => ("".to_string(), bytes.len() as u64, 0, 0, 0),
         ParquetSource::Path(path) => {
             let (store, obj_path) = get_object_store(path).await?;
             let head = store.head(&obj_path).await?;
@@ -312,7 +1502,7 @@ pub async fn build_distributed_index(
 
             // Read the last 8 bytes to get footer length
             if file_size < 8 {
-                return Err("Parquet file too small".into());
+                return Err(parquet::errors::ParquetError::General("Parquet file too small".to_string()).into());
             }
 
             let footer_slice = &vec[(file_size - 8) as usize..];
@@ -330,152 +1520,202 @@ pub async fn build_distributed_index(
         }
     };
 
-    // Sort keywords for deterministic layout
+    let monitor = options.monitor();
+    monitor.check_cancelled()?;
+    monitor.report(|progress| progress.phase = BuildPhase::Chunking);
+
+    // Sort keywords for deterministic layout. Keywords in spilled runs are merged in
+    // sorted order on each pass, without loading them all.
     let mut sorted_keywords: Vec<_> = result.keyword_map.iter().collect();
     sorted_keywords.sort_by(|a, b| a.0.cmp(b.0));
 
+    // Builds that spilled keep keyword lists and data.bin in temporary files, and build
+    // the Bloom filters while merging, so that keyword strings are not all held at once
+    let spill_directory = (!result.spilled_runs.is_empty()).then(|| options.spill_directory());
+    let mut spilled_filters = spill_directory.is_some().then(SpilledFilters::default);
+
     // =========================================================================
-    // Pass 1: Determine chunk boundaries based on ~1MB serialized size
+    // Pass 1: Determine chunk boundaries based on ~1MB serialized size, store the
+    // keyword lists of the chunks, and collect the per-column summaries
     // =========================================================================
 
-    // First, we need to estimate sizes and determine chunk boundaries
-    let mut chunk_boundaries = Vec::new(); // Stores (start_idx, end_idx) for each chunk
-    let mut current_chunk_start = 0;
+    let chunk_size_bytes = options.chunk_size_bytes();
+    let mut chunk_lists = ChunkKeywordLists::new(spill_directory.as_deref())?;
+    let mut current_chunk: Vec<Rc<str>> = Vec::new(); // Keywords of the chunk being filled
     let mut current_chunk_estimated_size = 0;
 
-    for (idx, (_keyword, keyword_data)) in sorted_keywords.iter().enumerate() {
-        // Rough size estimation without full serialization
-        // KeywordOneFile structure: column_references, row_groups, row_group_to_rows
-        let mut estimated_size = 100; // Base overhead per keyword
-
-        estimated_size += keyword_data.column_references.len() * 20; // Column references
+    let mut prefix_suggestions = options.prefix_suggestions.then(PrefixSuggestionsBuilder::default);
+    let mut column_statistics = ColumnStatisticsBuilder::default();
+    let mut column_sketches = (options.column_sketches || options.row_group_sketches)
+        .then(|| ColumnSketchesBuilder::new(options.row_group_sketches))
+        .transpose()?;
+    let mut trigram_index = options.trigram_index.then(TrigramIndexBuilder::default);
 
-        // Estimate row group data: row_group_to_rows is Vec<Vec<Vec<Row>>>
-        // Outer Vec = columns, Middle Vec = row groups, Inner Vec = rows
-        for col_rgs in &keyword_data.row_group_to_rows {
-            for rg_rows in col_rgs {
-                estimated_size += 10; // Row group overhead
-                estimated_size += rg_rows.len() * 40; // ~40 bytes per row entry
-            }
-        }
+    for_each_sorted_keyword(&sorted_keywords, &result.spilled_runs, |keyword, keyword_data| {
+        // Rough size estimation without full serialization
+        let coarse = exceeds_detail_limit(keyword_data, options.keyword_detail_limit);
+        let estimated_size = estimated_keyword_size(keyword_data, coarse, options.granularity, !result.page_tables.is_empty());
 
         // If adding this keyword would exceed limit and we have at least one keyword, finalize chunk
-        if current_chunk_estimated_size + estimated_size > MAX_CHUNK_SIZE_BYTES && idx > current_chunk_start {
-            chunk_boundaries.push((current_chunk_start, idx));
-            current_chunk_start = idx;
+        if !current_chunk.is_empty() && current_chunk_estimated_size + estimated_size > chunk_size_bytes {
+            chunk_lists.push(std::mem::take(&mut current_chunk))?;
             current_chunk_estimated_size = 0;
         }
-
+        if current_chunk.is_empty() {
+            monitor.check_cancelled()?;
+            monitor.report(|progress| {
+                progress.index_chunks = chunk_lists.len() + 1;
+                progress.total_index_chunks = chunk_lists.len() + 1;
+            });
+        }
         current_chunk_estimated_size += estimated_size;
-    }
-
-    // Add final chunk
-    chunk_boundaries.push((current_chunk_start, sorted_keywords.len()));
-
-    println!("  Creating {} dynamic chunks (target: ~1MB per chunk)", chunk_boundaries.len());
+        current_chunk.push(keyword.clone());
 
-    // =========================================================================
-    // Pass 2: Build keyword → location mapping based on determined chunks
-    // =========================================================================
-
-    let keyword_to_location: HashMap<&str, KeywordLocation> = sorted_keywords.iter()
-        .enumerate()
-        .map(|(idx, (keyword, _))| {
-            // Find which chunk this keyword belongs to
-            let chunk_number = chunk_boundaries.iter()
-                .position(|(start, end)| idx >= *start && idx < *end)
-                .unwrap() as u16;
+        if let Some(prefix_suggestions) = &mut prefix_suggestions {
+            prefix_suggestions.add(keyword, keyword_data);
+        }
+        column_statistics.add(keyword, keyword_data);
+        if let Some(column_sketches) = &mut column_sketches {
+            column_sketches.add(keyword, keyword_data);
+        }
+        if let Some(trigram_index) = &mut trigram_index {
+            trigram_index.add(keyword)?;
+        }
+        if let Some(spilled_filters) = &mut spilled_filters {
+            spilled_filters.count(keyword_data);
+        }
+        Ok(())
+    })?;
 
-            // Position within that chunk
-            let chunk_start = chunk_boundaries[chunk_number as usize].0;
-            let position_in_chunk = (idx - chunk_start) as u16;
+    // The last chunk; an index without keywords still has one (empty) chunk
+    if !current_chunk.is_empty() || chunk_lists.len() == 0 {
+        chunk_lists.push(current_chunk)?;
+    }
 
-            (
-                keyword.as_ref(),
-                KeywordLocation { chunk_number, position_in_chunk }
-            )
-        })
-        .collect();
+    monitor.report(|progress| {
+        progress.phase = BuildPhase::Serializing;
+        progress.index_chunks = 0;
+        progress.total_index_chunks = chunk_lists.len();
+    });
 
     // =========================================================================
-    // Pass 3: Build data file with dynamically-sized chunks
+    // Pass 2: Build data file with dynamically-sized chunks
     // =========================================================================
 
-    let mut data_file = Vec::new();
+    let mut data_file = DataOutput::new(spill_directory.as_deref())?;
     let mut chunk_index = Vec::new();
+    let mut data_in_chunk = Vec::new();
+    if let Some(spilled_filters) = &mut spilled_filters {
+        spilled_filters.start(&result.column_pool, options, error_rate);
+    }
 
-    for (_chunk_idx, (start_idx, end_idx)) in chunk_boundaries.iter().enumerate() {
-        let chunk = &sorted_keywords[*start_idx..*end_idx];
-        let chunk_start_offset = data_file.len() as u64;
-        let chunk_start_keyword = chunk[0].0;
-
-        // Build keyword list for this chunk
-        let keywords_in_chunk: Vec<String> = chunk.iter()
-            .map(|(keyword, _)| keyword.to_string())
-            .collect();
-
-        // Build data list for this chunk
-        let mut data_in_chunk = Vec::new();
-
-        for (_keyword, keyword_data) in chunk {
-            // Convert to flat structure with parent chunk+position mapping
-            let flat_data = convert_to_flat(keyword_data, &keyword_to_location);
-            data_in_chunk.push(flat_data);
-        }
+    let mut write_chunk = |chunk_lists: &mut ChunkKeywordLists, data_in_chunk: &mut Vec<KeywordDataFlat>| -> Result<(), Error> {
+        monitor.check_cancelled()?;
+        let chunk_start_offset = data_file.len();
 
-        // Serialize keyword list section
-        let keyword_list_bytes = to_bytes::<RkyvError>(&keywords_in_chunk)
-            .map_err(|e| format!("Failed to serialize keyword list: {}", e))?;
+        // Keyword list section, as serialized in pass 1
+        let (start_keyword, keyword_list_bytes) = chunk_lists.serialized_list(chunk_index.len())?;
         let keyword_list_length = keyword_list_bytes.len() as u32;
-        data_file.extend_from_slice(&keyword_list_bytes);
+        data_file.write(&keyword_list_bytes)?;
 
         // Serialize data section
-        let data_bytes = to_bytes::<RkyvError>(&data_in_chunk)
-            .map_err(|e| format!("Failed to serialize chunk data: {}", e))?;
+        let data_bytes = to_bytes::<RkyvError>(&*data_in_chunk)
+            .map_err(|e| Error::Serialization(format!("Failed to serialize chunk data: {}", e)))?;
         let data_length = data_bytes.len() as u32;
-        data_file.extend_from_slice(&data_bytes);
-
-        let total_length = keyword_list_length + data_length;
-        let chunk_count = keywords_in_chunk.len() as u16;
+        data_file.write(&data_bytes)?;
+        let chunk_count = data_in_chunk.len() as u32;
+        data_in_chunk.clear();
 
         // Add to chunk index
         chunk_index.push(ChunkInfo {
-            start_keyword: chunk_start_keyword.to_string(),
+            start_keyword,
             offset: chunk_start_offset,
             keyword_list_length,
-            total_length,
+            total_length: keyword_list_length + data_length,
             count: chunk_count,
         });
+        monitor.report(|progress| progress.index_chunks = chunk_index.len());
+        Ok(())
+    };
+
+    let mut current_chunk = 0;
+    for_each_sorted_keyword(&sorted_keywords, &result.spilled_runs, |keyword, keyword_data| {
+        // Convert to flat structure with parent chunk+position mapping
+        let coarse = exceeds_detail_limit(keyword_data, options.keyword_detail_limit);
+        data_in_chunk.push(convert_to_flat(keyword_data, &mut chunk_lists, coarse, options.granularity, &result.page_tables)?);
+        if let Some(spilled_filters) = &mut spilled_filters {
+            spilled_filters.add(keyword, keyword_data);
+        }
+
+        if data_in_chunk.len() == chunk_lists.chunk_len(current_chunk) {
+            write_chunk(&mut chunk_lists, &mut data_in_chunk)?;
+            current_chunk += 1;
+        }
+        Ok(())
+    })?;
+
+    // The empty chunk of an index without keywords
+    if current_chunk < chunk_lists.len() {
+        write_chunk(&mut chunk_lists, &mut data_in_chunk)?;
     }
+    let data_file = data_file.finish()?;
 
     // Build filters file
-    let split_chars_vec: Vec<Vec<char>> = SPLIT_CHARS_INCLUSIVE.iter()
+    let split_chars_vec: Vec<Vec<char>> = SPLIT_CHARS_INCLUSIVE[..options.split_levels()].iter()
         .map(|&chars| chars.to_vec())
         .collect();
 
+    let (trigram_directory, trigrams_file) = match trigram_index {
+        Some(trigram_index) => trigram_index.finish()?,
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let (column_filters, global_filter) = match spilled_filters {
+        Some(spilled_filters) => spilled_filters.finish(&result.column_pool, error_rate),
+        None => (
+            result.column_filters.iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+            result.global_filter.clone(),
+        ),
+    };
+
     let index_filters = IndexFilters {
-        version: 1,
+        version: INDEX_FORMAT_VERSION,
         parquet_etag,
         parquet_size,
         parquet_last_modified,
         parquet_metadata_offset,
         parquet_metadata_length,
         error_rate,
+        chunk_size_bytes: options.chunk_size_bytes() as u64,
         split_chars_inclusive: split_chars_vec,
         column_pool: result.column_pool.clone(),
-        column_filters: result.column_filters.iter()
-            .map(|(k, v)| (k.to_string(), v.clone()))
-            .collect(),
-        global_filter: result.global_filter.clone(),
+        column_filters,
+        global_filter,
         chunk_index,
+        prefix_suggestions: prefix_suggestions.map(PrefixSuggestionsBuilder::finish).unwrap_or_default(),
+        column_statistics: column_statistics.finish(&result.column_rows_with_keywords),
+        column_sketches: column_sketches.map(ColumnSketchesBuilder::finish).unwrap_or_default(),
+        trigram_directory,
+        column_decisions: result.column_decisions.clone(),
+        granularity: options.granularity,
+        page_tables: result.page_tables.clone(),
+        text_layout: result.text_layout.clone(),
+        build_config: options.build_config.clone(),
     };
 
-    let filters_bytes = to_bytes::<RkyvError>(&index_filters)
-        .map_err(|e| format!("Failed to serialize filters: {}", e))?;
+    let archive = to_bytes::<RkyvError>(&index_filters)
+        .map_err(|e| Error::Serialization(format!("Failed to serialize filters: {}", e)))?;
+    let mut filters_bytes = Vec::with_capacity(FILTERS_MAGIC.len() + 4 + archive.len());
+    filters_bytes.extend_from_slice(FILTERS_MAGIC);
+    filters_bytes.extend_from_slice(&INDEX_FORMAT_VERSION.to_le_bytes());
+    filters_bytes.extend_from_slice(&archive);
 
     Ok(DistributedIndexFiles {
-        filters: filters_bytes.to_vec(),
+        filters: filters_bytes,
         data: data_file,
+        trigrams: trigrams_file,
     })
 }
 
@@ -484,9 +1724,82 @@ pub async fn build_distributed_index(
 /// Contains the two components of the 2-file index structure:
 /// - filters: Bloom filters, metadata, column pool, and chunk index
 /// - data: Chunked keyword lists and occurrence data
+///
+/// plus the optional trigram posting lists, empty unless
+/// [`BuildOptions::trigram_index`] was enabled.
 pub struct DistributedIndexFiles {
     pub filters: Vec<u8>,
-    pub data: Vec<u8>,
+    pub data: IndexData,
+    pub trigrams: Vec<u8>,
+}
+
+/// Contents of `data.bin`.
+///
+/// Builds whose keywords were spilled to disk (see [`BuildOptions::memory_budget`])
+/// write it to a temporary file in [`BuildOptions::spill_directory`] as the chunks are
+/// laid out, and [`save_distributed_index`] uploads it in parts, so that it is never
+/// held in memory. Other builds keep it in memory.
+#[derive(Debug)]
+pub enum IndexData {
+    Memory(Vec<u8>),
+    File {
+        file: TempFile,
+        length: u64,
+    },
+}
+
+impl Default for IndexData {
+    fn default() -> Self {
+        IndexData::Memory(Vec::new())
+    }
+}
+
+impl IndexData {
+    /// Size of `data.bin` in bytes.
+    pub fn len(&self) -> u64 {
+        match self {
+            IndexData::Memory(bytes) => bytes.len() as u64,
+            IndexData::File { length, .. } => *length,
+        }
+    }
+
+    /// Whether `data.bin` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the whole contents, reading them from the temporary file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the temporary file cannot be read.
+    pub fn to_vec(&self) -> Result<Vec<u8>, Error> {
+        match self {
+            IndexData::Memory(bytes) => Ok(bytes.clone()),
+            IndexData::File { file, .. } => Ok(std::fs::read(file.path())?),
+        }
+    }
+}
+
+/// Part size used to upload a `data.bin` held in a temporary file.
+const UPLOAD_PART_SIZE: usize = 10 * 1024 * 1024;
+
+/// Number of parts uploaded concurrently.
+const UPLOAD_CONCURRENCY: usize = 4;
+
+/// Contents of one index file to upload.
+enum UploadContents<'a> {
+    Bytes(&'a [u8]),
+    File(&'a Path, u64),
+}
+
+impl UploadContents<'_> {
+    fn len(&self) -> u64 {
+        match self {
+            UploadContents::Bytes(bytes) => bytes.len() as u64,
+            UploadContents::File(_, length) => *length,
+        }
+    }
 }
 
 /// Saves distributed index files to a directory structure.
@@ -499,7 +1812,8 @@ pub struct DistributedIndexFiles {
 /// ```text
 /// <base_path>.index/
 /// ├── filters.rkyv (or <prefix>_filters.rkyv)
-/// └── data.bin (or <prefix>_data.bin)
+/// ├── data.bin (or <prefix>_data.bin)
+/// └── trigrams.bin (or <prefix>_trigrams.bin, only if a trigram index was built)
 /// ```
 ///
 /// # Arguments
@@ -527,7 +1841,7 @@ pub struct DistributedIndexFiles {
 ///
 /// ```no_run
 /// # use keywords::index_data::save_distributed_index;
-/// use keywords::ParquetSource;
+/// use keywords::{BuildOptions, ParquetSource};
 /// use keywords::index_data::build_distributed_index;
 /// use keywords::column_parquet_reader::process_parquet_file;
 ///
@@ -536,7 +1850,7 @@ pub struct DistributedIndexFiles {
 ///     let parquet_bytes = vec![/* generated parquet data */];
 ///     let result = process_parquet_file(ParquetSource::from(parquet_bytes.clone()), None, None).await.unwrap();
 ///
-///     let index_files = build_distributed_index(&result, &ParquetSource::from(parquet_bytes), 0.01).await.unwrap();
+///     let index_files = build_distributed_index(&result, &ParquetSource::from(parquet_bytes), 0.01, &BuildOptions::default()).await.unwrap();
 ///
 ///     // Save without prefix (path can be arbitrary for in-memory sources)
 ///     save_distributed_index(&index_files, "my_data.parquet", None).await.unwrap();
@@ -555,21 +1869,78 @@ pub async fn save_distributed_index(
     files: &DistributedIndexFiles,
     base_path: &str,
     prefix: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    use bytes::Bytes;
-    use object_store::PutPayload;
+) -> Result<(), Error> {
+    save_distributed_index_with_options(files, base_path, prefix, &BuildOptions::default()).await
+}
 
-    // Build paths for index files
-    let filters_path = format!("{}.index/{}", base_path, index_filename(IndexFile::Filters, prefix));
-    let data_path = format!("{}.index/{}", base_path, index_filename(IndexFile::Data, prefix));
+/// Saves distributed index files like [`save_distributed_index`], reporting the bytes
+/// written to the progress callback of `options` and stopping before the next file when
+/// the build is cancelled.
+///
+/// # Errors
+///
+/// Same as [`save_distributed_index`], and returns an error if the build is cancelled;
+/// files written before are left in place.
+pub async fn save_distributed_index_with_options(
+    files: &DistributedIndexFiles,
+    base_path: &str,
+    prefix: Option<&str>,
+    options: &BuildOptions,
+) -> Result<(), Error> {
+    use bytes::Bytes;
+    use object_store::{PutPayload, WriteMultipart};
+    use tokio::io::AsyncReadExt;
 
-    // Write filters file using object store abstraction
-    let (store, filters_obj_path) = get_object_store(&filters_path).await?;
-    store.put(&filters_obj_path, PutPayload::from_bytes(Bytes::from(files.filters.clone()))).await?;
+    // The optional trigram file is only written if a trigram index was built
+    let data = match &files.data {
+        IndexData::Memory(bytes) => UploadContents::Bytes(bytes),
+        IndexData::File { file, length } => UploadContents::File(file.path(), *length),
+    };
+    let mut index_files = vec![(IndexFile::Filters, UploadContents::Bytes(&files.filters)), (IndexFile::Data, data)];
+    if !files.trigrams.is_empty() {
+        index_files.push((IndexFile::Trigrams, UploadContents::Bytes(&files.trigrams)));
+    }
 
-    // Write data file using object store abstraction
-    let (store, data_obj_path) = get_object_store(&data_path).await?;
-    store.put(&data_obj_path, PutPayload::from_bytes(Bytes::from(files.data.clone()))).await?;
+    let monitor = options.monitor();
+    monitor.report(|progress| {
+        progress.phase = BuildPhase::Uploading;
+        progress.bytes_uploaded = 0;
+        progress.total_upload_bytes = index_files.iter().map(|(_, contents)| contents.len()).sum();
+    });
+
+    // Write each file using object store abstraction
+    for (file, contents) in index_files {
+        monitor.check_cancelled()?;
+        let path = format!("{}.index/{}", base_path, index_filename(file, prefix));
+        let (store, obj_path) = get_object_store(&path).await?;
+
+        match contents {
+            UploadContents::Bytes(bytes) => {
+                store.put(&obj_path, PutPayload::from_bytes(Bytes::from(bytes.to_vec()))).await?;
+                monitor.report(|progress| progress.bytes_uploaded += bytes.len() as u64);
+            }
+            UploadContents::File(file_path, _) => {
+                // Stream the temporary file in parts instead of reading it into memory
+                let mut writer = WriteMultipart::new_with_chunk_size(store.put_multipart(&obj_path).await?, UPLOAD_PART_SIZE);
+                let mut reader = tokio::fs::File::open(file_path).await?;
+                let mut buffer = vec![0u8; UPLOAD_PART_SIZE];
+                loop {
+                    if let Err(error) = monitor.check_cancelled() {
+                        writer.abort().await?;
+                        return Err(error);
+                    }
+                    let read = reader.read(&mut buffer).await?;
+                    if read == 0 {
+                        break;
+                    }
+                    writer.wait_for_capacity(UPLOAD_CONCURRENCY).await?;
+                    writer.write(&buffer[..read]);
+                    monitor.report(|progress| progress.bytes_uploaded += read as u64);
+                }
+                writer.finish().await?;
+            }
+        }
+    }
 
     Ok(())
 }
\ No newline at end of file
@@ -9,6 +9,7 @@ use indexmap::IndexSet;
 use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, Archived};
 use rkyv::rancor::Error as RkyvError;
 use rkyv::util::AlignedVec;
+use crate::error::Error;
 
 
 /// Minimum number of keywords before considering Bloom filter.
@@ -103,6 +104,31 @@ impl ColumnFilter {
         }
     }
 
+    /// Checks that the filter's data can be read, as it comes from an index file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CorruptIndex`] if a Bloom filter has fewer bytes than bits, or
+    /// if the serialized HashSet cannot be deserialized.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self {
+            ColumnFilter::BloomFilter { data, num_bits, .. } => {
+                if *num_bits == 0 || *num_bits > data.len() as u64 * 8 {
+                    return Err(Error::CorruptIndex(format!(
+                        "Bloom filter of {} bits has {} bytes", num_bits, data.len()
+                    )));
+                }
+            }
+            ColumnFilter::RkyvHashSet(bytes) => {
+                let mut aligned_bytes = AlignedVec::<16>::new();
+                aligned_bytes.extend_from_slice(bytes);
+                rkyv::access::<Archived<Vec<String>>, RkyvError>(&aligned_bytes)
+                    .map_err(|e| Error::CorruptIndex(format!("Failed to deserialize keyword set: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Checks if a keyword might be in a Bloom filter.
     ///
     /// This method is public to the crate to allow access by integration tests.
@@ -149,11 +175,9 @@ impl ColumnFilter {
     ///
     /// # Returns
     ///
-    /// Returns `true` if the keyword is in the set, `false` otherwise.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the bytes cannot be deserialized, which indicates data corruption.
+    /// Returns `true` if the keyword is in the set, `false` otherwise. Bytes that cannot
+    /// be deserialized are rejected by [`validate`](Self::validate) when the index is
+    /// loaded; should they get here, every keyword might be in the set.
     ///
     /// # Performance
     ///
@@ -165,11 +189,11 @@ impl ColumnFilter {
         let mut aligned_bytes = AlignedVec::<16>::new();
         aligned_bytes.extend_from_slice(bytes);
 
-        // Deserialize with validation - panic on invalid data
-        let archived: &Archived<Vec<String>> = rkyv::access::<_, RkyvError>(&aligned_bytes)
-            .expect("Failed to deserialize rkyv HashSet - data corruption detected");
-
-        archived.iter().any(|k| k.as_str() == keyword)
+        // Deserialize with validation
+        match rkyv::access::<Archived<Vec<String>>, RkyvError>(&aligned_bytes) {
+            Ok(archived) => archived.iter().any(|k| k.as_str() == keyword),
+            Err(_) => true,
+        }
     }
 
     /// Hashes a keyword with a seed for Bloom filter operations.
@@ -370,6 +394,59 @@ impl ColumnFilter {
     }
 }
 
+/// Builds a [`ColumnFilter`] from keywords added one at a time, for a known number of
+/// distinct keywords.
+///
+/// Gives the same filter as [`ColumnFilter::create_column_filter`] without holding the
+/// keywords of a Bloom filter in memory, so that filters can be built while spilled
+/// keywords are merged.
+pub(crate) enum ColumnFilterBuilder {
+    HashSet(IndexSet<Rc<str>>),
+    BloomFilter {
+        data: Vec<u8>,
+        num_hashes: u32,
+        num_bits: u64,
+    },
+}
+
+impl ColumnFilterBuilder {
+    /// Creates a builder for `num_keywords` distinct keywords.
+    pub(crate) fn new(num_keywords: usize, error_rate: f64) -> ColumnFilterBuilder {
+        if num_keywords < MIN_KEYWORDS_FOR_BLOOM {
+            return ColumnFilterBuilder::HashSet(IndexSet::with_capacity(num_keywords));
+        }
+
+        let (num_bits, num_hashes) = ColumnFilter::calculate_bloom_params(num_keywords, error_rate);
+        ColumnFilterBuilder::BloomFilter {
+            data: vec![0u8; num_bits.div_ceil(8) as usize],
+            num_hashes,
+            num_bits,
+        }
+    }
+
+    /// Adds a keyword to the filter.
+    pub(crate) fn insert(&mut self, keyword: &Rc<str>) {
+        match self {
+            ColumnFilterBuilder::HashSet(keywords) => {
+                keywords.insert(keyword.clone());
+            }
+            ColumnFilterBuilder::BloomFilter { data, num_hashes, num_bits } => {
+                ColumnFilter::bloom_filter_insert(keyword, data, *num_hashes, *num_bits);
+            }
+        }
+    }
+
+    /// Returns the filter of the keywords added.
+    pub(crate) fn finish(self) -> ColumnFilter {
+        match self {
+            ColumnFilterBuilder::HashSet(keywords) => ColumnFilter::create_rkyv_hashset(&keywords),
+            ColumnFilterBuilder::BloomFilter { data, num_hashes, num_bits } => {
+                ColumnFilter::BloomFilter { data, num_hashes, num_bits }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,4 +549,36 @@ mod tests {
         assert!(large_filter.might_contain("keyword50"));
         assert!(small_filter.might_contain("k5"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_corrupt_filters() {
+        let filter = ColumnFilter::RkyvHashSet(vec![0xFF; 7]);
+        assert!(matches!(filter.validate(), Err(Error::CorruptIndex(_))));
+        // Reported as possibly present rather than panicking
+        assert!(filter.might_contain("rust"));
+
+        let filter = ColumnFilter::BloomFilter { data: vec![0; 2], num_hashes: 3, num_bits: 64 };
+        assert!(matches!(filter.validate(), Err(Error::CorruptIndex(_))));
+    }
+
+    #[test]
+    fn test_builder_matches_create_column_filter() {
+        for count in [20, 150] {
+            let keywords: IndexSet<Rc<str>> = (0..count).map(|i| Rc::from(format!("keyword{}", i))).collect();
+
+            let mut builder = ColumnFilterBuilder::new(keywords.len(), 0.01);
+            for keyword in &keywords {
+                builder.insert(keyword);
+            }
+
+            match (builder.finish(), ColumnFilter::create_column_filter(&keywords, 0.01)) {
+                (ColumnFilter::RkyvHashSet(built), ColumnFilter::RkyvHashSet(expected)) => assert_eq!(built, expected),
+                (
+                    ColumnFilter::BloomFilter { data, num_hashes, num_bits },
+                    ColumnFilter::BloomFilter { data: expected_data, num_hashes: expected_hashes, num_bits: expected_bits },
+                ) => assert_eq!((data, num_hashes, num_bits), (expected_data, expected_hashes, expected_bits)),
+                _ => panic!("Builder chose a different filter type for {} keywords", count),
+            }
+        }
+    }
+}
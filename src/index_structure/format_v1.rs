@@ -0,0 +1,203 @@
+//! Reading indexes of format version 1.
+//!
+//! Version 1 is the layout of the first released index format. It stored row group IDs,
+//! the keyword count of each chunk and parent keyword references (chunk number and
+//! position within the chunk) as `u16`, which limited indexes to 65,535 row groups,
+//! chunks and keywords per chunk, and had no header in `filters.rkyv`. Version 2 (see
+//! [`INDEX_FORMAT_VERSION`]) stores them as `u32` or varints, starts `filters.rkyv` with
+//! a version header and adds everything introduced since: prefix suggestions, column
+//! statistics and sketches, the trigram directory, column decisions, row group
+//! granularity, page tables, text sources and the build configuration.
+//!
+//! The types here mirror the version 1 layout of every archived structure that
+//! changed, so that version 1 files can still be read: filters are converted to the
+//! current [`IndexFilters`] once when the index is loaded, with the sections version 1
+//! did not have left empty, and chunks of `data.bin` are converted to the current
+//! layout as they are read. The keyword list of a chunk (`Vec<String>`), the column
+//! pool and the column filters kept their layout.
+
+use std::collections::HashMap as StdHashMap;
+use rkyv::{Archive, Archived, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, to_bytes};
+use rkyv::rancor::Error as RkyvError;
+use rkyv::util::AlignedVec;
+use crate::error::Error;
+use crate::index_data::{
+    ChunkInfo, ColumnDataFlat, FlatRow, IndexFilters, IndexGranularity, KeywordDataFlat, RowGroupDataFlat,
+    INDEX_FORMAT_VERSION,
+};
+use crate::index_structure::column_filter::ColumnFilter;
+use crate::utils::column_pool::ColumnPool;
+use crate::DEFAULT_CHUNK_SIZE_BYTES;
+
+/// [`IndexFilters`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug)]
+pub(crate) struct IndexFiltersV1 {
+    pub version: u32,
+    pub parquet_etag: String,
+    pub parquet_size: u64,
+    pub parquet_last_modified: u64,
+    pub parquet_metadata_offset: u64,
+    pub parquet_metadata_length: u64,
+    pub error_rate: f64,
+    pub split_chars_inclusive: Vec<Vec<char>>,
+    pub column_pool: ColumnPool,
+    pub column_filters: StdHashMap<String, ColumnFilter>,
+    pub global_filter: ColumnFilter,
+    pub chunk_index: Vec<ChunkInfoV1>,
+}
+
+/// [`ChunkInfo`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub(crate) struct ChunkInfoV1 {
+    pub start_keyword: String,
+    pub offset: u64,
+    pub keyword_list_length: u32,
+    pub total_length: u32,
+    pub count: u16,
+}
+
+/// [`KeywordDataFlat`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub(crate) struct KeywordDataFlatV1 {
+    pub columns: Vec<ColumnDataFlatV1>,
+    pub splits_matched: u16,
+}
+
+/// [`ColumnDataFlat`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub(crate) struct ColumnDataFlatV1 {
+    pub column_id: u32,
+    pub row_groups: Vec<RowGroupDataFlatV1>,
+}
+
+/// [`RowGroupDataFlat`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub(crate) struct RowGroupDataFlatV1 {
+    pub row_group_id: u16,
+    pub rows: Vec<FlatRowV1>,
+}
+
+/// [`FlatRow`] of a version 1 index.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub(crate) struct FlatRowV1 {
+    pub row: u32,
+    pub additional_rows: u32,
+    pub splits_matched: u16,
+    pub parent_chunk: Option<u16>,
+    pub parent_position: Option<u16>,
+}
+
+impl From<IndexFiltersV1> for IndexFilters {
+    fn from(filters: IndexFiltersV1) -> Self {
+        let mut column_pool = filters.column_pool;
+        column_pool.rebuild_lookup();
+
+        IndexFilters {
+            version: filters.version,
+            parquet_etag: filters.parquet_etag,
+            parquet_size: filters.parquet_size,
+            parquet_last_modified: filters.parquet_last_modified,
+            parquet_metadata_offset: filters.parquet_metadata_offset,
+            parquet_metadata_length: filters.parquet_metadata_length,
+            error_rate: filters.error_rate,
+            // Version 1 builds always used the default chunk size
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES as u64,
+            split_chars_inclusive: filters.split_chars_inclusive,
+            column_pool,
+            column_filters: filters.column_filters,
+            global_filter: filters.global_filter,
+            chunk_index: filters.chunk_index.into_iter()
+                .map(|chunk| ChunkInfo {
+                    start_keyword: chunk.start_keyword,
+                    offset: chunk.offset,
+                    keyword_list_length: chunk.keyword_list_length,
+                    total_length: chunk.total_length,
+                    count: chunk.count as u32,
+                })
+                .collect(),
+            prefix_suggestions: Vec::new(),
+            column_statistics: Vec::new(),
+            column_sketches: Vec::new(),
+            trigram_directory: Vec::new(),
+            column_decisions: Vec::new(),
+            granularity: IndexGranularity::Rows,
+            page_tables: Vec::new(),
+            text_layout: None,
+            build_config: None,
+        }
+    }
+}
+
+impl From<KeywordDataFlatV1> for KeywordDataFlat {
+    fn from(data: KeywordDataFlatV1) -> Self {
+        KeywordDataFlat {
+            columns: data.columns.into_iter()
+                .map(|column| ColumnDataFlat {
+                    column_id: column.column_id,
+                    row_groups: column.row_groups.into_iter()
+                        .map(|rg| RowGroupDataFlat {
+                            row_group_id: rg.row_group_id as u32,
+                            rows: FlatRow::encode(&rg.rows.into_iter()
+                                .map(|row| FlatRow {
+                                    row: row.row,
+                                    additional_rows: row.additional_rows,
+                                    splits_matched: row.splits_matched,
+                                    parent_chunk: row.parent_chunk.map(u32::from),
+                                    parent_position: row.parent_position.map(u32::from),
+                                })
+                                .collect::<Vec<_>>()),
+                            summary: None,
+                        })
+                        .collect(),
+                    row_group_bitmap: None,
+                })
+                .collect(),
+            splits_matched: data.splits_matched,
+            coarse: false,
+        }
+    }
+}
+
+/// Reads the filters file of a version 1 index.
+///
+/// # Errors
+///
+/// Returns [`Error::CorruptIndex`] if the bytes are not version 1 filters.
+pub(crate) fn read_filters(bytes: &[u8]) -> Result<IndexFilters, Error> {
+    let mut aligned_bytes = AlignedVec::<16>::new();
+    aligned_bytes.extend_from_slice(bytes);
+
+    let archived: &Archived<IndexFiltersV1> = rkyv::access(&aligned_bytes)
+        .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to access archived filters: {}", e)))?;
+    if archived.version.to_native() != 1 {
+        return Err(Error::CorruptIndex(format!(
+            "Unsupported index format version {} (this library reads versions 1 to {})",
+            archived.version.to_native(),
+            INDEX_FORMAT_VERSION
+        )));
+    }
+
+    let filters: IndexFiltersV1 = rkyv::deserialize::<_, RkyvError>(archived)
+        .map_err(|e| Error::CorruptIndex(format!("Failed to deserialize filters: {}", e)))?;
+    Ok(filters.into())
+}
+
+/// Converts the data section of a version 1 chunk (`Vec<KeywordDataFlatV1>`) to the
+/// current layout (`Vec<KeywordDataFlat>`).
+///
+/// # Errors
+///
+/// Returns [`Error::CorruptIndex`] if the bytes are not a version 1 data section.
+pub(crate) fn upgrade_chunk_data(bytes: &[u8]) -> Result<AlignedVec<16>, Error> {
+    let mut aligned_bytes = AlignedVec::<16>::new();
+    aligned_bytes.extend_from_slice(bytes);
+
+    let archived: &Archived<Vec<KeywordDataFlatV1>> = rkyv::access(&aligned_bytes)
+        .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize chunk data: {}", e)))?;
+    let data: Vec<KeywordDataFlatV1> = rkyv::deserialize::<_, RkyvError>(archived)
+        .map_err(|e| Error::CorruptIndex(format!("Failed to deserialize chunk data: {}", e)))?;
+
+    let data: Vec<KeywordDataFlat> = data.into_iter().map(KeywordDataFlat::from).collect();
+    to_bytes::<RkyvError>(&data)
+        .map_err(|e| Error::Serialization(format!("Failed to serialize chunk data: {}", e)))
+}
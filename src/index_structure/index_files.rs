@@ -10,7 +10,8 @@
 //! ```text
 //! data/records.parquet.index/
 //! ├── filters.rkyv          # Bloom filters, metadata, and chunk index
-//! └── data.bin              # Keyword data (keywords + occurrence data per chunk)
+//! ├── data.bin              # Keyword data (keywords + occurrence data per chunk)
+//! └── trigrams.bin          # Optional trigram posting lists for substring search
 //! ```
 //!
 //! # File Prefixes
@@ -33,6 +34,7 @@
 ///
 /// - **Filters**: Bloom filters, metadata, column pool, and chunk index for navigation
 /// - **Data**: Chunked keyword lists and occurrence data in binary format
+/// - **Trigrams**: Optional trigram posting lists for substring search
 ///
 /// # Examples
 ///
@@ -62,6 +64,13 @@ pub enum IndexFile {
     /// This structure allows reading just keyword strings for parent lookups
     /// or reading the full chunk for search operations.
     Data,
+
+    /// Trigram posting lists (trigrams.bin), only present when built with
+    /// [`crate::BuildOptions::trigram_index`].
+    ///
+    /// Contains one serialized `Vec<u32>` of keyword ids per trigram, located through
+    /// the trigram directory in filters.rkyv.
+    Trigrams,
 }
 
 impl IndexFile {
@@ -84,6 +93,7 @@ impl IndexFile {
         match self {
             IndexFile::Filters => "filters.rkyv",
             IndexFile::Data => "data.bin",
+            IndexFile::Trigrams => "trigrams.bin",
         }
     }
 }
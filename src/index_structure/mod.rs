@@ -1,2 +1,5 @@
 pub(crate) mod column_filter;
-pub(crate) mod index_files;
\ No newline at end of file
+pub(crate) mod format_v1;
+pub mod hyperloglog;
+pub(crate) mod index_files;
+pub mod trigram_index;
@@ -0,0 +1,195 @@
+//! Optional trigram side index for substring search.
+//!
+//! Keyword search only matches whole tokens. To find keywords containing an arbitrary
+//! substring, the index can optionally store, for every trigram (three consecutive
+//! characters) occurring in any keyword, the sorted list of keywords containing it.
+//!
+//! Keywords are identified by their ordinal across all chunks, a `u32` that is mapped
+//! back to a chunk and a position within it with the keyword counts of the chunk index.
+//! Because keywords are laid out in sorted order, ids are also sorted by keyword, and
+//! posting lists built in keyword order are sorted without extra work.
+//!
+//! # Storage
+//!
+//! ```text
+//! filters.rkyv   trigram_directory: Vec<TrigramPostings>  (sorted by trigram)
+//! trigrams.bin   [Vec<u32>][Vec<u32>]...                  (one rkyv list per trigram)
+//! ```
+//!
+//! The directory records where each trigram's posting list lives in `trigrams.bin`, so a
+//! substring search reads only the lists for the trigrams of the query.
+
+use std::collections::BTreeMap;
+use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, to_bytes};
+use rkyv::rancor::Error as RkyvError;
+use crate::error::Error;
+
+/// Number of characters per n-gram.
+pub const TRIGRAM_CHARS: usize = 3;
+
+/// Location of one trigram's posting list in `trigrams.bin`.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize, Debug, Clone)]
+pub struct TrigramPostings {
+    /// The trigram (exactly [`TRIGRAM_CHARS`] characters)
+    pub trigram: String,
+
+    /// Byte offset of the serialized `Vec<u32>` in trigrams.bin
+    pub offset: u64,
+
+    /// Length in bytes of the serialized list
+    pub length: u32,
+
+    /// Number of keywords containing the trigram
+    pub count: u32,
+}
+
+/// Splits a keyword id back into chunk number and position within the chunk.
+///
+/// # Arguments
+///
+/// * `id` - The keyword id (ordinal of the keyword across all chunks)
+/// * `chunk_starts` - Id of the first keyword of every chunk, ascending
+pub(crate) fn split_keyword_id(id: u32, chunk_starts: &[u32]) -> (u32, u32) {
+    let chunk = chunk_starts.partition_point(|&start| start <= id).saturating_sub(1);
+    (chunk as u32, id - chunk_starts.get(chunk).copied().unwrap_or(0))
+}
+
+/// Returns the distinct trigrams of a string, in order of first occurrence.
+///
+/// Strings shorter than [`TRIGRAM_CHARS`] characters have no trigrams.
+pub(crate) fn trigrams(text: &str) -> Vec<&str> {
+    let boundaries: Vec<usize> = text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect();
+
+    let mut result: Vec<&str> = Vec::new();
+    for window in boundaries.windows(TRIGRAM_CHARS + 1) {
+        let trigram = &text[window[0]..window[TRIGRAM_CHARS]];
+        if !result.contains(&trigram) {
+            result.push(trigram);
+        }
+    }
+    result
+}
+
+/// Builds the trigram directory and the contents of trigrams.bin from keywords added
+/// one at a time, so the keywords themselves need not be kept.
+#[derive(Default)]
+pub(crate) struct TrigramIndexBuilder {
+    postings: BTreeMap<Box<str>, Vec<u32>>,
+    keywords: u64,
+}
+
+impl TrigramIndexBuilder {
+    /// Adds the next keyword, in chunk order and sorted within each chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if there are more than `u32::MAX` keywords.
+    pub(crate) fn add(&mut self, keyword: &str) -> Result<(), Error> {
+        let id = u32::try_from(self.keywords)
+            .map_err(|_| Error::LimitExceeded(format!("Only {} keywords are supported in a trigram index", u32::MAX)))?;
+        self.keywords += 1;
+
+        for trigram in trigrams(keyword) {
+            match self.postings.get_mut(trigram) {
+                Some(ids) => ids.push(id),
+                None => {
+                    self.postings.insert(trigram.into(), vec![id]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the directory (sorted by trigram) and the concatenated serialized posting
+    /// lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a posting list cannot be serialized.
+    pub(crate) fn finish(self) -> Result<(Vec<TrigramPostings>, Vec<u8>), Error> {
+        let mut directory = Vec::with_capacity(self.postings.len());
+        let mut file = Vec::new();
+
+        for (trigram, ids) in self.postings {
+            let bytes = to_bytes::<RkyvError>(&ids)
+                .map_err(|e| Error::Serialization(format!("Failed to serialize trigram postings: {}", e)))?;
+            directory.push(TrigramPostings {
+                trigram: trigram.into(),
+                offset: file.len() as u64,
+                length: bytes.len() as u32,
+                count: ids.len() as u32,
+            });
+            file.extend_from_slice(&bytes);
+        }
+
+        Ok((directory, file))
+    }
+}
+
+/// Intersects two sorted id lists.
+pub(crate) fn intersect_sorted(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().min(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigrams() {
+        assert_eq!(trigrams("example"), vec!["exa", "xam", "amp", "mpl", "ple"]);
+        assert_eq!(trigrams("aaaa"), vec!["aaa"]);
+        assert!(trigrams("ab").is_empty());
+        assert_eq!(trigrams("čaše"), vec!["čaš", "aše"]);
+    }
+
+    #[test]
+    fn test_split_keyword_id() {
+        let chunk_starts = [0, 70_000, 70_002];
+        assert_eq!(split_keyword_id(0, &chunk_starts), (0, 0));
+        assert_eq!(split_keyword_id(69_999, &chunk_starts), (0, 69_999));
+        assert_eq!(split_keyword_id(70_001, &chunk_starts), (1, 1));
+        assert_eq!(split_keyword_id(70_002, &chunk_starts), (2, 0));
+    }
+
+    #[test]
+    fn test_intersect_sorted() {
+        assert_eq!(intersect_sorted(&[1, 3, 5, 7], &[2, 3, 4, 7, 9]), vec![3, 7]);
+        assert!(intersect_sorted(&[1, 2], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_build_trigram_index() {
+        let mut builder = TrigramIndexBuilder::default();
+        for keyword in ["example", "sample", "simple"] {
+            builder.add(keyword).unwrap();
+        }
+        let (directory, file) = builder.finish().unwrap();
+
+        assert!(directory.windows(2).all(|w| w[0].trigram < w[1].trigram));
+
+        let mpl = directory.iter().find(|p| p.trigram == "mpl").unwrap();
+        assert_eq!(mpl.count, 3);
+        let amp = directory.iter().find(|p| p.trigram == "amp").unwrap();
+        assert_eq!(amp.count, 2);
+
+        let end = (mpl.offset + mpl.length as u64) as usize;
+        assert!(end <= file.len());
+    }
+}
@@ -0,0 +1,268 @@
+//! Parquet writer that builds the keyword index of the file while writing it.
+//!
+//! Indexing a file after writing it reads all of its data back. [`IndexingArrowWriter`]
+//! wraps Parquet's `AsyncArrowWriter` (the async form of `ArrowWriter`) and hands every
+//! batch to a [`RecordBatchIndexer`] as well, so that on close only the footer of the
+//! written file is read back to complete the index.
+
+use std::collections::HashSet;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::async_writer::{AsyncArrowWriter, ParquetObjectWriter};
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::properties::{WriterProperties, DEFAULT_MAX_ROW_GROUP_SIZE};
+use crate::error::Error;
+use crate::index_data::save_distributed_index;
+use crate::record_batch_indexer::RecordBatchIndexer;
+use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::{BuildOptions, ParquetSource};
+
+/// Writes record batches to a Parquet file and saves its keyword index on close.
+///
+/// Batches are tokenized as they are written, following the row groups of the writer
+/// (both end after the `max_row_group_size` of the writer properties, or on
+/// [`flush`](Self::flush)). [`close`](Self::close) finishes the file and saves the
+/// index to its `.index` directory, with the validation data (size, ETag and
+/// modification time) and metadata location of the stored file, so it is ready for
+/// [`KeywordSearcher::load`](crate::searching::keyword_search::KeywordSearcher::load).
+///
+/// The file can be a local path, an `s3://` URL or a `memory://` path (see
+/// [`get_object_store`]); it is uploaded in parts as row groups are written.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use arrow::record_batch::RecordBatch;
+/// use keywords::BuildOptions;
+/// use keywords::indexing_writer::IndexingArrowWriter;
+///
+/// # async fn example(batches: Vec<RecordBatch>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let schema = batches[0].schema();
+/// let mut writer = IndexingArrowWriter::try_new(
+///     "s3://bucket/logs/2025-01-01.parquet", schema, None, None, None, &BuildOptions::default(),
+/// ).await?;
+/// for batch in &batches {
+///     writer.write(batch).await?;
+/// }
+/// writer.close(None).await?;
+/// // s3://bucket/logs/2025-01-01.parquet and its .index directory are now written
+/// # Ok(())
+/// # }
+/// ```
+pub struct IndexingArrowWriter {
+    parquet_path: String,
+    writer: AsyncArrowWriter<ParquetObjectWriter>,
+    indexer: RecordBatchIndexer,
+}
+
+impl IndexingArrowWriter {
+    /// Creates a writer for a Parquet file at `parquet_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `parquet_path` - Path of the Parquet file to write (local, `s3://` or `memory://`)
+    /// * `schema` - Schema of the record batches
+    /// * `props` - Properties of the Parquet writer (Parquet defaults if `None`)
+    /// * `exclude_columns` - Optional set of column names to skip during indexing
+    /// * `error_rate` - Optional bloom filter false positive rate (default: 0.01 = 1%)
+    /// * `options` - How the batches are indexed (see [`RecordBatchIndexer::try_new`])
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the object store cannot be created, or if the writer or the
+    /// indexer cannot be created for `schema` and `options`.
+    pub async fn try_new(
+        parquet_path: &str,
+        schema: SchemaRef,
+        props: Option<WriterProperties>,
+        exclude_columns: Option<HashSet<String>>,
+        error_rate: Option<f64>,
+        options: &BuildOptions,
+    ) -> Result<Self, Error> {
+        let max_row_group_size = props.as_ref()
+            .map_or(DEFAULT_MAX_ROW_GROUP_SIZE, |props| props.max_row_group_size());
+        let indexer = RecordBatchIndexer::try_new(schema.clone(), exclude_columns, error_rate, options)?
+            .with_max_row_group_size(max_row_group_size);
+
+        let (store, path) = get_object_store(parquet_path).await?;
+        let writer = AsyncArrowWriter::try_new(ParquetObjectWriter::new(store, path), schema, props)?;
+
+        Ok(IndexingArrowWriter {
+            parquet_path: parquet_path.to_string(),
+            writer,
+            indexer,
+        })
+    }
+
+    /// Writes a record batch, and tokenizes it with the row group it lands in.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the batch does not have the writer's schema, if it cannot be
+    /// written, or if its row group cannot be indexed.
+    pub async fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        self.indexer.write(batch)?;
+        self.writer.write(batch).await?;
+        Ok(())
+    }
+
+    /// Ends the row group in progress in both the file and the index.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the row group cannot be written or indexed.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.indexer.flush()?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Number of rows in the row group in progress.
+    pub fn in_progress_rows(&self) -> usize {
+        self.writer.in_progress_rows()
+    }
+
+    /// Number of bytes written to the file so far.
+    pub fn bytes_written(&self) -> usize {
+        self.writer.bytes_written()
+    }
+
+    /// Finishes the Parquet file, then builds and saves its index.
+    ///
+    /// The index is saved to `{parquet_path}.index/`, with `index_file_prefix` if given
+    /// (see [`save_distributed_index`]).
+    ///
+    /// # Returns
+    ///
+    /// The metadata of the written Parquet file.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be finished, or if the index cannot be built
+    /// or saved (see [`RecordBatchIndexer::build_index`]).
+    pub async fn close(
+        self,
+        index_file_prefix: Option<&str>,
+    ) -> Result<ParquetMetaData, Error> {
+        let metadata = self.writer.close().await?;
+
+        let source = ParquetSource::Path(self.parquet_path.clone());
+        let files = self.indexer.build_index(&source).await?;
+        save_distributed_index(&files, &self.parquet_path, index_file_prefix).await?;
+
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use futures::TryStreamExt;
+    use crate::build_index_in_memory;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    const ROWS_PER_GROUP: usize = 300;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("message", DataType::Utf8, false),
+            Field::new("host", DataType::Utf8, true),
+        ]))
+    }
+
+    fn batches() -> Vec<RecordBatch> {
+        (0..5)
+            .map(|batch| {
+                let rows = batch * 170..(batch + 1) * 170;
+                let message: Vec<String> = rows.clone().map(|row| format!("GET /items/{} status-{}", row, row % 4)).collect();
+                let host: Vec<Option<String>> = rows.map(|row| (row % 9 != 0).then(|| format!("web-{}", row % 5))).collect();
+                RecordBatch::try_new(schema(), vec![
+                    Arc::new(StringArray::from(message)),
+                    Arc::new(StringArray::from(host)),
+                ]).unwrap()
+            })
+            .collect()
+    }
+
+    fn props() -> WriterProperties {
+        WriterProperties::builder().set_max_row_group_size(ROWS_PER_GROUP).build()
+    }
+
+    async fn write(path: &str) -> ParquetMetaData {
+        let mut writer = IndexingArrowWriter::try_new(path, schema(), Some(props()), None, None, &BuildOptions::default())
+            .await
+            .unwrap();
+        for (index, batch) in batches().iter().enumerate() {
+            writer.write(batch).await.unwrap();
+            if index == 0 {
+                writer.flush().await.unwrap();
+            }
+        }
+        writer.close(None).await.unwrap()
+    }
+
+    async fn vocabulary(searcher: &KeywordSearcher) -> Vec<(String, Option<u64>)> {
+        searcher.vocabulary(VocabularyOptions { with_counts: true, ..Default::default() })
+            .map_ok(|entry| (entry.keyword, entry.total_occurrences))
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_writes_file_and_index() {
+        let path = "memory://indexing-writer.parquet";
+        let metadata = write(path).await;
+        let row_groups: Vec<i64> = metadata.row_groups().iter().map(|row_group| row_group.num_rows()).collect();
+        assert_eq!(row_groups, vec![170, 300, 300, 80]);
+
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        let source = ParquetSource::Path(path.to_string());
+        assert!(searcher.validate_index(&source).await.unwrap());
+
+        // Same index as reading the written file back
+        let (store, object_path) = get_object_store(path).await.unwrap();
+        let bytes = store.get(&object_path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(searcher.filters.parquet_size, bytes.len() as u64);
+        let expected = build_index_in_memory(ParquetSource::Bytes(bytes), None, None).await.unwrap();
+        assert_eq!(searcher.filters.parquet_metadata_offset, expected.filters.parquet_metadata_offset);
+        assert_eq!(searcher.filters.parquet_metadata_length, expected.filters.parquet_metadata_length);
+        assert_eq!(vocabulary(&searcher).await, vocabulary(&expected).await);
+
+        let result = searcher.search("400", Some("message"), true).await.unwrap();
+        let data = result.verified_matches.unwrap();
+        let row_group = &data.column_details[0].row_groups[0];
+        // Row 400 is the 230th row of the second row group (after the flush at 170)
+        assert_eq!((row_group.row_group_id, row_group.row_ranges[0].start_row), (1, 230));
+    }
+
+    #[tokio::test]
+    async fn test_local_file() {
+        let directory = std::env::temp_dir().join(format!("keywords-indexing-writer-{}", std::process::id()));
+        let path = directory.join("data.parquet");
+        let path = path.to_str().unwrap();
+        write(path).await;
+
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        assert!(searcher.validate_index(&ParquetSource::Path(path.to_string())).await.unwrap());
+        let result = searcher.search("web-3", Some("host"), true).await.unwrap();
+        assert!(result.found);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_schema_mismatch_rejected() {
+        let mut writer = IndexingArrowWriter::try_new(
+            "memory://indexing-writer-mismatch.parquet", schema(), None, None, None, &BuildOptions::default(),
+        ).await.unwrap();
+        let other_schema = Arc::new(Schema::new(vec![Field::new("message", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(other_schema.clone(), vec![Arc::new(StringArray::from(vec!["a"]))]).unwrap();
+        assert!(writer.write(&batch).await.is_err());
+        assert_eq!(writer.in_progress_rows(), 0);
+    }
+}
@@ -14,8 +14,22 @@ pub const SPLIT_CHARS_INCLUSIVE: &[&[char]] = &[
 ];
 pub const SPLIT_CHARS_COUNT: usize = SPLIT_CHARS_INCLUSIVE.len();
 
+/// How the values of a column are turned into keywords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tokenization {
+    /// Split values at the first `levels` levels of [`SPLIT_CHARS_INCLUSIVE`] (see
+    /// [`perform_split_levels`])
+    Split { levels: usize },
+    /// Add each value whole (see [`add_exact_value`])
+    ExactValues,
+}
+
 pub const ADDITIONAL_ROWS_CAP: u16 = u16::MAX - 1;
 
+/// Approximate heap and map overhead of one keyword entry in a keyword map, excluding
+/// the keyword bytes and rows (map slot, `Rc` header, bucket vectors).
+const KEYWORD_ENTRY_OVERHEAD: usize = 160;
+
 // Build lookup table once as a static
 static SPLIT_LOOKUP: [[bool; 128]; 4] = {
     let mut lookup = [[false; 128]; 4];
@@ -59,7 +73,7 @@ pub fn is_delimiter(c: char, level: usize) -> bool {
     c.is_ascii() && unsafe { *SPLIT_LOOKUP[level].get_unchecked(c as usize) }
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct Row {
     pub(crate) row: u32,
     pub(crate) additional_rows: u16,
@@ -157,13 +171,45 @@ impl Row {
             })
         }
     }
+
+    /// Appends a run of rows built separately (e.g. by another thread), merging it into
+    /// this row when possible.
+    ///
+    /// Follows the same rules as [`Row::add`]: a single-row run on the last row seen with the
+    /// same parent ORs its splits in, and a run starting right after this one with the same
+    /// split pattern and parent extends it, as long as the cap allows.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The run to append, starting at or after the last row of this run
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(Row)` if `next` could not be merged and must be pushed as a new row,
+    /// or `None` if it was merged into this row.
+    pub(crate) fn absorb(&mut self, next: Row) -> Option<Row> {
+        let previous_row_seen_number = self.row + self.additional_rows as u32;
+        if self.parent_keyword != next.parent_keyword {
+            Some(next)
+        } else if previous_row_seen_number == next.row && next.additional_rows == 0 {
+            self.splits_matched |= next.splits_matched;
+            None
+        } else if previous_row_seen_number + 1 == next.row
+            && self.splits_matched == next.splits_matched
+            && (self.additional_rows as u32 + next.additional_rows as u32) < ADDITIONAL_ROWS_CAP as u32 {
+            self.additional_rows += next.additional_rows + 1;
+            None
+        } else {
+            Some(next)
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct KeywordOneFile {
     pub(crate) splits_matched: u16,
     pub(crate) column_references: SmallVec<[u32; 2]>,  // Stack-allocated for ≤2 columns
-    pub(crate) row_groups: Vec<SmallVec<[u16; 4]>>,   // Adaptive storage based on file's row group count
+    pub(crate) row_groups: Vec<SmallVec<[u32; 4]>>,   // Adaptive storage based on file's row group count
     pub(crate) row_group_to_rows: Vec<Vec<Vec<Row>>>
 }
 
@@ -191,6 +237,113 @@ impl std::fmt::Display for KeywordOneFile {
 
 impl KeywordOneFile {
 
+    /// Creates an entry with no occurrences, holding only the global bucket (index 0).
+    fn empty() -> KeywordOneFile {
+        KeywordOneFile {
+            splits_matched: 0,
+            column_references: smallvec![0],
+            row_groups: vec![SmallVec::new()],
+            row_group_to_rows: vec![Vec::new()],
+        }
+    }
+
+    /// Adds a run of rows to a column and row group, and to the global bucket.
+    ///
+    /// Counterpart of [`add_keyword_details`](Self::add_keyword_details) for rows that were
+    /// already run-length encoded elsewhere. Runs for a column and row group must be added
+    /// in row order.
+    ///
+    /// # Arguments
+    ///
+    /// * `column_reference` - The column identifier from the column pool
+    /// * `row_group` - The row group number
+    /// * `run` - The run of rows to add
+    fn add_row_run(&mut self, column_reference: u32, row_group: u32, run: Row) {
+        self.splits_matched |= run.splits_matched;
+
+        let column_idx = match self.column_references.iter().position(|&c| c == column_reference) {
+            Some(idx) => idx,
+            None => {
+                self.column_references.push(column_reference);
+                self.row_groups.push(SmallVec::new());
+                self.row_group_to_rows.push(Vec::new());
+                self.column_references.len() - 1
+            }
+        };
+
+        for column_idx_u in [0, column_idx] {
+            let row_idx = match self.row_groups[column_idx_u].iter().position(|&c| c == row_group) {
+                Some(idx) => idx,
+                None => {
+                    self.row_groups[column_idx_u].push(row_group);
+                    self.row_group_to_rows[column_idx_u].push(Vec::new());
+                    self.row_groups[column_idx_u].len() - 1
+                }
+            };
+
+            let rows = &mut self.row_group_to_rows[column_idx_u][row_idx];
+            let new_row = match rows.last_mut() {
+                Some(previous_row) => previous_row.absorb(run.clone()),
+                None => Some(run.clone()),
+            };
+            if let Some(new_row_value) = new_row {
+                rows.push(new_row_value);
+            }
+        }
+    }
+
+    /// Appends the occurrences of the same keyword from a later part of the file.
+    ///
+    /// Used to merge spilled runs: every column and row group of `other` (including the
+    /// global bucket) is appended to the matching bucket of `self`, merging runs at the
+    /// junction with [`Row::absorb`]. `other` must hold rows that follow the rows of `self`
+    /// in processing order.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Occurrences of the same keyword, processed after those in `self`
+    pub(crate) fn append(&mut self, other: KeywordOneFile) {
+        self.splits_matched |= other.splits_matched;
+
+        let columns = other.column_references.into_iter()
+            .zip(other.row_groups)
+            .zip(other.row_group_to_rows);
+
+        for ((column_reference, row_groups), row_group_rows) in columns {
+            let column_idx = match self.column_references.iter().position(|&c| c == column_reference) {
+                Some(idx) => idx,
+                None => {
+                    self.column_references.push(column_reference);
+                    self.row_groups.push(SmallVec::new());
+                    self.row_group_to_rows.push(Vec::new());
+                    self.column_references.len() - 1
+                }
+            };
+
+            for (row_group, runs) in row_groups.into_iter().zip(row_group_rows) {
+                let row_idx = match self.row_groups[column_idx].iter().position(|&rg| rg == row_group) {
+                    Some(idx) => idx,
+                    None => {
+                        self.row_groups[column_idx].push(row_group);
+                        self.row_group_to_rows[column_idx].push(Vec::new());
+                        self.row_groups[column_idx].len() - 1
+                    }
+                };
+
+                let rows = &mut self.row_group_to_rows[column_idx][row_idx];
+                for run in runs {
+                    let new_row = match rows.last_mut() {
+                        Some(previous_row) => previous_row.absorb(run),
+                        None => Some(run),
+                    };
+                    if let Some(new_row_value) = new_row {
+                        rows.push(new_row_value);
+                    }
+                }
+            }
+        }
+    }
+
     /// Adds a row to a specific column and row group index.
     ///
     /// This method attempts to merge the row with the last row in the vector if possible,
@@ -244,7 +397,7 @@ impl KeywordOneFile {
     fn add_group(
         &mut self,
         column_idx: usize,
-        row_group: u16,
+        row_group: u32,
         row_number: u32,
         split_match_bit: u16,
         parent_keyword: &Option<Rc<str>>,
@@ -281,7 +434,7 @@ impl KeywordOneFile {
     fn add_keyword_details(
         &mut self,
         column_reference: u32,
-        row_group: u16,
+        row_group: u32,
         row_number: u32,
         split_match_bit: u16,
         parent_keyword: &Option<Rc<str>>,
@@ -322,7 +475,7 @@ impl KeywordOneFile {
 #[inline(always)]
 fn create_new_keyword_one_file(
     column_reference: u32,
-    row_group: u16,
+    row_group: u32,
     row_number: u32,
     split_match_bit: u16,
     parent_keyword: &Option<Rc<str>>
@@ -377,7 +530,7 @@ fn create_new_keyword_one_file(
 fn merge_or_add_keyword_return_rc(
     keyword_string: &str,
     column_reference: u32,
-    row_group: u16,
+    row_group: u32,
     row_number: u32,
     split_match_bit: u16,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
@@ -431,7 +584,7 @@ fn merge_or_add_keyword_return_rc(
 fn merge_or_add_keyword_no_return(
     keyword_string: &str,
     column_reference: u32,
-    row_group: u16,
+    row_group: u32,
     row_number: u32,
     split_match_bit: u16,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
@@ -483,6 +636,7 @@ fn merge_or_add_keyword_no_return(
 /// * `row_number` - The row number
 /// * `keyword_map` - Mutable reference to the keyword map
 /// * `split_level` - Current split-level (0-3)
+/// * `split_levels` - Number of split-levels applied; the last one adds the splits
 /// * `incomplete_split_match_bit_in` - Accumulated bitmask of splits matched so far
 /// * `parent_keyword` - Reference to the parent keyword in the split hierarchy
 // Optimized version: Use lookup table and optimized character iteration
@@ -490,10 +644,11 @@ fn merge_or_add_keyword_no_return(
 fn perform_split_inner(
     keyword_string: &str,
     column_reference: u32,
-    row_group: u16,
+    row_group: u32,
     row_number: u32,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
     split_level: usize,
+    split_levels: usize,
     incomplete_split_match_bit_in: u16,
     parent_keyword: &Option<Rc<str>>
 ) {
@@ -505,7 +660,7 @@ fn perform_split_inner(
         if split.len() == keyword_string.len() {
             let combined_match_bit: u16 = incomplete_split_match_bit_in | current_split_level;
             output_parent_decision_complete = true;
-            if split_level + 1 == SPLIT_CHARS_COUNT {
+            if split_level + 1 == split_levels {
                 merge_or_add_keyword_no_return(
                     keyword_string,
                     column_reference,
@@ -524,6 +679,7 @@ fn perform_split_inner(
                     row_number,
                     keyword_map,
                     split_level + 1,
+                    split_levels,
                     combined_match_bit,
                     parent_keyword
                 );
@@ -551,7 +707,7 @@ fn perform_split_inner(
                 parent_keyword
             };
 
-            if split_level + 1 == SPLIT_CHARS_COUNT {
+            if split_level + 1 == split_levels {
                 merge_or_add_keyword_no_return(
                     split,
                     column_reference,
@@ -569,6 +725,7 @@ fn perform_split_inner(
                     row_number,
                     keyword_map,
                     split_level + 1,
+                    split_levels,
                     current_split_level,
                     parent_to_use
                 );
@@ -618,10 +775,43 @@ fn perform_split_inner(
 pub fn perform_split(
     keyword_string: &str,
     column_reference: u32,  // From a column pool
-    row_group: u16,
+    row_group: u32,
     row_number: u32,
     keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>
 ) {
+    perform_split_levels(keyword_string, column_reference, row_group, row_number, keyword_map, SPLIT_CHARS_COUNT);
+}
+
+/// Performs hierarchical keyword splitting with only the first `split_levels` delimiter
+/// levels of [`SPLIT_CHARS_INCLUSIVE`].
+///
+/// Same as [`perform_split`], which applies every level; characters of the levels left
+/// out stay within keywords.
+///
+/// # Panics
+///
+/// Panics if `split_levels` is 0 or more than [`SPLIT_CHARS_COUNT`].
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::keyword_shred::perform_split_levels;
+/// use hashbrown::HashMap;
+/// let mut keyword_map = HashMap::new();
+/// perform_split_levels("hello-world next", 1, 0, 42, &mut keyword_map, 3);
+/// // The last level (`-` and `_`) is not applied, so "hello-world" is not split
+/// assert!(keyword_map.contains_key("hello-world"));
+/// assert!(!keyword_map.contains_key("hello"));
+/// ```
+pub fn perform_split_levels(
+    keyword_string: &str,
+    column_reference: u32,
+    row_group: u32,
+    row_number: u32,
+    keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+    split_levels: usize,
+) {
+    assert!((1..=SPLIT_CHARS_COUNT).contains(&split_levels), "split_levels must be between 1 and {}", SPLIT_CHARS_COUNT);
     perform_split_inner(
         keyword_string,
         column_reference,
@@ -629,11 +819,142 @@ pub fn perform_split(
         row_number,
         keyword_map,
         0,
+        split_levels,
         1,
         &None,  // No parent - this is the root/original string from parquet
     );
 }
 
+/// Split bits of a value that no delimiter level split: the root bit plus every level.
+const UNSPLIT_MATCH_BITS: u16 = (1 << (SPLIT_CHARS_COUNT + 1)) - 1;
+
+/// Adds a whole value from a Parquet file as a single keyword, without splitting it.
+///
+/// Used for columns indexed in exact-value mode, where splitting would only produce
+/// keywords unique to one row (e.g. UUIDs or hashes). The value is stored as if no
+/// delimiter had split it, so it is found by an exact keyword search for the whole value.
+///
+/// # Arguments
+///
+/// * `keyword_string` - The original string from the Parquet file
+/// * `column_reference` - The column identifier from the column pool
+/// * `row_group` - The row group number in the Parquet file
+/// * `row_number` - The row number in the Parquet file
+/// * `keyword_map` - Mutable reference to the keyword map where results are stored
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::keyword_shred::add_exact_value;
+/// use hashbrown::HashMap;
+/// let mut keyword_map = HashMap::new();
+/// add_exact_value("3f2b-41c8", 1, 0, 42, &mut keyword_map);
+/// assert_eq!(keyword_map.len(), 1);
+/// ```
+pub fn add_exact_value(
+    keyword_string: &str,
+    column_reference: u32,
+    row_group: u32,
+    row_number: u32,
+    keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>
+) {
+    merge_or_add_keyword_no_return(
+        keyword_string,
+        column_reference,
+        row_group,
+        row_number,
+        UNSPLIT_MATCH_BITS,
+        keyword_map,
+        &None,
+    );
+}
+
+/// The keywords a value splits into, for adding the same value to many rows.
+///
+/// Adding a `SplitValue` to a row adds exactly the entries [`perform_split_levels`] (or
+/// [`add_exact_value`]) would add for the value, so repeated values, such as the entries
+/// of a dictionary-encoded column chunk, only need to be split once.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SplitValue {
+    /// Keyword, split bits and parent keyword of each entry, in the order they are added
+    keywords: Vec<(Rc<str>, u16, Option<Rc<str>>)>,
+}
+
+impl SplitValue {
+    /// Splits a value as [`perform_split_levels`] does.
+    pub(crate) fn split(value: &str, split_levels: usize) -> Self {
+        let mut keyword_map = HashMap::new();
+        perform_split_levels(value, 1, 0, 0, &mut keyword_map, split_levels);
+
+        // Index 1 holds the value's column (index 0 is the global bucket). The order of
+        // entries only matters within a keyword, where it is kept
+        let keywords = keyword_map.into_iter()
+            .flat_map(|(keyword, keyword_one_file)| {
+                keyword_one_file.row_group_to_rows.into_iter()
+                    .nth(1)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(move |row| (Rc::clone(&keyword), row.splits_matched, row.parent_keyword))
+            })
+            .collect();
+        SplitValue { keywords }
+    }
+
+    /// Keeps a value whole, as [`add_exact_value`] does.
+    pub(crate) fn exact(value: &str) -> Self {
+        SplitValue { keywords: vec![(Rc::from(value), UNSPLIT_MATCH_BITS, None)] }
+    }
+
+    /// A value that adds no keywords (empty, or only top-level delimiters).
+    pub(crate) fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Adds the value's keywords to a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `column_reference` - The column identifier from the column pool
+    /// * `row_group` - The row group number in the Parquet file
+    /// * `row_number` - The row number in the Parquet file
+    /// * `keyword_map` - Mutable reference to the keyword map where results are stored
+    pub(crate) fn add_to_row(
+        &self,
+        column_reference: u32,
+        row_group: u32,
+        row_number: u32,
+        keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>
+    ) {
+        for (keyword, split_match_bit, parent_keyword) in &self.keywords {
+            match keyword_map.raw_entry_mut().from_key(keyword.as_ref()) {
+                RawEntryMut::Occupied(mut keyword_one_file_entry) => {
+                    keyword_one_file_entry.get_mut().add_keyword_details(
+                        column_reference,
+                        row_group,
+                        row_number,
+                        *split_match_bit,
+                        parent_keyword
+                    );
+                }
+                RawEntryMut::Vacant(keyword_one_file_entry) => {
+                    // Share the key with the parent references of other entries
+                    keyword_one_file_entry.insert(
+                        Rc::clone(keyword),
+                        create_new_keyword_one_file(
+                            column_reference,
+                            row_group,
+                            row_number,
+                            *split_match_bit,
+                            parent_keyword,
+                        )
+                    );
+                }
+            }
+        }
+    }
+}
+
 /// Builds a mapping of column names to the set of keywords found in each column.
 ///
 /// This function should be called once after all keyword splitting is complete. It
@@ -696,6 +1017,124 @@ pub fn build_column_keywords_map(
     column_keywords_map
 }
 
+/// Keywords extracted from a single column chunk, detached from `Rc` so they can be built
+/// on a worker thread and sent back for merging.
+///
+/// A column chunk covers one column of one row group, so every keyword holds a single
+/// list of rows. Parent keywords are stored as indexes into `keywords`.
+pub(crate) struct ChunkKeywords {
+    pub(crate) column_reference: u32,
+    pub(crate) row_group: u32,
+    keywords: Vec<ChunkKeyword>,
+}
+
+/// One keyword of a [`ChunkKeywords`] with its rows.
+struct ChunkKeyword {
+    keyword: Box<str>,
+    splits_matched: u16,
+    rows: Vec<ChunkRow>,
+}
+
+/// A [`Row`] whose parent keyword is an index into [`ChunkKeywords::keywords`].
+struct ChunkRow {
+    row: u32,
+    additional_rows: u16,
+    splits_matched: u16,
+    parent: Option<u32>,
+}
+
+impl ChunkKeywords {
+    /// Converts a keyword map holding a single column chunk.
+    ///
+    /// # Arguments
+    ///
+    /// * `keyword_map` - Keywords of the chunk, as built by [`perform_split`]
+    /// * `column_reference` - The column the chunk belongs to
+    /// * `row_group` - The row group the chunk belongs to
+    pub(crate) fn from_keyword_map(
+        keyword_map: HashMap<Rc<str>, KeywordOneFile>,
+        column_reference: u32,
+        row_group: u32,
+    ) -> ChunkKeywords {
+        let entries: Vec<(Rc<str>, KeywordOneFile)> = keyword_map.into_iter().collect();
+        let positions: HashMap<Rc<str>, u32> = entries.iter()
+            .enumerate()
+            .map(|(idx, (keyword, _))| (keyword.clone(), idx as u32))
+            .collect();
+
+        let keywords = entries.iter()
+            .map(|(keyword, keyword_file)| {
+                let rows = keyword_file.column_references.iter()
+                    .position(|&c| c == column_reference)
+                    .and_then(|column_idx| {
+                        let row_idx = keyword_file.row_groups[column_idx].iter().position(|&rg| rg == row_group)?;
+                        Some(&keyword_file.row_group_to_rows[column_idx][row_idx])
+                    })
+                    .map(|rows| rows.iter()
+                        .map(|row| ChunkRow {
+                            row: row.row,
+                            additional_rows: row.additional_rows,
+                            splits_matched: row.splits_matched,
+                            parent: row.parent_keyword.as_ref().map(|parent| positions[parent]),
+                        })
+                        .collect())
+                    .unwrap_or_default();
+
+                ChunkKeyword {
+                    keyword: Box::from(keyword.as_ref()),
+                    splits_matched: keyword_file.splits_matched,
+                    rows,
+                }
+            })
+            .collect();
+
+        ChunkKeywords { column_reference, row_group, keywords }
+    }
+
+    /// Rough number of bytes the chunk adds to a keyword map when merged.
+    ///
+    /// Counts every keyword as new, so it overestimates for keywords already in the map.
+    pub(crate) fn estimated_size(&self) -> usize {
+        self.keywords.iter()
+            .map(|chunk_keyword| chunk_keyword.keyword.len()
+                + KEYWORD_ENTRY_OVERHEAD
+                + chunk_keyword.rows.len() * 2 * std::mem::size_of::<Row>())
+            .sum()
+    }
+
+    /// Merges the chunk's keywords into the file-wide keyword map.
+    ///
+    /// Chunks must be merged in the order they would have been processed sequentially
+    /// (file order) so that rows within the global bucket keep the same order.
+    pub(crate) fn merge_into(self, keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>) {
+        // Intern every keyword first so parents can be resolved to the map's keys
+        let keys: Vec<Rc<str>> = self.keywords.iter()
+            .map(|chunk_keyword| match keyword_map.raw_entry_mut().from_key(chunk_keyword.keyword.as_ref()) {
+                RawEntryMut::Occupied(entry) => entry.key().clone(),
+                RawEntryMut::Vacant(entry) => {
+                    let key_rc: Rc<str> = Rc::from(chunk_keyword.keyword.as_ref());
+                    entry.insert(key_rc.clone(), KeywordOneFile::empty());
+                    key_rc
+                }
+            })
+            .collect();
+
+        for (chunk_keyword, key) in self.keywords.into_iter().zip(&keys) {
+            let keyword_file = keyword_map.get_mut(key).expect("Keyword interned above");
+            keyword_file.splits_matched |= chunk_keyword.splits_matched;
+
+            for row in chunk_keyword.rows {
+                keyword_file.add_row_run(self.column_reference, self.row_group, Row {
+                    row: row.row,
+                    additional_rows: row.additional_rows,
+                    splits_matched: row.splits_matched,
+                    parent_keyword: row.parent.map(|idx| keys[idx as usize].clone()),
+                });
+            }
+        }
+    }
+}
+
 // Link to test module (only compiled during tests)
 #[cfg(test)]
 #[path = "keyword_shred/tests/mod.rs"]
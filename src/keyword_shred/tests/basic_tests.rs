@@ -36,13 +36,76 @@ fn test_row_equality() {
     assert_eq!(row1, row2);
 }
 
+#[test]
+fn test_row_absorb() {
+    let run = |row: u32, additional_rows: u16, splits_matched: u16| Row {
+        row,
+        additional_rows,
+        splits_matched,
+        parent_keyword: None,
+    };
+
+    // Contiguous run with the same splits extends the row
+    let mut row = run(10, 2, 0b0010);
+    assert_eq!(row.absorb(run(13, 4, 0b0010)), None);
+    assert_eq!(row, run(10, 7, 0b0010));
+
+    // A single row on the last row seen merges its splits
+    assert_eq!(row.absorb(run(17, 0, 0b0100)), None);
+    assert_eq!(row, run(10, 7, 0b0110));
+
+    // Different splits, gaps or parents start a new row
+    assert_eq!(row.absorb(run(18, 0, 0b0010)), Some(run(18, 0, 0b0010)));
+    assert_eq!(row.absorb(run(20, 0, 0b0110)), Some(run(20, 0, 0b0110)));
+    let parented = Row { parent_keyword: Some(Rc::from("parent")), ..run(18, 0, 0b0110) };
+    assert_eq!(row.absorb(parented.clone()), Some(parented));
+
+    // Runs never grow past the cap
+    let mut row = run(0, ADDITIONAL_ROWS_CAP - 1, 0b0010);
+    assert_eq!(row.absorb(run(ADDITIONAL_ROWS_CAP as u32, 1, 0b0010)), Some(run(ADDITIONAL_ROWS_CAP as u32, 1, 0b0010)));
+}
+
+#[test]
+fn test_keyword_append_matches_single_map() {
+    let mut pool = ColumnPool::new();
+    let a = pool.intern("a");
+    let b = pool.intern("b");
+    let values = [(a, 0, 0, "x-y"), (b, 0, 0, "x"), (a, 0, 1, "x-y"), (a, 1, 0, "x"), (b, 1, 3, "x-y")];
+
+    let mut whole: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
+    for &(column, row_group, row, value) in &values {
+        perform_split(value, column, row_group, row, &mut whole);
+    }
+
+    // The same values split across two maps, the second appended to the first
+    let (mut first, mut second): (HashMap<Rc<str>, KeywordOneFile>, HashMap<Rc<str>, KeywordOneFile>) = Default::default();
+    for (idx, &(column, row_group, row, value)) in values.iter().enumerate() {
+        perform_split(value, column, row_group, row, if idx < 3 { &mut first } else { &mut second });
+    }
+    for (keyword, data) in second {
+        match first.get_mut(&keyword) {
+            Some(existing) => existing.append(data),
+            None => { first.insert(keyword, data); }
+        }
+    }
+
+    assert_eq!(first.len(), whole.len());
+    for (keyword, expected) in &whole {
+        let actual = &first[keyword];
+        assert_eq!(actual.splits_matched, expected.splits_matched, "{}", keyword);
+        assert_eq!(actual.column_references, expected.column_references, "{}", keyword);
+        assert_eq!(actual.row_groups, expected.row_groups, "{}", keyword);
+        assert_eq!(actual.row_group_to_rows, expected.row_group_to_rows, "{}", keyword);
+    }
+}
+
 #[test]
 fn test_simple_keyword_no_splits() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("simple").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("simple").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     assert_eq!(keyword_map.len(), 1);
     assert!(keyword_map.contains_key("simple"));
     let kw = keyword_map.get("simple").unwrap();
@@ -69,7 +132,7 @@ fn test_keyword_with_space_split() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("hello world").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("hello world").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 2);
     assert!(keyword_map.contains_key("hello"));
@@ -114,7 +177,7 @@ fn test_keyword_with_space_start() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from(" hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from(" hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     assert!(keyword_map.contains_key("hello"));
@@ -142,7 +205,7 @@ fn test_keyword_with_space_end() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("hello ").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("hello ").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     assert!(keyword_map.contains_key("hello"));
@@ -170,7 +233,7 @@ fn test_keyword_with_space_middle() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from(" hello ").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from(" hello ").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     assert!(keyword_map.contains_key("hello"));
@@ -198,8 +261,8 @@ fn test_row_add_consecutive_rows() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 1u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 1u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     let kw = keyword_map.get("Hello").unwrap();
@@ -225,9 +288,9 @@ fn test_row_add_non_consecutive_rows() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Goodbye").as_str(), col_ref, 0u16, 1u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 2u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Goodbye").as_str(), col_ref, 0u32, 1u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 2u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 2);
     let kw = keyword_map.get("Hello").unwrap();
@@ -259,9 +322,9 @@ fn test_row_add_multiple_consecutive_rows() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 1u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 2u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 1u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 2u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     let kw = keyword_map.get("Hello").unwrap();
@@ -287,10 +350,10 @@ fn test_row_add_interspersed_keywords() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Goodbye").as_str(), col_ref, 0u16, 1u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 2u32, &mut keyword_map);
-    perform_split(String::from("Goodbye").as_str(), col_ref, 0u16, 3u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Goodbye").as_str(), col_ref, 0u32, 1u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 2u32, &mut keyword_map);
+    perform_split(String::from("Goodbye").as_str(), col_ref, 0u32, 3u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 2);
     let kw = keyword_map.get("Hello").unwrap();
@@ -322,10 +385,10 @@ fn test_row_add_consecutive_interspersed_keywords() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 1u32, &mut keyword_map);
-    perform_split(String::from("Goodbye").as_str(), col_ref, 0u16, 2u32, &mut keyword_map);
-    perform_split(String::from("Goodbye").as_str(), col_ref, 0u16, 3u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 1u32, &mut keyword_map);
+    perform_split(String::from("Goodbye").as_str(), col_ref, 0u32, 2u32, &mut keyword_map);
+    perform_split(String::from("Goodbye").as_str(), col_ref, 0u32, 3u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 2);
     let kw = keyword_map.get("Hello").unwrap();
@@ -351,7 +414,7 @@ fn test_single_char_keywords_extracted() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("a bc d efg h").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("a bc d efg h").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
 
     println!("{:?}", keyword_map);
 
@@ -380,7 +443,7 @@ fn test_single_digit_extracted() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("1").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("1").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
 
     println!("{:?}", keyword_map);
 
@@ -850,4 +913,59 @@ fn test_deeply_nested_delimiters() {
     assert!(keyword_map.contains_key("f"));
 
     println!("Deeply nested test passed!");
-}
\ No newline at end of file
+}
+#[test]
+fn test_split_value_matches_perform_split() {
+    let values = ["hello-world", "a-b a", "x.y/z x", "  ", "--", "same same", "user@example.com/path?q=1"];
+    let mut expected = HashMap::new();
+    let mut actual = HashMap::new();
+    let splits: Vec<SplitValue> = values.iter().map(|value| SplitValue::split(value, SPLIT_CHARS_COUNT)).collect();
+
+    // Repeat values over consecutive rows so runs are extended as well as started
+    for row in 0..40u32 {
+        let index = (row as usize / 3) % values.len();
+        perform_split(values[index], 1, 0, row, &mut expected);
+        splits[index].add_to_row(1, 0, row, &mut actual);
+    }
+
+    assert!(splits[3].is_empty());
+    assert_eq!(actual.len(), expected.len());
+    for (keyword, expected) in &expected {
+        let actual = &actual[keyword];
+        assert_eq!(actual.splits_matched, expected.splits_matched, "splits of {}", keyword);
+        assert_eq!(actual.row_group_to_rows, expected.row_group_to_rows, "rows of {}", keyword);
+    }
+}
+
+#[test]
+fn test_split_levels() {
+    let value = "GET /api/v1.2 user-agent";
+    let keywords = |split_levels: usize| {
+        let mut keyword_map = HashMap::new();
+        perform_split_levels(value, 1, 0, 0, &mut keyword_map, split_levels);
+        let mut keywords: Vec<String> = keyword_map.keys().map(|keyword| keyword.to_string()).collect();
+        keywords.sort();
+        keywords
+    };
+
+    assert_eq!(keywords(1), vec!["/api/v1.2", "GET", "user-agent"]);
+    assert_eq!(keywords(2), vec!["/api/v1.2", "GET", "api", "user-agent", "v1.2"]);
+    assert_eq!(keywords(3), vec!["/api/v1.2", "2", "GET", "api", "user-agent", "v1", "v1.2"]);
+
+    let mut all = HashMap::new();
+    perform_split(value, 1, 0, 0, &mut all);
+    assert_eq!(keywords(SPLIT_CHARS_COUNT).len(), all.len());
+    assert!(all.contains_key("agent"));
+
+    // Split values add the same entries at every level
+    for split_levels in 1..=SPLIT_CHARS_COUNT {
+        let mut expected = HashMap::new();
+        let mut actual = HashMap::new();
+        perform_split_levels(value, 1, 0, 0, &mut expected, split_levels);
+        SplitValue::split(value, split_levels).add_to_row(1, 0, 0, &mut actual);
+        assert_eq!(actual.len(), expected.len());
+        for (keyword, expected) in &expected {
+            assert_eq!(actual[keyword].row_group_to_rows, expected.row_group_to_rows, "rows of {}", keyword);
+        }
+    }
+}
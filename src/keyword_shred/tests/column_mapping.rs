@@ -5,7 +5,7 @@ fn test_column_keywords_map_single_value() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
 
-    perform_split("hello".into(), column_pool.intern("col1"), 0u16, 0u32, &mut keyword_map);
+    perform_split("hello".into(), column_pool.intern("col1"), 0u32, 0u32, &mut keyword_map);
 
     let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
 
@@ -22,7 +22,7 @@ fn test_column_keywords_map_split_values() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
 
-    perform_split("hello world".into(), column_pool.intern("col1"), 0u16, 0u32, &mut keyword_map);
+    perform_split("hello world".into(), column_pool.intern("col1"), 0u32, 0u32, &mut keyword_map);
 
     let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
 
@@ -38,9 +38,9 @@ fn test_column_keywords_map_multiple_columns() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
 
-    perform_split("apple".into(), column_pool.intern("col1"), 0u16, 0u32, &mut keyword_map);
-    perform_split("banana orange".into(), column_pool.intern("col2"), 0u16, 0u32, &mut keyword_map);
-    perform_split("apple".into(), column_pool.intern("col3"), 0u16, 0u32, &mut keyword_map);
+    perform_split("apple".into(), column_pool.intern("col1"), 0u32, 0u32, &mut keyword_map);
+    perform_split("banana orange".into(), column_pool.intern("col2"), 0u32, 0u32, &mut keyword_map);
+    perform_split("apple".into(), column_pool.intern("col3"), 0u32, 0u32, &mut keyword_map);
 
     let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
 
@@ -66,7 +66,7 @@ fn test_column_keywords_map_complex_splitting() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
 
-    perform_split("path/to/file.txt".into(), column_pool.intern("filepath"), 0u16, 0u32, &mut keyword_map);
+    perform_split("path/to/file.txt".into(), column_pool.intern("filepath"), 0u32, 0u32, &mut keyword_map);
 
     let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
 
@@ -84,7 +84,7 @@ fn test_column_keywords_map_single_chars() {
     let mut keyword_map: HashMap<Rc<str>, KeywordOneFile> = HashMap::new();
     let mut column_pool = ColumnPool::new();
 
-    perform_split("a bc d efg h".into(), column_pool.intern("mixed"), 0u16, 0u32, &mut keyword_map);
+    perform_split("a bc d efg h".into(), column_pool.intern("mixed"), 0u32, 0u32, &mut keyword_map);
 
     let column_keywords_map = build_column_keywords_map(&keyword_map, &column_pool);
 
@@ -116,13 +116,13 @@ fn test_max_row_group() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    // Test with u16::MAX
-    perform_split("keyword", col_ref, u16::MAX, 0, &mut keyword_map);
+    // Test with u32::MAX
+    perform_split("keyword", col_ref, u32::MAX, 0, &mut keyword_map);
 
     let kw = keyword_map.get("keyword").unwrap();
     assert!(
-        kw.row_groups[0].contains(&u16::MAX),
-        "Should handle u16::MAX row group"
+        kw.row_groups[0].contains(&u32::MAX),
+        "Should handle u32::MAX row group"
     );
 }
 
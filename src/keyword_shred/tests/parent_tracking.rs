@@ -6,8 +6,8 @@ fn test_row_add_same_row_same_parent() {
     let mut column_pool = ColumnPool::new();
     let col_ref = column_pool.intern("test_col");
 
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
-    perform_split(String::from("Hello").as_str(), col_ref, 0u16, 0u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
+    perform_split(String::from("Hello").as_str(), col_ref, 0u32, 0u32, &mut keyword_map);
     println!("{:?}", keyword_map);
     assert_eq!(keyword_map.len(), 1);
     let kw = keyword_map.get("Hello").unwrap();
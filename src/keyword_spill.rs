@@ -0,0 +1,453 @@
+//! Bounded-memory indexing: spilling partial keyword maps to disk and merging them back.
+//!
+//! Without a memory budget, the whole keyword map (every keyword with all of its rows) is
+//! held in memory until [`build_distributed_index`](crate::index_data::build_distributed_index)
+//! lays it out into chunks. With [`BuildOptions::memory_budget`](crate::BuildOptions::memory_budget)
+//! set, the keyword map is flushed whenever its estimated size exceeds the budget:
+//!
+//! ```text
+//! column chunks ──▶ keyword_map ──(budget exceeded)──▶ run 0: sorted keywords ─┐
+//!                       │       ──(budget exceeded)──▶ run 1: sorted keywords ─┤ k-way
+//!                       └──────── (end of file) ────── remaining keywords ─────┤ merge ──▶ chunks
+//!                                                                              ┘
+//! ```
+//!
+//! Each run is a temporary file holding keywords in sorted order, one length-prefixed rkyv
+//! record per keyword, with parent keywords stored as strings. Runs are written in file
+//! order, so when the same keyword appears in several runs its occurrences are appended
+//! run by run, exactly as if the map had never been flushed.
+//!
+//! Keyword strings are not kept either: once runs exist,
+//! [`build_distributed_index`](crate::index_data::build_distributed_index) builds the
+//! Bloom filters while merging, keeps the keyword lists of the chunks in a [`TempFile`]
+//! to resolve parent keywords, and writes `data.bin` to another one.
+//!
+//! Builds with a [checkpoint](crate::checkpoint) write their runs to the checkpoint
+//! directory instead, numbered in order and kept until the checkpoint is removed, and also
+//! flush the keyword map whenever a checkpoint is taken.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use hashbrown::HashMap;
+use rkyv::{Archive, Serialize as RkyvSerialize, Deserialize as RkyvDeserialize, to_bytes};
+use rkyv::rancor::Error as RkyvError;
+use rkyv::util::AlignedVec;
+use smallvec::SmallVec;
+use crate::error::Error;
+use crate::keyword_shred::{KeywordOneFile, Row};
+
+/// A keyword with its occurrence data, as read back from a run.
+type SpilledEntry = (Rc<str>, KeywordOneFile);
+/// A keyword from a merge source, borrowed when it is still in memory.
+type MergeEntry<'a> = (Rc<str>, Cow<'a, KeywordOneFile>);
+
+/// Counter making run and temporary file names unique within the process.
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A [`KeywordOneFile`] as stored in a run file.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize)]
+struct SpilledKeyword {
+    keyword: String,
+    splits_matched: u16,
+    column_references: Vec<u32>,
+    row_groups: Vec<Vec<u32>>,
+    row_group_to_rows: Vec<Vec<Vec<SpilledRow>>>,
+}
+
+/// A [`Row`] as stored in a run file, with the parent keyword as a string.
+#[derive(Archive, RkyvSerialize, RkyvDeserialize)]
+struct SpilledRow {
+    row: u32,
+    additional_rows: u16,
+    splits_matched: u16,
+    parent_keyword: Option<String>,
+}
+
+impl SpilledKeyword {
+    fn new(keyword: &str, keyword_data: &KeywordOneFile) -> SpilledKeyword {
+        SpilledKeyword {
+            keyword: keyword.to_string(),
+            splits_matched: keyword_data.splits_matched,
+            column_references: keyword_data.column_references.to_vec(),
+            row_groups: keyword_data.row_groups.iter().map(|rgs| rgs.to_vec()).collect(),
+            row_group_to_rows: keyword_data.row_group_to_rows.iter()
+                .map(|rgs| rgs.iter()
+                    .map(|rows| rows.iter()
+                        .map(|row| SpilledRow {
+                            row: row.row,
+                            additional_rows: row.additional_rows,
+                            splits_matched: row.splits_matched,
+                            parent_keyword: row.parent_keyword.as_ref().map(|parent| parent.to_string()),
+                        })
+                        .collect())
+                    .collect())
+                .collect(),
+        }
+    }
+
+    fn into_keyword_data(self) -> SpilledEntry {
+        let keyword_data = KeywordOneFile {
+            splits_matched: self.splits_matched,
+            column_references: SmallVec::from_vec(self.column_references),
+            row_groups: self.row_groups.into_iter().map(SmallVec::from_vec).collect(),
+            row_group_to_rows: self.row_group_to_rows.into_iter()
+                .map(|rgs| rgs.into_iter()
+                    .map(|rows| rows.into_iter()
+                        .map(|row| Row {
+                            row: row.row,
+                            additional_rows: row.additional_rows,
+                            splits_matched: row.splits_matched,
+                            parent_keyword: row.parent_keyword.map(Rc::from),
+                        })
+                        .collect())
+                    .collect())
+                .collect(),
+        };
+        (Rc::from(self.keyword), keyword_data)
+    }
+}
+
+/// A sorted run of keywords flushed to a temporary file.
+///
+/// The file is deleted when the run is dropped, unless it belongs to a checkpoint.
+#[derive(Debug)]
+pub struct SpillRun {
+    path: PathBuf,
+    keywords: usize,
+    persistent: bool,
+}
+
+impl SpillRun {
+    /// Number of keywords in the run.
+    pub fn len(&self) -> usize {
+        self.keywords
+    }
+
+    /// Whether the run holds no keywords.
+    pub fn is_empty(&self) -> bool {
+        self.keywords == 0
+    }
+
+    /// Path of the run file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the keywords of a map to a new run file at `path` in sorted order.
+    fn write(
+        path: PathBuf,
+        persistent: bool,
+        keyword_map: &HashMap<Rc<str>, KeywordOneFile>,
+    ) -> Result<SpillRun, Error> {
+        let mut sorted_keywords: Vec<_> = keyword_map.iter().collect();
+        sorted_keywords.sort_by(|a, b| a.0.cmp(b.0));
+
+        // Create the run before writing so a failed write still removes the file
+        let mut run = SpillRun { path, keywords: sorted_keywords.len(), persistent: false };
+        let mut writer = BufWriter::new(File::create(&run.path)?);
+
+        for (keyword, keyword_data) in sorted_keywords {
+            let bytes = to_bytes::<RkyvError>(&SpilledKeyword::new(keyword, keyword_data))
+                .map_err(|e| Error::Serialization(format!("Failed to serialize spilled keyword: {}", e)))?;
+            writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            writer.write_all(&bytes)?;
+        }
+        writer.flush()?;
+
+        run.persistent = persistent;
+        Ok(run)
+    }
+
+    /// Opens the run for reading in keyword order.
+    fn reader(&self) -> Result<RunReader, Error> {
+        Ok(RunReader { reader: BufReader::new(File::open(&self.path)?) })
+    }
+}
+
+impl Drop for SpillRun {
+    fn drop(&mut self) {
+        if !self.persistent {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// A temporary file written while laying out an index, deleted when dropped.
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates an empty file with a unique name in `directory`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be created.
+    pub(crate) fn create(directory: &Path, kind: &str) -> Result<(TempFile, File), Error> {
+        let path = directory.join(format!(
+            "keywords-{}-{}-{}.tmp",
+            kind,
+            std::process::id(),
+            RUN_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        let file = File::options().read(true).write(true).create_new(true).open(&path)?;
+        Ok((TempFile { path }, file))
+    }
+
+    /// Path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Path of the run file numbered `number` in a checkpoint directory.
+fn checkpoint_run_path(directory: &Path, number: usize) -> PathBuf {
+    directory.join(format!("run-{}.run", number))
+}
+
+/// Sequential reader over the keywords of a run file.
+struct RunReader {
+    reader: BufReader<File>,
+}
+
+impl RunReader {
+    /// Reads the next keyword, or `None` at the end of the run.
+    fn next(&mut self) -> Result<Option<SpilledEntry>, Error> {
+        let mut length = [0u8; 4];
+        match self.reader.read_exact(&mut length) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let length = u32::from_le_bytes(length) as usize;
+        let mut aligned_buffer = AlignedVec::<16>::with_capacity(length);
+        aligned_buffer.resize(length, 0);
+        self.reader.read_exact(&mut aligned_buffer)?;
+
+        let spilled = rkyv::from_bytes::<SpilledKeyword, RkyvError>(&aligned_buffer)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Failed to deserialize spilled keyword: {}", e)))?;
+        Ok(Some(spilled.into_keyword_data()))
+    }
+}
+
+/// Tracks the estimated size of the keyword map during indexing and flushes it to a
+/// [`SpillRun`] whenever it exceeds the memory budget.
+pub(crate) struct KeywordSpill {
+    budget: usize,
+    directory: PathBuf,
+    estimated_size: usize,
+    runs: Vec<SpillRun>,
+
+    /// Whether runs belong to a checkpoint, numbered in order and kept when dropped
+    persistent: bool,
+}
+
+impl KeywordSpill {
+    /// Creates a spill with the given budget in bytes, writing runs to `directory`.
+    pub(crate) fn new(budget: usize, directory: PathBuf) -> KeywordSpill {
+        KeywordSpill {
+            budget,
+            directory,
+            estimated_size: 0,
+            runs: Vec::new(),
+            persistent: false,
+        }
+    }
+
+    /// Creates a spill writing the runs of a checkpoint to `directory`, after the runs of
+    /// a resumed checkpoint holding `run_keywords` keywords each.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a resumed run cannot be read.
+    pub(crate) fn checkpointed(
+        budget: usize,
+        directory: PathBuf,
+        run_keywords: &[u64],
+    ) -> Result<KeywordSpill, Error> {
+        let mut spill = KeywordSpill { persistent: true, ..KeywordSpill::new(budget, directory) };
+        for (number, &keywords) in run_keywords.iter().enumerate() {
+            let run = SpillRun {
+                path: checkpoint_run_path(&spill.directory, number),
+                keywords: keywords as usize,
+                persistent: true,
+            };
+
+            let mut reader = run.reader()?;
+            let mut read = 0;
+            while reader.next()?.is_some() {
+                read += 1;
+            }
+            if read != run.keywords {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Run {} holds {} keywords, expected {}", run.path.display(), read, run.keywords)).into());
+            }
+            spill.runs.push(run);
+        }
+        Ok(spill)
+    }
+
+    /// Accounts for data merged into the keyword map, flushing the map to a new run
+    /// (and clearing it) if the budget is exceeded.
+    ///
+    /// # Arguments
+    ///
+    /// * `added` - Estimated bytes just added to the map
+    /// * `keyword_map` - The keyword map being built
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the run file cannot be written.
+    pub(crate) fn account(
+        &mut self,
+        added: usize,
+        keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+    ) -> Result<(), Error> {
+        self.estimated_size += added;
+        if self.estimated_size <= self.budget {
+            return Ok(());
+        }
+        self.flush(keyword_map)
+    }
+
+    /// Flushes the keyword map to a new run (and clears it), unless it is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the run file cannot be written.
+    pub(crate) fn flush(
+        &mut self,
+        keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+    ) -> Result<(), Error> {
+        if keyword_map.is_empty() {
+            return Ok(());
+        }
+
+        let path = if self.persistent {
+            checkpoint_run_path(&self.directory, self.runs.len())
+        } else {
+            self.directory.join(format!(
+                "keywords-spill-{}-{}.run",
+                std::process::id(),
+                RUN_COUNTER.fetch_add(1, Ordering::Relaxed),
+            ))
+        };
+        self.runs.push(SpillRun::write(path, self.persistent, keyword_map)?);
+
+        keyword_map.clear();
+        keyword_map.shrink_to(0);
+        self.estimated_size = 0;
+        Ok(())
+    }
+
+    /// Number of keywords of every run written so far.
+    pub(crate) fn run_keywords(&self) -> Vec<u64> {
+        self.runs.iter().map(|run| run.keywords as u64).collect()
+    }
+
+    /// Returns the runs written so far.
+    pub(crate) fn finish(self) -> Vec<SpillRun> {
+        self.runs
+    }
+}
+
+/// One input of the k-way merge.
+enum MergeSource<'a> {
+    Run(RunReader),
+    Memory(std::slice::Iter<'a, (&'a Rc<str>, &'a KeywordOneFile)>),
+}
+
+impl<'a> MergeSource<'a> {
+    /// The next keyword of the source. Keywords in memory are borrowed, not copied.
+    fn next(&mut self) -> Result<Option<MergeEntry<'a>>, Error> {
+        match self {
+            MergeSource::Run(reader) => Ok(reader.next()?.map(|(keyword, data)| (keyword, Cow::Owned(data)))),
+            MergeSource::Memory(iter) => Ok(iter.next().map(|&(keyword, data)| (keyword.clone(), Cow::Borrowed(data)))),
+        }
+    }
+}
+
+/// Calls `f` for every keyword in sorted order, merging spilled runs with the keywords
+/// still in memory.
+///
+/// Without runs, `sorted_in_memory` is visited directly. Otherwise the runs and the
+/// in-memory keywords (which were processed last) are merged k-way, and a keyword found
+/// in several of them is combined with [`KeywordOneFile::append`] in processing order.
+///
+/// # Arguments
+///
+/// * `sorted_in_memory` - Keywords still in memory, sorted by keyword
+/// * `runs` - Spilled runs, in the order they were written
+/// * `f` - Called once per distinct keyword with its complete data
+///
+/// # Errors
+///
+/// Returns error if a run cannot be read, or if `f` fails.
+pub(crate) fn for_each_sorted_keyword(
+    sorted_in_memory: &[(&Rc<str>, &KeywordOneFile)],
+    runs: &[SpillRun],
+    mut f: impl FnMut(&Rc<str>, &KeywordOneFile) -> Result<(), Error>,
+) -> Result<(), Error> {
+    if runs.is_empty() {
+        for (keyword, keyword_data) in sorted_in_memory {
+            f(keyword, keyword_data)?;
+        }
+        return Ok(());
+    }
+
+    let mut sources: Vec<MergeSource> = runs.iter()
+        .map(|run| run.reader().map(MergeSource::Run))
+        .collect::<Result<_, _>>()?;
+    sources.push(MergeSource::Memory(sorted_in_memory.iter()));
+
+    // Next keyword of each source; the heap orders sources by keyword, then source index
+    let mut pending: Vec<Option<Cow<KeywordOneFile>>> = Vec::with_capacity(sources.len());
+    let mut heap: BinaryHeap<Reverse<(Rc<str>, usize)>> = BinaryHeap::with_capacity(sources.len());
+    for (source_idx, source) in sources.iter_mut().enumerate() {
+        let next = source.next()?;
+        if let Some((keyword, _)) = &next {
+            heap.push(Reverse((keyword.clone(), source_idx)));
+        }
+        pending.push(next.map(|(_, keyword_data)| keyword_data));
+    }
+
+    while let Some(Reverse((keyword, source_idx))) = heap.pop() {
+        let mut merged = pending[source_idx].take().expect("Pending keyword for source on heap");
+        let mut advance = vec![source_idx];
+
+        while let Some(Reverse((next_keyword, next_idx))) = heap.peek() {
+            if *next_keyword != keyword {
+                break;
+            }
+            let next_idx = *next_idx;
+            heap.pop();
+            // Only a keyword also found in a run is copied out of memory
+            merged.to_mut().append(pending[next_idx].take().expect("Pending keyword for source on heap").into_owned());
+            advance.push(next_idx);
+        }
+
+        f(&keyword, &merged)?;
+
+        for source_idx in advance {
+            if let Some((keyword, keyword_data)) = sources[source_idx].next()? {
+                heap.push(Reverse((keyword, source_idx)));
+                pending[source_idx] = Some(keyword_data);
+            }
+        }
+    }
+
+    Ok(())
+}
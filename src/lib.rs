@@ -41,23 +41,48 @@ pub mod index_data;
 pub mod searching;
 pub mod index_structure;
 pub mod keyword_shred;
+pub mod keyword_spill;
 #[cfg(test)]
 pub mod unit_tests;
 pub mod utils;
 pub mod column_parquet_reader;
+pub mod column_sampling;
+pub mod build_progress;
+pub mod checkpoint;
+pub mod record_batch_indexer;
+pub mod indexing_writer;
+pub mod text_source;
+pub mod error;
+pub mod index_builder;
 
 use hashbrown::HashMap;
 use indexmap::IndexSet;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::collections::HashSet as StdHashSet;
+use arrow::datatypes::DataType;
 use bytes::Bytes;
-use crate::index_data::{build_distributed_index, save_distributed_index};
+use futures::TryStreamExt;
+use crate::error::Error;
+use crate::index_data::{build_distributed_index, save_distributed_index_with_options, ColumnDecision, IndexGranularity, KeywordCount, PageTable, TextFormat, TextLayout, COLUMN_SKETCH_PRECISION};
 use crate::utils::column_pool::ColumnPool;
 use crate::index_structure::column_filter::ColumnFilter;
-use crate::column_parquet_reader::process_parquet_file;
-use crate::keyword_shred::KeywordOneFile;
+use crate::index_structure::hyperloglog::HyperLogLog;
+use crate::column_parquet_reader::process_parquet_file_with_options;
+use crate::build_progress::{BuildMonitor, CancellationToken, ProgressCallback};
+use crate::checkpoint::remove_checkpoint;
+use crate::index_builder::BuildConfig;
+use crate::utils::glob::column_matches;
+use crate::column_sampling::CardinalityCheck;
+use crate::text_source::{process_text_file, TextOptions};
+use crate::keyword_shred::{KeywordOneFile, SPLIT_CHARS_COUNT};
+use crate::keyword_spill::SpillRun;
 use crate::searching::keyword_search::KeywordSearcher;
-use crate::searching::search_results::SearchResult;
+use crate::searching::search_results::{
+    ColumnStatisticsInfo, HighlightedSearchResult, SearchResult, VerifiedSearchResult, VocabularyEntry,
+};
+use crate::searching::vocabulary::VocabularyOptions;
 use crate::utils::file_interaction_local_and_cloud::get_object_store;
 
 // ============================================================================
@@ -102,18 +127,42 @@ impl From<Vec<u8>> for ParquetSource {
 // Distributed Index Structures
 // ============================================================================
 
-/// Maximum size per metadata chunk in bytes for partial loading (1MB)
-const MAX_CHUNK_SIZE_BYTES: usize = 1_000_000;
+/// Default target size per metadata chunk in bytes for partial loading (1MB), see
+/// [`BuildOptions::chunk_size_bytes`]
+pub const DEFAULT_CHUNK_SIZE_BYTES: usize = 1_000_000;
 
 /// Result of processing a Parquet file.
-/// Contains the keyword map, the column name pool, column keywords map, column filters, and global filter.
+/// Contains the keyword map, the column name pool, column keywords map, column filters, global filter,
+/// and the number of rows per column (by column ID) that produced at least one keyword.
 #[derive(Debug)]
 pub struct ProcessResult {
     pub keyword_map: HashMap<Rc<str>, KeywordOneFile>,
     pub column_pool: ColumnPool,
+
+    /// Keywords and Bloom filters per column, and the filter of all keywords. Empty when
+    /// keywords were spilled (see `spilled_runs`): the filters are then built by
+    /// [`build_distributed_index`] while merging the runs.
     pub column_keywords_map: HashMap<Rc<str>, IndexSet<Rc<str>>>,
     pub column_filters: HashMap<Rc<str>, ColumnFilter>,
     pub global_filter: ColumnFilter,
+    pub column_rows_with_keywords: HashMap<u32, u64>,
+
+    /// Sorted runs flushed to disk because [`BuildOptions::memory_budget`] was exceeded,
+    /// in the order they were written. `keyword_map` then holds only the keywords
+    /// processed after the last run. Empty without a memory budget.
+    pub spilled_runs: Vec<SpillRun>,
+
+    /// Decisions of the cardinality sampling pass, one per sampled column. Empty unless
+    /// [`BuildOptions::cardinality_check`] was set.
+    pub column_decisions: Vec<ColumnDecision>,
+
+    /// Data pages of every indexed column chunk, sorted by column and row group. Empty
+    /// unless [`BuildOptions::page_locations`] was set.
+    pub page_tables: Vec<PageTable>,
+
+    /// Batches of rows a CSV or JSON lines file was indexed in, with their byte offsets.
+    /// `None` for Parquet files (see [`text_source`]).
+    pub text_layout: Option<TextLayout>,
 }
 
 /// Information about a keyword index for a Parquet file.
@@ -146,6 +195,11 @@ pub struct IndexInfo {
 
     /// Index configuration
     pub error_rate: f64,
+    pub granularity: IndexGranularity,
+
+    /// Format and number of batches of a CSV or JSON lines file (None for Parquet files)
+    pub text_format: Option<TextFormat>,
+    pub num_batches: Option<usize>,
 
     /// Column information
     pub num_columns: usize,
@@ -153,50 +207,377 @@ pub struct IndexInfo {
 
     /// Keyword statistics
     pub total_keywords: usize,
+    pub column_statistics: Vec<ColumnStatisticsInfo>,
+
+    /// Cardinality sampling decisions (empty unless built with a cardinality check)
+    pub column_decisions: Vec<ColumnDecision>,
 
     /// Chunk information
     pub num_chunks: usize,
     pub max_chunk_size_bytes: usize,  // Target max size per chunk in bytes
 
+    /// Configuration the index was built with (None unless built with an [`IndexBuilder`](index_builder::IndexBuilder))
+    pub build_config: Option<BuildConfig>,
+
     /// Index file sizes (in bytes)
     pub filters_size: u64,
     pub data_size: u64,
+    pub trigrams_size: u64,  // 0 when built without a trigram index
     pub total_size: u64,
 }
 
+/// Optional index structures to build alongside the core index.
+///
+/// The default builds only what every index needs; each option trades extra index
+/// size or build time for additional capabilities.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Store a distinct-keyword sketch per column (and one of all columns together), so
+    /// that [`KeywordSearcher::estimate_distinct`] and [`estimate_distinct`] work.
+    ///
+    /// Adds about 4 KB to the filters file per column.
+    pub column_sketches: bool,
+
+    /// Store a distinct-keyword sketch per column and row group. Implies
+    /// [`column_sketches`](Self::column_sketches).
+    ///
+    /// Adds about 1 KB to the filters file per column and row group.
+    pub row_group_sketches: bool,
+
+    /// Store a trigram index of all keywords in `trigrams.bin`, so that
+    /// [`KeywordSearcher::search_substring`] reads only candidate chunks instead of
+    /// scanning every keyword.
+    pub trigram_index: bool,
+
+    /// Store the most frequent keywords of every prefix of up to
+    /// [`SUGGESTION_PREFIX_MAX_CHARS`](index_data::SUGGESTION_PREFIX_MAX_CHARS) characters,
+    /// per column, so that [`KeywordSearcher::suggest`] answers short prefixes without
+    /// reading `data.bin`.
+    ///
+    /// The tables are stored in the filters file, which every search loads, and grow
+    /// with the number of columns and distinct short prefixes. Without them, suggestions
+    /// scan the chunks covering the prefix.
+    pub prefix_suggestions: bool,
+
+    /// Number of threads used to split column chunks into keywords.
+    ///
+    /// `0` (the default) uses all available cores; `1` processes every chunk on the
+    /// calling task. Every thread count indexes the same keywords, columns and rows.
+    pub threads: usize,
+
+    /// Approximate limit in bytes for the keyword occurrence data held in memory while
+    /// processing the Parquet file.
+    ///
+    /// When exceeded, the keywords processed so far are written as a sorted run to a
+    /// temporary file and merged back when the index is laid out. `None` (the default)
+    /// keeps everything in memory. Once keywords were spilled, the keyword lists and
+    /// `data.bin` are also written to temporary files while the runs are merged, and
+    /// `data.bin` is uploaded from there in parts.
+    pub memory_budget: Option<usize>,
+
+    /// Directory for the temporary run files written under a [`memory_budget`](Self::memory_budget).
+    ///
+    /// Defaults to [`std::env::temp_dir`].
+    pub spill_directory: Option<PathBuf>,
+
+    /// Maximum number of row entries stored for a single keyword.
+    ///
+    /// Keywords with more entries (typically very frequent ones such as `GET` or `com`)
+    /// keep only the row groups they occur in, and their search results are marked
+    /// [`coarse`](crate::searching::search_results::RowGroupLocation::coarse). `None`
+    /// (the default) stores row-level detail for every keyword.
+    pub keyword_detail_limit: Option<usize>,
+
+    /// Store row ranges (the default) or only row group membership for every keyword.
+    ///
+    /// [`IndexGranularity::RowGroups`] suits archival data where knowing which row groups
+    /// to read is enough: `data.bin` holds one bitmap of row groups per keyword and
+    /// column, and every search result is coarse.
+    pub granularity: IndexGranularity,
+
+    /// Record the data page of every keyword occurrence, using the Parquet offset index.
+    ///
+    /// Search results then carry the rows of the matching pages
+    /// ([`RowGroupLocation::pages`](crate::searching::search_results::RowGroupLocation::pages)),
+    /// so that coarse locations are read page by page instead of as whole row groups.
+    /// Column chunks without an offset index get no pages. Has no effect with
+    /// [`IndexGranularity::RowGroups`].
+    pub page_locations: bool,
+
+    /// Sample every column before indexing and exclude high-cardinality columns (such as
+    /// UUIDs or hashes) or index their values whole.
+    ///
+    /// The decision and its reason are stored in the index for every sampled column, see
+    /// [`KeywordSearcher::column_decisions`]. `None` (the default) indexes every column
+    /// that is not explicitly excluded.
+    pub cardinality_check: Option<CardinalityCheck>,
+
+    /// Index only the columns matching one of these column names or glob patterns
+    /// (`*` and `?`, see [`glob_match`](crate::utils::glob::glob_match)).
+    ///
+    /// Applies together with `exclude_columns` and [`column_types`](Self::column_types):
+    /// a column is indexed when it is included, of a selected type, and not excluded.
+    /// Chunks of other columns are never read. `None` (the default) includes every column.
+    pub include_columns: Option<Vec<String>>,
+
+    /// Index only the columns of these Arrow data types, e.g. `Utf8` and `LargeUtf8`.
+    ///
+    /// A dictionary type matches dictionaries of any key type with the same value type.
+    /// Nested columns have the type of their top-level field. `None` (the default)
+    /// selects every type.
+    pub column_types: Option<Vec<DataType>>,
+
+    /// Receives the progress of the build: bytes streamed, column chunks processed,
+    /// keywords found and the current phase (see [`build_progress`]). `None` (the
+    /// default) reports nothing.
+    pub progress: Option<ProgressCallback>,
+
+    /// Stops the build when cancelled, at the next column chunk, index chunk or index
+    /// file; the build then returns an error. `None` (the default) builds to the end.
+    pub cancellation: Option<CancellationToken>,
+
+    /// Directory where the keyword data of completed row groups is checkpointed, so that
+    /// a failed build started again with the same directory resumes after the last
+    /// checkpoint (see [`checkpoint`]).
+    ///
+    /// Only for Parquet files read from a path. `None` (the default) takes no checkpoints.
+    pub checkpoint_directory: Option<PathBuf>,
+
+    /// Number of completed row groups between checkpoints of a
+    /// [`checkpoint_directory`](Self::checkpoint_directory).
+    ///
+    /// Each checkpoint flushes the keywords in memory to a run file. `0` (the default)
+    /// checkpoints after every row group.
+    pub checkpoint_interval: usize,
+
+    /// Target size in bytes of the keyword chunks of `data.bin`, each of which a search
+    /// reads with a single range request.
+    ///
+    /// Smaller chunks make every lookup read less, at the cost of a larger chunk index in
+    /// the filters file. `None` (the default) uses [`DEFAULT_CHUNK_SIZE_BYTES`].
+    pub chunk_size_bytes: Option<usize>,
+
+    /// Number of delimiter levels of
+    /// [`SPLIT_CHARS_INCLUSIVE`](keyword_shred::SPLIT_CHARS_INCLUSIVE) that values are split
+    /// at, from the first (whitespace and punctuation) on.
+    ///
+    /// Fewer levels keep more of a value together: with 3, `-` and `_` no longer split
+    /// keywords, so `user-agent` is indexed and found as one keyword only. The levels are
+    /// stored in the index, and searches split queries the same way. `None` (the default)
+    /// applies all [`SPLIT_CHARS_COUNT`](keyword_shred::SPLIT_CHARS_COUNT) levels.
+    pub split_levels: Option<usize>,
+
+    /// Bloom filter false positive rates of single columns, by column name or glob
+    /// pattern, overriding the error rate of the build.
+    ///
+    /// The first matching pattern applies. The global filter always uses the error rate
+    /// of the build. Empty (the default) uses the same rate for every column.
+    pub column_error_rates: Vec<(String, f64)>,
+
+    /// Columns (names or glob patterns) whose values are indexed whole, as a single
+    /// keyword each, instead of being split, like the high-cardinality columns of a
+    /// [`CardinalityCheck`] with
+    /// [`ExactValues`](crate::column_sampling::HighCardinalityAction::ExactValues).
+    ///
+    /// Empty (the default) splits the values of every column.
+    pub exact_value_columns: Vec<String>,
+
+    /// Configuration of the build, stored in the index so that it can be rebuilt the
+    /// same way (see [`IndexBuilder`](crate::index_builder::IndexBuilder), which sets it).
+    /// `None` (the default) stores no configuration.
+    pub build_config: Option<BuildConfig>,
+}
+
+impl BuildOptions {
+    /// The number of tokenizer threads to use, resolving `0` to the available parallelism.
+    pub fn thread_count(&self) -> usize {
+        match self.threads {
+            0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            threads => threads,
+        }
+    }
+
+    /// The directory for spilled runs, resolving `None` to the system temporary directory.
+    pub fn spill_directory(&self) -> PathBuf {
+        self.spill_directory.clone().unwrap_or_else(std::env::temp_dir)
+    }
+
+    /// The progress callback and cancellation token of the build.
+    pub(crate) fn monitor(&self) -> BuildMonitor {
+        BuildMonitor::new(self.progress.clone(), self.cancellation.clone())
+    }
+
+    /// The target size of index chunks, resolving `None` to [`DEFAULT_CHUNK_SIZE_BYTES`].
+    pub fn chunk_size_bytes(&self) -> usize {
+        self.chunk_size_bytes.unwrap_or(DEFAULT_CHUNK_SIZE_BYTES)
+    }
+
+    /// The number of delimiter levels values are split at, resolving `None` to all
+    /// [`SPLIT_CHARS_COUNT`](keyword_shred::SPLIT_CHARS_COUNT) levels.
+    pub fn split_levels(&self) -> usize {
+        self.split_levels.unwrap_or(SPLIT_CHARS_COUNT)
+    }
+
+    /// The Bloom filter error rate of `column_name`: the rate of the first matching
+    /// [`column_error_rates`](Self::column_error_rates) pattern, or `error_rate`.
+    pub fn column_error_rate(&self, column_name: &str, error_rate: f64) -> f64 {
+        self.column_error_rates.iter()
+            .find(|(pattern, _)| column_matches(pattern, column_name))
+            .map_or(error_rate, |&(_, rate)| rate)
+    }
+
+    /// Whether the values of `column_name` are indexed whole (see
+    /// [`exact_value_columns`](Self::exact_value_columns)).
+    pub fn is_exact_value_column(&self, column_name: &str) -> bool {
+        self.exact_value_columns.iter().any(|pattern| column_matches(pattern, column_name))
+    }
+
+    /// Checks the per-column error rates, the chunk size and the split levels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidArgument`] if a column error rate is outside the range
+    /// accepted for the error rate of the build, the chunk size is 0, or the split levels
+    /// are not between 1 and [`SPLIT_CHARS_COUNT`](keyword_shred::SPLIT_CHARS_COUNT).
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        for (pattern, error_rate) in &self.column_error_rates {
+            if !ERROR_RATE_RANGE.contains(error_rate) {
+                return Err(Error::InvalidArgument(format!(
+                    "error_rate of column '{}' must be between 0.0000000001 and 0.5, got {}",
+                    pattern,
+                    error_rate
+                )));
+            }
+        }
+        if self.chunk_size_bytes == Some(0) {
+            return Err(Error::InvalidArgument("chunk_size_bytes must be positive".to_string()));
+        }
+        if !(1..=SPLIT_CHARS_COUNT).contains(&self.split_levels()) {
+            return Err(Error::InvalidArgument(format!(
+                "split_levels must be between 1 and {}, got {}",
+                SPLIT_CHARS_COUNT,
+                self.split_levels()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Bloom filter false positive rates accepted for a build.
+const ERROR_RATE_RANGE: RangeInclusive<f64> = 0.0000000001..=0.5;
+
+/// Checks the error rate of a build.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidArgument`] if the error rate is outside [`ERROR_RATE_RANGE`].
+pub(crate) fn validate_error_rate(error_rate: f64) -> Result<(), Error> {
+    if !ERROR_RATE_RANGE.contains(&error_rate) {
+        return Err(Error::InvalidArgument(format!(
+            "error_rate must be between 0.0000000001 and 0.5, got {}",
+            error_rate
+        )));
+    }
+    Ok(())
+}
+
 /// Build and save distributed index in one step
+///
+/// See [`IndexBuilder`](index_builder::IndexBuilder) for every other setting of a build,
+/// and a build configuration stored in the index.
 pub async fn build_and_save_index(
     parquet_path: &str,
     exclude_columns: Option<StdHashSet<String>>,
     error_rate: Option<f64>,
     index_file_prefix: Option<&str>
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(), Error> {
+    build_and_save_index_with_options(
+        parquet_path,
+        exclude_columns,
+        error_rate,
+        index_file_prefix,
+        &BuildOptions::default(),
+    ).await
+}
+
+/// Build and save distributed index in one step, with optional index structures.
+///
+/// Same as [`build_and_save_index`], with `options` selecting additional structures
+/// to store in the index.
+/// A [checkpoint](BuildOptions::checkpoint_directory) of the build is removed once
+/// the index is saved.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::{build_and_save_index_with_options, BuildOptions};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let options = BuildOptions { row_group_sketches: true, ..Default::default() };
+/// build_and_save_index_with_options("data.parquet", None, None, None, &options).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn build_and_save_index_with_options(
+    parquet_path: &str,
+    exclude_columns: Option<StdHashSet<String>>,
+    error_rate: Option<f64>,
+    index_file_prefix: Option<&str>,
+    options: &BuildOptions,
+) -> Result<(), Error> {
     let error_rate = error_rate.unwrap_or(0.01);
 
-    // Validate error_rate is within acceptable range
-    if error_rate < 0.0000000001 || error_rate > 0.5 {
-        return Err(format!(
-            "error_rate must be between 0.0000000001 and 0.5, got {}",
-            error_rate
-        ).into());
-    }
+    validate_error_rate(error_rate)?;
 
-    println!("Processing parquet file...");
     let source = ParquetSource::Path(parquet_path.to_string());
-    let result = process_parquet_file(source.clone(), exclude_columns, Some(error_rate)).await?;
+    let result = process_parquet_file_with_options(source.clone(), exclude_columns, Some(error_rate), options).await?;
 
-    println!("Building distributed index...");
-    let files = build_distributed_index(&result, &source, error_rate).await?;
+    let files = build_distributed_index(&result, &source, error_rate, options).await?;
 
-    println!("Saving index files...");
-    save_distributed_index(&files, parquet_path, index_file_prefix).await?;
+    save_distributed_index_with_options(&files, parquet_path, index_file_prefix, options).await?;
 
-    println!("Index created successfully!");
-    println!("  filters.rkyv: {} bytes ({:.2} KB)", files.filters.len(), files.filters.len() as f64 / 1024.0);
-    println!("  data.bin: {} bytes ({:.2} KB)", files.data.len(), files.data.len() as f64 / 1024.0);
+    // The index is saved, so the build no longer needs its checkpoint
+    if let Some(directory) = &options.checkpoint_directory {
+        drop(result);
+        remove_checkpoint(directory)?;
+    }
 
-    let total_size = files.filters.len() + files.data.len();
-    println!("  Total: {} bytes ({:.2} MB)", total_size, total_size as f64 / (1024.0 * 1024.0));
+    Ok(())
+}
+
+/// Build and save the index of a CSV or JSON lines file in one step.
+///
+/// Same as [`build_and_save_index_with_options`] for text files: rows are indexed in
+/// batches of [`TextOptions::rows_per_batch`] rows, whose byte ranges are stored in the
+/// index (see [`text_source`]). The index is saved to `{text_path}.index/` and loads
+/// with [`KeywordSearcher::load`] like the index of a Parquet file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::{build_and_save_text_index, BuildOptions};
+/// # use keywords::text_source::TextOptions;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let text_options = TextOptions { rows_per_batch: 50_000, ..Default::default() };
+/// build_and_save_text_index("export.csv", &text_options, None, None, None, &BuildOptions::default()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn build_and_save_text_index(
+    text_path: &str,
+    text_options: &TextOptions,
+    exclude_columns: Option<StdHashSet<String>>,
+    error_rate: Option<f64>,
+    index_file_prefix: Option<&str>,
+    options: &BuildOptions,
+) -> Result<(), Error> {
+    let error_rate = error_rate.unwrap_or(0.01);
+
+    let source = ParquetSource::Path(text_path.to_string());
+    let result = process_text_file(source.clone(), text_options, exclude_columns, Some(error_rate), options).await?;
+
+    let files = build_distributed_index(&result, &source, error_rate, options).await?;
+
+    save_distributed_index_with_options(&files, text_path, index_file_prefix, options).await?;
 
     Ok(())
 }
@@ -246,7 +627,7 @@ pub async fn build_and_save_index(
 pub async fn get_index_info(
     parquet_path: &str,
     index_file_prefix: Option<&str>,
-) -> Result<IndexInfo, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<IndexInfo, Error> {
     use crate::index_structure::index_files::{index_filename, IndexFile};
 
     // Build paths for index files
@@ -264,17 +645,25 @@ pub async fn get_index_info(
     let data_meta = store.head(&data_obj_path).await?;
     let data_size = data_meta.size;
 
-    let total_size = filters_size + data_size;
-
     // Load the searcher to access index data
     let searcher = KeywordSearcher::load(parquet_path, index_file_prefix).await?;
 
+    // The trigram file is optional; its size follows from the last posting list
+    let trigrams_size = searcher.filters.trigram_directory.last()
+        .map(|postings| postings.offset + postings.length as u64)
+        .unwrap_or(0);
+
+    let total_size = filters_size + data_size + trigrams_size;
+
     // Extract information from filters
     let version = searcher.filters.version;
     let parquet_etag = searcher.filters.parquet_etag.clone();
     let parquet_size = searcher.filters.parquet_size;
     let parquet_last_modified = searcher.filters.parquet_last_modified;
     let error_rate = searcher.filters.error_rate;
+    let granularity = searcher.filters.granularity;
+    let text_format = searcher.text_layout().map(|layout| layout.format);
+    let num_batches = searcher.text_layout().map(|layout| layout.num_batches());
     let num_chunks = searcher.filters.chunk_index.len();
 
     // Get column information (skip index 0 which is reserved for "All Columns")
@@ -289,6 +678,8 @@ pub async fn get_index_info(
     let total_keywords: usize = searcher.filters.chunk_index.iter()
         .map(|chunk| chunk.count as usize)
         .sum();
+    let column_statistics = searcher.column_statistics();
+    let column_decisions = searcher.column_decisions().to_vec();
 
     Ok(IndexInfo {
         version,
@@ -296,13 +687,20 @@ pub async fn get_index_info(
         parquet_size,
         parquet_last_modified,
         error_rate,
+        granularity,
+        text_format,
+        num_batches,
         num_columns,
         indexed_columns,
         total_keywords,
+        column_statistics,
+        column_decisions,
         num_chunks,
-        max_chunk_size_bytes: MAX_CHUNK_SIZE_BYTES,
+        max_chunk_size_bytes: searcher.filters.chunk_size_bytes as usize,
+        build_config: searcher.build_config().cloned(),
         filters_size,
         data_size,
+        trigrams_size,
         total_size,
     })
 }
@@ -321,6 +719,7 @@ pub async fn get_index_info(
 /// * `in_columns` - Optional column filter:
 ///   - `None` - Search all columns (uses optimized index 0)
 ///   - `Some("column")` - Search only specified column
+///   - `Some("http_*")` - Search all columns matching a glob pattern
 /// * `keyword_only` - Search mode:
 ///   - `true` - Search for exact keyword only
 ///   - `false` - Split into tokens and do phrase search (default, more flexible)
@@ -383,11 +782,252 @@ pub async fn search(
     search_for: &str,
     in_columns: Option<&str>,
     keyword_only: bool,
-) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<SearchResult, Error> {
     let searcher = KeywordSearcher::load(parquet_path, None).await?;
     searcher.search(search_for, in_columns, keyword_only).await
 }
 
+/// Search for a phrase and verify every match against the Parquet file.
+///
+/// Like a phrase search with [`search`], but candidates the index cannot confirm are
+/// checked by reading only the affected cells, so every returned match is verified.
+/// See [`KeywordSearcher::search_verified`] for details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::search_verified;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let verified = search_verified("data.parquet", "user@example.com", None).await?;
+///
+/// if let Some(matches) = verified.result.verified_matches {
+///     println!("Matches: {}", matches.total_occurrences);
+/// }
+/// println!("Rejected {} of {} candidates", verified.candidates_rejected, verified.candidates_checked);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_verified(
+    parquet_path: &str,
+    search_for: &str,
+    in_columns: Option<&str>,
+) -> Result<VerifiedSearchResult, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.search_verified(parquet_path, search_for, in_columns).await
+}
+
+/// Search for a keyword or phrase and return the matching cells with hit positions.
+///
+/// Reads only the matching cells from the Parquet file and returns, for each, the
+/// byte offset and length of every occurrence of the query. See
+/// [`KeywordSearcher::search_highlighted`] for details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::search_highlighted;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let highlighted = search_highlighted("data.parquet", "admin", Some("username")).await?;
+///
+/// for cell in &highlighted.cells {
+///     println!("row {}: {} hit(s) in {:?}", cell.row, cell.highlights.len(), cell.value);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_highlighted(
+    parquet_path: &str,
+    search_for: &str,
+    in_columns: Option<&str>,
+) -> Result<HighlightedSearchResult, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.search_highlighted(parquet_path, search_for, in_columns).await
+}
+
+/// Search for all keywords containing a substring.
+///
+/// Uses the trigram index when the index was built with [`BuildOptions::trigram_index`],
+/// and scans the keyword lists otherwise. See [`KeywordSearcher::search_substring`]
+/// for details.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::search_substring;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let result = search_substring("data.parquet", "ample", None).await?;
+///
+/// println!("Keywords containing 'ample': {:?}", result.tokens);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_substring(
+    parquet_path: &str,
+    substring: &str,
+    in_columns: Option<&str>,
+) -> Result<SearchResult, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.search_substring(substring, in_columns).await
+}
+
+
+/// Search for keywords or phrases in several columns of a Parquet file.
+///
+/// Convenience wrapper around [`KeywordSearcher::search_columns`]. Each entry in
+/// `columns` is either an exact column name or a glob pattern (`*` matches any
+/// sequence, `?` matches one character). Matches from all selected columns are
+/// merged into one `SearchResult`.
+///
+/// # Arguments
+///
+/// * `parquet_path` - Path to the Parquet file (index is at `{parquet_path}.index`)
+/// * `search_for` - The text to search for (keyword or phrase)
+/// * `columns` - Column names and/or glob patterns to search
+/// * `keyword_only` - `true` for exact keyword search, `false` for phrase search
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::search_columns;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let result = search_columns("logs.parquet", "timeout", &["message", "http_*"], true).await?;
+///
+/// if let Some(verified) = result.verified_matches {
+///     println!("Found in columns: {:?}", verified.columns);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn search_columns(
+    parquet_path: &str,
+    search_for: &str,
+    columns: &[&str],
+    keyword_only: bool,
+) -> Result<SearchResult, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.search_columns(search_for, columns, keyword_only).await
+}
+
+
+/// List the keywords stored in the index of a Parquet file.
+///
+/// Convenience wrapper around [`KeywordSearcher::vocabulary`] that collects the whole
+/// enumeration into a `Vec`. For large vocabularies, load a `KeywordSearcher` and
+/// consume the stream instead to keep memory use bounded.
+///
+/// # Arguments
+///
+/// * `parquet_path` - Path to the Parquet file (index is at `{parquet_path}.index`)
+/// * `options` - Column, prefix and key range restrictions, and whether to compute
+///   per-column occurrence counts
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::vocabulary;
+/// # use keywords::searching::vocabulary::VocabularyOptions;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let options = VocabularyOptions { with_counts: true, ..Default::default() };
+/// for entry in vocabulary("data.parquet", options).await? {
+///     println!("{}: {:?}", entry.keyword, entry.column_counts);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn vocabulary(
+    parquet_path: &str,
+    options: VocabularyOptions,
+) -> Result<Vec<VocabularyEntry>, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.vocabulary(options).try_collect().await
+}
+
+/// Suggest the most frequent keywords starting with a prefix.
+///
+/// Convenience wrapper around [`KeywordSearcher::suggest`] for one-off lookups. For
+/// type-ahead use, load a `KeywordSearcher` once and call `suggest` on it for each
+/// keystroke instead.
+///
+/// # Arguments
+///
+/// * `parquet_path` - Path to the Parquet file (index is at `{parquet_path}.index`)
+/// * `prefix` - Prefix typed so far (case-sensitive)
+/// * `column` - Optional column name or glob pattern; `None` uses all columns
+/// * `k` - Maximum number of suggestions
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::suggest;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// for suggestion in suggest("data.parquet", "ex", Some("email"), 5).await? {
+///     println!("{} ({})", suggestion.keyword, suggestion.occurrences);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn suggest(
+    parquet_path: &str,
+    prefix: &str,
+    column: Option<&str>,
+    k: usize,
+) -> Result<Vec<KeywordCount>, Error> {
+    let searcher = KeywordSearcher::load(parquet_path, None).await?;
+    searcher.suggest(prefix, column, k).await
+}
+
+/// Estimate the number of distinct keywords in a column across one or more indexed files.
+///
+/// Merges the HyperLogLog sketches stored in each file's index, so keywords shared
+/// between files are counted once. Only the filters files are read; the Parquet data
+/// is never touched.
+///
+/// # Arguments
+///
+/// * `parquet_paths` - Paths to the indexed Parquet files
+/// * `column` - Column name, or `None` for keywords across all columns
+///
+/// # Returns
+///
+/// The estimated distinct keyword count (standard error about 1.6%). Files whose index
+/// has no such column contribute nothing.
+///
+/// # Errors
+///
+/// Returns error if any index cannot be loaded, or was built without
+/// [`BuildOptions::column_sketches`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::estimate_distinct;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let files = ["2024-01.parquet", "2024-02.parquet"];
+/// let distinct = estimate_distinct(&files, Some("email")).await?;
+/// println!("~{:.0} distinct email keywords", distinct);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn estimate_distinct(
+    parquet_paths: &[&str],
+    column: Option<&str>,
+) -> Result<f64, Error> {
+    let mut merged = HyperLogLog::new(COLUMN_SKETCH_PRECISION)?;
+
+    for parquet_path in parquet_paths {
+        let searcher = KeywordSearcher::load(parquet_path, None).await?;
+        if !searcher.has_column_sketches() {
+            return Err(Error::InvalidArgument(format!(
+                "Index of '{}' was built without column sketches", parquet_path
+            )));
+        }
+        if let Some(sketch) = searcher.column_sketch(column) {
+            merged.merge(&sketch)?;
+        }
+    }
+
+    Ok(merged.estimate())
+}
 
 /// Check if the index exists and is up-to-date with the parquet file
 ///
@@ -419,7 +1059,7 @@ pub async fn search(
 /// ```
 pub async fn validate_index(
     parquet_path: &str,
-) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<bool, Error> {
     // Check if index exists first
     if !index_exists(parquet_path).await {
         return Ok(false);
@@ -489,17 +1129,25 @@ pub async fn build_index_in_memory(
     source: ParquetSource,
     exclude_columns: Option<StdHashSet<String>>,
     error_rate: Option<f64>,
-) -> Result<KeywordSearcher, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<KeywordSearcher, Error> {
+    build_index_in_memory_with_options(source, exclude_columns, error_rate, &BuildOptions::default()).await
+}
+
+/// Build index in memory with optional index structures (test-only).
+///
+/// Same as [`build_index_in_memory`], with `options` selecting additional structures.
+#[cfg(test)]
+pub async fn build_index_in_memory_with_options(
+    source: ParquetSource,
+    exclude_columns: Option<StdHashSet<String>>,
+    error_rate: Option<f64>,
+    options: &BuildOptions,
+) -> Result<KeywordSearcher, Error> {
     use crate::utils::file_interaction_local_and_cloud::register_memory_file;
 
     let error_rate = error_rate.unwrap_or(0.01);
 
-    if error_rate < 0.0000000001 || error_rate > 0.5 {
-        return Err(format!(
-            "error_rate must be between 0.0000000001 and 0.5, got {}",
-            error_rate
-        ).into());
-    }
+    validate_error_rate(error_rate)?;
 
     // Convert source to a memory:// path
     let memory_path = match source {
@@ -525,11 +1173,11 @@ pub async fn build_index_in_memory(
     };
 
     // Build index using memory path
-    let result = process_parquet_file(source, exclude_columns, Some(error_rate)).await?;
-    let files = build_distributed_index(&result, &ParquetSource::Path(memory_path.clone()), error_rate).await?;
+    let result = process_parquet_file_with_options(source, exclude_columns, Some(error_rate), options).await?;
+    let files = build_distributed_index(&result, &ParquetSource::Path(memory_path.clone()), error_rate, options).await?;
 
     // Save to memory using the abstraction
-    save_distributed_index(&files, &memory_path, None).await?;
+    save_distributed_index_with_options(&files, &memory_path, None, options).await?;
 
     // Load and return searcher
     KeywordSearcher::load(&memory_path, None).await
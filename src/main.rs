@@ -9,6 +9,7 @@
 //! - **`index`** - Creates a distributed keyword index for a Parquet file
 //! - **`search`** - Searches for a keyword in an indexed Parquet file
 //! - **`index_info`** - Displays detailed information about an index
+//! - **`index_config`** - Prints the build configuration stored in an index
 //!
 //! # Index Structure
 //!
@@ -32,6 +33,10 @@
 //! # View index information
 //! keywords index_info data.parquet
 //!
+//! # Rebuild another file with the configuration of an index
+//! keywords index_config data.parquet > build.conf
+//! keywords index data-2.parquet --config build.conf
+//!
 //! # Show help
 //! keywords --help
 //! ```
@@ -54,6 +59,9 @@
 
 use std::env;
 use std::process;
+use keywords::build_progress::{BuildPhase, BuildProgress, CancellationToken, ProgressCallback};
+use keywords::index_builder::{BuildConfig, IndexBuilder};
+use keywords::searching::keyword_search::KeywordSearcher;
 
 /// Entry point for the Parquet keyword indexer and searcher CLI.
 ///
@@ -70,9 +78,10 @@ use std::process;
 ///
 /// # Supported Commands
 ///
-/// - **`index <file.parquet>`** - Creates a keyword index
+/// - **`index <file.parquet> [--config <config-file>]`** - Creates a keyword index
 /// - **`search <file.parquet> <keyword>`** - Searches for a keyword
 /// - **`index_info <file.parquet>`** - Displays index information
+/// - **`index_config <file.parquet> [--prefix <prefix>]`** - Prints the stored build configuration
 /// - **`--help` or `-h`** - Displays help information
 ///
 /// # Error Handling
@@ -130,13 +139,17 @@ async fn main() {
 
     match command.as_str() {
         "index" => {
-            if args.len() != 3 {
-                eprintln!("Error: 'index' command requires exactly one file path\n");
-                print_help();
-                process::exit(1);
-            }
+            let config_path = match args.len() {
+                3 => None,
+                5 if args[3] == "--config" => Some(&args[4]),
+                _ => {
+                    eprintln!("Error: 'index' command requires exactly one file path and an optional --config <file>\n");
+                    print_help();
+                    process::exit(1);
+                }
+            };
             let file_path = &args[2];
-            handle_index(file_path).await;
+            handle_index(file_path, config_path.map(|path| read_build_config(path))).await;
         }
         "search" => {
             if args.len() != 4 {
@@ -157,6 +170,19 @@ async fn main() {
             let file_path = &args[2];
             handle_index_info(file_path).await;
         }
+        "index_config" => {
+            let prefix = match args.len() {
+                3 => None,
+                5 if args[3] == "--prefix" => Some(args[4].as_str()),
+                _ => {
+                    eprintln!("Error: 'index_config' command requires exactly one file path and an optional --prefix <prefix>\n");
+                    print_help();
+                    process::exit(1);
+                }
+            };
+            let file_path = &args[2];
+            handle_index_config(file_path, prefix).await;
+        }
         _ => {
             eprintln!("Error: Unknown command '{}'\n", command);
             print_help();
@@ -178,6 +204,8 @@ async fn main() {
 /// # Arguments
 ///
 /// * `file_path` - Path to the Parquet file to index (local or S3 path)
+/// * `config` - Build configuration read from the `--config` file (see [`BuildConfig`]),
+///   or `None` for the defaults
 ///
 /// # Index Output
 ///
@@ -209,21 +237,57 @@ async fn main() {
 /// Indexing file: s3://my-bucket/data.parquet
 /// This may take a while for large files...
 /// ✓ Indexing completed successfully!
+///
+/// # Rebuild another file with the configuration of an existing index
+/// $ keywords index_config data.parquet > build.conf
+/// $ keywords index data-2.parquet --config build.conf
 /// ```
 ///
 /// # Notes
 ///
 /// - If an index already exists, it will be overwritten
-/// - The process is single-threaded but I/O optimized
+/// - The build configuration is stored in the index, see `index_config`
+/// - Tokenization runs on all available cores; merging and index serialization remain
+///   single-threaded
+/// - `.csv`, `.tsv`, `.ndjson` and `.jsonl` files are indexed as text files, in
+///   batches of 10,000 rows
+/// - Progress of each phase is printed in steps of 10%, and Ctrl-C cancels the build
 /// - Memory usage is proportional to the number of unique keywords
 /// - S3 access requires appropriate AWS credentials
-async fn handle_index(file_path: &str) {
+async fn handle_index(file_path: &str, config: Option<BuildConfig>) {
     println!("Indexing file: {}", file_path);
     println!("This may take a while for large files...\n");
 
-    match keywords::build_and_save_index(file_path, None, None, None).await {
-        Ok(()) => {
+    let (progress, mut updates) = ProgressCallback::channel();
+    let cancellation = CancellationToken::new();
+    // CSV and JSON lines files are recognized by their extension
+    let builder = IndexBuilder::from_config(file_path, config.unwrap_or_default())
+        .with_progress(progress)
+        .with_cancellation(cancellation.clone());
+    tokio::spawn(async move {
+        let mut last_step = None;
+        while updates.changed().await.is_ok() {
+            let progress = updates.borrow_and_update().clone();
+            let step = progress_step(&progress);
+            if last_step != Some(step) {
+                println!("  {}", progress_line(&progress, step.1));
+                last_step = Some(step);
+            }
+        }
+    });
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("\nCancelling...");
+            cancellation.cancel();
+        }
+    });
+
+    match builder.build_and_save().await {
+        Ok(files) => {
             println!("\n✓ Indexing completed successfully!");
+            println!("  Index:        {}.index/", builder.index_path());
+            println!("  filters.rkyv: {} bytes ({:.2} KB)", files.filters.len(), files.filters.len() as f64 / 1024.0);
+            println!("  data.bin:     {} bytes ({:.2} KB)", files.data.len(), files.data.len() as f64 / 1024.0);
         }
         Err(e) => {
             eprintln!("\n✗ Error during indexing: {}", e);
@@ -232,6 +296,49 @@ async fn handle_index(file_path: &str) {
     }
 }
 
+/// Current phase of an index build, with its percentage done in steps of 10%.
+fn progress_step(progress: &BuildProgress) -> (BuildPhase, u64) {
+    let (done, total) = match progress.phase {
+        BuildPhase::Streaming => (progress.bytes_streamed, progress.total_bytes),
+        BuildPhase::Chunking => (0, 0),
+        BuildPhase::Serializing => (progress.index_chunks as u64, progress.total_index_chunks as u64),
+        BuildPhase::Uploading => (progress.bytes_uploaded, progress.total_upload_bytes),
+    };
+    (progress.phase, (done * 10).checked_div(total).unwrap_or(0) * 10)
+}
+
+/// Describes the progress of an index build, `percent` done with its current phase.
+fn progress_line(progress: &BuildProgress, percent: u64) -> String {
+    match progress.phase {
+        BuildPhase::Streaming => format!(
+            "Streaming: {}% ({} column chunks, {} keywords)",
+            percent, progress.column_chunks_processed, progress.keywords_found,
+        ),
+        BuildPhase::Chunking => "Chunking keywords".to_string(),
+        BuildPhase::Serializing => format!("Serializing: {}% of {} index chunks", percent, progress.total_index_chunks),
+        BuildPhase::Uploading => format!("Uploading: {}% of {} bytes", percent, progress.total_upload_bytes),
+    }
+}
+
+/// Reads the build configuration file given with `index --config`, exiting with code 1
+/// if it cannot be read or parsed.
+fn read_build_config(path: &str) -> BuildConfig {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Error: Could not read build configuration '{}': {}", path, e);
+            process::exit(1);
+        }
+    };
+    match text.parse() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: Invalid build configuration '{}': {}", path, e);
+            process::exit(1);
+        }
+    }
+}
+
 /// Handles the `search` command to find a keyword in an indexed Parquet file.
 ///
 /// This function performs a fast lookup of a keyword in the pre-built index. The search process:
@@ -395,6 +502,7 @@ async fn handle_search(file_path: &str, keyword: &str) {
 /// --------------
 /// Version:              1
 /// Error Rate:           0.01 (1.00%)
+/// Granularity:          Rows
 /// Max Chunk Size:       1000000 bytes (~1 MB target)
 /// Number of Chunks:     15
 ///
@@ -418,6 +526,29 @@ async fn handle_search(file_path: &str, keyword: &str) {
 /// ------------------
 /// Total Keywords:       15234
 ///
+/// COLUMN STATISTICS
+/// -----------------
+/// email
+///   Distinct Keywords:  8120
+///   Rows With Keywords: 100000
+///   Top Keywords:
+///         100000  com
+///          61234  gmail.com
+///   ...
+///
+/// COLUMN DECISIONS                  (only for indexes built with a cardinality check)
+/// ----------------
+/// request_id
+///   Action:             Excluded
+///   Sampled Values:     10000
+///   Reason:             High cardinality: 100% of sampled values distinct, 78% of keyword occurrences unique to one row
+///
+/// BUILD CONFIGURATION               (only for indexes with a stored configuration)
+/// -------------------
+/// error_rate = 0.01
+/// chunk_size_bytes = 1000000
+/// ...
+///
 /// INDEX FILE SIZES
 /// ----------------
 /// filters.rkyv:         704512 bytes (688.00 KB)
@@ -473,6 +604,10 @@ async fn handle_index_info(file_path: &str) {
             println!("──────────────");
             println!("Version:              {}", info.version);
             println!("Error Rate:           {} ({:.2}%)", info.error_rate, info.error_rate * 100.0);
+            println!("Granularity:          {:?}", info.granularity);
+            if let (Some(format), Some(num_batches)) = (info.text_format, info.num_batches) {
+                println!("Text Format:          {:?} ({} batches)", format, num_batches);
+            }
             println!("Max Chunk Size:       {} bytes ({:.2} MB)",
                      info.max_chunk_size_bytes,
                      info.max_chunk_size_bytes as f64 / (1024.0 * 1024.0));
@@ -507,6 +642,41 @@ async fn handle_index_info(file_path: &str) {
             println!("Total Keywords:       {}", info.total_keywords);
             println!();
 
+            // Per-column Statistics
+            println!("COLUMN STATISTICS");
+            println!("─────────────────");
+            for stats in &info.column_statistics {
+                println!("{}", stats.column_name);
+                println!("  Distinct Keywords:  {}", stats.distinct_keywords);
+                println!("  Rows With Keywords: {}", stats.rows_with_keywords);
+                println!("  Top Keywords:");
+                for top in &stats.top_keywords {
+                    println!("    {:>10}  {}", top.occurrences, top.keyword);
+                }
+            }
+            println!();
+
+            // Cardinality sampling decisions (only for indexes built with a cardinality check)
+            if !info.column_decisions.is_empty() {
+                println!("COLUMN DECISIONS");
+                println!("────────────────");
+                for decision in &info.column_decisions {
+                    println!("{}", decision.column_name);
+                    println!("  Action:             {:?}", decision.action);
+                    println!("  Sampled Values:     {}", decision.sampled_values);
+                    println!("  Reason:             {}", decision.reason);
+                }
+                println!();
+            }
+
+            // Build configuration (only for indexes built with an IndexBuilder)
+            if let Some(config) = &info.build_config {
+                println!("BUILD CONFIGURATION");
+                println!("───────────────────");
+                print!("{}", config);
+                println!();
+            }
+
             // Index File Sizes
             println!("INDEX FILE SIZES");
             println!("────────────────");
@@ -516,6 +686,11 @@ async fn handle_index_info(file_path: &str) {
             println!("data.bin:             {} bytes ({:.2} KB)",
                      info.data_size,
                      info.data_size as f64 / 1024.0);
+            if info.trigrams_size > 0 {
+                println!("trigrams.bin:         {} bytes ({:.2} KB)",
+                         info.trigrams_size,
+                         info.trigrams_size as f64 / 1024.0);
+            }
             println!("────────────────────────────────────────");
             println!("Total Index Size:     {} bytes ({:.2} MB)",
                      info.total_size,
@@ -530,6 +705,54 @@ async fn handle_index_info(file_path: &str) {
     }
 }
 
+/// Handles the `index_config` command to print the build configuration of an index.
+///
+/// The output is a configuration file (see [`BuildConfig`]) that rebuilds the index the
+/// same way with `keywords index <file> --config <config-file>`.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the indexed file
+/// * `prefix` - Prefix of the index file names, for indexes built with an
+///   `index_file_prefix`
+///
+/// # Exit Codes
+///
+/// - `0` - Configuration printed successfully
+/// - `1` - Index not found, error reading the index, or no configuration stored in it
+///
+/// # Examples
+///
+/// ```bash
+/// $ keywords index_config data.parquet > build.conf
+/// $ keywords index data-2.parquet --config build.conf
+///
+/// # Indexes with prefixed file names (v2_filters.rkyv)
+/// $ keywords index_config data.parquet --prefix v2_
+/// ```
+async fn handle_index_config(file_path: &str, prefix: Option<&str>) {
+    // `index_exists` only knows the unprefixed file names
+    if prefix.is_none() && !keywords::index_exists(file_path).await {
+        eprintln!("Error: No index found for '{}'", file_path);
+        eprintln!("Please run 'index' command first to create the index.");
+        process::exit(1);
+    }
+
+    match KeywordSearcher::load(file_path, prefix).await {
+        Ok(searcher) => match searcher.build_config() {
+            Some(config) => print!("{}", config),
+            None => {
+                eprintln!("Error: The index of '{}' has no stored build configuration", file_path);
+                process::exit(1);
+            }
+        },
+        Err(e) => {
+            eprintln!("\n✗ Error reading index: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
 /// Prints comprehensive help information for the CLI.
 ///
 /// This function displays usage information, command descriptions, examples, and notes
@@ -555,17 +778,21 @@ async fn handle_index_info(file_path: &str) {
 /// Parquet Keyword Indexer and Searcher
 ///
 /// USAGE:
-///   keywords index <file.parquet>
+///   keywords index <file.parquet> [--config <config-file>]
 ///   keywords search <file.parquet> <keyword>
 ///   keywords index_info <file.parquet>
+///   keywords index_config <file.parquet> [--prefix <prefix>]
 ///   keywords --help
 ///
 /// COMMANDS:
 ///   index              Create an index for a parquet file
 ///   search             Search for a keyword in an indexed parquet file
 ///   index_info         Display detailed information about an index
+///   index_config       Print the build configuration stored in an index
 ///
 /// OPTIONS:
+///   --config <file>    Build the index with the settings of a configuration file
+///   --prefix <prefix>  Read an index whose file names start with <prefix>
 ///   --help, -h         Show this help message
 ///
 /// EXAMPLES:
@@ -578,6 +805,10 @@ async fn handle_index_info(file_path: &str) {
 ///   # View index information
 ///   keywords index_info data.parquet
 ///
+///   # Rebuild another file with the configuration of an index
+///   keywords index_config data.parquet > build.conf
+///   keywords index data-2.parquet --config build.conf
+///
 /// NOTE:
 ///   - The index command creates a .index directory next to the parquet file
 ///   - You must create an index before searching
@@ -587,17 +818,21 @@ fn print_help() {
     println!("Parquet Keyword Indexer and Searcher");
     println!();
     println!("USAGE:");
-    println!("  {} index <file.parquet>", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
+    println!("  {} index <file.parquet> [--config <config-file>]", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
     println!("  {} search <file.parquet> <keyword>", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
     println!("  {} index_info <file.parquet>", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
+    println!("  {} index_config <file.parquet> [--prefix <prefix>]", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
     println!("  {} --help", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
     println!();
     println!("COMMANDS:");
     println!("  index              Create an index for a parquet file");
     println!("  search             Search for a keyword in an indexed parquet file");
     println!("  index_info         Display detailed information about an index");
+    println!("  index_config       Print the build configuration stored in an index");
     println!();
     println!("OPTIONS:");
+    println!("  --config <file>    Build the index with the settings of a configuration file");
+    println!("  --prefix <prefix>  Read an index whose file names start with <prefix>");
     println!("  --help, -h         Show this help message");
     println!();
     println!("EXAMPLES:");
@@ -610,6 +845,10 @@ fn print_help() {
     println!("  # View index information");
     println!("  {} index_info data.parquet", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
     println!();
+    println!("  # Rebuild another file with the configuration of an index");
+    println!("  {} index_config data.parquet > build.conf", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
+    println!("  {} index data-2.parquet --config build.conf", env::args().nth(0).unwrap_or_else(|| "program".to_string()));
+    println!();
     println!("NOTE:");
     println!("  - The index command creates a .index directory next to the parquet file");
     println!("  - You must create an index before searching");
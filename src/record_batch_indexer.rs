@@ -0,0 +1,627 @@
+//! Keyword indexes built from Arrow record batches instead of a Parquet file.
+//!
+//! Services that already hold their data as Arrow `RecordBatch`es can index the batches
+//! while (or before) writing them to Parquet, instead of reading the file back. Row
+//! numbers of an index are relative to row groups, so the batches must be split into
+//! row groups exactly as the Parquet writer splits them: [`RecordBatchIndexer`] follows
+//! the rules of `parquet::arrow::ArrowWriter`, ending a row group after
+//! `max_row_group_size` rows (splitting batches as needed) or on an explicit
+//! [`flush`](RecordBatchIndexer::flush).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use arrow::record_batch::RecordBatch;
+//! # use parquet::arrow::ArrowWriter;
+//! use keywords::{BuildOptions, ParquetSource};
+//! use keywords::index_data::save_distributed_index;
+//! use keywords::record_batch_indexer::RecordBatchIndexer;
+//!
+//! # async fn example(batches: Vec<RecordBatch>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let schema = batches[0].schema();
+//! let mut writer = ArrowWriter::try_new(std::fs::File::create("data.parquet")?, schema.clone(), None)?;
+//! let mut indexer = RecordBatchIndexer::try_new(schema, None, None, &BuildOptions::default())?;
+//! for batch in &batches {
+//!     writer.write(batch)?;
+//!     indexer.write(batch)?;
+//! }
+//! writer.close()?;
+//!
+//! let files = indexer.build_index(&ParquetSource::Path("data.parquet".to_string())).await?;
+//! save_distributed_index(&files, "data.parquet", None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::rc::Rc;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt};
+use hashbrown::HashMap;
+use parquet::arrow::ArrowSchemaConverter;
+use parquet::file::properties::DEFAULT_MAX_ROW_GROUP_SIZE;
+use crate::error::Error;
+use crate::column_parquet_reader::{excluded_leaf_columns, finish_process_result, process_leaf_values, read_metadata, read_page_tables, DictionarySplits};
+use crate::index_data::{build_distributed_index, DistributedIndexFiles, IndexGranularity};
+use crate::keyword_shred::{ChunkKeywords, KeywordOneFile, Tokenization};
+use crate::keyword_spill::KeywordSpill;
+use crate::utils::column_pool::ColumnPool;
+use crate::utils::nested_columns::{arrow_leaf_columns, leaf_values, LeafColumn};
+use crate::{validate_error_rate, BuildOptions, ParquetSource, ProcessResult};
+
+/// Builds a keyword index from record batches, one row group at a time.
+///
+/// Batches are buffered until their row group ends, then every indexed leaf column of
+/// the row group is processed in column order, like the column chunks of a Parquet file.
+/// The result is the same [`ProcessResult`] that
+/// [`process_parquet_file_with_options`](crate::column_parquet_reader::process_parquet_file_with_options)
+/// returns for the Parquet file the batches are written to, provided its row groups
+/// match (see [`build_index`](Self::build_index)).
+///
+/// Columns are selected with `exclude_columns`, [`BuildOptions::include_columns`] and
+/// [`BuildOptions::column_types`], and tokenized on [`BuildOptions::threads`] threads
+/// within the [`BuildOptions::memory_budget`]. The cardinality sampling pass
+/// ([`BuildOptions::cardinality_check`]) needs the whole file and is not supported, nor
+/// are [checkpoints](BuildOptions::checkpoint_directory).
+pub struct RecordBatchIndexer {
+    schema: SchemaRef,
+    /// Leaf columns of the schema, with whether each one is indexed
+    leaves: Vec<(LeafColumn, bool)>,
+    max_row_group_size: usize,
+    error_rate: f64,
+    options: BuildOptions,
+
+    keyword_map: HashMap<Rc<str>, KeywordOneFile>,
+    column_pool: ColumnPool,
+    column_rows_with_keywords: HashMap<u32, u64>,
+    spill: Option<KeywordSpill>,
+
+    /// Batches of the row group in progress
+    buffered: Vec<RecordBatch>,
+    buffered_rows: usize,
+    /// Number of rows of every finished row group
+    row_group_rows: Vec<u64>,
+}
+
+impl RecordBatchIndexer {
+    /// Creates an indexer for record batches of `schema`.
+    ///
+    /// Row groups end after [`DEFAULT_MAX_ROW_GROUP_SIZE`] rows, the default of Parquet
+    /// writers; see [`with_max_row_group_size`](Self::with_max_row_group_size).
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - Schema of the record batches
+    /// * `exclude_columns` - Optional set of column names to skip during indexing
+    /// * `error_rate` - Optional bloom filter false positive rate (default: 0.01 = 1%)
+    /// * `options` - How the batches are indexed (see [`BuildOptions`])
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - The schema cannot be written to Parquet
+    /// - `error_rate` is out of range
+    /// - [`BuildOptions::cardinality_check`] or [`BuildOptions::checkpoint_directory`] is set
+    pub fn try_new(
+        schema: SchemaRef,
+        exclude_columns: Option<HashSet<String>>,
+        error_rate: Option<f64>,
+        options: &BuildOptions,
+    ) -> Result<Self, Error> {
+        let error_rate = error_rate.unwrap_or(0.01);
+        validate_error_rate(error_rate)?;
+        options.validate()?;
+        if options.cardinality_check.is_some() {
+            return Err(Error::InvalidArgument("Cardinality sampling is not supported when indexing record batches".to_string()));
+        }
+        if options.checkpoint_directory.is_some() {
+            return Err(Error::InvalidArgument("Checkpoints are not supported when indexing record batches".to_string()));
+        }
+
+        // Leaves must match the leaf columns of the Parquet file the batches are written to
+        let schema_descr = ArrowSchemaConverter::new().convert(&schema)?;
+        let leaves = arrow_leaf_columns(&schema);
+        if leaves.len() != schema_descr.num_columns() {
+            return Err(Error::UnsupportedSchema(format!(
+                "Schema has {} leaf columns, but is written as {} Parquet columns",
+                leaves.len(),
+                schema_descr.num_columns()
+            )));
+        }
+        let excluded = excluded_leaf_columns(&leaves, exclude_columns, options).unwrap_or_default();
+        if leaves.iter().any(|leaf| leaf.name.is_empty()) && !excluded.contains("") {
+            return Err(Error::UnsupportedSchema("Columns with a blank name cannot be indexed".to_string()));
+        }
+        let leaves = leaves.into_iter()
+            .map(|leaf| {
+                let indexed = !excluded.contains(&leaf.name);
+                (leaf, indexed)
+            })
+            .collect();
+
+        Ok(RecordBatchIndexer {
+            schema,
+            leaves,
+            max_row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+            error_rate,
+            options: options.clone(),
+            keyword_map: HashMap::new(),
+            column_pool: ColumnPool::new(),
+            column_rows_with_keywords: HashMap::new(),
+            spill: options.memory_budget
+                .map(|budget| KeywordSpill::new(budget, options.spill_directory())),
+            buffered: Vec::new(),
+            buffered_rows: 0,
+            row_group_rows: Vec::new(),
+        })
+    }
+
+    /// Ends row groups after `max_row_group_size` rows, which must match the
+    /// `max_row_group_size` of the writer's `WriterProperties`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_row_group_size` is 0.
+    pub fn with_max_row_group_size(mut self, max_row_group_size: usize) -> Self {
+        assert!(max_row_group_size > 0, "max_row_group_size must be positive");
+        self.max_row_group_size = max_row_group_size;
+        self
+    }
+
+    /// Adds a record batch to the row group in progress.
+    ///
+    /// Like `ArrowWriter::write`, the row group ends once it holds `max_row_group_size`
+    /// rows, and a batch that does not fit is split between row groups.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the batch does not have the indexer's schema, or if a row group
+    /// cannot be processed (see [`flush`](Self::flush)).
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        if batch.schema().fields() != self.schema.fields() {
+            return Err(Error::InvalidArgument("Record batch schema does not match the indexer schema".to_string()));
+        }
+
+        let mut batch = batch.clone();
+        while batch.num_rows() > 0 {
+            let to_write = batch.num_rows().min(self.max_row_group_size - self.buffered_rows);
+            self.buffered.push(batch.slice(0, to_write));
+            self.buffered_rows += to_write;
+            batch = batch.slice(to_write, batch.num_rows() - to_write);
+
+            if self.buffered_rows >= self.max_row_group_size {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds every record batch of a stream, see [`write`](Self::write).
+    ///
+    /// The row group in progress is not ended at the end of the stream; call
+    /// [`flush`](Self::flush) for an explicit boundary.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the stream, or of [`write`](Self::write).
+    pub async fn write_stream<S, E>(&mut self, stream: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<RecordBatch, E>>,
+        E: Into<Error>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        while let Some(batch) = stream.next().await {
+            self.write(&batch.map_err(Into::into)?)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the row group in progress, like `ArrowWriter::flush`, and processes its
+    /// columns. Does nothing if no rows were written since the last row group.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the index would exceed `u32::MAX` row groups, or if the memory
+    /// budget is exceeded and the keywords cannot be spilled to disk.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let row_group = u32::try_from(self.row_group_rows.len())
+            .map_err(|_| Error::LimitExceeded(format!("Only {} row groups are supported (u32::MAX)", u32::MAX)))?;
+
+        let batches = std::mem::take(&mut self.buffered);
+        let columns: Vec<(&LeafColumn, u32, Tokenization)> = self.leaves.iter()
+            .filter(|(_, indexed)| *indexed)
+            .map(|(leaf, _)| {
+                let tokenization = if self.options.is_exact_value_column(&leaf.name) {
+                    Tokenization::ExactValues
+                } else {
+                    Tokenization::Split { levels: self.options.split_levels() }
+                };
+                (leaf, self.column_pool.intern(&leaf.name), tokenization)
+            })
+            .collect();
+
+        let threads = self.options.thread_count();
+        if threads <= 1 && self.spill.is_none() {
+            for (leaf, column_reference, tokenization) in columns {
+                let rows = process_leaf_column(&batches, leaf, column_reference, row_group, tokenization, &mut self.keyword_map)?;
+                if rows > 0 {
+                    *self.column_rows_with_keywords.entry(column_reference).or_default() += rows;
+                }
+            }
+        } else {
+            // Columns are tokenized into their own keyword maps on up to `threads`
+            // threads, then merged in column order (see `stream_and_process_parquet`)
+            for group in columns.chunks(threads.max(1)) {
+                let tokenized: Vec<(ChunkKeywords, u64)> = std::thread::scope(|scope| -> Result<_, Error> {
+                    let handles: Vec<_> = group.iter()
+                        .map(|&(leaf, column_reference, tokenization)| {
+                            let batches = &batches;
+                            scope.spawn(move || {
+                                let mut keyword_map = HashMap::new();
+                                let rows = process_leaf_column(batches, leaf, column_reference, row_group, tokenization, &mut keyword_map)?;
+                                Ok::<_, Error>((ChunkKeywords::from_keyword_map(keyword_map, column_reference, row_group), rows))
+                            })
+                        })
+                        .collect();
+                    handles.into_iter()
+                        .map(|handle| handle.join().map_err(|_| Error::from(std::io::Error::other("Tokenizer thread panicked")))?)
+                        .collect()
+                })?;
+
+                for (chunk_keywords, rows) in tokenized {
+                    if rows > 0 {
+                        *self.column_rows_with_keywords.entry(chunk_keywords.column_reference).or_default() += rows;
+                    }
+                    let added = chunk_keywords.estimated_size();
+                    chunk_keywords.merge_into(&mut self.keyword_map);
+                    if let Some(spill) = self.spill.as_mut() {
+                        spill.account(added, &mut self.keyword_map)?;
+                    }
+                }
+            }
+        }
+
+        self.row_group_rows.push(self.buffered_rows as u64);
+        self.buffered_rows = 0;
+        Ok(())
+    }
+
+    /// Number of rows in the row group in progress.
+    pub fn in_progress_rows(&self) -> usize {
+        self.buffered_rows
+    }
+
+    /// Number of rows of every finished row group.
+    pub fn row_group_rows(&self) -> &[u64] {
+        &self.row_group_rows
+    }
+
+    /// Number of distinct keywords held in memory (since the last spilled run).
+    pub(crate) fn keywords_found(&self) -> usize {
+        self.keyword_map.len()
+    }
+
+    /// Ends the last row group and returns the processed keywords with their filters.
+    ///
+    /// The result has no page tables, as those come from the written file: build the
+    /// index with [`build_index`](Self::build_index) for [`BuildOptions::page_locations`].
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the last row group cannot be processed (see [`flush`](Self::flush)).
+    pub fn finish(mut self) -> Result<ProcessResult, Error> {
+        self.flush()?;
+        Ok(finish_process_result(
+            self.keyword_map,
+            self.column_pool,
+            self.column_rows_with_keywords,
+            self.spill,
+            Vec::new(),
+            Vec::new(),
+            self.error_rate,
+            &self.options,
+        ))
+    }
+
+    /// Ends the last row group and builds the index of the Parquet file the batches were
+    /// written to.
+    ///
+    /// The file provides the validation data of the index (size, ETag and modification
+    /// time), the location of its metadata, and its data pages when
+    /// [`BuildOptions::page_locations`] is set. Its row groups must hold the same numbers
+    /// of rows as the indexed ones, so that row numbers in the index match the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read, if its row groups do not match the
+    /// indexed row groups, or if the index cannot be built.
+    pub async fn build_index(
+        mut self,
+        parquet_file: &ParquetSource,
+    ) -> Result<DistributedIndexFiles, Error> {
+        self.flush()?;
+        let metadata_with_cache = read_metadata(parquet_file.clone(), None).await?;
+        let file_rows: Vec<u64> = metadata_with_cache.metadata.row_groups().iter()
+            .map(|row_group| row_group.num_rows() as u64)
+            .collect();
+        if file_rows != self.row_group_rows {
+            return Err(Error::InvalidArgument(format!(
+                "Row groups of the Parquet file ({} rows in {} row groups) do not match the \
+                 indexed row groups ({} rows in {} row groups)",
+                file_rows.iter().sum::<u64>(),
+                file_rows.len(),
+                self.row_group_rows.iter().sum::<u64>(),
+                self.row_group_rows.len(),
+            )));
+        }
+
+        let options = self.options.clone();
+        let error_rate = self.error_rate;
+        let mut result = self.finish()?;
+        if options.page_locations && options.granularity == IndexGranularity::Rows {
+            result.page_tables = read_page_tables(&metadata_with_cache, &result.column_pool).await?;
+        }
+        build_distributed_index(&result, parquet_file, error_rate, &options).await
+    }
+}
+
+/// Processes the values of one leaf column in the batches of a row group, splitting them
+/// or indexing each value whole as given by `tokenization`.
+///
+/// # Returns
+///
+/// The number of rows that produced at least one keyword.
+///
+/// # Errors
+///
+/// Returns error if a batch's column does not have the structure of the leaf, or if its
+/// values cannot be cast to strings.
+fn process_leaf_column(
+    batches: &[RecordBatch],
+    leaf: &LeafColumn,
+    column_reference: u32,
+    row_group: u32,
+    tokenization: Tokenization,
+    keyword_map: &mut HashMap<Rc<str>, KeywordOneFile>,
+) -> Result<u64, Error> {
+    let mut rows_with_keywords = 0u64;
+    let mut dictionary_splits = DictionarySplits::new(tokenization);
+    let mut row_offset = 0u32;
+    for batch in batches {
+        let values = leaf_values(batch.column(leaf.root), leaf).map_err(|e| Error::UnsupportedSchema(
+            format!("Column '{}' does not have the structure of its schema: {}", leaf.name, e)
+        ))?;
+        rows_with_keywords += process_leaf_values(
+            &values.values,
+            column_reference,
+            row_group,
+            row_offset,
+            values.rows.as_deref(),
+            &mut dictionary_splits,
+            keyword_map,
+        )?;
+        row_offset += batch.num_rows() as u32;
+    }
+    Ok(rows_with_keywords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, DictionaryArray, Int64Array, ListBuilder, StringArray, StringBuilder};
+    use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+    use arrow::error::ArrowError;
+    use bytes::Bytes;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use crate::column_parquet_reader::process_parquet_file_with_options;
+    use crate::column_sampling::CardinalityCheck;
+    use crate::index_data::save_distributed_index;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS_PER_GROUP: usize = 200;
+
+    fn schema() -> SchemaRef {
+        let level = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let tags = DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+        Arc::new(Schema::new(vec![
+            Field::new("message", DataType::Utf8, true),
+            Field::new("level", level, false),
+            Field::new("tags", tags, true),
+            Field::new("id", DataType::Int64, false),
+        ]))
+    }
+
+    /// Batches of different sizes, starting at row `start`.
+    fn batches() -> Vec<RecordBatch> {
+        [130, 70, 250, 30, 160, 90].iter()
+            .scan(0usize, |start, &rows| {
+                let batch = create_batch(*start, rows);
+                *start += rows;
+                Some(batch)
+            })
+            .collect()
+    }
+
+    fn create_batch(start: usize, rows: usize) -> RecordBatch {
+        let rows = start..start + rows;
+        let message: Vec<Option<String>> = rows.clone()
+            .map(|row| (row % 11 != 0).then(|| format!("request-{} from user_{}@example.com", row, row % 13)))
+            .collect();
+        let level: DictionaryArray<Int32Type> = rows.clone()
+            .map(|row| ["info", "warn", "error"][row % 3])
+            .collect();
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        for row in rows.clone() {
+            for tag in 0..row % 3 {
+                tags.values().append_value(format!("tag-{}", (row + tag) % 17));
+            }
+            tags.append(row % 5 != 0);
+        }
+        let id = Int64Array::from_iter_values(rows.map(|row| row as i64 * 1000));
+
+        RecordBatch::try_new(schema(), vec![
+            Arc::new(StringArray::from(message)) as ArrayRef,
+            Arc::new(level),
+            Arc::new(tags.finish()),
+            Arc::new(id),
+        ]).unwrap()
+    }
+
+    /// Writes the batches like the indexer indexes them, flushing after the batch at
+    /// `flush_after`.
+    async fn write_parquet(path: &str, max_row_group_size: usize, flush_after: usize) -> ParquetSource {
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(max_row_group_size)
+            .set_data_page_row_count_limit(50)
+            .set_write_batch_size(50)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema(), Some(props)).unwrap();
+        for (index, batch) in batches().iter().enumerate() {
+            writer.write(batch).unwrap();
+            if index == flush_after {
+                writer.flush().unwrap();
+            }
+        }
+        writer.close().unwrap();
+        register_memory_file(path, Bytes::from(buffer)).await.unwrap();
+        ParquetSource::Path(path.to_string())
+    }
+
+    fn indexer(options: &BuildOptions) -> RecordBatchIndexer {
+        RecordBatchIndexer::try_new(schema(), None, None, options)
+            .unwrap()
+            .with_max_row_group_size(ROWS_PER_GROUP)
+    }
+
+    fn index_batches(options: &BuildOptions) -> RecordBatchIndexer {
+        let mut indexer = indexer(options);
+        for (index, batch) in batches().iter().enumerate() {
+            indexer.write(batch).unwrap();
+            if index == 1 {
+                indexer.flush().unwrap();
+            }
+        }
+        indexer
+    }
+
+    async fn load(files: &DistributedIndexFiles, path: &str) -> KeywordSearcher {
+        save_distributed_index(files, path, None).await.unwrap();
+        KeywordSearcher::load(path, None).await.unwrap()
+    }
+
+    /// Columns and row ranges of every search result.
+    async fn locations(searcher: &KeywordSearcher) -> Vec<Vec<(String, u32, u32, u32)>> {
+        let mut locations = Vec::new();
+        for keyword in ["request-123", "user_7", "example", "warn", "tag-3", "45000", "request-799"] {
+            let result = searcher.search(keyword, None, true).await.unwrap();
+            let mut keyword_locations = Vec::new();
+            for column in result.verified_matches.iter().flat_map(|data| &data.column_details) {
+                for row_group in &column.row_groups {
+                    for range in &row_group.row_ranges {
+                        keyword_locations.push((column.column_name.clone(), row_group.row_group_id, range.start_row, range.end_row));
+                    }
+                }
+            }
+            locations.push(keyword_locations);
+        }
+        locations
+    }
+
+    #[test]
+    fn test_row_groups_split_like_writer() {
+        let indexer = index_batches(&BuildOptions::default());
+        // 130 + 70 rows, flushed; then 250 + 30 + 160 + 90 rows in row groups of 200
+        assert_eq!(indexer.row_group_rows(), [200, 200, 200]);
+        assert_eq!(indexer.in_progress_rows(), 130);
+
+        // A second flush has no rows to end a row group with
+        let mut indexer = indexer;
+        indexer.flush().unwrap();
+        indexer.flush().unwrap();
+        assert_eq!(indexer.row_group_rows(), [200, 200, 200, 130]);
+    }
+
+    #[tokio::test]
+    async fn test_index_matches_written_file() {
+        let options = BuildOptions::default();
+        let source = write_parquet("memory://batches-match.parquet", ROWS_PER_GROUP, 1).await;
+
+        let files = index_batches(&options).build_index(&source).await.unwrap();
+        let result = process_parquet_file_with_options(source.clone(), None, None, &options).await.unwrap();
+        let expected = build_distributed_index(&result, &source, 0.01, &options).await.unwrap();
+        assert_eq!(files.data.to_vec().unwrap(), expected.data.to_vec().unwrap());
+
+        let searcher = load(&files, "memory://batches-match.parquet").await;
+        assert!(searcher.validate_index(&source).await.unwrap());
+        let expected_searcher = load(&expected, "memory://batches-match-expected.parquet").await;
+        assert_eq!(locations(&searcher).await, locations(&expected_searcher).await);
+        assert_eq!(
+            searcher.filters.column_pool.strings,
+            vec!["", "message", "level", "tags[]", "id"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_with_threads_and_budget() {
+        let source = write_parquet("memory://batches-stream.parquet", ROWS_PER_GROUP, 1).await;
+        let expected = load(
+            &index_batches(&BuildOptions::default()).build_index(&source).await.unwrap(),
+            "memory://batches-stream-expected.parquet",
+        ).await;
+
+        let options = BuildOptions { threads: 3, memory_budget: Some(64 * 1024), ..Default::default() };
+        let mut indexer = indexer(&options);
+        let mut batches = batches().into_iter().map(Ok::<_, ArrowError>);
+        indexer.write_stream(futures::stream::iter(batches.by_ref().take(2))).await.unwrap();
+        indexer.flush().unwrap();
+        indexer.write_stream(futures::stream::iter(batches)).await.unwrap();
+        let files = indexer.build_index(&source).await.unwrap();
+
+        let searcher = load(&files, "memory://batches-stream.parquet").await;
+        assert_eq!(locations(&searcher).await, locations(&expected).await);
+    }
+
+    #[tokio::test]
+    async fn test_page_locations_and_column_selection() {
+        let source = write_parquet("memory://batches-pages.parquet", ROWS_PER_GROUP, 1).await;
+        let options = BuildOptions {
+            page_locations: true,
+            include_columns: Some(vec!["message".to_string(), "tags[]".to_string()]),
+            ..Default::default()
+        };
+        let files = index_batches(&options).build_index(&source).await.unwrap();
+        let searcher = load(&files, "memory://batches-pages.parquet").await;
+        assert_eq!(searcher.filters.column_pool.strings, vec!["", "message", "tags[]"]);
+
+        let result = searcher.search("request-123", None, true).await.unwrap();
+        let data = result.verified_matches.unwrap();
+        let row_group = &data.column_details[0].row_groups[0];
+        assert_eq!((row_group.row_group_id, row_group.row_ranges[0].start_row), (0, 123));
+        assert_eq!(row_group.pages.len(), 1);
+        assert!((row_group.pages[0].start_row..=row_group.pages[0].end_row).contains(&123));
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_input_rejected() {
+        // Row groups of the file must match the indexed ones
+        let source = write_parquet("memory://batches-mismatch.parquet", 300, usize::MAX).await;
+        let error = index_batches(&BuildOptions::default()).build_index(&source).await.err().unwrap();
+        assert!(error.to_string().contains("do not match"), "{}", error);
+
+        let other_schema = Arc::new(Schema::new(vec![Field::new("message", DataType::Utf8, true)]));
+        let batch = RecordBatch::try_new(other_schema, vec![Arc::new(StringArray::from(vec!["a"]))]).unwrap();
+        assert!(indexer(&BuildOptions::default()).write(&batch).is_err());
+
+        let options = BuildOptions { cardinality_check: Some(CardinalityCheck::default()), ..Default::default() };
+        assert!(RecordBatchIndexer::try_new(schema(), None, None, &options).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Distinct keyword estimates from the sketches stored in the index.
+//!
+//! Indexes built with [`crate::BuildOptions::column_sketches`] store a HyperLogLog sketch
+//! of the keywords in each column (and of all columns together), and optionally one per
+//! column and row group with [`crate::BuildOptions::row_group_sketches`]. Estimates are
+//! computed from the filters file alone, so they cost no I/O once the index is loaded.
+//! Sketches returned by [`KeywordSearcher::column_sketch`] can be merged across indexes
+//! to estimate distinct keywords over many files, which is what
+//! [`crate::estimate_distinct`] does.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!
+//!     if let Some(distinct) = searcher.estimate_distinct(Some("email")) {
+//!         println!("~{:.0} distinct keywords in email", distinct);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use crate::index_structure::hyperloglog::HyperLogLog;
+use crate::searching::keyword_search::KeywordSearcher;
+
+impl KeywordSearcher {
+    /// Get the distinct keyword sketch of a column.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column name or glob pattern; a pattern matching several columns
+    ///   returns the merged sketch of all of them. `None` returns the sketch of all columns.
+    ///
+    /// # Returns
+    ///
+    /// `None` if no indexed column matches or the index has no column sketches.
+    pub fn column_sketch(&self, column: Option<&str>) -> Option<HyperLogLog> {
+        let column_ids = self.sketch_column_ids(column);
+
+        let mut merged: Option<HyperLogLog> = None;
+        for column_sketch in self.filters.column_sketches.iter()
+            .filter(|column_sketch| column_ids.contains(&column_sketch.column_id))
+        {
+            match merged.as_mut() {
+                Some(merged) => merged.merge(&column_sketch.sketch).ok()?,
+                None => merged = Some(column_sketch.sketch.clone()),
+            }
+        }
+        merged
+    }
+
+    /// Estimate the number of distinct keywords in a column.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column name or glob pattern, or `None` for all columns
+    ///
+    /// # Returns
+    ///
+    /// The estimate (standard error about 1.6%), or `None` if no indexed column matches or
+    /// the index was built without [`crate::BuildOptions::column_sketches`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// let total = searcher.estimate_distinct(None).unwrap_or(0.0);
+    /// println!("~{:.0} distinct keywords", total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn estimate_distinct(&self, column: Option<&str>) -> Option<f64> {
+        self.column_sketch(column).map(|sketch| sketch.estimate())
+    }
+
+    /// Estimate the number of distinct keywords in a column within some row groups.
+    ///
+    /// Requires an index built with [`crate::BuildOptions::row_group_sketches`].
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - Column name or glob pattern, or `None` for all columns
+    /// * `row_groups` - Row group IDs to include
+    ///
+    /// # Returns
+    ///
+    /// The estimate (standard error about 3.3%), or `None` if the index has no row group
+    /// sketches or no indexed column matches.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// if let Some(distinct) = searcher.estimate_distinct_in_row_groups(Some("status"), &[0, 1]) {
+    ///     println!("~{:.0} distinct statuses in the first two row groups", distinct);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn estimate_distinct_in_row_groups(&self, column: Option<&str>, row_groups: &[u32]) -> Option<f64> {
+        let column_ids = self.sketch_column_ids(column);
+
+        let mut merged: Option<HyperLogLog> = None;
+        let mut has_row_group_sketches = false;
+
+        for column_sketch in self.filters.column_sketches.iter()
+            .filter(|column_sketch| column_ids.contains(&column_sketch.column_id))
+        {
+            has_row_group_sketches |= !column_sketch.row_groups.is_empty();
+
+            for row_group in column_sketch.row_groups.iter()
+                .filter(|row_group| row_groups.contains(&row_group.row_group_id))
+            {
+                match merged.as_mut() {
+                    Some(merged) => merged.merge(&row_group.sketch).ok()?,
+                    None => merged = Some(row_group.sketch.clone()),
+                }
+            }
+        }
+
+        if !has_row_group_sketches {
+            return None;
+        }
+        // Selected row groups without keywords in the column have no sketch
+        Some(merged.map(|sketch| sketch.estimate()).unwrap_or(0.0))
+    }
+
+    /// Whether the index was built with column sketches. An index without keywords has
+    /// nothing to sketch and counts as having them.
+    pub fn has_column_sketches(&self) -> bool {
+        !self.filters.column_sketches.is_empty() || self.filters.chunk_index.iter().all(|chunk| chunk.count == 0)
+    }
+
+    /// Column IDs whose sketches make up the sketch of `column` (0 = all columns).
+    fn sketch_column_ids(&self, column: Option<&str>) -> Vec<u32> {
+        match column {
+            None => vec![0],
+            Some(column) => self.resolve_column_ids(&[column]),
+        }
+    }
+}
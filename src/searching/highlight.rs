@@ -0,0 +1,218 @@
+//! Hit highlighting for search results.
+//!
+//! The index records which rows contain a keyword, not where in the cell it occurs.
+//! This module reads the matching cells with [`PrunedParquetReader::read_cells`] and
+//! computes the byte offset and length of every occurrence of the query, so UIs can
+//! highlight hits and build snippets without scanning the cells again. Keyword and
+//! phrase queries are matched at token boundaries, as the index matched them; substring
+//! queries anywhere in the cell.
+//!
+//! Candidates that still need verification are read in the same pass, so a
+//! highlighted result is also a verified one.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!     let highlighted = searcher.search_highlighted("data.parquet", "example", Some("email")).await?;
+//!
+//!     for cell in &highlighted.cells {
+//!         let first = cell.highlights[0];
+//!         println!("{}: ...{}...", cell.row, &cell.value[first.offset..first.offset + first.length]);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::Error;
+use crate::keyword_shred::SPLIT_CHARS_COUNT;
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::pruned_reader::PrunedParquetReader;
+use crate::searching::search_results::{Highlight, HighlightedCell, HighlightedSearchResult, SearchResult};
+use crate::searching::verify::{add_location_rows, phrase_offsets, rows_to_location_data, CoarseRowGroups, RowsByColumn};
+
+/// Find the non-overlapping occurrences of a keyword or phrase in a cell value.
+///
+/// Only occurrences that start and end at token boundaries are returned, using all
+/// delimiter levels of the default tokenizer, so `admin` is not found inside
+/// `administrator`.
+///
+/// # Examples
+///
+/// ```
+/// use keywords::searching::highlight::find_highlights;
+///
+/// let hits = find_highlights("alpha-beta alphabet alpha", "alpha");
+/// assert_eq!(hits.iter().map(|h| h.offset).collect::<Vec<_>>(), vec![0, 20]);
+/// assert!(hits.iter().all(|h| h.length == 5));
+/// ```
+pub fn find_highlights(value: &str, query: &str) -> Vec<Highlight> {
+    token_highlights(value, query, SPLIT_CHARS_COUNT)
+}
+
+/// [`find_highlights`] with token boundaries at the first `split_levels` delimiter levels,
+/// as an index built with that many levels splits values.
+fn token_highlights(value: &str, query: &str, split_levels: usize) -> Vec<Highlight> {
+    phrase_offsets(value, query, split_levels)
+        .map(|offset| Highlight { offset, length: query.len() })
+        .collect()
+}
+
+/// Find the non-overlapping occurrences of a substring in a cell value, wherever they are.
+///
+/// # Examples
+///
+/// ```
+/// use keywords::searching::highlight::find_substring_highlights;
+///
+/// let hits = find_substring_highlights("alpha-beta alphabet", "lph");
+/// assert_eq!(hits.iter().map(|h| h.offset).collect::<Vec<_>>(), vec![1, 12]);
+/// ```
+pub fn find_substring_highlights(value: &str, substring: &str) -> Vec<Highlight> {
+    if substring.is_empty() {
+        return Vec::new();
+    }
+    value.match_indices(substring)
+        .map(|(offset, matched)| Highlight { offset, length: matched.len() })
+        .collect()
+}
+
+impl KeywordSearcher {
+    /// Search for a keyword or phrase and return each matching cell with hit positions.
+    ///
+    /// Reads the cells of all matches from `parquet_path`. Only the affected rows of the
+    /// affected columns are read.
+    ///
+    /// # Arguments
+    ///
+    /// * `parquet_path` - Path of the Parquet file the index was built from
+    /// * `search_for` - The keyword or phrase to search for
+    /// * `in_columns` - Optional column name or glob pattern to restrict the search to
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleIndex`] if the Parquet file changed since the index was
+    /// built, or an error if the search fails or the Parquet file cannot be read.
+    pub async fn search_highlighted(
+        &self,
+        parquet_path: &str,
+        search_for: &str,
+        in_columns: Option<&str>,
+    ) -> Result<HighlightedSearchResult, Error> {
+        let reader = PrunedParquetReader::from_path(parquet_path);
+
+        let result = self.search(search_for, in_columns, false).await?;
+        self.highlight_search_result(result, &reader).await
+    }
+
+    /// Read the cells of a search result and locate the query in each of them.
+    ///
+    /// Both `verified_matches` and `needs_verification` are read, coarse locations in
+    /// full. Cells that do not contain the query (rejected candidates and nulls) are
+    /// dropped, and `verified_matches` is rebuilt from the remaining cells, so it has
+    /// no coarse locations.
+    ///
+    /// Occurrences of `result.query` are found like [`find_highlights`] does, at the token
+    /// boundaries of the index's split levels. Use [`highlight_substring_result`](Self::highlight_substring_result)
+    /// for results of [`search_substring`](Self::search_substring).
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - Result of a search on this index
+    /// * `reader` - Reader for the Parquet file the index was built from
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleIndex`] if the Parquet file changed since the index was
+    /// built, or an error if it cannot be read.
+    pub async fn highlight_search_result(
+        &self,
+        result: SearchResult,
+        reader: &PrunedParquetReader,
+    ) -> Result<HighlightedSearchResult, Error> {
+        let split_levels = self.filters.split_chars_inclusive.len();
+        self.highlight_cells(result, reader, |value, query| token_highlights(value, query, split_levels)).await
+    }
+
+    /// Read the cells of a [`search_substring`](Self::search_substring) result and locate
+    /// the substring in each of them.
+    ///
+    /// Works like [`highlight_search_result`](Self::highlight_search_result), but finds
+    /// occurrences with [`find_substring_highlights`], anywhere in the cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleIndex`] if the Parquet file changed since the index was
+    /// built, or an error if it cannot be read.
+    pub async fn highlight_substring_result(
+        &self,
+        result: SearchResult,
+        reader: &PrunedParquetReader,
+    ) -> Result<HighlightedSearchResult, Error> {
+        self.highlight_cells(result, reader, find_substring_highlights).await
+    }
+
+    async fn highlight_cells(
+        &self,
+        mut result: SearchResult,
+        reader: &PrunedParquetReader,
+        find: impl Fn(&str, &str) -> Vec<Highlight>,
+    ) -> Result<HighlightedSearchResult, Error> {
+        let mut locations = RowsByColumn::new();
+        let mut coarse = CoarseRowGroups::new();
+        let mut splits_matched = 0u16;
+        for data in result.verified_matches.iter().chain(result.needs_verification.iter()) {
+            add_location_rows(&mut locations, &mut coarse, data);
+            splits_matched |= data.splits_matched;
+        }
+        result.needs_verification = None;
+
+        if locations.is_empty() {
+            result.found = false;
+            return Ok(HighlightedSearchResult { result, cells: Vec::new() });
+        }
+        self.check_not_stale(reader).await?;
+
+        let mut cells = Vec::new();
+        let mut rows = RowsByColumn::new();
+
+        for column_cells in reader.read_cells(&rows_to_location_data(locations, &coarse, splits_matched)).await? {
+            for (row, value) in column_cells.rows {
+                let Some(value) = value else { continue };
+                let highlights = find(&value, &result.query);
+                if highlights.is_empty() {
+                    continue;
+                }
+
+                rows.entry(column_cells.column_name.clone())
+                    .or_default()
+                    .entry(column_cells.row_group_id)
+                    .or_default()
+                    .insert(row);
+                cells.push(HighlightedCell {
+                    column_name: column_cells.column_name.clone(),
+                    row_group_id: column_cells.row_group_id,
+                    row,
+                    value,
+                    highlights,
+                });
+            }
+        }
+
+        cells.sort_by(|a, b| (&a.column_name, a.row_group_id, a.row).cmp(&(&b.column_name, b.row_group_id, b.row)));
+
+        result.verified_matches = if rows.is_empty() {
+            None
+        } else {
+            Some(rows_to_location_data(rows, &CoarseRowGroups::new(), splits_matched))
+        };
+        result.found = result.verified_matches.is_some();
+
+        Ok(HighlightedSearchResult { result, cells })
+    }
+}
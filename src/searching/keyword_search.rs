@@ -44,12 +44,21 @@ use std::collections::HashMap;
 use rkyv::Archived;
 use rkyv::util::AlignedVec;
 use rkyv::rancor::Error as RkyvError;
-use crate::index_data::{IndexFilters, KeywordDataFlat, ChunkInfo};
+use crate::error::Error;
+use crate::index_builder::BuildConfig;
+use crate::index_data::{IndexFilters, INDEX_FORMAT_VERSION, KeywordDataFlat, ChunkInfo, PrefixSuggestions, KeywordCount, ColumnStatistics, ColumnSketch, RowGroupSketch, ColumnDecision, ColumnAction, IndexGranularity, RowGroupBitmap, PageTable, TextFormat, TextLayout, filters_file_version, find_page_table};
 use crate::index_structure::column_filter::ColumnFilter;
+use crate::index_structure::format_v1;
 use crate::index_structure::index_files::{index_filename, IndexFile};
+use crate::index_structure::trigram_index::TrigramPostings;
 use crate::ParquetSource;
 use crate::searching::search_results::*;
 use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::utils::glob::{glob_match, is_glob_pattern};
+
+/// Confirmed matches, matches needing verification and coarse (column, row group)
+/// pairs of a phrase search.
+type PhraseMatches = (Vec<PotentialMatch>, Vec<PotentialMatch>, Vec<(String, u32)>);
 
 /// Helper function to convert sorted row numbers into ranges
 fn rows_to_ranges(sorted_rows: &[u32]) -> Vec<CombinedRowRange> {
@@ -75,6 +84,35 @@ fn rows_to_ranges(sorted_rows: &[u32]) -> Vec<CombinedRowRange> {
     ranges
 }
 
+/// Helper function to convert an unordered set of row numbers into ranges
+fn row_set_to_ranges(rows: std::collections::HashSet<u32>) -> Vec<CombinedRowRange> {
+    let mut sorted_rows: Vec<u32> = rows.into_iter().collect();
+    sorted_rows.sort_unstable();
+    rows_to_ranges(&sorted_rows)
+}
+
+/// Rows of a search result per row group, merged across its columns.
+///
+/// Coarse row groups map to `None`, as any of their rows may match.
+fn result_row_groups(data: Option<&KeywordLocationData>) -> HashMap<u32, Option<std::collections::HashSet<u32>>> {
+    let mut row_groups: HashMap<u32, Option<std::collections::HashSet<u32>>> = HashMap::new();
+
+    for col in data.iter().flat_map(|data| &data.column_details) {
+        for rg in &col.row_groups {
+            let rows = row_groups.entry(rg.row_group_id)
+                .or_insert_with(|| Some(std::collections::HashSet::new()));
+            match rows {
+                Some(rows) if !rg.coarse => rows.extend(
+                    rg.row_ranges.iter().flat_map(|range| range.start_row..=range.end_row)
+                ),
+                _ => *rows = None,
+            }
+        }
+    }
+
+    row_groups
+}
+
 /// Handle for searching keywords in a distributed index.
 ///
 /// The searcher is created by loading a pre-built index from disk. Once loaded,
@@ -136,28 +174,42 @@ impl KeywordSearcher {
     ///
     /// # Errors
     ///
-    /// Returns error if rkyv deserialization fails
+    /// Returns [`Error::CorruptIndex`] if rkyv deserialization fails or a filter is malformed
     pub fn from_serialized(
         files: &crate::index_data::DistributedIndexFiles,
         index_dir: String,
         index_file_prefix: Option<String>,
-    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Self, Error> {
+        // The header holds the version, which selects the layout of the archive
+        let (version, archive) = filters_file_version(&files.filters);
+        if version > INDEX_FORMAT_VERSION {
+            return Err(Error::CorruptIndex(format!(
+                "Unsupported index format version {} (this library reads versions 1 to {})",
+                version,
+                INDEX_FORMAT_VERSION
+            )));
+        }
+        if version == 1 {
+            return Self::from_filters(format_v1::read_filters(archive)?, index_dir, index_file_prefix);
+        }
+
         // Copy to aligned buffer
         let mut filters_bytes = AlignedVec::<16>::new();
-        filters_bytes.extend_from_slice(&files.filters);
+        filters_bytes.extend_from_slice(archive);
 
         // Deserialize filters
-        let archived_filters: &Archived<IndexFilters> = rkyv::access(&filters_bytes)
-            .map_err(|e: RkyvError| format!("Failed to access archived filters: {}", e))?;
+        let archived_filters = rkyv::access::<Archived<IndexFilters>, RkyvError>(&filters_bytes)
+            .map_err(|e| Error::CorruptIndex(format!("Failed to access archived filters: {}", e)))?;
 
         let filters = IndexFilters {
-            version: archived_filters.version.to_native(),
+            version,
             parquet_etag: archived_filters.parquet_etag.to_string(),
             parquet_size: archived_filters.parquet_size.to_native(),
             parquet_last_modified: archived_filters.parquet_last_modified.to_native(),
             parquet_metadata_offset: archived_filters.parquet_metadata_offset.to_native(),
             parquet_metadata_length: archived_filters.parquet_metadata_length.to_native(),
             error_rate: archived_filters.error_rate.to_native(),
+            chunk_size_bytes: archived_filters.chunk_size_bytes.to_native(),
             split_chars_inclusive: archived_filters.split_chars_inclusive.iter()
                 .map(|v| v.iter().map(|c| char::from(*c)).collect())
                 .collect(),
@@ -207,8 +259,111 @@ impl KeywordSearcher {
                     count: chunk.count.to_native(),
                 })
                 .collect(),
+            prefix_suggestions: archived_filters.prefix_suggestions.iter()
+                .map(|table| PrefixSuggestions {
+                    column_id: table.column_id.to_native(),
+                    prefixes: table.prefixes.iter()
+                        .map(|(prefix, top)| {
+                            let top = top.iter()
+                                .map(|kc| KeywordCount {
+                                    keyword: kc.keyword.to_string(),
+                                    occurrences: kc.occurrences.to_native(),
+                                })
+                                .collect();
+                            (prefix.to_string(), top)
+                        })
+                        .collect(),
+                })
+                .collect(),
+            column_statistics: archived_filters.column_statistics.iter()
+                .map(|stats| ColumnStatistics {
+                    column_id: stats.column_id.to_native(),
+                    distinct_keywords: stats.distinct_keywords.to_native(),
+                    rows_with_keywords: stats.rows_with_keywords.to_native(),
+                    top_keywords: stats.top_keywords.iter()
+                        .map(|kc| KeywordCount {
+                            keyword: kc.keyword.to_string(),
+                            occurrences: kc.occurrences.to_native(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            column_sketches: archived_filters.column_sketches.iter()
+                .map(|column_sketch| ColumnSketch {
+                    column_id: column_sketch.column_id.to_native(),
+                    sketch: column_sketch.sketch.to_owned_sketch(),
+                    row_groups: column_sketch.row_groups.iter()
+                        .map(|rg| RowGroupSketch {
+                            row_group_id: rg.row_group_id.to_native(),
+                            sketch: rg.sketch.to_owned_sketch(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            trigram_directory: archived_filters.trigram_directory.iter()
+                .map(|postings| TrigramPostings {
+                    trigram: postings.trigram.to_string(),
+                    offset: postings.offset.to_native(),
+                    length: postings.length.to_native(),
+                    count: postings.count.to_native(),
+                })
+                .collect(),
+            column_decisions: archived_filters.column_decisions.iter()
+                .map(|decision| ColumnDecision {
+                    column_name: decision.column_name.to_string(),
+                    sampled_values: decision.sampled_values.to_native(),
+                    distinct_value_ratio: decision.distinct_value_ratio.to_native(),
+                    unique_keyword_ratio: decision.unique_keyword_ratio.to_native(),
+                    action: match decision.action {
+                        rkyv::Archived::<ColumnAction>::Indexed => ColumnAction::Indexed,
+                        rkyv::Archived::<ColumnAction>::ExactValues => ColumnAction::ExactValues,
+                        rkyv::Archived::<ColumnAction>::Excluded => ColumnAction::Excluded,
+                    },
+                    reason: decision.reason.to_string(),
+                })
+                .collect(),
+            granularity: match archived_filters.granularity {
+                rkyv::Archived::<IndexGranularity>::Rows => IndexGranularity::Rows,
+                rkyv::Archived::<IndexGranularity>::RowGroups => IndexGranularity::RowGroups,
+            },
+            page_tables: archived_filters.page_tables.iter()
+                .map(|table| PageTable {
+                    column_id: table.column_id.to_native(),
+                    row_group_id: table.row_group_id.to_native(),
+                    first_rows: table.first_rows.iter().map(|row| row.to_native()).collect(),
+                    num_rows: table.num_rows.to_native(),
+                })
+                .collect(),
+            text_layout: archived_filters.text_layout.as_ref().map(|layout| TextLayout {
+                format: match layout.format {
+                    rkyv::Archived::<TextFormat>::Csv { delimiter, has_header } => TextFormat::Csv { delimiter, has_header },
+                    rkyv::Archived::<TextFormat>::JsonLines => TextFormat::JsonLines,
+                },
+                schema: layout.schema.to_vec(),
+                rows_per_batch: layout.rows_per_batch.to_native(),
+                batch_offsets: layout.batch_offsets.iter().map(|offset| offset.to_native()).collect(),
+            }),
+            build_config: archived_filters.build_config.as_ref()
+                .map(rkyv::deserialize::<BuildConfig, RkyvError>)
+                .transpose()
+                .map_err(|e| Error::CorruptIndex(format!("Failed to deserialize build configuration: {}", e)))?,
         };
 
+        Self::from_filters(filters, index_dir, index_file_prefix)
+    }
+
+    /// Create a searcher from deserialized filters, after validating them.
+    fn from_filters(
+        filters: IndexFilters,
+        index_dir: String,
+        index_file_prefix: Option<String>,
+    ) -> Result<Self, Error> {
+        // Filters are checked once here, so that lookups never read past their data
+        filters.global_filter.validate()?;
+        for filter in filters.column_filters.values() {
+            filter.validate()?;
+        }
+
         Ok(Self {
             filters,
             index_dir,
@@ -238,7 +393,7 @@ impl KeywordSearcher {
     /// Returns error if:
     /// * Index directory does not exist (`{parquet_path}.index/` not found)
     /// * Any index file is missing or cannot be read (filters.rkyv, data.bin)
-    /// * Index files are corrupted or have invalid rkyv serialization
+    /// * Index files are corrupted or have invalid rkyv serialization ([`Error::CorruptIndex`])
     /// * Insufficient memory to load the index
     /// * File system permissions prevent reading
     ///
@@ -273,17 +428,18 @@ impl KeywordSearcher {
     /// **Handle missing index:**
     ///
     /// ```no_run
+    /// use keywords::error::Error;
     /// use keywords::searching::keyword_search::KeywordSearcher;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     ///     match KeywordSearcher::load("data.parquet", None).await {
     ///         Ok(searcher) => println!("Index loaded"),
-    ///         Err(e) if e.to_string().contains("not found") => {
+    ///         Err(Error::ObjectStore(object_store::Error::NotFound { .. })) => {
     ///             println!("Index needs to be built first");
     ///             // Build index using build_and_save_index()
     ///         }
-    ///         Err(e) => return Err(e),
+    ///         Err(e) => return Err(e.into()),
     ///     }
     ///     Ok(())
     /// }
@@ -291,7 +447,7 @@ impl KeywordSearcher {
     pub async fn load(
         parquet_path: &str,
         index_file_prefix: Option<&str>
-    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Self, Error> {
         let index_dir = format!("{}.index", parquet_path);
         let index_dir_clone = index_dir.clone(); // Clone for use in closure
 
@@ -300,7 +456,7 @@ impl KeywordSearcher {
             let file_path = format!("{}/{}", index_dir_clone, filename);
             let (store, path) = get_object_store(&file_path).await?;
             let bytes = store.get(&path).await?.bytes().await?;
-            Ok::<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>(bytes.to_vec())
+            Ok::<Vec<u8>, Error>(bytes.to_vec())
         };
 
         // Read only the filters file - data.bin is read on-demand
@@ -308,7 +464,8 @@ impl KeywordSearcher {
 
         let files = crate::index_data::DistributedIndexFiles {
             filters: filters_bytes,
-            data: Vec::new(), // Not loaded into memory
+            data: crate::index_data::IndexData::default(), // Not loaded into memory
+            trigrams: Vec::new(), // Posting lists are read on-demand
         };
 
         Self::from_serialized(&files, index_dir, index_file_prefix.map(|s| s.to_string()))
@@ -333,9 +490,9 @@ impl KeywordSearcher {
     /// - Chunk number is out of bounds
     /// - File I/O fails
     /// - Deserialization fails
-    async fn read_chunk_keywords(&self, chunk_number: u16) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+    pub(super) async fn read_chunk_keywords(&self, chunk_number: u32) -> Result<Vec<String>, Error> {
         let chunk_info = self.filters.chunk_index.get(chunk_number as usize)
-            .ok_or_else(|| format!("Chunk {} not found in index", chunk_number))?;
+            .ok_or_else(|| Error::CorruptIndex(format!("Chunk {} not found in index", chunk_number)))?;
 
         let data_path = format!("{}/{}", self.index_dir,
                                 index_filename(IndexFile::Data, self.index_file_prefix.as_deref()));
@@ -357,7 +514,7 @@ impl KeywordSearcher {
         aligned_buffer.extend_from_slice(&buffer);
 
         let archived: &Archived<Vec<String>> = rkyv::access(&aligned_buffer)
-            .map_err(|e: RkyvError| format!("Failed to deserialize keyword list: {}", e))?;
+            .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize keyword list: {}", e)))?;
 
         Ok(archived.iter().map(|s| s.to_string()).collect())
     }
@@ -381,9 +538,9 @@ impl KeywordSearcher {
     /// - Chunk number is out of bounds
     /// - File I/O fails
     /// - Deserialization fails
-    async fn read_full_chunk(&self, chunk_number: u16) -> Result<(Vec<String>, Vec<KeywordDataFlat>), Box<dyn std::error::Error + Send + Sync>> {
+    pub(super) async fn read_full_chunk(&self, chunk_number: u32) -> Result<(Vec<String>, Vec<KeywordDataFlat>), Error> {
         let chunk_info = self.filters.chunk_index.get(chunk_number as usize)
-            .ok_or_else(|| format!("Chunk {} not found in index", chunk_number))?;
+            .ok_or_else(|| Error::CorruptIndex(format!("Chunk {} not found in index", chunk_number)))?;
 
         let data_path = format!("{}/{}", self.index_dir,
                                 index_filename(IndexFile::Data, self.index_file_prefix.as_deref()));
@@ -410,16 +567,19 @@ impl KeywordSearcher {
         keyword_buffer.extend_from_slice(keyword_bytes);
 
         let archived_keywords: &Archived<Vec<String>> = rkyv::access(&keyword_buffer)
-            .map_err(|e: RkyvError| format!("Failed to deserialize keyword list: {}", e))?;
+            .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize keyword list: {}", e)))?;
 
         let keywords: Vec<String> = archived_keywords.iter().map(|s| s.to_string()).collect();
 
         // Deserialize data section
-        let mut data_buffer = AlignedVec::<16>::new();
-        data_buffer.extend_from_slice(data_bytes);
+        let data_buffer = self.chunk_data_buffer(data_bytes)?;
 
         let archived_data: &Archived<Vec<KeywordDataFlat>> = rkyv::access(&data_buffer)
-            .map_err(|e: RkyvError| format!("Failed to deserialize chunk data: {}", e))?;
+            .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize chunk data: {}", e)))?;
+
+        for item in archived_data.iter() {
+            item.validate_rows()?;
+        }
 
         let data: Vec<KeywordDataFlat> = archived_data.iter().map(|item| {
             KeywordDataFlat {
@@ -429,26 +589,39 @@ impl KeywordSearcher {
                         row_groups: col.row_groups.iter().map(|rg| {
                             crate::index_data::RowGroupDataFlat {
                                 row_group_id: rg.row_group_id.to_native(),
-                                rows: rg.rows.iter().map(|row| {
-                                    crate::index_data::FlatRow {
-                                        row: row.row.to_native(),
-                                        additional_rows: row.additional_rows.to_native(),
-                                        splits_matched: row.splits_matched.to_native(),
-                                        parent_chunk: row.parent_chunk.as_ref().map(|c| c.to_native()),
-                                        parent_position: row.parent_position.as_ref().map(|p| p.to_native()),
-                                    }
-                                }).collect(),
+                                rows: rg.rows.to_vec(),
+                                summary: rg.summary.as_ref().map(|summary| Box::new(crate::index_data::RowGroupSummary {
+                                    occurrences: summary.occurrences.to_native(),
+                                    pages: summary.pages.iter().map(|page| page.to_native()).collect(),
+                                })),
                             }
                         }).collect(),
+                        row_group_bitmap: col.row_group_bitmap.as_ref().map(|bitmap| Box::new(RowGroupBitmap {
+                            words: bitmap.words.iter().map(|word| word.to_native()).collect(),
+                            occurrences: bitmap.occurrences.to_native(),
+                        })),
                     }
                 }).collect(),
                 splits_matched: item.splits_matched.to_native(),
+                coarse: item.coarse,
             }
         }).collect();
 
         Ok((keywords, data))
     }
 
+    /// Copy the data section of a chunk into an aligned buffer, converting chunks of
+    /// version 1 indexes to the current layout.
+    fn chunk_data_buffer(&self, data_bytes: &[u8]) -> Result<AlignedVec<16>, Error> {
+        if self.filters.version == 1 {
+            return format_v1::upgrade_chunk_data(data_bytes);
+        }
+
+        let mut data_buffer = AlignedVec::<16>::new();
+        data_buffer.extend_from_slice(data_bytes);
+        Ok(data_buffer)
+    }
+
     /// Look up a parent keyword by its chunk and position.
     ///
     /// Reads the keyword list for the specified chunk and returns the keyword
@@ -470,12 +643,12 @@ impl KeywordSearcher {
     /// - Chunk number is out of bounds
     /// - Position is out of bounds within the chunk
     /// - File I/O or deserialization fails
-    async fn lookup_parent_keyword(&self, chunk: u16, position: u16) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    async fn lookup_parent_keyword(&self, chunk: u32, position: u32) -> Result<String, Error> {
         let keywords = self.read_chunk_keywords(chunk).await?;
 
         keywords.get(position as usize)
             .cloned()
-            .ok_or_else(|| format!("Position {} out of bounds in chunk {}", position, chunk).into())
+            .ok_or_else(|| Error::CorruptIndex(format!("Position {} out of bounds in chunk {}", position, chunk)))
     }
 
     /// Batch lookup multiple parent keywords efficiently.
@@ -495,12 +668,12 @@ impl KeywordSearcher {
     /// # Errors
     ///
     /// Returns error if any chunk read or lookup fails
-    async fn batch_lookup_parents<I>(&self, parents: I) -> Result<HashMap<(u16, u16), String>, Box<dyn std::error::Error + Send + Sync>>
+    async fn batch_lookup_parents<I>(&self, parents: I) -> Result<HashMap<(u32, u32), String>, Error>
     where
-        I: IntoIterator<Item = (u16, u16)>
+        I: IntoIterator<Item = (u32, u32)>
     {
         let mut result = HashMap::new();
-        let mut chunks_needed: HashMap<u16, Vec<u16>> = HashMap::new();
+        let mut chunks_needed: HashMap<u32, Vec<u32>> = HashMap::new();
 
         // Collect unique chunks and their positions
         for (chunk, position) in parents {
@@ -536,6 +709,7 @@ impl KeywordSearcher {
     /// * `in_columns` - Optional column filter:
     ///   - `None` - Search all columns
     ///   - `Some("column")` - Search only specified column
+    ///   - `Some("http_*")` - Search all columns matching a glob pattern (`*` and `?`)
     /// * `keyword_only` - Search mode:
     ///   - `true` - Search for exact keyword only (faster, exact match)
     ///   - `false` - Split into tokens and do phrase search (default, more flexible)
@@ -583,7 +757,64 @@ impl KeywordSearcher {
         search_for: &str,
         in_columns: Option<&str>,
         keyword_only: bool,
-    ) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<SearchResult, Error> {
+        match in_columns {
+            Some(column) => self.search_columns(search_for, &[column], keyword_only).await,
+            None => self.search_with_column_ids(search_for, None, keyword_only).await,
+        }
+    }
+
+    /// Search for keywords or phrases restricted to a set of columns.
+    ///
+    /// Works like [`search`](Self::search), but accepts several column names and/or glob
+    /// patterns. Each entry is either an exact column name or a pattern using `*` (any
+    /// sequence) and `?` (single character) wildcards, e.g. `"http_*"` or `"headers.*"`.
+    /// Results from all selected columns are merged into a single `SearchResult`.
+    ///
+    /// Each selected column's bloom filter is checked before any chunk is read, so
+    /// columns that cannot contain the keyword are skipped cheaply.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_for` - The text to search for (keyword or phrase)
+    /// * `columns` - Column names and/or glob patterns. Entries that match no indexed
+    ///   column are ignored; if nothing matches, the result is `found: false`.
+    /// * `keyword_only` - `true` for exact keyword search, `false` for phrase search
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("logs.parquet", None).await?;
+    ///
+    /// // Search the "message" column and every column starting with "http_"
+    /// let result = searcher.search_columns("timeout", &["message", "http_*"], true).await?;
+    ///
+    /// if let Some(verified) = &result.verified_matches {
+    ///     println!("Found in columns: {:?}", verified.columns);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_columns(
+        &self,
+        search_for: &str,
+        columns: &[&str],
+        keyword_only: bool,
+    ) -> Result<SearchResult, Error> {
+        let column_ids = self.resolve_column_ids(columns);
+        self.search_with_column_ids(search_for, Some(&column_ids), keyword_only).await
+    }
+
+    /// Shared implementation of [`search`](Self::search) and [`search_columns`](Self::search_columns)
+    /// once column selections have been resolved to column IDs.
+    async fn search_with_column_ids(
+        &self,
+        search_for: &str,
+        in_columns: Option<&[u32]>,
+        keyword_only: bool,
+    ) -> Result<SearchResult, Error> {
         if keyword_only {
             // Exact keyword search - all matches are verified
             let old_result = self.search_keyword_internal_zerocopy(search_for, in_columns).await?;
@@ -614,7 +845,7 @@ impl KeywordSearcher {
     ///
     /// * `keyword` - The exact keyword to search for (case-sensitive). Must be a
     ///   complete token as it was indexed, not a partial match or pattern.
-    /// * `column_filter` - Optional column name to restrict search to a specific column.
+    /// * `column_filter` - Optional set of column IDs to restrict the search to.
     ///   If `None`, searches all columns using the global bloom filter.
     ///
     /// # Returns
@@ -637,21 +868,13 @@ impl KeywordSearcher {
     async fn search_keyword_internal_zerocopy(
         &self,
         keyword: &str,
-        column_filter: Option<&str>
-    ) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+        column_filter: Option<&[u32]>
+    ) -> Result<SearchResult, Error> {
         // Step 1: Check appropriate filter
-        if let Some(col_name) = column_filter {
-            if let Some(filter) = self.filters.column_filters.get(col_name) {
-                if !filter.might_contain(keyword) {
-                    return Ok(SearchResult {
-                        query: keyword.to_string(),
-                        found: false,
-                        tokens: vec![keyword.to_string()],
-                        verified_matches: None,
-                        needs_verification: None,
-                    });
-                }
-            } else {
+        let candidate_columns = column_filter
+            .map(|column_ids| self.columns_that_might_contain(keyword, column_ids));
+        if let Some(candidates) = &candidate_columns {
+            if candidates.is_empty() {
                 return Ok(SearchResult {
                     query: keyword.to_string(),
                     found: false,
@@ -707,7 +930,7 @@ impl KeywordSearcher {
 
         // Access keyword section directly in aligned buffer
         let archived_keywords: &Archived<Vec<String>> = rkyv::access(&buffer[..keyword_length])
-            .map_err(|e: RkyvError| format!("Failed to deserialize keyword list: {}", e))?;
+            .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize keyword list: {}", e)))?;
 
         // Binary search (zero-copy)
         let position = match archived_keywords.binary_search_by(|k| k.as_str().cmp(keyword)) {
@@ -723,27 +946,29 @@ impl KeywordSearcher {
             }
         };
 
-        // Access data section directly in aligned buffer (no additional copy)
-        let archived_data: &Archived<Vec<KeywordDataFlat>> = rkyv::access(&buffer[keyword_length..])
-            .map_err(|e: RkyvError| format!("Failed to deserialize chunk data: {}", e))?;
+        // Access data section directly in aligned buffer (no additional copy, except for
+        // chunks of version 1 indexes, which are converted to the current layout)
+        let upgraded;
+        let data_bytes = if self.filters.version == 1 {
+            upgraded = format_v1::upgrade_chunk_data(&buffer[keyword_length..])?;
+            &upgraded[..]
+        } else {
+            &buffer[keyword_length..]
+        };
+        let archived_data: &Archived<Vec<KeywordDataFlat>> = rkyv::access(data_bytes)
+            .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize chunk data: {}", e)))?;
 
         // Get only the one item we need (still zero-copy)
         let archived_item = &archived_data[position];
+        archived_item.validate_rows()?;
 
         // Determine which column(s) to process
-        let filter_to_column_id: Option<u32> = if let Some(col_name) = column_filter {
-            let found_id = archived_item.columns.iter()
-                .map(|col| col.column_id.to_native())
-                .find(|&id| {
-                    if id == 0 { return false; }
-                    if let Some(name) = self.filters.column_pool.get(id) {
-                        name == col_name
-                    } else {
-                        false
-                    }
-                });
+        let use_aggregate = candidate_columns.is_none();
+        if let Some(candidates) = &candidate_columns {
+            let any_present = archived_item.columns.iter()
+                .any(|col| candidates.contains(&col.column_id.to_native()));
 
-            if found_id.is_none() {
+            if !any_present {
                 return Ok(SearchResult {
                     query: keyword.to_string(),
                     found: false,
@@ -752,10 +977,7 @@ impl KeywordSearcher {
                     needs_verification: None,
                 });
             }
-            found_id
-        } else {
-            Some(0)
-        };
+        }
 
         // Step 5: Build result - convert ONLY this one keyword's data
         let mut column_details = Vec::new();
@@ -769,36 +991,42 @@ impl KeywordSearcher {
         for col in archived_item.columns.iter() {
             let column_id: u32 = col.column_id.to_native();
 
-            if let Some(target_id) = filter_to_column_id {
-                if column_id != target_id {
-                    continue;
-                }
+            if !self.column_selected(column_id, candidate_columns.as_deref()) {
+                continue;
             }
 
             let column_name = if column_id == 0 {
                 "_all_columns_aggregate_".to_string()
             } else {
                 self.filters.column_pool.get(column_id)
-                    .ok_or("Column not found in pool")?
+                    .ok_or_else(|| Error::CorruptIndex("Column not found in pool".to_string()))?
                     .to_string()
             };
 
             let mut row_groups = Vec::new();
 
             for rg in col.row_groups.iter() {
-                let row_group_id: u16 = rg.row_group_id.to_native();
+                let row_group_id: u32 = rg.row_group_id.to_native();
                 let mut row_ranges = Vec::new();
 
-                for flat_row in rg.rows.iter() {
-                    let row: u32 = flat_row.row.to_native();
-                    let additional_rows: u32 = flat_row.additional_rows.to_native();
-                    let splits_matched: u16 = flat_row.splits_matched.to_native();
-                    let parent_chunk: Option<u16> = flat_row.parent_chunk.as_ref().map(|c| c.to_native());
-                    let parent_position: Option<u16> = flat_row.parent_position.as_ref().map(|p| p.to_native());
+                // Coarse keywords only record that the row group contains them
+                if archived_item.coarse {
+                    total_occurrences = total_occurrences.saturating_add(rg.occurrences() as u64);
+                }
 
+                for flat_row in rg.rows() {
+                    let row: u32 = flat_row.row;
+                    let additional_rows: u32 = flat_row.additional_rows;
+                    let splits_matched: u16 = flat_row.splits_matched;
+                    let parent_chunk: Option<u32> = flat_row.parent_chunk;
+                    let parent_position: Option<u32> = flat_row.parent_position;
+
+                    let end_row = row.checked_add(additional_rows).ok_or_else(|| Error::CorruptIndex(
+                        format!("Row range {}+{} out of bounds", row, additional_rows)
+                    ))?;
                     row_ranges.push(RowRange {
                         start_row: row,
-                        end_row: row + additional_rows,
+                        end_row,
                         splits_matched,
                         parent_chunk,
                         parent_position,
@@ -810,30 +1038,53 @@ impl KeywordSearcher {
                 row_groups.push(RowGroupLocation {
                     row_group_id,
                     row_ranges,
+                    coarse: archived_item.coarse,
+                    pages: self.page_spans(column_id, row_group_id, rg.pages()),
                 });
             }
 
+            // Row-group granularity indexes store a bitmap of row groups instead
+            if let Some(bitmap) = col.row_group_bitmap.as_ref() {
+                total_occurrences = total_occurrences.saturating_add(bitmap.occurrences.to_native() as u64);
+                row_groups.extend(bitmap.row_groups().map(|row_group_id| RowGroupLocation {
+                    row_group_id,
+                    row_ranges: Vec::new(),
+                    coarse: true,
+                    pages: Vec::new(),
+                }));
+            }
+
             column_details.push(ColumnLocation {
                 column_name,
                 row_groups,
             });
         }
 
-        if filter_to_column_id == Some(0) && !column_details.is_empty() {
+        if use_aggregate && !column_details.is_empty() {
             let aggregate_row_groups = column_details[0].row_groups.clone();
             column_details.clear();
 
             for column_id in all_column_ids {
                 if let Some(column_name) = self.filters.column_pool.get(column_id) {
+                    // The aggregate has no pages: each column gets its own
+                    let mut row_groups = aggregate_row_groups.clone();
+                    if !self.filters.page_tables.is_empty()
+                        && let Some(col) = archived_item.columns.iter().find(|col| col.column_id.to_native() == column_id) {
+                        for rg in &mut row_groups {
+                            if let Some(column_rg) = col.row_groups.iter().find(|column_rg| column_rg.row_group_id.to_native() == rg.row_group_id) {
+                                rg.pages = self.page_spans(column_id, rg.row_group_id, column_rg.pages());
+                            }
+                        }
+                    }
                     column_details.push(ColumnLocation {
                         column_name: column_name.to_string(),
-                        row_groups: aggregate_row_groups.clone(),
+                        row_groups,
                     });
                 }
             }
         }
 
-        let columns: Vec<String> = if filter_to_column_id == Some(0) {
+        let columns: Vec<String> = if use_aggregate {
             archived_item.columns.iter()
                 .map(|col| col.column_id.to_native())
                 .filter(|&id| id != 0)
@@ -869,7 +1120,7 @@ impl KeywordSearcher {
     /// # Arguments
     ///
     /// * `keyword` - The exact keyword to search for (case-sensitive)
-    /// * `column_filter` - Optional column name to restrict search to a specific column
+    /// * `column_filter` - Optional set of column IDs to restrict the search to
     ///
     /// # Returns
     ///
@@ -891,23 +1142,16 @@ impl KeywordSearcher {
     async fn search_keyword_internal(
         &self,
         keyword: &str,
-        column_filter: Option<&str>
-    ) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+        column_filter: Option<&[u32]>
+    ) -> Result<SearchResult, Error> {
         // Step 1: Check appropriate filter based on whether we have a column filter
-        if let Some(col_name) = column_filter {
-            // When filtering to a specific column, check that column's bloom filter first
-            if let Some(filter) = self.filters.column_filters.get(col_name) {
-                if !filter.might_contain(keyword) {
-                    return Ok(SearchResult {
-                        query: keyword.to_string(),
-                        found: false,
-                        tokens: vec![keyword.to_string()],
-                        verified_matches: None,
-                        needs_verification: None,
-                    });
-                }
-            } else {
-                // Column doesn't exist in the index
+        // When filtering to specific columns, check each column's bloom filter first and
+        // keep only the columns that might contain the keyword
+        let candidate_columns = column_filter
+            .map(|column_ids| self.columns_that_might_contain(keyword, column_ids));
+        if let Some(candidates) = &candidate_columns {
+            if candidates.is_empty() {
+                // No requested column can contain the keyword (or none exist in the index)
                 return Ok(SearchResult {
                     query: keyword.to_string(),
                     found: false,
@@ -968,24 +1212,14 @@ impl KeywordSearcher {
 
         // Determine which column(s) to process
         // When column_filter is None, use column_id 0 (aggregate of all columns)
-        // When column_filter is Some, process only that specific column
-        let filter_to_column_id: Option<u32> = if let Some(col_name) = column_filter {
-            // Find the column_id for the requested column
-            let found_id = archived_data.columns.iter()
-                .map(|col| col.column_id)
-                .find(|&id| {
-                    if id == 0 {
-                        return false; // Never match column_id 0 here
-                    }
-                    if let Some(name) = self.filters.column_pool.get(id) {
-                        name == col_name
-                    } else {
-                        false
-                    }
-                });
-
-            if found_id.is_none() {
-                // Column not found in results - return not found
+        // When column_filter is Some, process only the candidate columns
+        let use_aggregate = candidate_columns.is_none();
+        if let Some(candidates) = &candidate_columns {
+            let any_present = archived_data.columns.iter()
+                .any(|col| candidates.contains(&col.column_id));
+
+            if !any_present {
+                // None of the requested columns contain the keyword - return not found
                 return Ok(SearchResult {
                     query: keyword.to_string(),
                     found: false,
@@ -994,11 +1228,7 @@ impl KeywordSearcher {
                     needs_verification: None,
                 });
             }
-            found_id
-        } else {
-            // No filter - use column_id 0 (aggregate)
-            Some(0)
-        };
+        }
 
         // Step 5: Convert to owned types and build result
         let mut column_details = Vec::new();
@@ -1014,10 +1244,8 @@ impl KeywordSearcher {
             let column_id: u32 = col.column_id;
 
             // Apply column filter
-            if let Some(target_id) = filter_to_column_id {
-                if column_id != target_id {
-                    continue;
-                }
+            if !self.column_selected(column_id, candidate_columns.as_deref()) {
+                continue;
             }
 
             // Get column name
@@ -1026,26 +1254,34 @@ impl KeywordSearcher {
                 "_all_columns_aggregate_".to_string()
             } else {
                 self.filters.column_pool.get(column_id)
-                    .ok_or("Column not found in pool")?
+                    .ok_or_else(|| Error::CorruptIndex("Column not found in pool".to_string()))?
                     .to_string()
             };
 
             let mut row_groups = Vec::new();
 
             for rg in &col.row_groups {
-                let row_group_id: u16 = rg.row_group_id;
+                let row_group_id: u32 = rg.row_group_id;
                 let mut row_ranges = Vec::new();
 
-                for flat_row in &rg.rows {
+                // Coarse keywords only record that the row group contains them
+                if archived_data.coarse {
+                    total_occurrences = total_occurrences.saturating_add(rg.occurrences() as u64);
+                }
+
+                for flat_row in rg.rows() {
                     let row: u32 = flat_row.row;
                     let additional_rows: u32 = flat_row.additional_rows;
                     let splits_matched: u16 = flat_row.splits_matched;
-                    let parent_chunk: Option<u16> = flat_row.parent_chunk;
-                    let parent_position: Option<u16> = flat_row.parent_position;
+                    let parent_chunk: Option<u32> = flat_row.parent_chunk;
+                    let parent_position: Option<u32> = flat_row.parent_position;
 
+                    let end_row = row.checked_add(additional_rows).ok_or_else(|| Error::CorruptIndex(
+                        format!("Row range {}+{} out of bounds", row, additional_rows)
+                    ))?;
                     row_ranges.push(RowRange {
                         start_row: row,
-                        end_row: row + additional_rows,
+                        end_row,
                         splits_matched,
                         parent_chunk,
                         parent_position,
@@ -1057,9 +1293,22 @@ impl KeywordSearcher {
                 row_groups.push(RowGroupLocation {
                     row_group_id,
                     row_ranges,
+                    coarse: archived_data.coarse,
+                    pages: self.page_spans(column_id, row_group_id, rg.pages().iter().copied()),
                 });
             }
 
+            // Row-group granularity indexes store a bitmap of row groups instead
+            if let Some(bitmap) = &col.row_group_bitmap {
+                total_occurrences = total_occurrences.saturating_add(bitmap.occurrences as u64);
+                row_groups.extend(bitmap.row_groups().map(|row_group_id| RowGroupLocation {
+                    row_group_id,
+                    row_ranges: Vec::new(),
+                    coarse: true,
+                    pages: Vec::new(),
+                }));
+            }
+
             column_details.push(ColumnLocation {
                 column_name,
                 row_groups,
@@ -1086,7 +1335,7 @@ impl KeywordSearcher {
 
         // If we used column_id 0 (aggregate), expand it to actual columns
         // This ensures column_details has entries for each actual column, not just "_all_columns_aggregate_"
-        if filter_to_column_id == Some(0) && !column_details.is_empty() {
+        if use_aggregate && !column_details.is_empty() {
             // Extract the aggregate row group data
             let aggregate_row_groups = column_details[0].row_groups.clone();
             column_details.clear();
@@ -1094,9 +1343,19 @@ impl KeywordSearcher {
             // Create one ColumnLocation per actual column, all sharing the same row group data
             for column_id in all_column_ids {
                 if let Some(column_name) = self.filters.column_pool.get(column_id) {
+                    // The aggregate has no pages: each column gets its own
+                    let mut row_groups = aggregate_row_groups.clone();
+                    if !self.filters.page_tables.is_empty()
+                        && let Some(col) = archived_data.columns.iter().find(|col| col.column_id == column_id) {
+                        for rg in &mut row_groups {
+                            if let Some(column_rg) = col.row_groups.iter().find(|column_rg| column_rg.row_group_id == rg.row_group_id) {
+                                rg.pages = self.page_spans(column_id, rg.row_group_id, column_rg.pages().iter().copied());
+                            }
+                        }
+                    }
                     column_details.push(ColumnLocation {
                         column_name: column_name.to_string(),
-                        row_groups: aggregate_row_groups.clone(),
+                        row_groups,
                     });
                 }
             }
@@ -1106,7 +1365,7 @@ impl KeywordSearcher {
         // Build column list
         // When we used column_id 0, we need to get the actual column names from archived_data.columns
         // When we used a specific column, we already have it in column_details
-        let columns: Vec<String> = if filter_to_column_id == Some(0) {
+        let columns: Vec<String> = if use_aggregate {
             // We used the aggregate (column_id 0), so get actual column names
             archived_data.columns.iter()
                 .map(|col| col.column_id)
@@ -1135,6 +1394,50 @@ impl KeywordSearcher {
         })
     }
 
+    /// Resolve column names and glob patterns to column IDs from the column pool.
+    ///
+    /// Exact names and patterns may overlap; each matching column is returned once,
+    /// in column pool order. Column ID 0 (the aggregate) is never returned.
+    pub(super) fn resolve_column_ids(&self, columns: &[&str]) -> Vec<u32> {
+        self.filters.column_pool.strings.iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, name)| columns.iter().any(|pattern| {
+                if is_glob_pattern(pattern) {
+                    glob_match(pattern, name)
+                } else {
+                    *pattern == name.as_str()
+                }
+            }))
+            .map(|(id, _)| id as u32)
+            .collect()
+    }
+
+    /// Narrow a set of column IDs down to those whose column filter might contain the keyword.
+    ///
+    /// Columns without a filter (not indexed) are dropped.
+    fn columns_that_might_contain(&self, keyword: &str, column_ids: &[u32]) -> Vec<u32> {
+        column_ids.iter()
+            .copied()
+            .filter(|&id| {
+                self.filters.column_pool.get(id)
+                    .and_then(|name| self.filters.column_filters.get(name))
+                    .is_some_and(|filter| filter.might_contain(keyword))
+            })
+            .collect()
+    }
+
+    /// Whether a column entry of a keyword's data should be included in a result.
+    ///
+    /// Without candidate columns only the aggregate (column ID 0) is used; otherwise only
+    /// the candidate columns are.
+    fn column_selected(&self, column_id: u32, candidate_columns: Option<&[u32]>) -> bool {
+        match candidate_columns {
+            Some(candidates) => candidates.contains(&column_id),
+            None => column_id == 0,
+        }
+    }
+
     /// Find the chunk that potentially contains a keyword using binary search.
     ///
     /// Performs binary search on the chunk index to locate which chunk should contain
@@ -1167,7 +1470,7 @@ impl KeywordSearcher {
     ///
     /// O(log n) where n is the number of chunks, typically very fast since
     /// chunk count is usually small (< 100 for most indexes)
-    fn find_chunk_for_keyword(&self, keyword: &str) -> Option<(u16, &ChunkInfo)> {
+    pub(super) fn find_chunk_for_keyword(&self, keyword: &str) -> Option<(u32, &ChunkInfo)> {
         // Binary search to find the right chunk
         // We want to find the chunk where: chunk.start_keyword <= keyword < next_chunk.start_keyword
 
@@ -1179,14 +1482,14 @@ impl KeywordSearcher {
         match self.filters.chunk_index.binary_search_by(|chunk| {
             chunk.start_keyword.as_str().cmp(keyword)
         }) {
-            Ok(idx) => Some((idx as u16, &self.filters.chunk_index[idx])),
+            Ok(idx) => Some((idx as u32, &self.filters.chunk_index[idx])),
             Err(idx) => {
                 if idx == 0 {
                     // Keyword is before first chunk - still check first chunk
                     Some((0, &self.filters.chunk_index[0]))
                 } else {
                     // Keyword belongs to previous chunk
-                    Some(((idx - 1) as u16, &self.filters.chunk_index[idx - 1]))
+                    Some(((idx - 1) as u32, &self.filters.chunk_index[idx - 1]))
                 }
             }
         }
@@ -1237,7 +1540,7 @@ impl KeywordSearcher {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn search_in_column(&self, keyword: &str, column_name: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn search_in_column(&self, keyword: &str, column_name: &str) -> Result<bool, Error> {
         // Check column filter first
         if let Some(filter) = self.filters.column_filters.get(column_name) {
             if !filter.might_contain(keyword) {
@@ -1248,15 +1551,115 @@ impl KeywordSearcher {
         // Do filtered search for this specific column
         let result = self.search(keyword, Some(column_name), true).await?;
 
-        if !result.found {
-            return Ok(false);
-        }
-
         // Check if keyword exists in the specified column
-        let data = result.verified_matches.as_ref().expect("No verified matches");
+        let Some(data) = result.verified_matches.as_ref() else {
+            return Ok(false);
+        };
         Ok(data.columns.iter().any(|col| col == column_name))
     }
 
+    /// Get the per-column keyword statistics computed when the index was built.
+    ///
+    /// # Returns
+    ///
+    /// One [`ColumnStatisticsInfo`] per indexed column, in column pool order, with the
+    /// number of distinct keywords, the number of rows containing at least one keyword,
+    /// and the most frequent keywords.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// for stats in searcher.column_statistics() {
+    ///     println!("{}: {} distinct keywords in {} rows",
+    ///         stats.column_name, stats.distinct_keywords, stats.rows_with_keywords);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn column_statistics(&self) -> Vec<ColumnStatisticsInfo> {
+        self.filters.column_statistics.iter()
+            .filter_map(|stats| {
+                let column_name = self.filters.column_pool.get(stats.column_id)?;
+                Some(ColumnStatisticsInfo {
+                    column_name: column_name.to_string(),
+                    distinct_keywords: stats.distinct_keywords,
+                    rows_with_keywords: stats.rows_with_keywords,
+                    top_keywords: stats.top_keywords.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Get the decisions of the cardinality sampling pass run when the index was built.
+    ///
+    /// # Returns
+    ///
+    /// One [`ColumnDecision`] per sampled column, in file column order, including columns
+    /// that were excluded from the index. Empty unless the index was built with
+    /// [`BuildOptions::cardinality_check`](crate::BuildOptions::cardinality_check).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// for decision in searcher.column_decisions() {
+    ///     println!("{}: {:?} ({})", decision.column_name, decision.action, decision.reason);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn column_decisions(&self) -> &[ColumnDecision] {
+        &self.filters.column_decisions
+    }
+
+    /// Rows of data pages of a column chunk, from the page tables of the index.
+    ///
+    /// Empty when no page table was recorded for the column chunk.
+    pub(super) fn page_spans(&self, column_id: u32, row_group_id: u32, pages: impl Iterator<Item = u32>) -> Vec<PageSpan> {
+        let Some(page_table) = find_page_table(&self.filters.page_tables, column_id, row_group_id) else {
+            return Vec::new();
+        };
+        pages
+            .filter_map(|page| {
+                let (start_row, end_row) = page_table.page_rows(page)?;
+                Some(PageSpan { page, start_row, end_row })
+            })
+            .collect()
+    }
+
+    /// Get the granularity the index was built with.
+    ///
+    /// With [`IndexGranularity::RowGroups`] every search result location is
+    /// [`coarse`](crate::searching::search_results::RowGroupLocation::coarse).
+    pub fn granularity(&self) -> IndexGranularity {
+        self.filters.granularity
+    }
+
+    /// Get the batches of rows a CSV or JSON lines file was indexed in.
+    ///
+    /// `None` for indexes of Parquet files. For text files, the row group IDs of search
+    /// results are batch numbers (see [`TextLayout`]).
+    pub fn text_layout(&self) -> Option<&TextLayout> {
+        self.filters.text_layout.as_ref()
+    }
+
+    /// Get the configuration the index was built with.
+    ///
+    /// `None` unless the index was built with an
+    /// [`IndexBuilder`](crate::index_builder::IndexBuilder). Pass it to
+    /// [`IndexBuilder::from_config`](crate::index_builder::IndexBuilder::from_config) to
+    /// rebuild the index the same way.
+    pub fn build_config(&self) -> Option<&BuildConfig> {
+        self.filters.build_config.as_ref()
+    }
+
     /// Get information about the index
     /// Get metadata about the index.
     ///
@@ -1399,85 +1802,49 @@ impl KeywordSearcher {
             });
         }
 
-        // Build per-column row sets for each result
-        // Structure: result_idx -> column_name -> row_group_id -> HashSet<row>
-        let mut per_result_columns: Vec<HashMap<String, HashMap<u16, std::collections::HashSet<u32>>>> = Vec::new();
-
-        for result in results {
-            let mut column_map: HashMap<String, HashMap<u16, std::collections::HashSet<u32>>> = HashMap::new();
-
-            if let Some(data) = &result.verified_matches {
-                for col in &data.column_details {
-                    let rg_map = column_map.entry(col.column_name.clone()).or_insert_with(HashMap::new);
-
-                    for rg in &col.row_groups {
-                        let rows = rg_map.entry(rg.row_group_id).or_insert_with(std::collections::HashSet::new);
-                        for range in &rg.row_ranges {
-                            for row in range.start_row..=range.end_row {
-                                rows.insert(row);
-                            }
-                        }
-                    }
-                }
-            }
-
-            per_result_columns.push(column_map);
-        }
+        // Build the rows of each result per row group, across all of its columns
+        // Structure: result_idx -> row_group_id -> rows (None = coarse, any row)
+        let per_result_rows: Vec<HashMap<u32, Option<std::collections::HashSet<u32>>>> = results.iter()
+            .map(|result| result_row_groups(result.verified_matches.as_ref()))
+            .collect();
 
         // Find intersection: rows that appear across all results in ANY column combination
-        let mut combined_row_groups: HashMap<u16, std::collections::HashSet<u32>> = HashMap::new();
+        let mut row_groups = Vec::new();
 
         // Start with all row groups from first result
-        if let Some(first_columns) = per_result_columns.first() {
-            for rg_map in first_columns.values() {
-                for (&rg_id, first_rows) in rg_map {
-                    // For each row in this row group, check if it exists in remaining results
-                    for &row in first_rows {
-                        let mut found_in_all = true;
-
-                        // Check if this row exists in ANY column of each remaining result
-                        for other_columns in &per_result_columns[1..] {
-                            let mut found_in_this_result = false;
-
-                            for other_rg_map in other_columns.values() {
-                                if let Some(other_rows) = other_rg_map.get(&rg_id) {
-                                    if other_rows.contains(&row) {
-                                        found_in_this_result = true;
-                                        break;
-                                    }
-                                }
-                            }
-
-                            if !found_in_this_result {
-                                found_in_all = false;
-                                break;
-                            }
+        if let Some((first, others)) = per_result_rows.split_first() {
+            for (&rg_id, first_rows) in first {
+                let mut rows = first_rows.clone();
+                let mut coarse = first_rows.is_none();
+                let mut found_in_all = true;
+
+                // Keep the rows that exist in ANY column of each remaining result
+                for other in others {
+                    match other.get(&rg_id) {
+                        None => {
+                            found_in_all = false;
+                            break;
                         }
-
-                        if found_in_all {
-                            combined_row_groups
-                                .entry(rg_id)
-                                .or_insert_with(std::collections::HashSet::new)
-                                .insert(row);
+                        Some(None) => coarse = true,
+                        Some(Some(other_rows)) => {
+                            rows = Some(match rows {
+                                Some(rows) => rows.intersection(other_rows).copied().collect(),
+                                None => other_rows.clone(),
+                            });
                         }
                     }
                 }
-            }
-        }
 
-        // Convert to result format
-        let mut row_groups = Vec::new();
-        for (rg_id, rows) in combined_row_groups {
-            let mut sorted_rows: Vec<u32> = rows.into_iter().collect();
-            sorted_rows.sort_unstable();
-
-            // Combine consecutive rows into ranges
-            let ranges = rows_to_ranges(&sorted_rows);
+                if !found_in_all || rows.as_ref().is_some_and(|rows| rows.is_empty()) {
+                    continue;
+                }
 
-            row_groups.push(CombinedRowGroupLocation {
-                row_group_id: rg_id,
-                row_ranges: ranges,
-            });
+                row_groups.push(CombinedRowGroupLocation {
+                    row_group_id: rg_id,
+                    row_ranges: rows.map(row_set_to_ranges).unwrap_or_default(),
+                    coarse,
+                });
+            }
         }
 
         row_groups.sort_by_key(|rg| rg.row_group_id);
@@ -1572,7 +1939,7 @@ impl KeywordSearcher {
             return None;
         }
 
-        let mut combined_row_groups: HashMap<u16, std::collections::HashSet<u32>> =
+        let mut combined_row_groups: HashMap<u32, Option<std::collections::HashSet<u32>>> =
             HashMap::new();
 
         // Union all rows from all results
@@ -1582,32 +1949,25 @@ impl KeywordSearcher {
             }
 
             let data = result.verified_matches.as_ref()?;
-            for col in &data.column_details {
-                for rg in &col.row_groups {
-                    let rows = combined_row_groups.entry(rg.row_group_id).or_insert_with(std::collections::HashSet::new);
-                    for range in &rg.row_ranges {
-                        for row in range.start_row..=range.end_row {
-                            rows.insert(row);
-                        }
-                    }
+            for (rg_id, rows) in result_row_groups(Some(data)) {
+                let combined = combined_row_groups.entry(rg_id)
+                    .or_insert_with(|| Some(std::collections::HashSet::new()));
+                match (combined.as_mut(), rows) {
+                    (Some(combined_rows), Some(rows)) => combined_rows.extend(rows),
+                    // A coarse row group makes every row a candidate
+                    _ => *combined = None,
                 }
             }
         }
 
         // Convert to result format
-        let mut row_groups = Vec::new();
-        for (rg_id, rows) in combined_row_groups {
-            let mut sorted_rows: Vec<u32> = rows.into_iter().collect();
-            sorted_rows.sort_unstable();
-
-            // Combine consecutive rows into ranges
-            let ranges = rows_to_ranges(&sorted_rows);
-
-            row_groups.push(CombinedRowGroupLocation {
+        let mut row_groups: Vec<CombinedRowGroupLocation> = combined_row_groups.into_iter()
+            .map(|(rg_id, rows)| CombinedRowGroupLocation {
                 row_group_id: rg_id,
-                row_ranges: ranges,
-            });
-        }
+                coarse: rows.is_none(),
+                row_ranges: rows.map(row_set_to_ranges).unwrap_or_default(),
+            })
+            .collect();
 
         row_groups.sort_by_key(|rg| rg.row_group_id);
 
@@ -1687,7 +2047,7 @@ impl KeywordSearcher {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn validate_index(&self, source: &ParquetSource) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn validate_index(&self, source: &ParquetSource) -> Result<bool, Error> {
         match source {
             ParquetSource::Path(path) => {
                 use crate::utils::file_interaction_local_and_cloud::get_object_store;
@@ -1851,7 +2211,7 @@ impl KeywordSearcher {
     ///     Ok(())
     /// }
     /// ```
-    async fn search_phrase_internal(&self, phrase: &str, column_filter: Option<&str>) -> Result<SearchResult, Box<dyn std::error::Error + Send + Sync>> {
+    async fn search_phrase_internal(&self, phrase: &str, column_filter: Option<&[u32]>) -> Result<SearchResult, Error> {
         // Split the phrase using the same logic as the index
         let tokens = self.split_phrase(phrase);
 
@@ -1905,20 +2265,20 @@ impl KeywordSearcher {
         }
 
         // Find rows where ALL tokens exist in the same column and check parents
-        let (confirmed, needs_verification) =
+        let (confirmed, needs_verification, coarse_row_groups) =
             self.find_and_verify_multi_token_matches(phrase, &token_results).await?;
 
-        let found = !confirmed.is_empty() || !needs_verification.is_empty();
+        let found = !confirmed.is_empty() || !needs_verification.is_empty() || !coarse_row_groups.is_empty();
 
         // Convert confirmed matches and needs_verification to KeywordLocationData
         let verified_data = if !confirmed.is_empty() {
-            Some(self.potential_matches_to_location_data(&confirmed))
+            Some(self.potential_matches_to_location_data(&confirmed, &[]))
         } else {
             None
         };
 
-        let needs_verification_data = if !needs_verification.is_empty() {
-            Some(self.potential_matches_to_location_data(&needs_verification))
+        let needs_verification_data = if !needs_verification.is_empty() || !coarse_row_groups.is_empty() {
+            Some(self.potential_matches_to_location_data(&needs_verification, &coarse_row_groups))
         } else {
             None
         };
@@ -2017,16 +2377,29 @@ impl KeywordSearcher {
     /// Convert a list of potential matches to KeywordLocationData structure.
     ///
     /// Takes a flat list of matches (column, row_group, row) and organizes them
-    /// into the hierarchical structure used by KeywordLocationData.
-    fn potential_matches_to_location_data(&self, matches: &[PotentialMatch]) -> KeywordLocationData {
+    /// into the hierarchical structure used by KeywordLocationData. The coarse row
+    /// groups (column, row_group) are added without rows.
+    fn potential_matches_to_location_data(
+        &self,
+        matches: &[PotentialMatch],
+        coarse_row_groups: &[(String, u32)],
+    ) -> KeywordLocationData {
         use std::collections::HashMap;
 
         // Group by column
-        let mut column_map: HashMap<String, HashMap<u16, Vec<u32>>> = HashMap::new();
+        let mut column_map: HashMap<String, HashMap<u32, Vec<u32>>> = HashMap::new();
         let mut all_columns = std::collections::HashSet::new();
         let mut total_rows = 0u64;
         let mut splits_matched = 0u16;
 
+        for (column_name, row_group_id) in coarse_row_groups {
+            all_columns.insert(column_name.clone());
+            column_map.entry(column_name.clone())
+                .or_default()
+                .entry(*row_group_id)
+                .or_default();
+        }
+
         for m in matches {
             all_columns.insert(m.column_name.clone());
             splits_matched |= 1 << m.split_level;
@@ -2088,6 +2461,8 @@ impl KeywordSearcher {
                 row_groups.push(RowGroupLocation {
                     row_group_id,
                     row_ranges,
+                    coarse: sorted_rows.is_empty(),
+                    pages: Vec::new(),
                 });
             }
 
@@ -2122,9 +2497,11 @@ impl KeywordSearcher {
     ///
     /// # Returns
     ///
-    /// `Ok((confirmed, needs_verification))` tuple containing:
+    /// `Ok((confirmed, needs_verification, coarse_row_groups))` tuple containing:
     /// * `confirmed` - Matches verified via parent keyword relationships
     /// * `needs_verification` - Potential matches requiring Parquet verification
+    /// * `coarse_row_groups` - (column, row group) pairs where every token has only a
+    ///   coarse location, so every row of the row group needs verification
     ///
     /// # Errors
     ///
@@ -2146,19 +2523,26 @@ impl KeywordSearcher {
     ///
     /// Uses batch parent lookups to minimize I/O. Groups all parent lookups
     /// for a column together and reads each chunk only once.
+    ///
+    /// # Coarse Tokens
+    ///
+    /// Tokens indexed at row group granularity have no rows or parents to check. In a
+    /// row group where any token is coarse, the rows of the remaining tokens need
+    /// verification, or the whole row group if every token is coarse.
     async fn find_and_verify_multi_token_matches(
         &self,
         phrase: &str,
         token_results: &[SearchResult],
-    ) -> Result<(Vec<PotentialMatch>, Vec<PotentialMatch>), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<PhraseMatches, Error> {
         let mut confirmed_matches = Vec::new();
         let mut needs_verification = Vec::new();
+        let mut coarse_row_groups = Vec::new();
 
         // Get the first token's results as the base
         let base_result = &token_results[0];
         let base_data = match &base_result.verified_matches {
             Some(d) => d,
-            None => return Ok((confirmed_matches, needs_verification)),
+            None => return Ok((confirmed_matches, needs_verification, coarse_row_groups)),
         };
 
         // For each column in the base result
@@ -2199,8 +2583,41 @@ impl KeywordSearcher {
             for rg in &col_detail.row_groups {
                 let row_group_id = rg.row_group_id;
 
+                let other_rgs: Option<Vec<&RowGroupLocation>> = other_token_column_data.iter()
+                    .map(|other_col| other_col.row_groups.iter().find(|r| r.row_group_id == row_group_id))
+                    .collect();
+                let Some(other_rgs) = other_rgs else { continue };
+
+                if rg.coarse || other_rgs.iter().any(|r| r.coarse) {
+                    // Candidates are the rows shared by all tokens that have rows
+                    let mut candidates: Option<std::collections::HashSet<u32>> = None;
+                    for token_rg in std::iter::once(rg).chain(other_rgs).filter(|r| !r.coarse) {
+                        let rows: std::collections::HashSet<u32> = token_rg.row_ranges.iter()
+                            .flat_map(|range| range.start_row..=range.end_row)
+                            .collect();
+                        candidates = Some(match candidates {
+                            Some(candidates) => candidates.intersection(&rows).copied().collect(),
+                            None => rows,
+                        });
+                    }
+
+                    match candidates {
+                        None => coarse_row_groups.push((column_name.clone(), row_group_id)),
+                        Some(rows) => needs_verification.extend(rows.into_iter().map(|row| PotentialMatch {
+                            column_name: column_name.clone(),
+                            row_group_id,
+                            row,
+                            split_level: self.get_min_phrase_split_level(phrase).unwrap_or(0) as u16,
+                            status: MatchStatus::NeedsVerification {
+                                reason: "A token is only indexed at row group granularity".to_string(),
+                            },
+                        })),
+                    }
+                    continue;
+                }
+
                 // Build map of row -> (split-level, parent chunk, parent position) for base token
-                let base_row_info: HashMap<u32, Vec<(u16, Option<u16>, Option<u16>)>> = rg.row_ranges.iter()
+                let base_row_info: HashMap<u32, Vec<(u16, Option<u32>, Option<u32>)>> = rg.row_ranges.iter()
                     .fold(HashMap::new(), |mut acc, range| {
                         for row in range.start_row..=range.end_row {
                             acc.entry(row)
@@ -2211,7 +2628,7 @@ impl KeywordSearcher {
                     });
 
                 // For each other token, build similar maps
-                let mut all_token_row_info: Vec<HashMap<u32, Vec<(u16, Option<u16>, Option<u16>)>>> = Vec::new();
+                let mut all_token_row_info: Vec<HashMap<u32, Vec<(u16, Option<u32>, Option<u32>)>>> = Vec::new();
                 let mut all_tokens_have_rows = true;
 
                 for other_col in &other_token_column_data {
@@ -2221,7 +2638,7 @@ impl KeywordSearcher {
 
                     match other_rg {
                         Some(rg_data) => {
-                            let row_info: HashMap<u32, Vec<(u16, Option<u16>, Option<u16>)>> = rg_data.row_ranges.iter()
+                            let row_info: HashMap<u32, Vec<(u16, Option<u32>, Option<u32>)>> = rg_data.row_ranges.iter()
                                 .fold(HashMap::new(), |mut acc, range| {
                                     for row in range.start_row..=range.end_row {
                                         acc.entry(row)
@@ -2288,7 +2705,7 @@ impl KeywordSearcher {
                     // Try all combinations of parent info for each token
                     for base_info in base_infos {
                         for other_info_combinations in self.cartesian_product(&other_token_infos) {
-                            let all_parent_refs: Vec<(Option<u16>, Option<u16>)> = std::iter::once((base_info.1, base_info.2))
+                            let all_parent_refs: Vec<(Option<u32>, Option<u32>)> = std::iter::once((base_info.1, base_info.2))
                                 .chain(other_info_combinations.iter().map(|(_, chunk, pos)| (*chunk, *pos)))
                                 .collect();
 
@@ -2335,7 +2752,7 @@ impl KeywordSearcher {
         needs_verification.sort_by_key(|m| (m.column_name.clone(), m.row_group_id, m.row));
         needs_verification.dedup_by_key(|m| (m.column_name.clone(), m.row_group_id, m.row));
 
-        Ok((confirmed_matches, needs_verification))
+        Ok((confirmed_matches, needs_verification, coarse_row_groups))
     }
 
     /// Verify a match using parent keyword information
@@ -2414,8 +2831,8 @@ impl KeywordSearcher {
     async fn verify_match_with_parent(
         &self,
         phrase: &str,
-        parent_refs: &[(Option<u16>, Option<u16>)],
-        parent_keywords: &HashMap<(u16, u16), String>,
+        parent_refs: &[(Option<u32>, Option<u32>)],
+        parent_keywords: &HashMap<(u32, u32), String>,
     ) -> MatchStatus {
         // Check if all tokens have the same parent
         let first_parent = parent_refs[0];
@@ -2597,7 +3014,7 @@ impl KeywordSearcher {
     /// Time complexity: O(∏ |vec_i|) - product of all vector lengths.
     /// Space complexity: Same as time (stores all combinations).
     /// Typically small since tokens rarely appear many times in same row.
-    fn cartesian_product<'a>(&self, vecs: &[&'a Vec<(u16, Option<u16>, Option<u16>)>]) -> Vec<Vec<&'a (u16, Option<u16>, Option<u16>)>> {
+    fn cartesian_product<'a>(&self, vecs: &[&'a Vec<(u16, Option<u32>, Option<u32>)>]) -> Vec<Vec<&'a (u16, Option<u32>, Option<u32>)>> {
         if vecs.is_empty() {
             return vec![Vec::new()];
         }
@@ -1,8 +1,15 @@
+pub mod cardinality;
+pub mod highlight;
 pub mod keyword_search;
 pub mod pruned_reader;
+pub mod pruned_text_reader;
 pub mod search_results;
+pub mod substring;
+pub mod suggest;
+pub mod verify;
+pub mod vocabulary;
 
 // Link to test module (only compiled during tests)
 #[cfg(test)]
 #[path = "tests/mod.rs"]
-mod tests;
\ No newline at end of file
+pub(crate) mod tests;
\ No newline at end of file
@@ -34,13 +34,18 @@
 //! }
 //! ```
 
-use arrow::array::RecordBatch;
+use arrow::array::{RecordBatch, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::error::ArrowError;
 use parquet::arrow::ProjectionMask;
+use crate::error::Error;
+use crate::utils::nested_columns::{leaf_columns, leaf_values, root_indices};
 use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
-use parquet::arrow::arrow_reader::{RowSelection, RowSelector};
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions, RowSelection, RowSelector};
 use parquet::file::reader::FileReader;
 use futures::StreamExt;
-use crate::searching::search_results::{SearchResult, CombinedSearchResult};
+use crate::searching::search_results::{SearchResult, CombinedSearchResult, KeywordLocationData, RowGroupLocation};
 use crate::utils::file_interaction_local_and_cloud::get_object_store;
 use crate::ParquetSource;
 use std::sync::Arc;
@@ -74,7 +79,21 @@ use object_store::path::Path as ObjectPath;
 /// }
 /// ```
 pub struct PrunedParquetReader {
-    source: ParquetSource,
+    pub(super) source: ParquetSource,
+}
+
+/// Row ranges (inclusive) to read for a row group location.
+///
+/// Coarse locations cover the rows of their pages, or the whole row group if no pages
+/// were recorded: the open-ended range is clamped to the row group size when reading.
+pub(super) fn location_ranges(rg: &RowGroupLocation) -> impl Iterator<Item = (u32, u32)> + '_ {
+    let whole_row_group = (rg.coarse && rg.pages.is_empty()).then_some((0, u32::MAX));
+    let pages = rg.pages.iter()
+        .filter(|_| rg.coarse)
+        .map(|page| (page.start_row, page.end_row));
+    whole_row_group.into_iter()
+        .chain(pages)
+        .chain(rg.row_ranges.iter().map(|range| (range.start_row, range.end_row)))
 }
 
 /// Convert row ranges to a RowSelection for efficient row-level pruning.
@@ -248,13 +267,17 @@ impl PrunedParquetReader {
     /// - Caching layer: Adds complexity, most benefit comes from OS page cache already
     /// Monitor S3 request costs if this becomes a bottleneck. Current approach aligns with
     /// standard Parquet reader behavior and works well for our access patterns.
-    async fn create_object_reader(&self) -> Result<ParquetObjectReader, Box<dyn std::error::Error + Send + Sync>> {
+    ///
+    /// The offset index is loaded with the footer when the file has one, so that reads with
+    /// a [`RowSelection`] only request the pages holding selected rows.
+    async fn create_object_reader(&self) -> Result<ParquetObjectReader, Error> {
         match &self.source {
             ParquetSource::Path(path) => {
                 let (store, obj_path) = get_object_store(path).await?;
                 let meta = store.head(&obj_path).await?;
                 Ok(ParquetObjectReader::new(store, obj_path)
-                    .with_file_size(meta.size))
+                    .with_file_size(meta.size)
+                    .with_preload_offset_index(true))
             }
             ParquetSource::Bytes(bytes) => {
                 // Create in-memory object store
@@ -265,7 +288,8 @@ impl PrunedParquetReader {
                 store.put(&path, bytes_copy.into()).await?;
 
                 Ok(ParquetObjectReader::new(store, path)
-                    .with_file_size(file_size))
+                    .with_file_size(file_size)
+                    .with_preload_offset_index(true))
             }
         }
     }
@@ -273,7 +297,13 @@ impl PrunedParquetReader {
     /// Read only the rows that match a single keyword search.
     ///
     /// Uses the search result to identify which row groups and rows to read, efficiently skipping
-    /// all non-matching data. Returns batches of up to 8192 rows each.
+    /// all non-matching data. Returns batches of up to 8192 rows each. Row groups with a
+    /// [`coarse`](crate::searching::search_results::RowGroupLocation::coarse) location are
+    /// read in full, or only the rows of its
+    /// [`pages`](crate::searching::search_results::RowGroupLocation::pages) if recorded.
+    ///
+    /// When the Parquet file has an offset index, only the data pages holding the
+    /// selected rows are requested from the object store.
     ///
     /// # Arguments
     ///
@@ -344,17 +374,17 @@ impl PrunedParquetReader {
         &self,
         search_result: &SearchResult,
         columns: Option<Vec<String>>,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<RecordBatch>, Error> {
         if !search_result.found {
             return Ok(Vec::new());
         }
 
         let data = search_result.verified_matches.as_ref()
-            .ok_or("Search result has no data")?;
+            .ok_or_else(|| Error::InvalidArgument("Search result has no data".to_string()))?;
 
         // Collect all row groups that contain the keyword
         let mut row_groups_to_read = std::collections::HashSet::new();
-        let mut row_group_ranges: std::collections::HashMap<u16, Vec<(u32, u32)>> =
+        let mut row_group_ranges: std::collections::HashMap<u32, Vec<(u32, u32)>> =
             std::collections::HashMap::new();
 
         for col_detail in &data.column_details {
@@ -362,9 +392,7 @@ impl PrunedParquetReader {
                 row_groups_to_read.insert(rg.row_group_id as usize);
 
                 let ranges = row_group_ranges.entry(rg.row_group_id).or_insert_with(Vec::new);
-                for range in &rg.row_ranges {
-                    ranges.push((range.start_row, range.end_row));
-                }
+                ranges.extend(location_ranges(rg));
             }
         }
 
@@ -372,30 +400,24 @@ impl PrunedParquetReader {
             return Ok(Vec::new());
         }
 
-        // Create ParquetObjectReader
-        let object_reader = self.create_object_reader().await?;
+        // Create ParquetObjectReader and load the footer once for all row groups
+        let mut object_reader = self.create_object_reader().await?;
+        let metadata = ArrowReaderMetadata::load_async(&mut object_reader, ArrowReaderOptions::new()).await?;
 
         let mut all_batches = Vec::new();
         let row_groups_vec: Vec<usize> = row_groups_to_read.into_iter().collect();
 
         for &rg_idx in &row_groups_vec {
             // Create async stream builder
-            let mut builder = ParquetRecordBatchStreamBuilder::new(object_reader.clone()).await?;
+            let mut builder = ParquetRecordBatchStreamBuilder::new_with_metadata(object_reader.clone(), metadata.clone());
 
             // Apply column projection if specified
             if let Some(cols) = &columns {
-                let schema = builder.schema();
-                let indices: Vec<usize> = cols.iter()
-                    .filter_map(|col_name| {
-                        schema.fields().iter().position(|f| f.name() == col_name)
-                    })
-                    .collect();
+                let file_metadata = builder.metadata().file_metadata();
+                let indices = root_indices(file_metadata.schema_descr(), file_metadata.key_value_metadata(), cols);
 
                 if !indices.is_empty() {
-                    let mask = ProjectionMask::leaves(
-                        builder.metadata().file_metadata().schema_descr(),
-                        indices
-                    );
+                    let mask = ProjectionMask::roots(file_metadata.schema_descr(), indices);
                     builder = builder.with_projection(mask);
                 }
             }
@@ -403,7 +425,7 @@ impl PrunedParquetReader {
             // Get row group size and row ranges for this row group
             let row_group_metadata = builder.metadata().row_group(rg_idx);
             let row_group_size = row_group_metadata.num_rows() as usize;
-            let ranges = row_group_ranges.get(&(rg_idx as u16))
+            let ranges = row_group_ranges.get(&(rg_idx as u32))
                 .map(|v| v.as_slice())
                 .unwrap_or(&[]);
 
@@ -440,7 +462,8 @@ impl PrunedParquetReader {
     /// Read Parquet data for unified SearchResult.
     ///
     /// Reads both verified matches and matches needing verification from the SearchResult.
-    /// This is the recommended method for use with the unified search API.
+    /// This is the recommended method for use with the unified search API. Coarse row
+    /// groups are read in full, or only their pages if recorded.
     ///
     /// # Arguments
     ///
@@ -472,14 +495,14 @@ impl PrunedParquetReader {
         &self,
         search_result: &SearchResult,
         columns: Option<Vec<String>>,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<RecordBatch>, Error> {
         if !search_result.found {
             return Ok(Vec::new());
         }
 
         // Collect row groups and ranges from both verified and needs_verification
         let mut row_groups_to_read = std::collections::HashSet::new();
-        let mut row_group_ranges: std::collections::HashMap<u16, Vec<(u32, u32)>> =
+        let mut row_group_ranges: std::collections::HashMap<u32, Vec<(u32, u32)>> =
             std::collections::HashMap::new();
 
         // Process verified matches
@@ -489,9 +512,7 @@ impl PrunedParquetReader {
                     row_groups_to_read.insert(rg.row_group_id as usize);
 
                     let ranges = row_group_ranges.entry(rg.row_group_id).or_insert_with(Vec::new);
-                    for range in &rg.row_ranges {
-                        ranges.push((range.start_row, range.end_row));
-                    }
+                    ranges.extend(location_ranges(rg));
                 }
             }
         }
@@ -503,9 +524,7 @@ impl PrunedParquetReader {
                     row_groups_to_read.insert(rg.row_group_id as usize);
 
                     let ranges = row_group_ranges.entry(rg.row_group_id).or_insert_with(Vec::new);
-                    for range in &rg.row_ranges {
-                        ranges.push((range.start_row, range.end_row));
-                    }
+                    ranges.extend(location_ranges(rg));
                 }
             }
         }
@@ -614,7 +633,7 @@ impl PrunedParquetReader {
         &self,
         combined_result: &CombinedSearchResult,
         columns: Option<Vec<String>>,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<RecordBatch>, Error> {
         self.read_combined_rows_with_metadata(combined_result, columns, None).await
     }
 
@@ -634,14 +653,14 @@ impl PrunedParquetReader {
         combined_result: &CombinedSearchResult,
         columns: Option<Vec<String>>,
         metadata_cache: Option<(u64, u64)>,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<RecordBatch>, Error> {
         if combined_result.row_groups.is_empty() {
             return Ok(Vec::new());
         }
 
         // Collect row groups and ranges
         let mut row_groups_to_read = std::collections::HashSet::new();
-        let mut row_group_ranges: std::collections::HashMap<u16, Vec<(u32, u32)>> =
+        let mut row_group_ranges: std::collections::HashMap<u32, Vec<(u32, u32)>> =
             std::collections::HashMap::new();
 
         for rg in &combined_result.row_groups {
@@ -714,24 +733,17 @@ impl PrunedParquetReader {
 
                 // Apply column projection if specified
                 if let Some(ref cols) = columns {
-                    let schema = builder.schema();
-                    let indices: Vec<usize> = cols.iter()
-                        .filter_map(|col_name| {
-                            schema.fields().iter().position(|f| f.name() == col_name)
-                        })
-                        .collect();
+                    let file_metadata = builder.metadata().file_metadata();
+                    let indices = root_indices(file_metadata.schema_descr(), file_metadata.key_value_metadata(), cols);
 
                     if !indices.is_empty() {
-                        let mask = ProjectionMask::leaves(
-                            builder.metadata().file_metadata().schema_descr(),
-                            indices
-                        );
+                        let mask = ProjectionMask::roots(file_metadata.schema_descr(), indices);
                         builder = builder.with_projection(mask);
                     }
                 }
 
                 // Get row ranges and build RowSelection
-                let ranges = row_group_ranges.get(&(rg_idx as u16))
+                let ranges = row_group_ranges.get(&(rg_idx as u32))
                     .map(|v| v.as_slice())
                     .unwrap_or(&[]);
                 let selection = build_row_selection(ranges, row_group_size);
@@ -774,10 +786,10 @@ impl PrunedParquetReader {
     async fn read_combined_rows_non_cached(
         &self,
         row_groups_vec: &[usize],
-        row_group_ranges: &std::collections::HashMap<u16, Vec<(u32, u32)>>,
+        row_group_ranges: &std::collections::HashMap<u32, Vec<(u32, u32)>>,
         columns: &Option<Vec<String>>,
         object_reader: ParquetObjectReader,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<RecordBatch>, Error> {
         let mut all_batches = Vec::with_capacity(row_groups_vec.len());
 
         for rg_idx in row_groups_vec {
@@ -797,24 +809,17 @@ impl PrunedParquetReader {
 
             // Apply column projection if specified
             if let Some(cols) = columns {
-                let schema = builder.schema();
-                let indices: Vec<usize> = cols.iter()
-                    .filter_map(|col_name| {
-                        schema.fields().iter().position(|f| f.name() == col_name)
-                    })
-                    .collect();
+                let file_metadata = builder.metadata().file_metadata();
+                let indices = root_indices(file_metadata.schema_descr(), file_metadata.key_value_metadata(), cols);
 
                 if !indices.is_empty() {
-                    let mask = ProjectionMask::leaves(
-                        builder.metadata().file_metadata().schema_descr(),
-                        indices
-                    );
+                    let mask = ProjectionMask::roots(file_metadata.schema_descr(), indices);
                     builder = builder.with_projection(mask);
                 }
             }
 
             // Get row ranges and build RowSelection
-            let ranges = row_group_ranges.get(&(rg_idx as u16))
+            let ranges = row_group_ranges.get(&(rg_idx as u32))
                 .map(|v| v.as_slice())
                 .unwrap_or(&[]);
             let selection = build_row_selection(ranges, row_group_size);
@@ -910,7 +915,7 @@ impl PrunedParquetReader {
     pub async fn get_pruning_stats(
         &self,
         search_result: &SearchResult,
-    ) -> Result<PruningStats, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<PruningStats, Error> {
         // Create ParquetObjectReader and builder to access metadata
         let object_reader = self.create_object_reader().await?;
         let builder = ParquetRecordBatchStreamBuilder::new(object_reader).await?;
@@ -919,7 +924,7 @@ impl PrunedParquetReader {
         let total_row_groups = metadata.num_row_groups();
         let total_rows: i64 = metadata.file_metadata().num_rows();
 
-        if !search_result.found {
+        let Some(data) = search_result.verified_matches.as_ref() else {
             return Ok(PruningStats {
                 total_row_groups,
                 row_groups_to_read: 0,
@@ -930,9 +935,7 @@ impl PrunedParquetReader {
                 rows_skipped: total_rows as u64,
                 row_skip_percentage: 100.0,
             });
-        }
-
-        let data = search_result.verified_matches.as_ref().unwrap();
+        };
 
         // Count row groups to read
         let mut row_groups_to_read = std::collections::HashSet::new();
@@ -942,6 +945,15 @@ impl PrunedParquetReader {
             for rg in &col_detail.row_groups {
                 row_groups_to_read.insert(rg.row_group_id);
 
+                if rg.coarse && rg.pages.is_empty() {
+                    // The whole row group is read
+                    rows_to_read += metadata.row_group(rg.row_group_id as usize).num_rows() as u64;
+                } else if rg.coarse {
+                    // Only the pages holding the keyword are read
+                    rows_to_read += rg.pages.iter()
+                        .map(|page| (page.end_row - page.start_row + 1) as u64)
+                        .sum::<u64>();
+                }
                 for range in &rg.row_ranges {
                     rows_to_read += (range.end_row - range.start_row + 1) as u64;
                 }
@@ -964,26 +976,164 @@ impl PrunedParquetReader {
         })
     }
 
+    /// Read the values of specific cells as strings.
+    ///
+    /// Reads only the listed rows of the listed columns: each column is projected on its
+    /// own and rows are selected with a [`RowSelection`], so pages without any requested
+    /// row can be skipped. Values are cast to strings the same way as during indexing.
+    /// Every row of a coarse location (of its pages, if recorded) is read.
+    ///
+    /// # Arguments
+    ///
+    /// * `locations` - Columns, row groups and row ranges to read, e.g. the
+    ///   `needs_verification` locations of a [`SearchResult`]
+    ///
+    /// # Returns
+    ///
+    /// One [`CellValues`] per column and row group, holding every requested row in
+    /// ascending order. Columns missing from the file and row groups or rows past its
+    /// end are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be accessed or decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::pruned_reader::PrunedParquetReader;
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    /// let result = searcher.search("john-doe", None, false).await?;
+    ///
+    /// if let Some(candidates) = &result.needs_verification {
+    ///     let reader = PrunedParquetReader::from_path("data.parquet");
+    ///     for cells in reader.read_cells(candidates).await? {
+    ///         for (row, value) in &cells.rows {
+    ///             println!("{}[{}:{}] = {:?}", cells.column_name, cells.row_group_id, row, value);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_cells(
+        &self,
+        locations: &KeywordLocationData,
+    ) -> Result<Vec<CellValues>, Error> {
+        let mut object_reader = self.create_object_reader().await?;
+        // Load the footer once and share it between all column and row group reads
+        let metadata = ArrowReaderMetadata::load_async(&mut object_reader, ArrowReaderOptions::new()).await?;
+        let schema_descr = metadata.metadata().file_metadata().schema_descr_ptr();
+        let leaves = leaf_columns(&schema_descr, metadata.metadata().file_metadata().key_value_metadata());
+
+        let mut cells = Vec::new();
+
+        for column in &locations.column_details {
+            let Some(leaf) = leaves.iter().find(|leaf| leaf.name == column.column_name) else {
+                continue;
+            };
+
+            for rg in &column.row_groups {
+                let rg_idx = rg.row_group_id as usize;
+                if rg_idx >= metadata.metadata().num_row_groups() {
+                    continue;
+                }
+                let row_group_size = metadata.metadata().row_group(rg_idx).num_rows() as usize;
+
+                let mut rows: Vec<u32> = location_ranges(rg)
+                    .filter(|&(start, _)| (start as usize) < row_group_size)
+                    .flat_map(|(start, end)| start..=end.min(row_group_size as u32 - 1))
+                    .collect();
+                rows.sort_unstable();
+                rows.dedup();
+                if rows.is_empty() {
+                    continue;
+                }
+
+                let ranges: Vec<(u32, u32)> = rows.iter().map(|&row| (row, row)).collect();
+                let builder = ParquetRecordBatchStreamBuilder::new_with_metadata(object_reader.clone(), metadata.clone())
+                    .with_projection(ProjectionMask::leaves(&schema_descr, leaf.projection.iter().copied()))
+                    .with_row_groups(vec![rg_idx])
+                    .with_batch_size(8192);
+                let builder = match build_row_selection(&ranges, row_group_size) {
+                    Some(selection) => builder.with_row_selection(selection),
+                    None => builder,
+                };
+                let mut stream = builder.build()?;
+
+                // Selected rows come back in ascending order
+                let mut values = Vec::with_capacity(rows.len());
+                let mut rows_read = 0;
+                while let Some(batch) = stream.next().await {
+                    let batch = batch?;
+                    let Some(batch_rows) = rows.get(rows_read..rows_read + batch.num_rows()) else {
+                        return Err(Error::StaleIndex(format!(
+                            "Read more than the {} requested rows from column '{}' row group {}",
+                            rows.len(), column.column_name, rg_idx
+                        )));
+                    };
+                    rows_read += batch.num_rows();
+
+                    let leaf_values = leaf_values(batch.column(0), leaf)?;
+                    let string_array_ref = cast(&leaf_values.values, &DataType::Utf8)?;
+                    let string_array = string_array_ref.as_any()
+                        .downcast_ref::<StringArray>()
+                        .ok_or_else(|| ArrowError::CastError("Cast to Utf8 should produce StringArray".to_string()))?;
+                    let strings = string_array.iter().map(|value| value.map(str::to_string));
+
+                    match &leaf_values.rows {
+                        None => values.extend(batch_rows.iter().copied().zip(strings)),
+                        Some(value_rows) => {
+                            // Every value of a nested leaf, and rows without any as null
+                            let mut next_row = 0;
+                            for (value, &value_row) in strings.zip(value_rows) {
+                                let value_row = value_row as usize;
+                                values.extend(batch_rows[next_row.min(value_row)..value_row].iter().map(|&row| (row, None)));
+                                values.push((batch_rows[value_row], value));
+                                next_row = value_row + 1;
+                            }
+                            values.extend(batch_rows[next_row..].iter().map(|&row| (row, None)));
+                        }
+                    }
+                }
+
+                if rows_read != rows.len() {
+                    return Err(Error::StaleIndex(format!(
+                        "Expected {} rows from column '{}' row group {}, read {}",
+                        rows.len(), column.column_name, rg_idx, rows_read
+                    )));
+                }
+
+                cells.push(CellValues {
+                    column_name: column.column_name.clone(),
+                    row_group_id: rg.row_group_id,
+                    rows: values,
+                });
+            }
+        }
+
+        Ok(cells)
+    }
+
     /// Internal helper to read specific row groups and ranges.
     async fn read_row_groups_and_ranges(
         &self,
         row_groups_to_read: std::collections::HashSet<usize>,
-        row_group_ranges: std::collections::HashMap<u16, Vec<(u32, u32)>>,
+        row_group_ranges: std::collections::HashMap<u32, Vec<(u32, u32)>>,
         columns: Option<Vec<String>>,
-    ) -> Result<Vec<RecordBatch>, Box<dyn std::error::Error + Send + Sync>> {
-        let reader = self.create_object_reader().await?;
-        let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+    ) -> Result<Vec<RecordBatch>, Error> {
+        let mut reader = self.create_object_reader().await?;
+        // Load the footer once and share it between all row group reads
+        let arrow_metadata = ArrowReaderMetadata::load_async(&mut reader, ArrowReaderOptions::new()).await?;
 
-        let metadata = builder.metadata().clone();
-        let schema = builder.schema().clone();
+        let metadata = arrow_metadata.metadata().clone();
 
         let projection_mask = if let Some(ref cols) = columns {
-            let indices: Vec<usize> = cols.iter()
-                .filter_map(|col_name| {
-                    schema.fields().iter().position(|f| f.name() == col_name)
-                })
-                .collect();
-            ProjectionMask::roots(metadata.file_metadata().schema_descr(), indices)
+            let file_metadata = metadata.file_metadata();
+            let indices = root_indices(file_metadata.schema_descr(), file_metadata.key_value_metadata(), cols);
+            ProjectionMask::roots(file_metadata.schema_descr(), indices)
         } else {
             ProjectionMask::all()
         };
@@ -996,14 +1146,27 @@ impl PrunedParquetReader {
             }
 
             // Recreate builder for each row group (builder doesn't implement Clone)
-            let reader = self.create_object_reader().await?;
-            let builder = ParquetRecordBatchStreamBuilder::new(reader).await?;
+            let builder = ParquetRecordBatchStreamBuilder::new_with_metadata(reader.clone(), arrow_metadata.clone());
             let builder = builder.with_projection(projection_mask.clone());
             let builder = builder.with_batch_size(8192);
-            let mut stream = builder.with_row_groups(vec![rg_idx]).build()?;
+            let builder = builder.with_row_groups(vec![rg_idx]);
 
             // Get the row ranges for this row group
-            let ranges = row_group_ranges.get(&(rg_idx as u16));
+            let ranges = row_group_ranges.get(&(rg_idx as u32));
+
+            // Select the rows up front when possible, so only the pages holding them are read
+            let row_group_size = metadata.row_group(rg_idx).num_rows() as usize;
+            if let Some(selection) = ranges.and_then(|ranges| build_row_selection(ranges, row_group_size)) {
+                let mut stream = builder.with_row_selection(selection).build()?;
+                while let Some(batch) = stream.next().await {
+                    let batch = batch?;
+                    if batch.num_rows() > 0 {
+                        batches.push(batch);
+                    }
+                }
+                continue;
+            }
+            let mut stream = builder.build()?;
 
             let mut row_offset = 0u32;
             while let Some(batch) = stream.next().await {
@@ -1111,12 +1274,29 @@ pub struct PruningStats {
     pub row_skip_percentage: f64,
 }
 
+/// Values of one column at specific rows of a row group, read by
+/// [`PrunedParquetReader::read_cells`].
+#[derive(Debug, Clone)]
+pub struct CellValues {
+    /// Name of the column.
+    pub column_name: String,
+
+    /// Row group the rows belong to.
+    pub row_group_id: u32,
+
+    /// Row number within the row group and the cell value (`None` for null), by ascending row.
+    ///
+    /// A nested column inside a list or map (e.g. `tags[]`) has one entry per element of
+    /// the row, or a single `None` entry when the row has no elements.
+    pub rows: Vec<(u32, Option<String>)>,
+}
+
 /// Filter a record batch to only include rows in the specified ranges
 /// OPTIMIZATION: Use fill() for contiguous ranges and slice() for single ranges
 fn filter_batch_to_ranges(
     batch: &RecordBatch,
     ranges: &[(u32, u32)],
-) -> Result<RecordBatch, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<RecordBatch, Error> {
     let num_rows = batch.num_rows();
 
     // Special case: single contiguous range - use zero-copy slice
@@ -0,0 +1,220 @@
+//! Pruned reading of CSV and JSON lines files indexed in batches of rows.
+//!
+//! The index of a text file records the byte range of every batch of rows (its
+//! [`TextLayout`]), so the batches holding matches are fetched with range requests and
+//! decoded on their own, like the row groups read by
+//! [`PrunedParquetReader`](crate::searching::pruned_reader::PrunedParquetReader).
+//!
+//! Phrase verification and highlighting read Parquet files, and are not available for
+//! text files.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::ParquetSource;
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//! use keywords::searching::pruned_text_reader::PrunedTextReader;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("export.csv", None).await?;
+//!     let result = searcher.search("keyword", None, true).await?;
+//!
+//!     let layout = searcher.text_layout().ok_or("Not the index of a text file")?;
+//!     let reader = PrunedTextReader::new(ParquetSource::Path("export.csv".to_string()), layout)?;
+//!     let batches = reader.read_search_result(&result, None).await?;
+//!
+//!     println!("Read {} batches", batches.len());
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use arrow::array::{BooleanArray, RecordBatch};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::SchemaRef;
+use bytes::Bytes;
+use crate::error::Error;
+use crate::index_data::TextLayout;
+use crate::searching::pruned_reader::location_ranges;
+use crate::searching::search_results::{KeywordLocationData, SearchResult};
+use crate::text_source::TextDecoder;
+use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::utils::nested_columns::arrow_leaf_columns;
+use crate::ParquetSource;
+
+/// Reads the rows of a CSV or JSON lines file that match search results.
+///
+/// Row group IDs of search results are batch numbers of the [`TextLayout`] stored in
+/// the index (see
+/// [`KeywordSearcher::text_layout`](crate::searching::keyword_search::KeywordSearcher::text_layout)).
+/// Each batch holding matches is fetched with one range request and decoded, then
+/// filtered to the matching rows.
+pub struct PrunedTextReader {
+    source: ParquetSource,
+    layout: TextLayout,
+    schema: SchemaRef,
+}
+
+impl PrunedTextReader {
+    /// Creates a reader of the text file at `source`, indexed with `layout`.
+    ///
+    /// No I/O is performed until a read method is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the schema stored in the layout cannot be decoded.
+    pub fn new(source: ParquetSource, layout: &TextLayout) -> Result<Self, Error> {
+        Ok(PrunedTextReader {
+            source,
+            layout: layout.clone(),
+            schema: Arc::new(layout.arrow_schema()?),
+        })
+    }
+
+    /// The schema the rows are decoded with.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    /// Reads all rows of a batch (pseudo row group).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `batch` is past the last batch, if its bytes cannot be fetched,
+    /// or if they cannot be decoded.
+    pub async fn read_batch(&self, batch: usize) -> Result<RecordBatch, Error> {
+        let range = self.layout.batch_range(batch)
+            .ok_or_else(|| Error::InvalidArgument(format!("Batch {} not found ({} batches)", batch, self.layout.num_batches())))?;
+        let bytes = match &self.source {
+            ParquetSource::Path(path) => {
+                let (store, obj_path) = get_object_store(path).await?;
+                store.get_range(&obj_path, range).await?
+            }
+            ParquetSource::Bytes(bytes) => {
+                let range = range.start as usize..range.end as usize;
+                if range.end > bytes.len() {
+                    return Err(Error::StaleIndex(format!("Batch {} ends past the end of the file", batch)));
+                }
+                bytes.slice(range)
+            }
+        };
+        self.decode(batch, &bytes)
+    }
+
+    /// Reads only the rows of verified matches of a keyword search.
+    ///
+    /// Same as [`PrunedParquetReader::read_matching_rows`](crate::searching::pruned_reader::PrunedParquetReader::read_matching_rows):
+    /// coarse locations read the whole batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `search_result` - Result of a search of the text file's index
+    /// * `columns` - Optional projection on top-level or leaf column names (unknown
+    ///   names are ignored; `None` reads all columns)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a batch cannot be read (see [`read_batch`](Self::read_batch)).
+    pub async fn read_matching_rows(
+        &self,
+        search_result: &SearchResult,
+        columns: Option<Vec<String>>,
+    ) -> Result<Vec<RecordBatch>, Error> {
+        if !search_result.found {
+            return Ok(Vec::new());
+        }
+        let data = search_result.verified_matches.as_ref()
+            .ok_or_else(|| Error::InvalidArgument("Search result has no data".to_string()))?;
+        self.read_locations([data], columns).await
+    }
+
+    /// Reads the rows of both verified matches and matches needing verification.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a batch cannot be read (see [`read_batch`](Self::read_batch)).
+    pub async fn read_search_result(
+        &self,
+        search_result: &SearchResult,
+        columns: Option<Vec<String>>,
+    ) -> Result<Vec<RecordBatch>, Error> {
+        if !search_result.found {
+            return Ok(Vec::new());
+        }
+        let locations = search_result.verified_matches.iter()
+            .chain(search_result.needs_verification.iter());
+        self.read_locations(locations, columns).await
+    }
+
+    /// Reads the rows of every location, one record batch per text batch, in batch order.
+    async fn read_locations(
+        &self,
+        locations: impl IntoIterator<Item = &KeywordLocationData>,
+        columns: Option<Vec<String>>,
+    ) -> Result<Vec<RecordBatch>, Error> {
+        let mut batch_ranges: BTreeMap<u32, Vec<(u32, u32)>> = BTreeMap::new();
+        for data in locations {
+            for col_detail in &data.column_details {
+                for rg in &col_detail.row_groups {
+                    batch_ranges.entry(rg.row_group_id).or_default().extend(location_ranges(rg));
+                }
+            }
+        }
+
+        let projection = columns.map(|columns| self.projection(&columns));
+        let mut batches = Vec::new();
+        for (batch, ranges) in batch_ranges {
+            let rows = self.read_batch(batch as usize).await?;
+            let mut selected = vec![false; rows.num_rows()];
+            for (start, end) in ranges {
+                // Ranges are inclusive, and open-ended for whole batches
+                let (start, end) = (start as usize, (end as usize + 1).min(rows.num_rows()));
+                if start < end {
+                    selected[start..end].fill(true);
+                }
+            }
+            let mut rows = filter_record_batch(&rows, &BooleanArray::from(selected))?;
+            if let Some(projection) = &projection {
+                rows = rows.project(projection)?;
+            }
+            if rows.num_rows() > 0 {
+                batches.push(rows);
+            }
+        }
+        Ok(batches)
+    }
+
+    /// Indices of the top-level fields selected by top-level or leaf column names.
+    fn projection(&self, columns: &[String]) -> Vec<usize> {
+        let leaves = arrow_leaf_columns(&self.schema);
+        let mut indices: Vec<usize> = columns.iter()
+            .filter_map(|column| {
+                self.schema.index_of(column).ok()
+                    .or_else(|| leaves.iter().find(|leaf| &leaf.name == column).map(|leaf| leaf.root))
+            })
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// Decodes the bytes of a batch. Only the first batch of a CSV file starts with the
+    /// header record.
+    fn decode(&self, batch: usize, bytes: &Bytes) -> Result<RecordBatch, Error> {
+        let batch_size = self.layout.rows_per_batch as usize;
+        let mut decoder = TextDecoder::try_new(self.layout.format, self.schema.clone(), batch_size, batch == 0)?;
+        let mut buf = &bytes[..];
+        while !buf.is_empty() {
+            let consumed = decoder.decode(buf)?;
+            if consumed == 0 {
+                return Err(Error::StaleIndex(format!("Batch {} has more than {} rows", batch, batch_size)));
+            }
+            buf = &buf[consumed..];
+        }
+        decoder.decode(&[])?;
+        let rows = decoder.flush()?.unwrap_or_else(|| RecordBatch::new_empty(self.schema.clone()));
+        Ok(rows)
+    }
+}
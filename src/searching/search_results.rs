@@ -51,6 +51,7 @@
 //! # }
 //! ```
 
+use crate::index_data::KeywordCount;
 
 /// Unified search result for all search operations.
 ///
@@ -141,6 +142,105 @@ pub struct SearchResult {
     pub needs_verification: Option<KeywordLocationData>,
 }
 
+/// Search result whose matches have all been checked against the Parquet data.
+///
+/// Returned by [`KeywordSearcher::search_verified`]. Candidates that the index could not
+/// confirm (`needs_verification`) are resolved by reading the affected cells: those
+/// containing the phrase are moved into `result.verified_matches`, the rest are dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::searching::keyword_search::KeywordSearcher;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+/// let verified = searcher.search_verified("data.parquet", "john-doe", None).await?;
+///
+/// assert!(verified.result.needs_verification.is_none());
+/// println!("{} of {} candidates rejected after reading Parquet",
+///     verified.candidates_rejected, verified.candidates_checked);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`KeywordSearcher::search_verified`]: crate::searching::keyword_search::KeywordSearcher::search_verified
+#[derive(Debug, Clone)]
+pub struct VerifiedSearchResult {
+    /// The search result, with `needs_verification` always `None`.
+    pub result: SearchResult,
+
+    /// Number of candidate cells that needed verification.
+    pub candidates_checked: u64,
+
+    /// Number of candidate cells that did not contain the phrase.
+    pub candidates_rejected: u64,
+}
+
+/// Position of one occurrence of the query within a cell value.
+///
+/// Offsets are in bytes into the UTF-8 cell value and always fall on character
+/// boundaries, so `&value[offset..offset + length]` is the matched text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    /// Byte offset of the occurrence within the cell.
+    pub offset: usize,
+
+    /// Length of the occurrence in bytes.
+    pub length: usize,
+}
+
+/// A matching cell with the positions of every occurrence of the query.
+#[derive(Debug, Clone)]
+pub struct HighlightedCell {
+    /// Name of the column.
+    pub column_name: String,
+
+    /// Row group the cell belongs to.
+    pub row_group_id: u32,
+
+    /// Row number within the row group.
+    pub row: u32,
+
+    /// The cell value as read from the Parquet file. For a nested column inside a list
+    /// or map, the matching element; a row can have one cell per matching element.
+    pub value: String,
+
+    /// Non-overlapping occurrences of the query, in ascending offset order.
+    pub highlights: Vec<Highlight>,
+}
+
+/// Search result together with the matching cells and their hit positions.
+///
+/// Returned by [`KeywordSearcher::search_highlighted`]. Every location of
+/// `result.verified_matches` has exactly one entry in `cells`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::searching::keyword_search::KeywordSearcher;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+/// let highlighted = searcher.search_highlighted("data.parquet", "john-doe", None).await?;
+///
+/// for cell in &highlighted.cells {
+///     for hit in &cell.highlights {
+///         println!("{}[{}]: {}", cell.column_name, cell.row,
+///             &cell.value[hit.offset..hit.offset + hit.length]);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`KeywordSearcher::search_highlighted`]: crate::searching::keyword_search::KeywordSearcher::search_highlighted
+#[derive(Debug, Clone)]
+pub struct HighlightedSearchResult {
+    /// The search result, with `needs_verification` always `None`.
+    pub result: SearchResult,
+
+    /// Matching cells ordered by column name, row group and row.
+    pub cells: Vec<HighlightedCell>,
+}
 
 /// Information about where a keyword appears in the Parquet file.
 ///
@@ -195,7 +295,8 @@ pub struct KeywordLocationData {
     /// Total number of keyword occurrences across all columns and row groups.
     ///
     /// This is the sum of all row ranges across all columns. Useful for ranking
-    /// search results by relevance.
+    /// search results by relevance. Coarse row groups are counted for single keyword
+    /// searches, where the index stores their row count, and not counted otherwise.
     pub total_occurrences: u64,
 
     /// Bitmask indicating which split levels matched this keyword.
@@ -262,6 +363,8 @@ pub struct ColumnLocation {
 ///
 /// - `row_group_id` - Row group identifier (0-indexed)
 /// - `row_ranges` - Contiguous ranges of rows containing the keyword
+/// - `coarse` - Only the row group is known, not the rows
+/// - `pages` - Data pages of the column chunk containing the keyword, if recorded
 ///
 /// # Row Group Context
 ///
@@ -275,26 +378,63 @@ pub struct ColumnLocation {
 ///
 /// ```no_run
 /// # use keywords::searching::search_results::RowGroupLocation;
-/// # let rg = RowGroupLocation {
-/// #     row_group_id: 2,
-/// #     row_ranges: vec![],
-/// # };
+/// # fn example(rg: &RowGroupLocation) {
 /// println!("Row group {} contains matches", rg.row_group_id);
 /// println!("Total row ranges: {}", rg.row_ranges.len());
+/// # }
 /// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct RowGroupLocation {
     /// Identifier for the row group (0-indexed).
     ///
     /// Used to target the correct row group when reading from the Parquet file.
-    pub row_group_id: u16,
+    pub row_group_id: u32,
 
     /// Contiguous ranges of rows where the keyword appears.
     ///
     /// Consecutive rows with the keyword are grouped into single ranges for efficiency.
     /// Row numbers are relative to the start of this row group (0-indexed).
-    /// Guaranteed to be non-empty when this struct exists.
+    /// Non-empty unless the location is `coarse`.
     pub row_ranges: Vec<RowRange>,
+
+    /// The keyword exceeded the index's per-keyword detail limit
+    /// ([`BuildOptions::keyword_detail_limit`](crate::BuildOptions::keyword_detail_limit)),
+    /// so the index only records that this row group contains it.
+    ///
+    /// `row_ranges` is empty, and any row of the row group may be a match.
+    /// [`PrunedParquetReader`](crate::searching::pruned_reader::PrunedParquetReader)
+    /// reads the whole row group for coarse locations, or only their `pages` if known.
+    pub coarse: bool,
+
+    /// Data pages of the column chunk that contain the keyword, ascending.
+    ///
+    /// Recorded when the index was built with
+    /// [`BuildOptions::page_locations`](crate::BuildOptions::page_locations); empty
+    /// otherwise. Every match lies within these pages, so a coarse location with pages
+    /// only needs their rows to be read.
+    pub pages: Vec<PageSpan>,
+}
+
+/// A data page of a column chunk, as the rows it holds.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::searching::search_results::PageSpan;
+/// # let page = PageSpan { page: 3, start_row: 60_000, end_row: 79_999 };
+/// println!("Page {} holds rows {}..={}", page.page, page.start_row, page.end_row);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PageSpan {
+    /// Page number within the column chunk (0-indexed, dictionary pages excluded).
+    pub page: u32,
+
+    /// First row of the page (0-indexed within the row group, inclusive).
+    pub start_row: u32,
+
+    /// Last row of the page (0-indexed within the row group, inclusive).
+    pub end_row: u32,
 }
 
 /// A contiguous range of rows where a keyword appears.
@@ -365,14 +505,14 @@ pub struct RowRange {
     ///
     /// Combined with `parent_position`, this enables efficient phrase matching by checking parent containment
     /// without reading the Parquet file.
-    pub parent_chunk: Option<u16>,
+    pub parent_chunk: Option<u32>,
 
     /// Position of parent keyword within its chunk (0-999 typically).
     ///
     /// Used in combination with `parent_chunk` to look up the parent keyword.
     /// - `None` - This is a root token from the original Parquet string
     /// - `Some(position)` - Index within the parent chunk's keyword list
-    pub parent_position: Option<u16>,
+    pub parent_position: Option<u32>,
 }
 
 /// Result of combining multiple keyword searches with set operations.
@@ -430,26 +570,33 @@ pub struct CombinedSearchResult {
 ///
 /// - `row_group_id` - Row group identifier
 /// - `row_ranges` - Row ranges satisfying the combination
+/// - `coarse` - A combined keyword only has a coarse location in this row group
 ///
 /// # Examples
 ///
 /// ```no_run
 /// # use keywords::searching::search_results::CombinedRowGroupLocation;
-/// # let rg = CombinedRowGroupLocation {
-/// #     row_group_id: 5,
-/// #     row_ranges: vec![],
-/// # };
+/// # fn example(rg: &CombinedRowGroupLocation) {
 /// println!("Row group {} has combined matches", rg.row_group_id);
+/// # }
 /// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct CombinedRowGroupLocation {
     /// Identifier for the row group (0-indexed).
-    pub row_group_id: u16,
+    pub row_group_id: u32,
 
     /// Row ranges in this row group that satisfy the combination criteria.
     ///
-    /// Guaranteed to be non-empty when this struct exists.
+    /// Non-empty unless the location is `coarse`.
     pub row_ranges: Vec<CombinedRowRange>,
+
+    /// At least one of the combined keywords has a coarse location in this row group
+    /// (see [`RowGroupLocation::coarse`]), so the rows are candidates rather than matches.
+    ///
+    /// `row_ranges` holds the candidate rows the other keywords allow, or is empty
+    /// when every row of the row group is a candidate.
+    pub coarse: bool,
 }
 
 /// Row range for combined search results.
@@ -536,7 +683,7 @@ pub struct PotentialMatch {
     pub column_name: String,
 
     /// Row group identifier (0-indexed).
-    pub row_group_id: u16,
+    pub row_group_id: u32,
 
     /// Row number within the row group (0-indexed).
     pub row: u32,
@@ -693,4 +840,95 @@ pub struct IndexInfo {
     /// The index is split into chunks for efficient partial loading.
     /// Typical chunk size is 1000 keywords.
     pub num_chunks: usize,
-}
\ No newline at end of file
+}
+
+/// A single keyword from the index dictionary.
+///
+/// Produced by [`KeywordSearcher::vocabulary`](crate::searching::keyword_search::KeywordSearcher::vocabulary)
+/// when enumerating the keywords stored in an index. Occurrence counts are only
+/// populated when requested, since they require reading each chunk's data section
+/// in addition to its keyword list.
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::searching::search_results::{VocabularyEntry, ColumnKeywordCount};
+/// let entry = VocabularyEntry {
+///     keyword: "example.com".to_string(),
+///     total_occurrences: Some(42),
+///     column_counts: Some(vec![ColumnKeywordCount {
+///         column_name: "email".to_string(),
+///         occurrences: 42,
+///     }]),
+/// };
+///
+/// if let Some(counts) = &entry.column_counts {
+///     for count in counts {
+///         println!("{} appears in {} rows of {}", entry.keyword, count.occurrences, count.column_name);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct VocabularyEntry {
+    /// The keyword exactly as it was indexed.
+    pub keyword: String,
+
+    /// Number of rows containing the keyword across the enumerated columns.
+    ///
+    /// When enumerating all columns this is the number of distinct rows in which the
+    /// keyword appears in any column. When restricted to columns, it is the sum of the
+    /// per-column counts. `None` unless counts were requested.
+    pub total_occurrences: Option<u64>,
+
+    /// Number of rows containing the keyword, per column.
+    ///
+    /// Sorted in column pool order. `None` unless counts were requested.
+    pub column_counts: Option<Vec<ColumnKeywordCount>>,
+}
+
+/// Occurrence count of a keyword within one column.
+///
+/// An occurrence is a row in which the column value contains the keyword; a keyword
+/// appearing several times in the same cell counts once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnKeywordCount {
+    /// Name of the column.
+    pub column_name: String,
+
+    /// Number of rows in this column containing the keyword.
+    pub occurrences: u64,
+}
+
+
+/// Keyword statistics for one indexed column.
+///
+/// Computed once at index build time and stored in the filters file, so reading them
+/// requires no access to the data file or the Parquet file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use keywords::searching::search_results::ColumnStatisticsInfo;
+/// # let stats: ColumnStatisticsInfo = todo!();
+/// // A column where one keyword covers most rows is likely skewed
+/// if let Some(top) = stats.top_keywords.first() {
+///     let share = top.occurrences as f64 / stats.rows_with_keywords.max(1) as f64;
+///     println!("{}: '{}' appears in {:.0}% of rows", stats.column_name, top.keyword, share * 100.0);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColumnStatisticsInfo {
+    /// Name of the column.
+    pub column_name: String,
+
+    /// Number of distinct keywords found in the column.
+    pub distinct_keywords: u64,
+
+    /// Number of rows with at least one keyword in the column.
+    ///
+    /// Rows that are null, empty, or contain only delimiters are not counted.
+    pub rows_with_keywords: u64,
+
+    /// Most frequent keywords in the column, by descending number of rows.
+    pub top_keywords: Vec<KeywordCount>,
+}
@@ -0,0 +1,249 @@
+//! Substring search over the indexed keywords.
+//!
+//! Keyword search only finds whole tokens. [`KeywordSearcher::search_substring`] instead
+//! finds every keyword that contains a given substring and returns the combined
+//! locations of all of them.
+//!
+//! When the index was built with [`BuildOptions::trigram_index`](crate::BuildOptions::trigram_index),
+//! the posting lists of the substring's trigrams are intersected to find candidate
+//! keywords, and only the chunks holding candidates are read. Without a trigram index
+//! (or for substrings shorter than three characters) every chunk's keyword list is
+//! scanned instead.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!     let result = searcher.search_substring("ample", Some("email")).await?;
+//!
+//!     println!("Matching keywords: {:?}", result.tokens);
+//!     if let Some(matches) = &result.verified_matches {
+//!         println!("{} rows", matches.total_occurrences);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use rkyv::Archived;
+use rkyv::util::AlignedVec;
+use rkyv::rancor::Error as RkyvError;
+use crate::error::Error;
+use crate::index_data::KeywordDataFlat;
+use crate::index_structure::index_files::{index_filename, IndexFile};
+use crate::index_structure::trigram_index::{
+    intersect_sorted, split_keyword_id, trigrams, TrigramPostings, TRIGRAM_CHARS,
+};
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::search_results::SearchResult;
+use crate::searching::verify::{add_coarse, rows_to_location_data, CoarseRowGroups, RowsByColumn};
+use crate::utils::file_interaction_local_and_cloud::get_object_store;
+
+impl KeywordSearcher {
+    /// Search for all keywords containing a substring.
+    ///
+    /// Matching is case-sensitive and applies to individual keywords, so a substring
+    /// spanning a split character (e.g. `"e@ex"` in `"user@example.com"`) is only found
+    /// if some keyword contains it. Every returned location is exact: the keyword, and
+    /// therefore the substring, occurs in the cell. The exception are row groups of
+    /// keywords indexed without row detail, which are returned as coarse locations.
+    ///
+    /// # Arguments
+    ///
+    /// * `substring` - The text to look for inside keywords
+    /// * `in_columns` - Optional column name or glob pattern to restrict the search to
+    ///
+    /// # Returns
+    ///
+    /// A [`SearchResult`] whose `tokens` are the matching keywords (sorted) and whose
+    /// `verified_matches` combine their locations. `needs_verification` is always `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `substring` is empty, or if the index files cannot be read.
+    pub async fn search_substring(
+        &self,
+        substring: &str,
+        in_columns: Option<&str>,
+    ) -> Result<SearchResult, Error> {
+        if substring.is_empty() {
+            return Err(Error::InvalidArgument("Substring must not be empty".to_string()));
+        }
+
+        let column_ids = in_columns.map(|columns| self.resolve_column_ids(&[columns]));
+
+        // Candidate positions per chunk; None means every keyword of the chunk
+        let candidates: BTreeMap<u32, Option<Vec<u32>>> =
+            if !self.filters.trigram_directory.is_empty() && substring.chars().count() >= TRIGRAM_CHARS {
+                let mut candidates = BTreeMap::new();
+                let chunk_starts = self.chunk_starts();
+                for id in self.trigram_candidates(substring).await? {
+                    let (chunk, position) = split_keyword_id(id, &chunk_starts);
+                    candidates.entry(chunk).or_insert_with(|| Some(Vec::new()))
+                        .get_or_insert_with(Vec::new)
+                        .push(position);
+                }
+                candidates
+            } else {
+                (0..self.filters.chunk_index.len() as u32).map(|chunk| (chunk, None)).collect()
+            };
+
+        let mut tokens = Vec::new();
+        let mut rows = RowsByColumn::new();
+        let mut coarse = CoarseRowGroups::new();
+        let mut splits_matched = 0u16;
+
+        for (chunk, positions) in candidates {
+            // Without candidates, only read the data section of chunks with a match
+            if positions.is_none() {
+                let keywords = self.read_chunk_keywords(chunk).await?;
+                if !keywords.iter().any(|keyword| keyword.contains(substring)) {
+                    continue;
+                }
+            }
+
+            let (keywords, chunk_data) = self.read_full_chunk(chunk).await?;
+
+            let matching: Vec<usize> = match positions {
+                Some(positions) => positions.into_iter()
+                    .map(|position| position as usize)
+                    .filter(|&position| keywords.get(position).is_some_and(|k| k.contains(substring)))
+                    .collect(),
+                None => (0..keywords.len()).filter(|&position| keywords[position].contains(substring)).collect(),
+            };
+
+            for position in matching {
+                let data = &chunk_data[position];
+                if self.add_keyword_rows(&mut rows, &mut coarse, data, column_ids.as_deref())? {
+                    splits_matched |= data.splits_matched;
+                    tokens.push(keywords[position].clone());
+                }
+            }
+        }
+
+        let verified_matches = if rows.is_empty() {
+            None
+        } else {
+            Some(rows_to_location_data(rows, &coarse, splits_matched))
+        };
+
+        Ok(SearchResult {
+            query: substring.to_string(),
+            found: verified_matches.is_some(),
+            tokens,
+            verified_matches,
+            needs_verification: None,
+        })
+    }
+
+    /// Id of the first keyword of every chunk: the keyword counts of the preceding chunks.
+    fn chunk_starts(&self) -> Vec<u32> {
+        self.filters.chunk_index.iter()
+            .scan(0u32, |start, chunk| {
+                let chunk_start = *start;
+                *start = start.saturating_add(chunk.count);
+                Some(chunk_start)
+            })
+            .collect()
+    }
+
+    /// Ids of the keywords containing every trigram of `substring`.
+    ///
+    /// Returns an empty list as soon as one trigram is absent from the index.
+    async fn trigram_candidates(&self, substring: &str) -> Result<Vec<u32>, Error> {
+        let directory = &self.filters.trigram_directory;
+
+        let mut postings: Vec<&TrigramPostings> = Vec::new();
+        for trigram in trigrams(substring) {
+            match directory.binary_search_by(|p| p.trigram.as_str().cmp(trigram)) {
+                Ok(index) => postings.push(&directory[index]),
+                Err(_) => return Ok(Vec::new()),
+            }
+        }
+
+        // Intersect the shortest lists first so the running result stays small
+        postings.sort_by_key(|p| p.count);
+
+        let trigrams_path = format!("{}/{}", self.index_dir,
+                                    index_filename(IndexFile::Trigrams, self.index_file_prefix.as_deref()));
+        let (store, obj_path) = get_object_store(&trigrams_path).await?;
+
+        let ranges: Vec<_> = postings.iter()
+            .map(|p| p.offset..(p.offset + p.length as u64))
+            .collect();
+        let lists = store.get_ranges(&obj_path, &ranges).await?;
+
+        let mut result: Option<Vec<u32>> = None;
+        for bytes in lists {
+            let mut aligned_buffer = AlignedVec::<16>::new();
+            aligned_buffer.extend_from_slice(&bytes);
+
+            let archived: &Archived<Vec<u32>> = rkyv::access(&aligned_buffer)
+                .map_err(|e: RkyvError| Error::CorruptIndex(format!("Failed to deserialize trigram postings: {}", e)))?;
+            let ids: Vec<u32> = archived.iter().map(|id| id.to_native()).collect();
+
+            let intersection = match result {
+                Some(previous) => intersect_sorted(&previous, &ids),
+                None => ids,
+            };
+            if intersection.is_empty() {
+                return Ok(Vec::new());
+            }
+            result = Some(intersection);
+        }
+
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Add the rows of one keyword in the selected columns to the grouped rows, or its
+    /// row groups to `coarse` if the keyword has no row detail.
+    ///
+    /// Returns whether the keyword occurs in any selected column.
+    fn add_keyword_rows(
+        &self,
+        rows: &mut RowsByColumn,
+        coarse: &mut CoarseRowGroups,
+        data: &KeywordDataFlat,
+        column_ids: Option<&[u32]>,
+    ) -> Result<bool, Error> {
+        let mut any = false;
+
+        // Column 0 is the aggregate of all columns and is skipped in favour of the
+        // per-column entries
+        for column in data.columns.iter().filter(|col| col.column_id != 0) {
+            if column_ids.is_some_and(|ids| !ids.contains(&column.column_id)) {
+                continue;
+            }
+
+            let column_name = self.filters.column_pool.get(column.column_id)
+                .ok_or_else(|| Error::CorruptIndex("Column not found in pool".to_string()))?;
+            let row_groups = rows.entry(column_name.to_string()).or_default();
+
+            for rg in &column.row_groups {
+                if data.coarse {
+                    let pages = self.page_spans(column.column_id, rg.row_group_id, rg.pages().iter().copied());
+                    add_coarse(coarse, column_name, rg.row_group_id, &pages);
+                }
+                let group_rows = row_groups.entry(rg.row_group_id).or_default();
+                for flat_row in rg.rows() {
+                    let end_row = flat_row.row.checked_add(flat_row.additional_rows).ok_or_else(|| Error::CorruptIndex(
+                        format!("Row range {}+{} out of bounds", flat_row.row, flat_row.additional_rows)
+                    ))?;
+                    group_rows.extend(flat_row.row..=end_row);
+                }
+            }
+            for row_group_id in column.row_group_bitmap.iter().flat_map(|bitmap| bitmap.row_groups()) {
+                add_coarse(coarse, column_name, row_group_id, &[]);
+                row_groups.entry(row_group_id).or_default();
+            }
+            any = true;
+        }
+
+        Ok(any)
+    }
+}
@@ -0,0 +1,148 @@
+//! Autocomplete suggestions for keyword prefixes.
+//!
+//! Short prefixes match a large share of the vocabulary, so answering them by scanning
+//! chunks would read much of the data file. An index built with
+//! [`BuildOptions::prefix_suggestions`](crate::BuildOptions::prefix_suggestions)
+//! therefore stores, in the filters file, the most frequent keywords for every prefix of
+//! up to [`SUGGESTION_PREFIX_MAX_CHARS`] characters per column. Longer prefixes, requests
+//! for more than [`SUGGESTION_TOP_K`] suggestions and indexes without these tables fall
+//! back to scanning the chunks covering the prefix, which the sorted keyword layout keeps
+//! small.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!
+//!     for suggestion in searcher.suggest("ex", Some("email"), 5).await? {
+//!         println!("{} ({} rows)", suggestion.keyword, suggestion.occurrences);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use futures::TryStreamExt;
+use crate::error::Error;
+use crate::index_data::{KeywordCount, SUGGESTION_PREFIX_MAX_CHARS, SUGGESTION_TOP_K};
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::vocabulary::VocabularyOptions;
+use crate::utils::glob::is_glob_pattern;
+
+impl KeywordSearcher {
+    /// Suggest the most frequent keywords starting with a prefix.
+    ///
+    /// Frequency is the number of rows a keyword occurs in, within the given column or
+    /// across all columns. Ties are broken alphabetically.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Prefix typed so far (case-sensitive, may be empty)
+    /// * `column` - Optional column name or glob pattern to restrict suggestions to.
+    ///   `None` suggests keywords from all columns.
+    /// * `k` - Maximum number of suggestions to return
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` keywords ordered by descending occurrences.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the fallback chunk scan fails to read or deserialize a chunk.
+    ///
+    /// # Performance
+    ///
+    /// With an index built with prefix suggestions, prefixes of up to
+    /// [`SUGGESTION_PREFIX_MAX_CHARS`] characters with `k` at most [`SUGGESTION_TOP_K`] on
+    /// a single exact column (or all columns) are answered from memory without I/O.
+    /// Everything else reads the chunks covering the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// let suggestions = searcher.suggest("jo", None, 5).await?;
+    /// let words: Vec<&str> = suggestions.iter().map(|s| s.keyword.as_str()).collect();
+    /// println!("Did you mean: {}", words.join(", "));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn suggest(
+        &self,
+        prefix: &str,
+        column: Option<&str>,
+        k: usize,
+    ) -> Result<Vec<KeywordCount>, Error> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        if let Some(suggestions) = self.precomputed_suggestions(prefix, column, k) {
+            return Ok(suggestions);
+        }
+
+        // Fall back to scanning the chunks covering the prefix
+        let options = VocabularyOptions {
+            column: column.map(|c| c.to_string()),
+            prefix: Some(prefix.to_string()),
+            with_counts: true,
+            ..Default::default()
+        };
+
+        // Min-heap holding the best k so far, worst suggestion on top
+        let mut top: BinaryHeap<Reverse<(u64, Reverse<String>)>> = BinaryHeap::new();
+        let mut entries = self.vocabulary(options);
+
+        while let Some(entry) = entries.try_next().await? {
+            let occurrences = entry.total_occurrences.unwrap_or(0);
+            top.push(Reverse((occurrences, Reverse(entry.keyword))));
+            if top.len() > k {
+                top.pop();
+            }
+        }
+
+        Ok(top.into_sorted_vec().into_iter()
+            .map(|Reverse((occurrences, Reverse(keyword)))| KeywordCount { keyword, occurrences })
+            .collect())
+    }
+
+    /// Answer a suggestion request from the tables stored in the filters file, if possible.
+    ///
+    /// Returns `None` when the prefix is too long, `k` exceeds the stored list length,
+    /// the column is a glob pattern, or the index has no table for the column (always
+    /// the case without prefix suggestions).
+    fn precomputed_suggestions(&self, prefix: &str, column: Option<&str>, k: usize) -> Option<Vec<KeywordCount>> {
+        if k > SUGGESTION_TOP_K || prefix.chars().count() > SUGGESTION_PREFIX_MAX_CHARS {
+            return None;
+        }
+
+        let column_id = match column {
+            None => 0,
+            Some(name) if is_glob_pattern(name) => return None,
+            Some(name) => {
+                let id = self.filters.column_pool.strings.iter().position(|s| s == name)?;
+                if id == 0 {
+                    return None;
+                }
+                id as u32
+            }
+        };
+
+        let table = self.filters.prefix_suggestions.iter()
+            .find(|table| table.column_id == column_id)?;
+
+        // The table covers every prefix in the column, so a missing prefix means no matches
+        Some(table.prefixes.get(prefix)
+            .map(|top| top.iter().take(k).cloned().collect())
+            .unwrap_or_default())
+    }
+}
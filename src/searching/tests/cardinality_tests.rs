@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use bytes::Bytes;
+    use tokio::sync::OnceCell;
+    use crate::{build_index_in_memory, build_index_in_memory_with_options, BuildOptions, ParquetSource};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{build_index, string_parquet};
+    use crate::searching::tests::search_tests::tests::{create_test_parquet, get_searcher};
+
+    const ROWS_PER_GROUP: usize = 100;
+    const ROW_GROUPS: usize = 4;
+
+    static SKETCH_SEARCHER: OnceCell<KeywordSearcher> = OnceCell::const_new();
+
+    /// The test index of [`get_searcher`], built with column sketches
+    async fn get_sketch_searcher() -> &'static KeywordSearcher {
+        SKETCH_SEARCHER.get_or_init(|| async {
+            build_index(create_test_parquet().unwrap(), &sketch_options()).await
+        }).await
+    }
+
+    fn sketch_options() -> BuildOptions {
+        BuildOptions { column_sketches: true, ..Default::default() }
+    }
+
+    fn assert_close(estimate: f64, actual: u64, tolerance: f64) {
+        let error = (estimate - actual as f64).abs() / actual as f64;
+        assert!(error <= tolerance, "estimate {} vs actual {} (error {:.3})", estimate, actual, error);
+    }
+
+    /// One column whose row group `g` holds the values `v{g * 50}` to `v{g * 50 + 99}`,
+    /// so neighbouring row groups share half their values.
+    fn overlapping_row_groups_parquet(offset: usize) -> Bytes {
+        let values: Vec<String> = (0..ROW_GROUPS)
+            .flat_map(|g| (0..ROWS_PER_GROUP).map(move |i| format!("v{}", offset + g * 50 + i)))
+            .collect();
+        string_parquet(vec![("value", values.into())], ROWS_PER_GROUP)
+    }
+
+    #[tokio::test]
+    async fn test_estimate_matches_column_statistics() {
+        let searcher = get_sketch_searcher().await;
+
+        for stats in searcher.column_statistics() {
+            let estimate = searcher.estimate_distinct(Some(&stats.column_name)).unwrap();
+            assert_close(estimate, stats.distinct_keywords, 0.05);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_estimate_all_columns() {
+        let searcher = get_sketch_searcher().await;
+
+        // The union of every column's keywords is the aggregate keyword set
+        let all = searcher.column_sketch(None).unwrap();
+        let union = searcher.column_sketch(Some("*")).unwrap();
+        assert_eq!(all, union);
+
+        let total_keywords: usize = searcher.filters.chunk_index.iter().map(|c| c.count as usize).sum();
+        assert_close(all.estimate(), total_keywords as u64, 0.05);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_unknown_column() {
+        let searcher = get_sketch_searcher().await;
+        assert!(searcher.estimate_distinct(Some("no_such_column")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_column_sketches_opt_in() {
+        let searcher = get_searcher().await;
+        assert!(!searcher.has_column_sketches());
+        assert!(searcher.estimate_distinct(None).is_none());
+        assert!(get_sketch_searcher().await.has_column_sketches());
+    }
+
+    #[tokio::test]
+    async fn test_row_group_sketches_opt_in() {
+        let source = ParquetSource::Bytes(overlapping_row_groups_parquet(0));
+
+        let default_searcher = build_index_in_memory(source.clone(), None, None).await.unwrap();
+        assert!(default_searcher.estimate_distinct_in_row_groups(Some("value"), &[0]).is_none());
+
+        let options = BuildOptions { row_group_sketches: true, ..Default::default() };
+        let searcher = build_index_in_memory_with_options(source, None, None, &options).await.unwrap();
+
+        assert_close(searcher.estimate_distinct_in_row_groups(Some("value"), &[0]).unwrap(), 100, 0.05);
+        assert_close(searcher.estimate_distinct_in_row_groups(Some("value"), &[0, 1]).unwrap(), 150, 0.05);
+        assert_close(searcher.estimate_distinct_in_row_groups(Some("value"), &[0, 2]).unwrap(), 200, 0.05);
+        assert_eq!(searcher.estimate_distinct_in_row_groups(Some("value"), &[99]), Some(0.0));
+    }
+
+    #[tokio::test]
+    async fn test_merge_sketches_across_indexes() {
+        let first = build_index(overlapping_row_groups_parquet(0), &sketch_options()).await;
+        let second = build_index(overlapping_row_groups_parquet(100), &sketch_options()).await;
+
+        // First file holds v0..v249, second v100..v349
+        let expected: HashSet<usize> = (0..250).chain(100..350).collect();
+
+        let mut merged = first.column_sketch(Some("value")).unwrap();
+        merged.merge(&second.column_sketch(Some("value")).unwrap()).unwrap();
+        assert_close(merged.estimate(), expected.len() as u64, 0.05);
+    }
+}
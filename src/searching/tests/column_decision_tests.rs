@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use crate::BuildOptions;
+    use crate::column_sampling::{CardinalityCheck, HighCardinalityAction};
+    use crate::index_data::ColumnAction;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{build_index, string_parquet};
+
+    const ROWS: usize = 400;
+    const ROWS_PER_GROUP: usize = 100;
+
+    /// A UUID-like value that differs in every part from row to row.
+    fn request_id_at(row: usize) -> String {
+        // SplitMix64, so the parts look random but are reproducible
+        let mut x = (row as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        format!("{:08x}-{:04x}-{:04x}-{:04x}", x >> 32, (x >> 16) & 0xffff, x & 0xffff, row)
+    }
+
+    fn create_parquet() -> Bytes {
+        let request_id: Vec<String> = (0..ROWS).map(request_id_at).collect();
+        let email: Vec<String> = (0..ROWS).map(|row| format!("user{}@example.com", row)).collect();
+        let status: Vec<&str> = (0..ROWS).map(|row| ["ok", "error", "pending"][row % 3]).collect();
+        let note: Vec<Option<String>> = (0..ROWS)
+            .map(|row| (row % 50 == 0).then(|| format!("note-{}", row)))
+            .collect();
+        string_parquet(vec![
+            ("request_id", request_id.into()),
+            ("email", email.into()),
+            ("status", status.into()),
+            ("note", note.into()),
+        ], ROWS_PER_GROUP)
+    }
+
+    fn actions(searcher: &KeywordSearcher) -> Vec<(&str, ColumnAction)> {
+        searcher.column_decisions().iter()
+            .map(|decision| (decision.column_name.as_str(), decision.action))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_high_cardinality_column_excluded() {
+        let check = CardinalityCheck { sample_rows: 250, action: HighCardinalityAction::Exclude, ..Default::default() };
+        let searcher = build_index(create_parquet(), &BuildOptions { cardinality_check: Some(check), ..Default::default() }).await;
+
+        assert_eq!(actions(&searcher), vec![
+            ("request_id", ColumnAction::Excluded),
+            ("email", ColumnAction::Indexed),
+            ("status", ColumnAction::Indexed),
+            ("note", ColumnAction::Indexed),
+        ]);
+
+        let decisions = searcher.column_decisions();
+        assert_eq!(decisions[0].sampled_values, 250);
+        assert_eq!(decisions[0].distinct_value_ratio, 1.0);
+        assert!(decisions[0].reason.starts_with("High cardinality"), "{}", decisions[0].reason);
+
+        // Distinct emails share their domain keywords
+        assert_eq!(decisions[1].distinct_value_ratio, 1.0);
+        assert!(decisions[1].unique_keyword_ratio < 0.6);
+
+        // Only 5 of the 250 sampled rows have a note
+        assert_eq!(decisions[3].sampled_values, 5);
+        assert!(decisions[3].reason.starts_with("Too few sampled values"), "{}", decisions[3].reason);
+
+        assert!(!searcher.filters.column_pool.strings.iter().any(|column| column.as_str() == "request_id"));
+        let result = searcher.search(&request_id_at(7), None, true).await.unwrap();
+        assert!(!result.found);
+        let result = searcher.search("user7@example.com", None, true).await.unwrap();
+        assert!(result.found);
+    }
+
+    #[tokio::test]
+    async fn test_high_cardinality_column_exact_values() {
+        let check = CardinalityCheck { sample_rows: 250, action: HighCardinalityAction::ExactValues, ..Default::default() };
+        let searcher = build_index(create_parquet(), &BuildOptions { cardinality_check: Some(check), ..Default::default() }).await;
+        assert_eq!(actions(&searcher)[0], ("request_id", ColumnAction::ExactValues));
+
+        // Whole values are found in their row, across every row group and not only the sample
+        for row in [7, 320] {
+            let result = searcher.search(&request_id_at(row), Some("request_id"), true).await.unwrap();
+            let data = result.verified_matches.unwrap();
+            assert_eq!(data.total_occurrences, 1);
+            let location = &data.column_details[0].row_groups[0];
+            assert_eq!(location.row_group_id, (row / ROWS_PER_GROUP) as u32);
+            assert_eq!(location.row_ranges[0].start_row, (row % ROWS_PER_GROUP) as u32);
+        }
+
+        // Parts of values are not indexed
+        let first_part = request_id_at(7).split('-').next().unwrap().to_string();
+        let result = searcher.search(&first_part, Some("request_id"), true).await.unwrap();
+        assert!(!result.found);
+
+        let statistics = searcher.column_statistics();
+        let request_id = statistics.iter().find(|stats| stats.column_name == "request_id").unwrap();
+        assert_eq!(request_id.distinct_keywords, ROWS as u64);
+        assert_eq!(request_id.rows_with_keywords, ROWS as u64);
+    }
+
+    #[tokio::test]
+    async fn test_no_cardinality_check() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+        assert!(searcher.column_decisions().is_empty());
+
+        let first_part = request_id_at(7).split('-').next().unwrap().to_string();
+        let result = searcher.search(&first_part, Some("request_id"), true).await.unwrap();
+        assert!(result.found);
+    }
+}
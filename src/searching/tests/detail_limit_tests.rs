@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use crate::BuildOptions;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::search_results::KeywordLocationData;
+    use crate::searching::tests::{build_index, location_rows, row_group_locations, string_parquet};
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    const ROWS: usize = 100;
+    const ROWS_PER_GROUP: usize = 25;
+
+    /// "tag-even"/"tag-odd" alternate, so their keywords store one row entry per row,
+    /// while "id-N" occurs in a single row.
+    fn text_at(row: usize) -> String {
+        format!("tag-{} id-{}", if row.is_multiple_of(2) { "even" } else { "odd" }, row)
+    }
+
+    fn create_parquet() -> Bytes {
+        let text: Vec<String> = (0..ROWS).map(text_at).collect();
+        string_parquet(vec![("text", text.into())], ROWS_PER_GROUP)
+    }
+
+    /// Every (column, row group, row) of a set of locations, failing on coarse ones.
+    fn cells(data: Option<&KeywordLocationData>) -> BTreeSet<(String, u32, u32)> {
+        data.map(|data| {
+            assert!(row_group_locations(data).all(|(_, rg)| !rg.coarse), "Unexpected coarse location");
+            location_rows(data)
+        }).unwrap_or_default()
+    }
+
+    fn expected_cells(matches: impl Fn(&str) -> bool) -> BTreeSet<(String, u32, u32)> {
+        (0..ROWS)
+            .filter(|&row| matches(&text_at(row)))
+            .map(|row| ("text".to_string(), (row / ROWS_PER_GROUP) as u32, (row % ROWS_PER_GROUP) as u32))
+            .collect()
+    }
+
+    /// Cells containing `keyword` as a whole word or part of one.
+    fn keyword_cells(keyword: &str) -> BTreeSet<(String, u32, u32)> {
+        expected_cells(|text| text.split([' ', '-']).any(|part| part == keyword))
+    }
+
+    #[tokio::test]
+    async fn test_frequent_keywords_are_coarse() {
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(50), ..Default::default() }).await;
+
+        for in_columns in [None, Some("text")] {
+            let result = searcher.search("even", in_columns, true).await.unwrap();
+            let data = result.verified_matches.unwrap();
+            assert_eq!(data.total_occurrences, (ROWS / 2) as u64);
+            assert_eq!(data.column_details.len(), 1);
+
+            let row_groups = &data.column_details[0].row_groups;
+            assert_eq!(row_groups.len(), ROWS / ROWS_PER_GROUP);
+            assert!(row_groups.iter().all(|rg| rg.coarse && rg.row_ranges.is_empty()));
+        }
+
+        // Keywords within the limit keep their rows
+        for keyword in ["id-7", "7"] {
+            let result = searcher.search(keyword, None, true).await.unwrap();
+            assert_eq!(
+                cells(result.verified_matches.as_ref()),
+                expected_cells(|text| text.ends_with(&format!("id-{}", keyword.trim_start_matches("id-")))),
+                "Rows of {:?}", keyword,
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summaries_only_for_coarse_keywords() {
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(50), ..Default::default() }).await;
+
+        let mut coarse = 0;
+        for chunk_number in 0..searcher.filters.chunk_index.len() as u32 {
+            let (_, data) = searcher.read_full_chunk(chunk_number).await.unwrap();
+            for keyword_data in data {
+                coarse += keyword_data.coarse as usize;
+                for rg in keyword_data.columns.iter().flat_map(|col| &col.row_groups) {
+                    // Built without page locations, rows are all that detailed keywords store
+                    assert_eq!(rg.summary.is_some(), keyword_data.coarse);
+                    assert!(rg.occurrences() > 0);
+                }
+            }
+        }
+        assert!(coarse > 0);
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_counts_kept() {
+        let vocabulary = |searcher: KeywordSearcher| async move {
+            searcher.vocabulary(VocabularyOptions { with_counts: true, ..Default::default() })
+                .map_ok(|entry| (entry.keyword, entry.total_occurrences))
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+        };
+        let coarse = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(0), ..Default::default() }).await;
+        let detailed = build_index(create_parquet(), &BuildOptions::default()).await;
+        assert_eq!(vocabulary(coarse).await, vocabulary(detailed).await);
+    }
+
+    #[tokio::test]
+    async fn test_pruned_reader_reads_coarse_row_groups() {
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(50), ..Default::default() }).await;
+        let reader = PrunedParquetReader::from_bytes(create_parquet());
+
+        let result = searcher.search("even", None, true).await.unwrap();
+        let batches = reader.read_matching_rows(&result, None).await.unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), ROWS);
+
+        let stats = reader.get_pruning_stats(&result).await.unwrap();
+        assert_eq!(stats.rows_to_read, ROWS as u64);
+
+        // Highlighting reads the coarse row groups and returns only the matching cells
+        let highlighted = searcher.highlight_search_result(result, &reader).await.unwrap();
+        let highlighted_cells: BTreeSet<_> = highlighted.cells.iter()
+            .map(|cell| (cell.column_name.clone(), cell.row_group_id, cell.row))
+            .collect();
+        assert_eq!(highlighted_cells, keyword_cells("even"));
+        assert_eq!(cells(highlighted.result.verified_matches.as_ref()), keyword_cells("even"));
+    }
+
+    #[tokio::test]
+    async fn test_phrase_with_coarse_tokens() {
+        let reader = PrunedParquetReader::from_bytes(create_parquet());
+
+        // With a limit of 50 "id-40" and "40" keep their rows and only "even" and "id"
+        // are coarse; with 0 every token is coarse and the row group is read in full
+        for (limit, candidates_checked) in [(Some(50), 1), (Some(0), ROWS_PER_GROUP)] {
+            let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: limit, ..Default::default() }).await;
+            let result = searcher.search("even id-40", None, false).await.unwrap();
+            assert!(result.found);
+            assert!(result.verified_matches.is_none());
+            let candidates = result.needs_verification.as_ref().unwrap();
+            assert!(candidates.column_details.iter()
+                .flat_map(|col| &col.row_groups)
+                .all(|rg| rg.coarse == (limit == Some(0))));
+
+            let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+            assert_eq!(cells(verified.result.verified_matches.as_ref()), expected_cells(|text| text.contains("even id-40")));
+            assert_eq!(verified.candidates_checked, candidates_checked as u64);
+        }
+
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(0), ..Default::default() }).await;
+        let result = searcher.search("tag-even", None, false).await.unwrap();
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(cells(verified.result.verified_matches.as_ref()), expected_cells(|text| text.contains("tag-even")));
+        assert_eq!(verified.candidates_checked, ROWS as u64);
+        assert_eq!(verified.candidates_rejected, (ROWS / 2) as u64);
+    }
+
+    #[tokio::test]
+    async fn test_combine_with_coarse_results() {
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(50), ..Default::default() }).await;
+        let even = searcher.search("even", None, true).await.unwrap();
+        let id = searcher.search("id-4", None, true).await.unwrap();
+
+        // Rows of the exact keyword are kept as candidates
+        let combined = KeywordSearcher::combine_and(&[even.clone(), id.clone()]).unwrap();
+        assert_eq!(combined.row_groups.len(), 1);
+        assert!(combined.row_groups[0].coarse);
+        assert_eq!(
+            combined.row_groups[0].row_ranges.iter().map(|r| (r.start_row, r.end_row)).collect::<Vec<_>>(),
+            vec![(4, 4)]
+        );
+
+        let combined = KeywordSearcher::combine_or(&[even, id]).unwrap();
+        assert_eq!(combined.row_groups.len(), ROWS / ROWS_PER_GROUP);
+        assert!(combined.row_groups.iter().all(|rg| rg.coarse && rg.row_ranges.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_substring_returns_coarse_locations() {
+        let searcher = build_index(create_parquet(), &BuildOptions { keyword_detail_limit: Some(50), ..Default::default() }).await;
+
+        let result = searcher.search_substring("ven", None).await.unwrap();
+        let row_groups = &result.verified_matches.unwrap().column_details[0].row_groups;
+        assert_eq!(row_groups.len(), ROWS / ROWS_PER_GROUP);
+        assert!(row_groups.iter().all(|rg| rg.coarse));
+
+        let result = searcher.search_substring("d-1", None).await.unwrap();
+        assert_eq!(cells(result.verified_matches.as_ref()), expected_cells(|text| text.contains("d-1")));
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use arrow::array::AsArray;
+    use bytes::Bytes;
+    use crate::{build_and_save_text_index, BuildOptions, ParquetSource};
+    use crate::column_parquet_reader::process_parquet_file_with_options;
+    use crate::error::Error;
+    use crate::index_data::{
+        build_distributed_index, filters_file_version, save_distributed_index, DistributedIndexFiles, FlatRow,
+        IndexData, IndexGranularity, TextFormat, FILTERS_MAGIC, INDEX_FORMAT_VERSION,
+    };
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::pruned_text_reader::PrunedTextReader;
+    use crate::searching::search_results::SearchResult;
+    use crate::searching::tests::row_group_locations;
+    use crate::text_source::TextOptions;
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    /// An index of `fixtures/version_1/data.parquet` written by the first release, which
+    /// used format version 1: two columns of 200 rows in row groups of 20 rows.
+    const V1_FILTERS: &[u8] = include_bytes!("fixtures/version_1/filters.rkyv");
+    const V1_DATA: &[u8] = include_bytes!("fixtures/version_1/data.bin");
+    const V1_PARQUET: &[u8] = include_bytes!("fixtures/version_1/data.parquet");
+
+    async fn build(path: &str, bytes: Bytes, options: &BuildOptions) -> DistributedIndexFiles {
+        register_memory_file(path, bytes).await.unwrap();
+        let source = ParquetSource::Path(path.to_string());
+        let result = process_parquet_file_with_options(source.clone(), None, None, options).await.unwrap();
+        build_distributed_index(&result, &source, 0.01, options).await.unwrap()
+    }
+
+    async fn load(files: &DistributedIndexFiles, path: &str, prefix: Option<&str>) -> KeywordSearcher {
+        save_distributed_index(files, path, prefix).await.unwrap();
+        KeywordSearcher::load(path, prefix).await.unwrap()
+    }
+
+    /// (column, row group, first row, last row, parent) of a location
+    type Location = (String, u32, u32, u32, Option<(u32, u32)>);
+
+    /// Every location in a result
+    fn locations(result: &SearchResult) -> BTreeSet<Location> {
+        result.verified_matches.iter()
+            .flat_map(row_group_locations)
+            .flat_map(|(col, rg)| rg.row_ranges.iter().map(move |range| (
+                col.column_name.clone(),
+                rg.row_group_id,
+                range.start_row,
+                range.end_row,
+                range.parent_chunk.zip(range.parent_position),
+            )))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_reads_version_1_index() {
+        let path = "memory://format-version-1.parquet";
+        let files = build(path, Bytes::from_static(V1_PARQUET), &BuildOptions::default()).await;
+        let current = load(&files, path, None).await;
+        assert_eq!(current.filters.version, INDEX_FORMAT_VERSION);
+        assert_eq!(filters_file_version(&files.filters).0, INDEX_FORMAT_VERSION);
+        assert_eq!(filters_file_version(V1_FILTERS).0, 1);
+
+        // Varint row entries keep the wider fields smaller than version 1's
+        assert!(files.data.len() < V1_DATA.len() as u64, "{} bytes", files.data.len());
+
+        let v1_files = DistributedIndexFiles { filters: V1_FILTERS.to_vec(), data: IndexData::Memory(V1_DATA.to_vec()), trigrams: Vec::new() };
+        let legacy = load(&v1_files, path, Some("v1")).await;
+        assert_eq!(legacy.filters.version, 1);
+        assert_eq!(legacy.filters.granularity, IndexGranularity::Rows);
+        assert!(legacy.filters.trigram_directory.is_empty());
+        assert!(legacy.build_config().is_none());
+
+        for query in ["example", "user@example.com", "sample-data", "from user@example.com", "error", "missing"] {
+            for keyword_only in [true, false] {
+                let expected = current.search(query, None, keyword_only).await.unwrap();
+                let result = legacy.search(query, None, keyword_only).await.unwrap();
+                assert_eq!(result.found, expected.found, "{}", query);
+                assert_eq!(locations(&result), locations(&expected), "{}", query);
+                assert_eq!(
+                    result.verified_matches.map(|data| data.total_occurrences),
+                    expected.verified_matches.map(|data| data.total_occurrences),
+                    "{}", query
+                );
+            }
+        }
+
+        let expected = current.search_substring("ampl", Some("text")).await.unwrap();
+        let result = legacy.search_substring("ampl", Some("text")).await.unwrap();
+        assert_eq!(result.tokens, expected.tokens);
+        assert_eq!(locations(&result), locations(&expected));
+        assert!(result.found);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_newer_version() {
+        let path = "memory://format-newer.parquet";
+        let mut files = build(path, Bytes::from_static(V1_PARQUET), &BuildOptions::default()).await;
+        files.filters[FILTERS_MAGIC.len()..FILTERS_MAGIC.len() + 4].copy_from_slice(&(INDEX_FORMAT_VERSION + 1).to_le_bytes());
+
+        let error = KeywordSearcher::from_serialized(&files, format!("{}.index", path), None).err().unwrap();
+        assert!(matches!(&error, Error::CorruptIndex(message) if message.contains("Unsupported index format version")), "{}", error);
+    }
+
+    #[test]
+    fn test_row_entries_round_trip() {
+        let rows = vec![
+            FlatRow { row: 1_000_000, additional_rows: 0, splits_matched: 3, parent_chunk: Some(u32::MAX - 1), parent_position: Some(u32::MAX) },
+            FlatRow { row: 5, additional_rows: u32::MAX - 5, splits_matched: u16::MAX, parent_chunk: None, parent_position: None },
+            FlatRow { row: 5, additional_rows: 1, splits_matched: 0, parent_chunk: Some(0), parent_position: Some(0) },
+            FlatRow { row: u32::MAX, additional_rows: 0, splits_matched: 1, parent_chunk: None, parent_position: None },
+        ];
+        let bytes = FlatRow::encode(&rows);
+        assert_eq!(FlatRow::decode(&bytes).collect::<Vec<_>>(), rows);
+
+        // Truncated entries end the rows instead of panicking
+        assert_eq!(FlatRow::decode(&bytes[..bytes.len() - 1]).count(), rows.len() - 1);
+        assert_eq!(FlatRow::decode(&[0xff; 3]).count(), 0);
+
+        // Searches reject them when reading the chunk
+        assert!(FlatRow::validate(&bytes).is_ok());
+        assert!(matches!(FlatRow::validate(&bytes[..bytes.len() - 1]), Err(Error::CorruptIndex(_))));
+        assert!(matches!(FlatRow::validate(&[0xff; 3]), Err(Error::CorruptIndex(_))));
+    }
+
+    #[tokio::test]
+    async fn test_more_than_u16_row_groups() {
+        // Parquet writers stop at 32,767 row groups, text sources have a batch per row
+        let path = "memory://format-row-groups.csv";
+        let mut csv = "text\n".to_string();
+        (0..65_540).for_each(|row| csv.push_str(if row == 65_537 { "needle in the last batches\n" } else { "haystack\n" }));
+        register_memory_file(path, Bytes::from(csv)).await.unwrap();
+        let text_options = TextOptions { rows_per_batch: 1, ..TextOptions::new(TextFormat::CSV) };
+        let options = BuildOptions { row_group_sketches: true, ..Default::default() };
+        build_and_save_text_index(path, &text_options, None, None, None, &options).await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+
+        let result = searcher.search("needle", None, true).await.unwrap();
+        let row_groups: Vec<u32> = result.verified_matches.iter()
+            .flat_map(|data| &data.column_details)
+            .flat_map(|col| col.row_groups.iter().map(|rg| rg.row_group_id))
+            .collect();
+        assert_eq!(row_groups, [65_537]);
+        assert!(searcher.estimate_distinct_in_row_groups(Some("text"), &[65_537]).is_some());
+
+        let result = searcher.search("needle in the last", None, false).await.unwrap();
+        assert!(result.found);
+        let reader = PrunedTextReader::new(ParquetSource::Path(path.to_string()), searcher.text_layout().unwrap()).unwrap();
+        let batches = reader.read_search_result(&result, None).await.unwrap();
+        let values: Vec<&str> = batches.iter().map(|batch| batch.column(0).as_string::<i32>().value(0)).collect();
+        assert_eq!(values, ["needle in the last batches"]);
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use crate::{BuildOptions, ParquetSource};
+    use crate::column_parquet_reader::process_parquet_file_with_options;
+    use crate::index_data::{build_distributed_index, IndexGranularity, RowGroupBitmap};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::search_results::KeywordLocationData;
+    use crate::searching::tests::{build_index, row_group_locations, string_parquet};
+    use crate::searching::vocabulary::VocabularyOptions;
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS: usize = 1000;
+    const ROWS_PER_GROUP: usize = 100;
+
+    /// "level" cycles through three values, "region" changes once per row group.
+    fn message_at(row: usize) -> String {
+        format!("{} request-{} region-{}", ["info", "warn", "error"][row % 3], row, row / ROWS_PER_GROUP)
+    }
+
+    fn create_parquet() -> Bytes {
+        let message: Vec<String> = (0..ROWS).map(message_at).collect();
+        let host: Vec<String> = (0..ROWS).map(|row| format!("host-{}", row % 7)).collect();
+        string_parquet(vec![("message", message.into()), ("host", host.into())], ROWS_PER_GROUP)
+    }
+
+    /// Row groups of every column of a set of locations, failing on non-coarse ones.
+    fn row_groups(data: Option<&KeywordLocationData>) -> BTreeSet<(String, u32)> {
+        data.into_iter()
+            .flat_map(row_group_locations)
+            .map(|(col, rg)| {
+                assert!(rg.coarse && rg.row_ranges.is_empty(), "Expected a coarse location");
+                (col.column_name.clone(), rg.row_group_id)
+            })
+            .collect()
+    }
+
+    fn expected_row_groups(column: &str, matches: impl Fn(usize) -> bool) -> BTreeSet<(String, u32)> {
+        (0..ROWS)
+            .filter(|&row| matches(row))
+            .map(|row| (column.to_string(), (row / ROWS_PER_GROUP) as u32))
+            .collect()
+    }
+
+    #[test]
+    fn test_row_group_bitmap_round_trip() {
+        let row_groups = [0u32, 3, 63, 64, 200, 65_535, 65_536, 70_000];
+        let bitmap = RowGroupBitmap::new(row_groups.iter().copied(), 42);
+        assert_eq!(bitmap.row_groups().collect::<Vec<_>>(), row_groups);
+        assert_eq!(bitmap.occurrences, 42);
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&bitmap).unwrap();
+        let archived = rkyv::access::<rkyv::Archived<RowGroupBitmap>, rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.row_groups().collect::<Vec<_>>(), row_groups);
+    }
+
+    #[tokio::test]
+    async fn test_data_file_smaller() {
+        let path = "memory://granularity-size.parquet";
+        register_memory_file(path, create_parquet()).await.unwrap();
+
+        let mut sizes = Vec::new();
+        for granularity in [IndexGranularity::Rows, IndexGranularity::RowGroups] {
+            let options = BuildOptions { granularity, ..Default::default() };
+            let result = process_parquet_file_with_options(ParquetSource::Path(path.to_string()), None, Some(0.01), &options)
+                .await
+                .unwrap();
+            let files = build_distributed_index(&result, &ParquetSource::Path(path.to_string()), 0.01, &options)
+                .await
+                .unwrap();
+            sizes.push(files.data.len());
+        }
+        // Row entries are small varints, so the keyword lists make up much of both files
+        assert!(sizes[1] * 4 < sizes[0] * 3, "data.bin sizes {:?}", sizes);
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_row_groups() {
+        let searcher = build_index(create_parquet(), &BuildOptions { granularity: IndexGranularity::RowGroups, ..Default::default() }).await;
+        assert_eq!(searcher.granularity(), IndexGranularity::RowGroups);
+
+        let result = searcher.search("region-4", None, true).await.unwrap();
+        let data = result.verified_matches.as_ref().unwrap();
+        assert_eq!(data.total_occurrences, ROWS_PER_GROUP as u64);
+        assert_eq!(row_groups(Some(data)), expected_row_groups("message", |row| row / ROWS_PER_GROUP == 4));
+
+        // "error" occurs in every row group, and every third row
+        let result = searcher.search("error", None, true).await.unwrap();
+        let data = result.verified_matches.as_ref().unwrap();
+        assert_eq!(data.total_occurrences, ROWS.div_ceil(3) as u64 - 1);
+        assert_eq!(row_groups(Some(data)), expected_row_groups("message", |_| true));
+
+        // Column filters apply to the bitmaps of each column
+        let result = searcher.search("host-3", Some("message"), true).await.unwrap();
+        assert!(!result.found);
+        let result = searcher.search("host-3", Some("host"), true).await.unwrap();
+        assert_eq!(row_groups(result.verified_matches.as_ref()), expected_row_groups("host", |row| row % 7 == 3));
+
+        let result = searcher.search("request-123", None, true).await.unwrap();
+        assert_eq!(row_groups(result.verified_matches.as_ref()), expected_row_groups("message", |row| row == 123));
+
+        let result = searcher.search_substring("gion-7", None).await.unwrap();
+        assert_eq!(row_groups(result.verified_matches.as_ref()), expected_row_groups("message", |row| row / ROWS_PER_GROUP == 7));
+    }
+
+    #[tokio::test]
+    async fn test_pruned_reader_reads_row_groups() {
+        let searcher = build_index(create_parquet(), &BuildOptions { granularity: IndexGranularity::RowGroups, ..Default::default() }).await;
+        let reader = PrunedParquetReader::from_bytes(create_parquet());
+
+        let result = searcher.search("request-123", None, true).await.unwrap();
+        let batches = reader.read_matching_rows(&result, None).await.unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), ROWS_PER_GROUP);
+
+        // Verifying a phrase narrows the row groups down to the matching rows
+        let result = searcher.search("warn request-124", None, false).await.unwrap();
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(verified.candidates_checked, ROWS_PER_GROUP as u64);
+        let data = verified.result.verified_matches.unwrap();
+        assert_eq!(data.column_details.len(), 1);
+        let row_groups = &data.column_details[0].row_groups;
+        assert_eq!(row_groups.len(), 1);
+        assert_eq!((row_groups[0].row_group_id, row_groups[0].row_ranges[0].start_row), (1, 24));
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_counts_kept() {
+        let vocabulary = |searcher: KeywordSearcher| async move {
+            searcher.vocabulary(VocabularyOptions { with_counts: true, ..Default::default() })
+                .map_ok(|entry| (entry.keyword, entry.total_occurrences))
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+        };
+        let row_groups = build_index(create_parquet(), &BuildOptions { granularity: IndexGranularity::RowGroups, ..Default::default() }).await;
+        let rows = build_index(create_parquet(), &BuildOptions { granularity: IndexGranularity::Rows, ..Default::default() }).await;
+        assert_eq!(vocabulary(row_groups).await, vocabulary(rows).await);
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use bytes::Bytes;
+    use crate::BuildOptions;
+    use crate::searching::highlight::{find_highlights, find_substring_highlights};
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::search_results::{Highlight, HighlightedSearchResult};
+    use crate::searching::tests::{build_index, string_parquet};
+
+    const TEXTS: [&str; 5] = [
+        "alpha-beta and alpha-beta again",
+        "beta-alpha",
+        "größe alpha-beta",
+        "gamma",
+        "alpha beta",
+    ];
+    const ROWS: usize = 40;
+    const ROWS_PER_GROUP: usize = 15;
+
+    fn text_at(row: usize) -> &'static str {
+        TEXTS[row % TEXTS.len()]
+    }
+
+    fn create_parquet() -> Bytes {
+        let text: Vec<&str> = (0..ROWS).map(text_at).collect();
+        let other: Vec<&str> = (0..ROWS).map(|row| text_at(row + 2)).collect();
+        string_parquet(vec![("text", text.into()), ("other", other.into())], ROWS_PER_GROUP)
+    }
+
+    async fn highlight(query: &str, in_columns: Option<&str>) -> HighlightedSearchResult {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        let result = searcher.search(query, in_columns, false).await.unwrap();
+        searcher.highlight_search_result(result, &reader).await.unwrap()
+    }
+
+    fn cell_value(column: &str, row_group_id: u32, row: u32) -> &'static str {
+        let row = row_group_id as usize * ROWS_PER_GROUP + row as usize;
+        match column {
+            "text" => text_at(row),
+            _ => text_at(row + 2),
+        }
+    }
+
+    #[test]
+    fn test_find_highlights() {
+        assert_eq!(
+            find_highlights("alpha-beta and alpha-beta again", "alpha-beta"),
+            vec![Highlight { offset: 0, length: 10 }, Highlight { offset: 15, length: 10 }]
+        );
+        // Offsets are byte offsets
+        assert_eq!(find_highlights("größe alpha", "alpha"), vec![Highlight { offset: 8, length: 5 }]);
+        // Occurrences do not overlap
+        assert_eq!(find_highlights("aa-aa", "aa").len(), 2);
+        assert!(find_highlights("gamma", "beta").is_empty());
+        assert!(find_highlights("gamma", "").is_empty());
+    }
+
+    #[test]
+    fn test_find_highlights_at_token_boundaries() {
+        assert!(find_highlights("administrator", "admin").is_empty());
+        assert!(find_highlights("sysadmin", "admin").is_empty());
+        assert_eq!(
+            find_highlights("administrator admin admin.example", "admin"),
+            vec![Highlight { offset: 14, length: 5 }, Highlight { offset: 20, length: 5 }]
+        );
+        // A phrase starting with a split character needs no boundary before it
+        assert_eq!(find_highlights("bigjohn-doe", "-doe"), vec![Highlight { offset: 7, length: 4 }]);
+        // A later occurrence overlapping a rejected one is still found
+        assert_eq!(find_highlights("xa-a-b", "a-b"), vec![Highlight { offset: 3, length: 3 }]);
+
+        assert_eq!(find_substring_highlights("administrator", "admin"), vec![Highlight { offset: 0, length: 5 }]);
+    }
+
+    #[tokio::test]
+    async fn test_highlight_phrase() {
+        let highlighted = highlight("alpha-beta", None).await;
+        assert!(highlighted.result.found);
+        assert!(highlighted.result.needs_verification.is_none());
+
+        // Every cell containing the phrase is returned, and nothing else
+        let expected: BTreeSet<(String, u32, u32)> = ["text", "other"].iter()
+            .flat_map(|&column| (0..ROWS).map(move |row| (column, row)))
+            .map(|(column, row)| (column.to_string(), (row / ROWS_PER_GROUP) as u32, (row % ROWS_PER_GROUP) as u32))
+            .filter(|(column, rg, row)| cell_value(column, *rg, *row).contains("alpha-beta"))
+            .collect();
+        let cells: BTreeSet<_> = highlighted.cells.iter()
+            .map(|cell| (cell.column_name.clone(), cell.row_group_id, cell.row))
+            .collect();
+        assert_eq!(cells, expected);
+        assert_eq!(highlighted.result.verified_matches.as_ref().unwrap().total_occurrences, expected.len() as u64);
+
+        for cell in &highlighted.cells {
+            assert_eq!(cell.value, cell_value(&cell.column_name, cell.row_group_id, cell.row));
+            assert_eq!(cell.highlights, find_highlights(&cell.value, "alpha-beta"));
+            for hit in &cell.highlights {
+                assert_eq!(&cell.value[hit.offset..hit.offset + hit.length], "alpha-beta");
+            }
+        }
+
+        let repeated = highlighted.cells.iter().find(|cell| cell.value.starts_with("alpha-beta and")).unwrap();
+        assert_eq!(repeated.highlights.len(), 2);
+        let unicode = highlighted.cells.iter().find(|cell| cell.value.starts_with("größe")).unwrap();
+        assert_eq!(unicode.highlights, vec![Highlight { offset: 8, length: 10 }]);
+    }
+
+    #[tokio::test]
+    async fn test_highlight_cells_sorted_and_filtered() {
+        let highlighted = highlight("gamma", Some("text")).await;
+
+        assert!(highlighted.cells.iter().all(|cell| cell.column_name == "text" && cell.value == "gamma"));
+        assert_eq!(highlighted.cells.len(), ROWS / TEXTS.len());
+        assert!(highlighted.cells.windows(2)
+            .all(|w| (w[0].row_group_id, w[0].row) < (w[1].row_group_id, w[1].row)));
+    }
+
+    #[tokio::test]
+    async fn test_highlight_substring() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        let result = searcher.search_substring("lph", Some("text")).await.unwrap();
+        let highlighted = searcher.highlight_substring_result(result, &reader).await.unwrap();
+
+        let rows_with_alpha = (0..ROWS).filter(|&row| text_at(row).contains("lph")).count();
+        assert_eq!(highlighted.cells.len(), rows_with_alpha);
+        for cell in &highlighted.cells {
+            assert!(!cell.highlights.is_empty());
+            assert!(cell.highlights.iter().all(|hit| &cell.value[hit.offset..hit.offset + hit.length] == "lph"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_highlight_not_found() {
+        let highlighted = highlight("delta", None).await;
+        assert!(!highlighted.result.found);
+        assert!(highlighted.cells.is_empty());
+        assert!(highlighted.result.verified_matches.is_none());
+    }
+}
@@ -2,8 +2,9 @@
 mod tests {
     use crate::searching::keyword_search::KeywordSearcher;
     use crate::index_structure::column_filter::ColumnFilter;
-    use crate::index_data::IndexFilters;
+    use crate::index_data::{IndexFilters, INDEX_FORMAT_VERSION};
     use crate::utils::column_pool::ColumnPool;
+    use crate::DEFAULT_CHUNK_SIZE_BYTES;
 
     /// Creates a test searcher with standard split character configuration
     fn create_test_searcher() -> KeywordSearcher {
@@ -15,16 +16,26 @@ mod tests {
         ];
 
         let filters = IndexFilters {
-            version: 1,
+            version: INDEX_FORMAT_VERSION,
             parquet_etag: "test".to_string(),
             parquet_size: 0,
             parquet_last_modified: 0,
             error_rate: 0.01,
+            chunk_size_bytes: DEFAULT_CHUNK_SIZE_BYTES as u64,
             split_chars_inclusive,
             column_pool: ColumnPool::new(),
             column_filters: std::collections::HashMap::new(),
             global_filter: ColumnFilter::RkyvHashSet(vec![]),
             chunk_index: vec![],
+            prefix_suggestions: vec![],
+            column_statistics: vec![],
+            column_sketches: vec![],
+            trigram_directory: vec![],
+            column_decisions: vec![],
+            granularity: Default::default(),
+            page_tables: vec![],
+            text_layout: None,
+            build_config: None,
             parquet_metadata_offset: 5,
             parquet_metadata_length: 5
         };
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use crate::{BuildOptions, ParquetSource};
+    use crate::column_parquet_reader::process_parquet_file_with_options;
+    use crate::index_data::{build_distributed_index, IndexData};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{build_index, string_parquet};
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    const ROWS: usize = 600;
+    const ROWS_PER_GROUP: usize = 100;
+
+    /// Two columns mixing frequent keywords, phrases with parents, and unique keywords,
+    /// so that the same keyword shows up in many runs.
+    fn create_parquet() -> Bytes {
+        let message: Vec<String> = (0..ROWS)
+            .map(|row| format!("GET /api/v{}/items-{} status:{}", row % 3, row, if row % 7 == 0 { "error" } else { "ok" }))
+            .collect();
+        let email: Vec<String> = (0..ROWS)
+            .map(|row| format!("user{}@example{}.com", row % 40, row % 2))
+            .collect();
+        string_parquet(vec![("message", message.into()), ("email", email.into())], ROWS_PER_GROUP)
+    }
+
+    fn budget_options(memory_budget: Option<usize>) -> BuildOptions {
+        BuildOptions { memory_budget, threads: 2, ..Default::default() }
+    }
+
+    async fn vocabulary(searcher: &KeywordSearcher) -> Vec<(String, Option<u64>)> {
+        searcher.vocabulary(VocabularyOptions { with_counts: true, ..Default::default() })
+            .map_ok(|entry| (entry.keyword, entry.total_occurrences))
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_small_budget_spills_runs() {
+        let bytes = create_parquet();
+        let result = process_parquet_file_with_options(
+            ParquetSource::Bytes(bytes.clone()), None, None, &budget_options(Some(1)),
+        ).await.unwrap();
+
+        // Every column chunk exceeds a one-byte budget, so nothing stays in memory
+        assert_eq!(result.spilled_runs.len(), 2 * ROWS / ROWS_PER_GROUP);
+        assert!(result.keyword_map.is_empty());
+        assert!(result.spilled_runs.iter().all(|run| !run.is_empty() && run.path().exists()));
+
+        // Keyword strings stay in the runs; the filters are built while merging them
+        assert!(result.column_keywords_map.is_empty());
+        let source = ParquetSource::Bytes(bytes);
+        let files = build_distributed_index(&result, &source, 0.01, &budget_options(Some(1))).await.unwrap();
+        let IndexData::File { file, length } = &files.data else { panic!("data.bin of a spilled build is kept in memory") };
+        assert_eq!(std::fs::metadata(file.path()).unwrap().len(), *length);
+        let data_path = file.path().to_path_buf();
+
+        let searcher = KeywordSearcher::from_serialized(&files, "memory://spilled.parquet.index".to_string(), None).unwrap();
+        assert!(searcher.filters.column_filters["email"].might_contain("example0"));
+        assert!(searcher.filters.column_filters["message"].might_contain("status:error"));
+        assert!(searcher.filters.global_filter.might_contain("status:error"));
+
+        let paths: Vec<_> = result.spilled_runs.iter().map(|run| run.path().to_path_buf()).collect();
+        drop(result);
+        drop(files);
+        assert!(paths.iter().chain([&data_path]).all(|path| !path.exists()));
+    }
+
+    #[tokio::test]
+    async fn test_large_budget_does_not_spill() {
+        let result = process_parquet_file_with_options(
+            ParquetSource::Bytes(create_parquet()), None, None, &budget_options(Some(usize::MAX)),
+        ).await.unwrap();
+        assert!(result.spilled_runs.is_empty());
+        assert!(!result.keyword_map.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_spilled_data_matches_in_memory() {
+        let bytes = create_parquet();
+        let source = ParquetSource::Bytes(bytes.clone());
+
+        let mut data_files = Vec::new();
+        for memory_budget in [None, Some(1), Some(20_000)] {
+            let options = budget_options(memory_budget);
+            let result = process_parquet_file_with_options(source.clone(), None, None, &options).await.unwrap();
+            assert_eq!(result.spilled_runs.is_empty(), memory_budget.is_none());
+            let files = build_distributed_index(&result, &source, 0.01, &options).await.unwrap();
+            data_files.push(files.data.to_vec().unwrap());
+        }
+
+        // Runs are merged back into exactly the same chunk layout and occurrence data
+        assert!(data_files.iter().all(|data| *data == data_files[0]));
+    }
+
+    /// Distinct keywords, rows with keywords and top keywords of a column
+    type ColumnSummary = (u64, u64, Vec<(String, u64)>);
+
+    #[tokio::test]
+    async fn test_spilled_index_search_results() {
+        let unbounded = build_index(create_parquet(), &budget_options(None)).await;
+        let spilled = build_index(create_parquet(), &budget_options(Some(1))).await;
+
+        assert_eq!(vocabulary(&unbounded).await, vocabulary(&spilled).await);
+
+        let statistics = |searcher: &KeywordSearcher| -> BTreeMap<String, ColumnSummary> {
+            searcher.column_statistics().into_iter()
+                .map(|stats| (
+                    stats.column_name,
+                    (
+                        stats.distinct_keywords,
+                        stats.rows_with_keywords,
+                        stats.top_keywords.into_iter().map(|k| (k.keyword, k.occurrences)).collect(),
+                    ),
+                ))
+                .collect()
+        };
+        assert_eq!(statistics(&unbounded), statistics(&spilled));
+
+        for query in ["GET", "status:error", "user7@example1.com", "items-599", "/api/v2/items-5", "missing"] {
+            let expected = unbounded.search(query, None, true).await.unwrap();
+            let actual = spilled.search(query, None, true).await.unwrap();
+            assert_eq!(actual.found, expected.found, "Found for {:?}", query);
+            assert_eq!(
+                format!("{:?}", actual.verified_matches),
+                format!("{:?}", expected.verified_matches),
+                "Matches for {:?}", query,
+            );
+        }
+
+        let expected = unbounded.suggest("us", Some("email"), 5).await.unwrap();
+        let actual = spilled.suggest("us", Some("email"), 5).await.unwrap();
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+}
@@ -1,2 +1,75 @@
 pub(crate) mod search_tests;
-mod keyword_search_tests;
\ No newline at end of file
+mod keyword_search_tests;
+mod vocabulary_tests;
+mod suggest_tests;
+mod cardinality_tests;
+mod verify_tests;
+mod substring_tests;
+mod highlight_tests;
+mod memory_budget_tests;
+mod detail_limit_tests;
+mod column_decision_tests;
+mod nested_column_tests;
+mod granularity_tests;
+mod page_location_tests;
+mod text_source_tests;
+mod format_version_tests;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use crate::{build_index_in_memory_with_options, BuildOptions, ParquetSource};
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::search_results::{ColumnLocation, KeywordLocationData, RowGroupLocation};
+
+/// A batch of Utf8 columns, given as name and values. A column is nullable when it has
+/// nulls.
+pub(crate) fn string_batch(columns: Vec<(&str, StringArray)>) -> RecordBatch {
+    let fields: Vec<Field> = columns.iter()
+        .map(|(name, values)| Field::new(*name, DataType::Utf8, values.null_count() > 0))
+        .collect();
+    let arrays: Vec<ArrayRef> = columns.into_iter().map(|(_, values)| Arc::new(values) as ArrayRef).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).unwrap()
+}
+
+/// Writes `batch` as a Parquet file with `props`.
+pub(crate) fn write_parquet(batch: &RecordBatch, props: WriterProperties) -> Bytes {
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props)).unwrap();
+    writer.write(batch).unwrap();
+    writer.close().unwrap();
+    Bytes::from(buffer)
+}
+
+/// Writes Utf8 columns (see [`string_batch`]) as a Parquet file in row groups of
+/// `rows_per_group` rows.
+pub(crate) fn string_parquet(columns: Vec<(&str, StringArray)>, rows_per_group: usize) -> Bytes {
+    let props = WriterProperties::builder().set_max_row_group_size(rows_per_group).build();
+    write_parquet(&string_batch(columns), props)
+}
+
+/// Builds the index of a Parquet file in memory with `options`.
+pub(crate) async fn build_index(bytes: Bytes, options: &BuildOptions) -> KeywordSearcher {
+    build_index_in_memory_with_options(ParquetSource::Bytes(bytes), None, None, options)
+        .await
+        .unwrap()
+}
+
+/// Every row group location in the data, with the column it belongs to.
+pub(crate) fn row_group_locations(data: &KeywordLocationData) -> impl Iterator<Item = (&ColumnLocation, &RowGroupLocation)> {
+    data.column_details.iter().flat_map(|col| col.row_groups.iter().map(move |rg| (col, rg)))
+}
+
+/// (column, row group, row) for every row range in the data.
+pub(crate) fn location_rows(data: &KeywordLocationData) -> BTreeSet<(String, u32, u32)> {
+    row_group_locations(data)
+        .flat_map(|(col, rg)| rg.row_ranges.iter()
+            .flat_map(|range| range.start_row..=range.end_row)
+            .map(move |row| (col.column_name.clone(), rg.row_group_id, row)))
+        .collect()
+}
@@ -0,0 +1,247 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+    use arrow::array::{Array, ArrayRef, ListBuilder, MapBuilder, StringArray, StringBuilder, StructArray};
+    use arrow::buffer::NullBuffer;
+    use arrow::datatypes::{DataType, Field, Fields, Schema};
+    use arrow::record_batch::RecordBatch;
+    use bytes::Bytes;
+    use parquet::file::properties::WriterProperties;
+    use crate::{build_index_in_memory_with_options, BuildOptions, ParquetSource};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::tests::{build_index, location_rows, write_parquet};
+
+    const ROWS_PER_GROUP: usize = 3;
+
+    /// Six rows in two row groups:
+    ///
+    /// | row | payload.user.email | payload.kind | tags[]               | attrs                      |
+    /// |-----|--------------------|--------------|----------------------|----------------------------|
+    /// | 0   | alice@example.com  | signup       | red, blue            | env: prod                  |
+    /// | 1   | (payload null)     |              | (null)               | (empty)                    |
+    /// | 2   | (user null)        | refund       | (empty)              | env: staging, team: core   |
+    /// | 3   | bob@example.org    | signup       | green, red           | (null)                     |
+    /// | 4   | carol@example.com  | login        | blue                 | region: eu                 |
+    /// | 5   | dave@example.net   | login        | crimson-red, yellow  | env: prod                  |
+    fn create_parquet() -> Bytes {
+        let user_fields = Fields::from(vec![
+            Field::new("email", DataType::Utf8, true),
+            Field::new("name", DataType::Utf8, true),
+        ]);
+        let user = StructArray::try_new(
+            user_fields.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    Some("alice@example.com"), None, None, Some("bob@example.org"),
+                    Some("carol@example.com"), Some("dave@example.net"),
+                ])) as ArrayRef,
+                Arc::new(StringArray::from(vec![
+                    Some("Alice"), None, None, Some("Bob"), Some("Carol"), Some("Dave"),
+                ])),
+            ],
+            Some(NullBuffer::from(vec![true, false, false, true, true, true])),
+        ).unwrap();
+        let payload_fields = Fields::from(vec![
+            Field::new("user", DataType::Struct(user_fields), true),
+            Field::new("kind", DataType::Utf8, true),
+        ]);
+        let payload = StructArray::try_new(
+            payload_fields.clone(),
+            vec![
+                Arc::new(user) as ArrayRef,
+                Arc::new(StringArray::from(vec![
+                    Some("signup"), None, Some("refund"), Some("signup"), Some("login"), Some("login"),
+                ])),
+            ],
+            Some(NullBuffer::from(vec![true, false, true, true, true, true])),
+        ).unwrap();
+
+        let mut tags = ListBuilder::new(StringBuilder::new());
+        for row in [Some(vec!["red", "blue"]), None, Some(vec![]), Some(vec!["green", "red"]),
+                    Some(vec!["blue"]), Some(vec!["crimson-red", "yellow"])] {
+            match row {
+                Some(values) => {
+                    for value in values {
+                        tags.values().append_value(value);
+                    }
+                    tags.append(true);
+                }
+                None => tags.append(false),
+            }
+        }
+        let tags = tags.finish();
+
+        let mut attrs = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for row in [Some(vec![("env", "prod")]), Some(vec![]), Some(vec![("env", "staging"), ("team", "core")]),
+                    None, Some(vec![("region", "eu")]), Some(vec![("env", "prod")])] {
+            match row {
+                Some(entries) => {
+                    for (key, value) in entries {
+                        attrs.keys().append_value(key);
+                        attrs.values().append_value(value);
+                    }
+                    attrs.append(true).unwrap();
+                }
+                None => attrs.append(false).unwrap(),
+            }
+        }
+        let attrs = attrs.finish();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("payload", DataType::Struct(payload_fields), true),
+            Field::new("tags", tags.data_type().clone(), true),
+            Field::new("attrs", attrs.data_type().clone(), true),
+        ]));
+        let batch = RecordBatch::try_new(schema, vec![
+            Arc::new(StringArray::from(vec!["r0", "r1", "r2", "r3", "r4", "r5"])),
+            Arc::new(payload),
+            Arc::new(tags),
+            Arc::new(attrs),
+        ]).unwrap();
+
+        write_parquet(&batch, WriterProperties::builder().set_max_row_group_size(ROWS_PER_GROUP).build())
+    }
+
+    fn rows(column: &str, rows: &[(u32, u32)]) -> BTreeSet<(String, u32, u32)> {
+        rows.iter().map(|&(rg, row)| (column.to_string(), rg, row)).collect()
+    }
+
+    async fn search_rows(searcher: &KeywordSearcher, keyword: &str, column: &str) -> BTreeSet<(String, u32, u32)> {
+        let result = searcher.search(keyword, Some(column), true).await.unwrap();
+        result.verified_matches.as_ref().map(location_rows).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_nested_column_names() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+        let columns: Vec<&str> = searcher.filters.column_pool.strings.iter().map(|column| column.as_str()).collect();
+
+        for column in ["id", "payload.user.email", "payload.user.name", "payload.kind", "tags[]", "attrs[].keys", "attrs[].values"] {
+            assert!(columns.contains(&column), "{} missing from {:?}", column, columns);
+        }
+        assert!(!columns.contains(&"payload"));
+        assert!(!columns.contains(&"tags"));
+    }
+
+    #[tokio::test]
+    async fn test_struct_field_search() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+
+        assert_eq!(search_rows(&searcher, "alice", "payload.user.email").await, rows("payload.user.email", &[(0, 0)]));
+        assert_eq!(search_rows(&searcher, "example.com", "payload.user.email").await,
+            rows("payload.user.email", &[(0, 0), (1, 1)]));
+        assert_eq!(search_rows(&searcher, "signup", "payload.kind").await, rows("payload.kind", &[(0, 0), (1, 0)]));
+
+        // Null structs at any level yield no values, so later rows keep their row numbers
+        assert_eq!(search_rows(&searcher, "refund", "payload.kind").await, rows("payload.kind", &[(0, 2)]));
+        assert!(search_rows(&searcher, "alice", "payload.kind").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_elements_attributed_to_rows() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+
+        assert_eq!(search_rows(&searcher, "red", "tags[]").await, rows("tags[]", &[(0, 0), (1, 0), (1, 2)]));
+        assert_eq!(search_rows(&searcher, "blue", "tags[]").await, rows("tags[]", &[(0, 0), (1, 1)]));
+        assert_eq!(search_rows(&searcher, "yellow", "tags[]").await, rows("tags[]", &[(1, 2)]));
+
+        // Rows with several tags are counted once
+        let statistics = searcher.column_statistics();
+        let tags = statistics.iter().find(|stats| stats.column_name == "tags[]").unwrap();
+        assert_eq!(tags.rows_with_keywords, 4);
+    }
+
+    #[tokio::test]
+    async fn test_map_keys_and_values() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+
+        assert_eq!(search_rows(&searcher, "team", "attrs[].keys").await, rows("attrs[].keys", &[(0, 2)]));
+        assert_eq!(search_rows(&searcher, "staging", "attrs[].values").await, rows("attrs[].values", &[(0, 2)]));
+        assert_eq!(search_rows(&searcher, "prod", "attrs[].values").await, rows("attrs[].values", &[(0, 0), (1, 2)]));
+        assert_eq!(search_rows(&searcher, "eu", "attrs[].values").await, rows("attrs[].values", &[(1, 1)]));
+    }
+
+    #[tokio::test]
+    async fn test_select_nested_columns() {
+        let options = BuildOptions {
+            include_columns: Some(vec!["payload.*".to_string(), "tags[]".to_string()]),
+            ..Default::default()
+        };
+        let searcher = build_index(create_parquet(), &options).await;
+        let mut columns: Vec<&str> = searcher.filters.column_pool.strings.iter()
+            .map(|column| column.as_str())
+            .filter(|column| !column.is_empty())
+            .collect();
+        columns.sort_unstable();
+        assert_eq!(columns, vec!["payload.kind", "payload.user.email", "payload.user.name", "tags[]"]);
+
+        // Leaves are selected by the type of their values
+        let options = BuildOptions {
+            column_types: Some(vec![DataType::Utf8]),
+            ..Default::default()
+        };
+        let exclude = ["id".to_string(), "payload.user.name".to_string()].into_iter().collect();
+        let searcher = build_index_in_memory_with_options(ParquetSource::Bytes(create_parquet()), Some(exclude), None, &options)
+            .await
+            .unwrap();
+        let mut columns: Vec<&str> = searcher.filters.column_pool.strings.iter()
+            .map(|column| column.as_str())
+            .filter(|column| !column.is_empty())
+            .collect();
+        columns.sort_unstable();
+        assert_eq!(columns, vec!["attrs[].keys", "attrs[].values", "payload.kind", "payload.user.email", "tags[]"]);
+    }
+
+    #[tokio::test]
+    async fn test_glob_over_nested_columns() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+
+        let result = searcher.search_columns("example.com", &["payload.*"], true).await.unwrap();
+        let found = location_rows(result.verified_matches.as_ref().unwrap());
+        assert_eq!(found, rows("payload.user.email", &[(0, 0), (1, 1)]));
+
+        let result = searcher.search_columns("env", &["attrs[]*"], true).await.unwrap();
+        assert_eq!(result.verified_matches.as_ref().unwrap().columns, vec!["attrs[].keys".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_verify_and_highlight_nested_values() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        // A phrase is verified against each element of the row
+        let result = searcher.search("crimson-red", Some("tags[]"), false).await.unwrap();
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(location_rows(verified.result.verified_matches.as_ref().unwrap()), rows("tags[]", &[(1, 2)]));
+
+        let result = searcher.search("red", Some("tags[]"), false).await.unwrap();
+        let highlighted = searcher.highlight_search_result(result, &reader).await.unwrap();
+        let cells: Vec<(u32, u32, &str)> = highlighted.cells.iter()
+            .map(|cell| (cell.row_group_id, cell.row, cell.value.as_str()))
+            .collect();
+        assert_eq!(cells, vec![(0, 0, "red"), (1, 0, "red"), (1, 2, "crimson-red")]);
+
+        let result = searcher.search("bob@example.org", Some("payload.user.email"), false).await.unwrap();
+        let highlighted = searcher.highlight_search_result(result, &reader).await.unwrap();
+        assert_eq!(highlighted.cells.len(), 1);
+        assert_eq!((highlighted.cells[0].row_group_id, highlighted.cells[0].row), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn test_read_matching_rows_projects_nested_column() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        let result = searcher.search("alice", Some("payload.user.email"), true).await.unwrap();
+        let batches = reader.read_search_result(&result, Some(vec!["payload.user.email".to_string()])).await.unwrap();
+        let schema = batches[0].schema();
+        let names: Vec<&str> = schema.fields().iter().map(|field| field.name().as_str()).collect();
+        assert_eq!(names, vec!["payload"]);
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 1);
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use arrow::record_batch::RecordBatch;
+    use bytes::Bytes;
+    use parquet::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+    use parquet::file::properties::WriterProperties;
+    use crate::BuildOptions;
+    use crate::index_data::PageTable;
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::search_results::{PageSpan, SearchResult};
+    use crate::searching::tests::{build_index, string_batch, write_parquet};
+
+    const ROWS: usize = 1000;
+    const ROWS_PER_GROUP: usize = 500;
+    const ROWS_PER_PAGE: usize = 50;
+
+    /// Rows holding the rare keyword "needle"
+    const NEEDLE_ROWS: [usize; 3] = [120, 130, 760];
+
+    fn text_at(row: usize) -> String {
+        let tag = if NEEDLE_ROWS.contains(&row) { "needle" } else { ["even", "odd"][row % 2] };
+        format!("{} id-{}", tag, row)
+    }
+
+    fn create_parquet() -> Bytes {
+        let text: Vec<String> = (0..ROWS).map(text_at).collect();
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(ROWS_PER_GROUP)
+            .set_data_page_row_count_limit(ROWS_PER_PAGE)
+            .set_write_batch_size(ROWS_PER_PAGE)
+            .set_dictionary_enabled(false)
+            .build();
+        write_parquet(&string_batch(vec![("text", text.into())]), props)
+    }
+
+    /// The page of every row group row, from the offset index of the file.
+    fn page_spans(row_group: usize, rows: &[usize]) -> Vec<PageSpan> {
+        let metadata = ParquetMetaDataReader::new()
+            .with_offset_index_policy(PageIndexPolicy::Required)
+            .parse_and_finish(&create_parquet())
+            .unwrap();
+        let locations = metadata.offset_index().unwrap()[row_group][0].page_locations();
+        let first_rows: Vec<u32> = locations.iter().map(|page| page.first_row_index as u32).collect();
+        assert!(first_rows.len() > 1, "Expected several pages per row group");
+
+        let pages: BTreeSet<usize> = rows.iter()
+            .map(|&row| first_rows.partition_point(|&first_row| first_row as usize <= row) - 1)
+            .collect();
+        pages.into_iter()
+            .map(|page| PageSpan {
+                page: page as u32,
+                start_row: first_rows[page],
+                end_row: first_rows.get(page + 1).map_or(ROWS_PER_GROUP as u32, |&next| next) - 1,
+            })
+            .collect()
+    }
+
+    /// Pages of every (row group, coarse) location of a search result.
+    fn result_pages(result: &SearchResult) -> Vec<(u32, bool, Vec<PageSpan>)> {
+        let data = result.verified_matches.as_ref().unwrap();
+        assert_eq!(data.column_details.len(), 1);
+        data.column_details[0].row_groups.iter()
+            .map(|rg| (rg.row_group_id, rg.coarse, rg.pages.clone()))
+            .collect()
+    }
+
+    fn row_count(batches: &[RecordBatch]) -> usize {
+        batches.iter().map(|batch| batch.num_rows()).sum()
+    }
+
+    #[test]
+    fn test_page_table_lookup() {
+        let table = PageTable { column_id: 1, row_group_id: 0, first_rows: vec![0, 50, 100], num_rows: 120 };
+        assert_eq!([0, 49, 50, 119].map(|row| table.page_of(row)), [0, 0, 1, 2]);
+        assert_eq!(table.page_rows(1), Some((50, 99)));
+        assert_eq!(table.page_rows(2), Some((100, 119)));
+        assert_eq!(table.page_rows(3), None);
+    }
+
+    #[tokio::test]
+    async fn test_pages_recorded() {
+        let searcher = build_index(create_parquet(), &BuildOptions { page_locations: true, ..Default::default() }).await;
+
+        let result = searcher.search("needle", None, true).await.unwrap();
+        assert_eq!(result_pages(&result), vec![
+            (0, false, page_spans(0, &[120, 130])),
+            (1, false, page_spans(1, &[760 - ROWS_PER_GROUP])),
+        ]);
+
+        // A run of rows spanning pages records each of them
+        let result = searcher.search("id", None, true).await.unwrap();
+        for (row_group, _, pages) in result_pages(&result) {
+            assert_eq!(pages, page_spans(row_group as usize, &(0..ROWS_PER_GROUP).collect::<Vec<_>>()));
+        }
+
+        // Without the option, no pages are recorded
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+        let result = searcher.search("needle", None, true).await.unwrap();
+        assert!(result_pages(&result).iter().all(|(_, _, pages)| pages.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_coarse_locations_read_pages() {
+        let searcher = build_index(create_parquet(), &BuildOptions {
+            page_locations: true,
+            keyword_detail_limit: Some(0),
+            ..Default::default()
+        }).await;
+        let reader = PrunedParquetReader::from_bytes(create_parquet());
+
+        let result = searcher.search("needle", None, true).await.unwrap();
+        let pages = result_pages(&result);
+        assert!(pages.iter().all(|(_, coarse, pages)| *coarse && !pages.is_empty()));
+        let page_rows: usize = pages.iter()
+            .flat_map(|(_, _, pages)| pages)
+            .map(|page| (page.end_row - page.start_row + 1) as usize)
+            .sum();
+        assert!(page_rows < ROWS / 4, "{} rows in pages", page_rows);
+
+        let batches = reader.read_matching_rows(&result, None).await.unwrap();
+        assert_eq!(row_count(&batches), page_rows);
+        let batches = reader.read_search_result(&result, None).await.unwrap();
+        assert_eq!(row_count(&batches), page_rows);
+        let stats = reader.get_pruning_stats(&result).await.unwrap();
+        assert_eq!(stats.rows_to_read, page_rows as u64);
+
+        // Highlighting checks the rows of the pages only and finds the exact cells
+        let highlighted = searcher.highlight_search_result(result, &reader).await.unwrap();
+        let cells: Vec<(u32, u32)> = highlighted.cells.iter().map(|cell| (cell.row_group_id, cell.row)).collect();
+        let expected: Vec<(u32, u32)> = NEEDLE_ROWS.iter()
+            .map(|&row| ((row / ROWS_PER_GROUP) as u32, (row % ROWS_PER_GROUP) as u32))
+            .collect();
+        assert_eq!(cells, expected);
+    }
+
+    #[tokio::test]
+    async fn test_substring_keeps_pages() {
+        let searcher = build_index(create_parquet(), &BuildOptions {
+            page_locations: true,
+            keyword_detail_limit: Some(0),
+            ..Default::default()
+        }).await;
+
+        let result = searcher.search_substring("eedl", None).await.unwrap();
+        assert_eq!(result_pages(&result), vec![
+            (0, true, page_spans(0, &[120, 130])),
+            (1, true, page_spans(1, &[760 - ROWS_PER_GROUP])),
+        ]);
+
+        // Merged with keywords of other pages, the pages are kept together
+        let result = searcher.search_substring("d-13", None).await.unwrap();
+        let pages = result_pages(&result);
+        assert_eq!(pages[0].2, page_spans(0, &[13, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139]));
+    }
+}
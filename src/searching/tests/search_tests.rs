@@ -1,5 +1,5 @@
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use tokio::sync::OnceCell;
     use std::sync::Arc;
     use arrow::array::{StringArray, Int32Array, Int64Array, Float64Array, BooleanArray};
@@ -16,7 +16,7 @@ mod tests {
     static TEST_SEARCHER: OnceCell<KeywordSearcher> = OnceCell::const_new();
 
     /// Generate a small test parquet file with 500 distinct values, 1000 rows
-    fn create_test_parquet() -> Result<Bytes, Box<dyn std::error::Error>> {
+    pub(crate) fn create_test_parquet() -> Result<Bytes, Box<dyn std::error::Error>> {
         const DISTINCT_VALUES: usize = 500;
         const TOTAL_ROWS: usize = 1000;
 
@@ -94,7 +94,7 @@ mod tests {
         Ok(Bytes::from(buffer))
     }
 
-    async fn get_searcher() -> &'static KeywordSearcher {
+    pub(crate) async fn get_searcher() -> &'static KeywordSearcher {
         TEST_SEARCHER.get_or_init(|| async {
             println!("Building test index in memory...");
             let parquet_bytes: Bytes = create_test_parquet().expect("Failed to create test parquet");
@@ -214,4 +214,70 @@ mod tests {
             }
         }
     }
+    #[tokio::test]
+    async fn test_search_multiple_columns() {
+        let searcher = get_searcher().await;
+
+        let result = searcher.search_columns("user_0", &["name", "email"], true).await.unwrap();
+        assert!(result.found);
+
+        let verified = result.verified_matches.as_ref().unwrap();
+        assert!(verified.columns.contains(&"name".to_string()));
+        assert!(verified.columns.contains(&"email".to_string()));
+        assert_eq!(verified.columns.len(), verified.column_details.len());
+    }
+
+    #[tokio::test]
+    async fn test_search_glob_column_filter() {
+        let searcher = get_searcher().await;
+
+        // "e*" only matches the email column
+        let result = searcher.search("user_0", Some("e*"), true).await.unwrap();
+        assert!(result.found);
+        assert_eq!(result.verified_matches.as_ref().unwrap().columns, vec!["email".to_string()]);
+
+        // Phrase search goes through the same column resolution
+        let result = searcher.search_columns("user_0@test0.com", &["?mail"], false).await.unwrap();
+        assert!(result.found);
+        for data in result.verified_matches.iter().chain(result.needs_verification.iter()) {
+            assert!(data.columns.iter().all(|c| c == "email"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_columns_no_match() {
+        let searcher = get_searcher().await;
+
+        let result = searcher.search_columns("user_0", &["does_not_exist*"], true).await.unwrap();
+        assert!(!result.found);
+
+        // Column exists but does not contain the keyword
+        let result = searcher.search_columns("user_0", &["status"], true).await.unwrap();
+        assert!(!result.found);
+
+        // Single exact column keeps working as before
+        let result = searcher.search("user_0", Some("name"), true).await.unwrap();
+        assert!(result.found);
+        assert_eq!(result.verified_matches.as_ref().unwrap().columns, vec!["name".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_column_statistics() {
+        let searcher = get_searcher().await;
+
+        let stats = searcher.column_statistics();
+        let status = stats.iter().find(|s| s.column_name == "status").expect("status column stats");
+        assert_eq!(status.distinct_keywords, 4);
+        assert_eq!(status.rows_with_keywords, 1000);
+        assert_eq!(status.top_keywords.len(), 4);
+        assert_eq!(status.top_keywords.iter().map(|k| k.occurrences).sum::<u64>(), 1000);
+        assert!(status.top_keywords.windows(2).all(|w| w[0].occurrences >= w[1].occurrences));
+
+        let name = stats.iter().find(|s| s.column_name == "name").expect("name column stats");
+        assert_eq!(name.rows_with_keywords, 1000);
+        assert_eq!(name.top_keywords.len(), crate::index_data::COLUMN_STATS_TOP_N);
+        // Every name is "user_N", so "user" is in every row
+        assert_eq!(name.top_keywords[0].keyword, "user");
+        assert_eq!(name.top_keywords[0].occurrences, 1000);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+    use crate::BuildOptions;
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::{build_index, location_rows, string_parquet};
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    const TEXTS: [&str; 7] = [
+        "user@example.com",
+        "sample data",
+        "simple-example",
+        "Ampleforth",
+        "trample the grass",
+        "no match here",
+        "exam paper",
+    ];
+    const ROWS: usize = 70;
+    const ROWS_PER_GROUP: usize = 20;
+
+    fn create_parquet() -> Bytes {
+        let text: Vec<&str> = (0..ROWS).map(|row| TEXTS[row % TEXTS.len()]).collect();
+        let other: Vec<&str> = (0..ROWS).map(|row| TEXTS[(row * 3) % TEXTS.len()]).collect();
+        string_parquet(vec![("text", text.into()), ("other", other.into())], ROWS_PER_GROUP)
+    }
+
+    /// Matching keywords and rows found by enumerating every keyword and searching
+    /// each match column by column
+    async fn brute_force(
+        searcher: &KeywordSearcher,
+        substring: &str,
+        columns: &[&str],
+    ) -> (Vec<String>, BTreeSet<(String, u32, u32)>) {
+        let keywords: Vec<String> = searcher.vocabulary(VocabularyOptions::default())
+            .map_ok(|entry| entry.keyword)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let mut tokens = Vec::new();
+        let mut rows = BTreeSet::new();
+        for keyword in keywords.into_iter().filter(|k| k.contains(substring)) {
+            let mut found = false;
+            for column in columns {
+                let result = searcher.search(&keyword, Some(column), true).await.unwrap();
+                if let Some(matches) = &result.verified_matches {
+                    rows.extend(location_rows(matches));
+                    found = true;
+                }
+            }
+            if found {
+                tokens.push(keyword);
+            }
+        }
+        (tokens, rows)
+    }
+
+    #[tokio::test]
+    async fn test_substring_matches_brute_force() {
+        let plain = build_index(create_parquet(), &BuildOptions::default()).await;
+        let trigram = build_index(create_parquet(), &BuildOptions { trigram_index: true, ..Default::default() }).await;
+        assert!(plain.filters.trigram_directory.is_empty());
+        assert!(!trigram.filters.trigram_directory.is_empty());
+
+        for substring in ["ampl", "ample", "exam", "am", "e", "@exa", "grass", "zzz", "mple-ex"] {
+            for (in_columns, columns) in [(None, vec!["text", "other"]), (Some("text"), vec!["text"])] {
+                let (expected_tokens, expected_rows) = brute_force(&plain, substring, &columns).await;
+
+                for searcher in [&plain, &trigram] {
+                    let result = searcher.search_substring(substring, in_columns).await.unwrap();
+                    assert_eq!(result.tokens, expected_tokens, "Tokens for {:?} in {:?}", substring, in_columns);
+
+                    let rows = result.verified_matches.as_ref().map(location_rows).unwrap_or_default();
+                    assert_eq!(rows, expected_rows, "Rows for {:?} in {:?}", substring, in_columns);
+                    assert_eq!(result.found, !rows.is_empty());
+                    assert!(result.needs_verification.is_none());
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_substring_inside_keyword() {
+        let searcher = build_index(create_parquet(), &BuildOptions { trigram_index: true, ..Default::default() }).await;
+
+        let result = searcher.search_substring("ampl", None).await.unwrap();
+        assert!(result.found);
+        assert!(result.tokens.iter().any(|token| token == "example"));
+        assert!(result.tokens.iter().any(|token| token == "trample"));
+        assert!(result.tokens.iter().all(|token| token.contains("ampl")));
+
+        // Matching is case-sensitive
+        let result = searcher.search_substring("Ampl", None).await.unwrap();
+        assert!(result.tokens.iter().all(|token| token.starts_with("Ampl")));
+    }
+
+    #[tokio::test]
+    async fn test_substring_unknown_column_and_empty() {
+        let searcher = build_index(create_parquet(), &BuildOptions { trigram_index: true, ..Default::default() }).await;
+
+        let result = searcher.search_substring("ampl", Some("no_such_column")).await.unwrap();
+        assert!(!result.found);
+        assert!(result.tokens.is_empty());
+
+        assert!(searcher.search_substring("", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trigram_index_opt_in() {
+        let searcher = build_index(create_parquet(), &BuildOptions::default()).await;
+        assert!(searcher.filters.trigram_directory.is_empty());
+    }
+}
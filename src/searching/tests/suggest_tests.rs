@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use arrow::array::StringArray;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use futures::TryStreamExt;
+    use parquet::arrow::ArrowWriter;
+    use tokio::sync::OnceCell;
+    use crate::{build_index_in_memory, build_index_in_memory_with_options, BuildOptions, ParquetSource};
+    use crate::index_data::{KeywordCount, SUGGESTION_TOP_K};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::tests::search_tests::tests::{create_test_parquet, get_searcher};
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    static SUGGESTIONS_SEARCHER: OnceCell<KeywordSearcher> = OnceCell::const_new();
+
+    /// The test index of [`get_searcher`], built with prefix suggestions
+    async fn get_suggestions_searcher() -> &'static KeywordSearcher {
+        SUGGESTIONS_SEARCHER.get_or_init(|| async {
+            let options = BuildOptions { prefix_suggestions: true, ..Default::default() };
+            build_index_in_memory_with_options(ParquetSource::Bytes(create_test_parquet().unwrap()), None, None, &options)
+                .await
+                .unwrap()
+        }).await
+    }
+
+    /// Reference implementation: rank the whole vocabulary for the prefix
+    async fn expected_suggestions(prefix: &str, column: Option<&str>, k: usize) -> Vec<KeywordCount> {
+        let searcher = get_searcher().await;
+        let options = VocabularyOptions {
+            column: column.map(|c| c.to_string()),
+            prefix: Some(prefix.to_string()),
+            with_counts: true,
+            ..Default::default()
+        };
+        let mut all: Vec<KeywordCount> = searcher.vocabulary(options)
+            .map_ok(|e| KeywordCount { keyword: e.keyword, occurrences: e.total_occurrences.unwrap() })
+            .try_collect()
+            .await
+            .unwrap();
+        all.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.keyword.cmp(&b.keyword)));
+        all.truncate(k);
+        all
+    }
+
+    #[tokio::test]
+    async fn test_suggest_precomputed_matches_scan() {
+        let searcher = get_suggestions_searcher().await;
+        assert!(!searcher.filters.prefix_suggestions.is_empty());
+        assert!(get_searcher().await.filters.prefix_suggestions.is_empty());
+
+        for (prefix, column) in [("", None), ("u", None), ("us", Some("name")), ("t", Some("email")), ("zz", None)] {
+            let suggestions = searcher.suggest(prefix, column, 5).await.unwrap();
+            assert_eq!(suggestions, expected_suggestions(prefix, column, 5).await,
+                       "Mismatch for prefix {:?} in {:?}", prefix, column);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suggest_glob_counts_rows_once() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("sender", DataType::Utf8, false),
+            Field::new("receiver", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from(vec!["alice", "alice", "bob", "carol"])),
+            Arc::new(StringArray::from(vec!["alice", "bob", "alice", "alice"])),
+        ]).unwrap();
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let searcher = build_index_in_memory(ParquetSource::from(buffer), None, None).await.unwrap();
+
+        // alice is in both columns of row 0, which counts once
+        let expected = vec![
+            KeywordCount { keyword: "alice".to_string(), occurrences: 4 },
+            KeywordCount { keyword: "bob".to_string(), occurrences: 2 },
+        ];
+        assert_eq!(searcher.suggest("", None, 2).await.unwrap(), expected);
+        assert_eq!(searcher.suggest("", Some("*er"), 2).await.unwrap(), expected);
+        assert_eq!(searcher.suggest("a", Some("sender"), 2).await.unwrap()[0].occurrences, 2);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_long_prefix_fallback() {
+        let searcher = get_searcher().await;
+
+        let suggestions = searcher.suggest("user_1", None, 3).await.unwrap();
+        assert_eq!(suggestions.len(), 3);
+        assert!(suggestions.iter().all(|s| s.keyword.starts_with("user_1")));
+        assert!(suggestions.windows(2).all(|w| w[0].occurrences >= w[1].occurrences));
+        assert_eq!(suggestions, expected_suggestions("user_1", None, 3).await);
+
+        // More suggestions than are precomputed also falls back to a scan
+        let suggestions = searcher.suggest("u", None, SUGGESTION_TOP_K + 5).await.unwrap();
+        assert_eq!(suggestions.len(), SUGGESTION_TOP_K + 5);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_column() {
+        let searcher = get_searcher().await;
+
+        let suggestions = searcher.suggest("", Some("status"), 10).await.unwrap();
+        let mut keywords: Vec<&str> = suggestions.iter().map(|s| s.keyword.as_str()).collect();
+        keywords.sort();
+        assert_eq!(keywords, vec!["active", "inactive", "pending", "suspended"]);
+        assert_eq!(suggestions.iter().map(|s| s.occurrences).sum::<u64>(), 1000);
+
+        let suggestions = searcher.suggest("a", Some("stat*"), 10).await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].keyword, "active");
+
+        assert!(searcher.suggest("a", Some("no_such_column"), 10).await.unwrap().is_empty());
+        assert!(searcher.suggest("a", None, 0).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use bytes::Bytes;
+    use crate::{build_and_save_text_index, BuildOptions, ParquetSource};
+    use crate::index_data::{build_distributed_index, TextFormat};
+    use crate::searching::keyword_search::KeywordSearcher;
+    use crate::searching::pruned_text_reader::PrunedTextReader;
+    use crate::searching::search_results::SearchResult;
+    use crate::text_source::{process_text_file, TextOptions};
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const ROWS: usize = 230;
+    const ROWS_PER_BATCH: usize = 50;
+
+    fn csv_line(row: usize) -> String {
+        format!("{},GET /items/{},web-{}\n", row, row, row % 7)
+    }
+
+    fn create_csv() -> String {
+        let mut csv = "id,request,host\n".to_string();
+        (0..ROWS).for_each(|row| csv.push_str(&csv_line(row)));
+        csv
+    }
+
+    fn create_ndjson() -> String {
+        (0..ROWS)
+            .map(|row| format!(
+                "{{\"id\": {}, \"payload\": {{\"user\": \"user-{}\", \"tags\": [\"t{}\", \"shared\"]}}}}\n",
+                row, row % 11, row,
+            ))
+            .collect()
+    }
+
+    fn options(format: TextFormat) -> TextOptions {
+        TextOptions { rows_per_batch: ROWS_PER_BATCH, ..TextOptions::new(format) }
+    }
+
+    async fn build(path: &str, data: &str, text_options: &TextOptions) -> (KeywordSearcher, PrunedTextReader) {
+        register_memory_file(path, Bytes::from(data.to_string())).await.unwrap();
+        build_and_save_text_index(path, text_options, None, None, None, &BuildOptions::default()).await.unwrap();
+        let searcher = KeywordSearcher::load(path, None).await.unwrap();
+        let reader = PrunedTextReader::new(ParquetSource::Path(path.to_string()), searcher.text_layout().unwrap()).unwrap();
+        (searcher, reader)
+    }
+
+    /// (batch, row) of every verified location of a search result.
+    fn locations(result: &SearchResult) -> Vec<(u32, u32)> {
+        let data = result.verified_matches.as_ref().unwrap();
+        let mut locations: Vec<(u32, u32)> = data.column_details.iter()
+            .flat_map(|col| &col.row_groups)
+            .flat_map(|rg| rg.row_ranges.iter().flat_map(move |range| (range.start_row..=range.end_row).map(move |row| (rg.row_group_id, row))))
+            .collect();
+        locations.sort_unstable();
+        locations.dedup();
+        locations
+    }
+
+    fn location(row: usize) -> (u32, u32) {
+        ((row / ROWS_PER_BATCH) as u32, (row % ROWS_PER_BATCH) as u32)
+    }
+
+    fn column_values(batches: &[RecordBatch], column: &str) -> Vec<String> {
+        batches.iter()
+            .flat_map(|batch| {
+                let array = arrow::compute::cast(batch.column_by_name(column).unwrap(), &DataType::Utf8).unwrap();
+                let array = array.as_string::<i32>().clone();
+                (0..array.len()).map(move |row| array.value(row).to_string()).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_csv_batches_and_offsets() {
+        let csv = create_csv();
+        let (searcher, _) = build("memory://text-source-offsets.csv", &csv, &options(TextFormat::CSV)).await;
+
+        let layout = searcher.text_layout().unwrap();
+        assert_eq!(layout.format, TextFormat::CSV);
+        assert_eq!(layout.num_batches(), ROWS.div_ceil(ROWS_PER_BATCH));
+
+        // Batches end after every ROWS_PER_BATCH lines; the first includes the header
+        let header_len = "id,request,host\n".len() as u64;
+        let mut expected = vec![0];
+        let mut offset = header_len;
+        for row in 0..ROWS {
+            offset += csv_line(row).len() as u64;
+            if (row + 1) % ROWS_PER_BATCH == 0 || row + 1 == ROWS {
+                expected.push(offset);
+            }
+        }
+        assert_eq!(layout.batch_offsets, expected);
+        assert_eq!(*layout.batch_offsets.last().unwrap(), csv.len() as u64);
+
+        let schema = layout.arrow_schema().unwrap();
+        let names: Vec<&str> = schema.fields().iter().map(|field| field.name().as_str()).collect();
+        assert_eq!(names, ["id", "request", "host"]);
+        assert!(schema.fields().iter().all(|field| field.data_type() == &DataType::Utf8));
+        assert_eq!(searcher.filters.parquet_size, csv.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_csv_search_and_read() {
+        let (searcher, reader) = build("memory://text-source-search.csv", &create_csv(), &options(TextFormat::CSV)).await;
+
+        let result = searcher.search("137", Some("request"), true).await.unwrap();
+        assert_eq!(locations(&result), vec![location(137)]);
+        let batches = reader.read_matching_rows(&result, None).await.unwrap();
+        assert_eq!(column_values(&batches, "request"), vec!["GET /items/137"]);
+
+        // Every batch holds matches of a frequent keyword
+        let result = searcher.search("web-3", Some("host"), true).await.unwrap();
+        let expected: Vec<(u32, u32)> = (0..ROWS).filter(|row| row % 7 == 3).map(location).collect();
+        assert_eq!(locations(&result), expected);
+        let batches = reader.read_matching_rows(&result, Some(vec!["id".to_string()])).await.unwrap();
+        assert_eq!(batches.len(), ROWS.div_ceil(ROWS_PER_BATCH));
+        assert!(batches.iter().all(|batch| batch.num_columns() == 1));
+        let ids: Vec<String> = (0..ROWS).filter(|row| row % 7 == 3).map(|row| row.to_string()).collect();
+        assert_eq!(column_values(&batches, "id"), ids);
+
+        // The header is not indexed as a row
+        assert!(!searcher.search("request", None, true).await.unwrap().found);
+
+        let batch = reader.read_batch(1).await.unwrap();
+        assert_eq!(batch.num_rows(), ROWS_PER_BATCH);
+        assert_eq!(column_values(&[batch], "id")[0], ROWS_PER_BATCH.to_string());
+        assert!(reader.read_batch(ROWS.div_ceil(ROWS_PER_BATCH)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv_without_header() {
+        // Tab-separated, quoted values spanning lines, no trailing newline
+        let data = "a\t\"first\nline two\"\nb\tsecond\nc\tthird\nd\t\"fourth, quoted\"";
+        let text_options = TextOptions {
+            rows_per_batch: 2,
+            ..TextOptions::new(TextFormat::Csv { delimiter: b'\t', has_header: false })
+        };
+        let (searcher, reader) = build("memory://text-source-no-header.tsv", data, &text_options).await;
+
+        let layout = searcher.text_layout().unwrap();
+        assert_eq!(layout.batch_offsets, vec![0, data.find("c\t").unwrap() as u64, data.len() as u64]);
+
+        let result = searcher.search("two", Some("column_2"), true).await.unwrap();
+        assert_eq!(locations(&result), vec![(0, 0)]);
+        let result = searcher.search("quoted", None, true).await.unwrap();
+        assert_eq!(locations(&result), vec![(1, 1)]);
+        let batches = reader.read_matching_rows(&result, None).await.unwrap();
+        assert_eq!(column_values(&batches, "column_1"), vec!["d"]);
+        assert_eq!(column_values(&batches, "column_2"), vec!["fourth, quoted"]);
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_nested() {
+        let (searcher, reader) = build("memory://text-source-events.ndjson", &create_ndjson(), &options(TextFormat::JsonLines)).await;
+
+        let mut columns: Vec<String> = searcher.filters.column_pool.strings.iter().skip(1).map(|s| s.to_string()).collect();
+        columns.sort();
+        assert_eq!(columns, ["id", "payload.tags[]", "payload.user"]);
+
+        let result = searcher.search("t201", Some("payload.tags[]"), true).await.unwrap();
+        assert_eq!(locations(&result), vec![location(201)]);
+        let batches = reader.read_matching_rows(&result, Some(vec!["id".to_string()])).await.unwrap();
+        assert_eq!(column_values(&batches, "id"), vec!["201"]);
+
+        let result = searcher.search("user-4", Some("payload.user"), true).await.unwrap();
+        let expected: Vec<(u32, u32)> = (0..ROWS).filter(|row| row % 11 == 4).map(location).collect();
+        assert_eq!(locations(&result), expected);
+        let batches = reader.read_search_result(&result, Some(vec!["payload.user".to_string()])).await.unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), expected.len());
+        assert_eq!(batches[0].schema().field(0).name(), "payload");
+    }
+
+    #[tokio::test]
+    async fn test_bytes_source_and_schema() {
+        let ndjson = create_ndjson();
+        let path = "memory://text-source-bytes.ndjson";
+        register_memory_file(path, Bytes::from(ndjson.clone())).await.unwrap();
+
+        // The same file from a path or from bytes has the same layout and data
+        let text_options = options(TextFormat::JsonLines);
+        let mut data = Vec::new();
+        for source in [ParquetSource::Path(path.to_string()), ParquetSource::Bytes(Bytes::from(ndjson.clone()))] {
+            let result = process_text_file(source.clone(), &text_options, None, None, &BuildOptions::default()).await.unwrap();
+            let files = build_distributed_index(&result, &source, 0.01, &BuildOptions::default()).await.unwrap();
+            data.push((result.text_layout.unwrap(), files.data.to_vec().unwrap()));
+        }
+        assert_eq!(data[0], data[1]);
+
+        // A given schema decodes only its fields
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, true)]));
+        let text_options = TextOptions { schema: Some(schema), ..text_options };
+        let result = process_text_file(ParquetSource::Bytes(Bytes::from(ndjson)), &text_options, None, None, &BuildOptions::default())
+            .await
+            .unwrap();
+        let columns: Vec<&str> = result.column_keywords_map.keys().map(|column| column.as_ref()).collect();
+        assert_eq!(columns, ["id"]);
+        assert_eq!(result.text_layout.unwrap().arrow_schema().unwrap().fields().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_input() {
+        let text_options = TextOptions { rows_per_batch: 0, ..Default::default() };
+        let source = ParquetSource::Bytes(Bytes::from(create_csv()));
+        assert!(process_text_file(source.clone(), &text_options, None, None, &BuildOptions::default()).await.is_err());
+
+        // A truncated JSON record
+        let source = ParquetSource::Bytes(Bytes::from_static(b"{\"a\": \"x\"}\n{\"a\": \"y\""));
+        let text_options = TextOptions { schema: Some(Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]))), ..TextOptions::new(TextFormat::JsonLines) };
+        assert!(process_text_file(source, &text_options, None, None, &BuildOptions::default()).await.is_err());
+
+        assert_eq!(TextFormat::from_path("s3://bucket/export.CSV"), Some(TextFormat::CSV));
+        assert_eq!(TextFormat::from_path("events.jsonl"), Some(TextFormat::JsonLines));
+        assert_eq!(TextFormat::from_path("data.parquet"), None);
+    }
+}
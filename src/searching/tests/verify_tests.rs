@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use bytes::Bytes;
+    use crate::{build_index_in_memory, BuildOptions, ParquetSource};
+    use crate::keyword_shred::SPLIT_CHARS_COUNT;
+    use crate::searching::pruned_reader::PrunedParquetReader;
+    use crate::searching::verify::phrase_offsets;
+    use crate::searching::search_results::{
+        ColumnLocation, KeywordLocationData, RowGroupLocation, RowRange, SearchResult,
+    };
+    use crate::searching::tests::{build_index, location_rows, string_parquet};
+    use crate::utils::file_interaction_local_and_cloud::register_memory_file;
+
+    const TEXTS: [&str; 7] = [
+        "alpha-beta gamma",
+        "beta-alpha",
+        "alpha beta",
+        "alpha-beta-gamma",
+        "gamma alpha-beta",
+        "alpha_beta",
+        "john smith bigjohn-doe",
+    ];
+    const ROWS: usize = 60;
+    const ROWS_PER_GROUP: usize = 25;
+
+    fn text_at(row: usize) -> &'static str {
+        TEXTS[row % TEXTS.len()]
+    }
+
+    fn create_parquet() -> Bytes {
+        let text: Vec<&str> = (0..ROWS).map(text_at).collect();
+        let other: Vec<&str> = (0..ROWS).map(|row| text_at(row + 1)).collect();
+        string_parquet(vec![("text", text.into()), ("other", other.into())], ROWS_PER_GROUP)
+    }
+
+    fn cell_value(column: &str, row_group_id: u32, row: u32) -> &'static str {
+        let row = row_group_id as usize * ROWS_PER_GROUP + row as usize;
+        match column {
+            "text" => text_at(row),
+            _ => text_at(row + 1),
+        }
+    }
+
+    /// Rows a verified search must return: the index's verified rows plus every
+    /// candidate whose cell contains the phrase
+    fn expected_rows(result: &SearchResult) -> BTreeSet<(String, u32, u32)> {
+        let mut rows = result.verified_matches.as_ref().map(location_rows).unwrap_or_default();
+        if let Some(candidates) = &result.needs_verification {
+            rows.extend(location_rows(candidates).into_iter()
+                .filter(|(column, rg, row)| contains_phrase(cell_value(column, *rg, *row), &result.query)));
+        }
+        rows
+    }
+
+    fn contains_phrase(value: &str, phrase: &str) -> bool {
+        phrase_offsets(value, phrase, SPLIT_CHARS_COUNT).next().is_some()
+    }
+
+    fn candidates(column: &str, row_group_id: u32, rows: &[u32]) -> KeywordLocationData {
+        let row_ranges = rows.iter()
+            .map(|&row| RowRange { start_row: row, end_row: row, splits_matched: 0b10, parent_chunk: None, parent_position: None })
+            .collect();
+        KeywordLocationData {
+            columns: vec![column.to_string()],
+            total_occurrences: rows.len() as u64,
+            splits_matched: 0b10,
+            column_details: vec![ColumnLocation {
+                column_name: column.to_string(),
+                row_groups: vec![RowGroupLocation { row_group_id, row_ranges, coarse: false, pages: Vec::new() }],
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_cells() {
+        let reader = PrunedParquetReader::from_bytes(create_parquet());
+        let cells = reader.read_cells(&candidates("text", 1, &[7, 0, 3, 24, 25])).await.unwrap();
+
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].column_name, "text");
+        assert_eq!(cells[0].row_group_id, 1);
+        // Row 25 is past the end of the row group and is skipped
+        let expected: Vec<(u32, Option<String>)> = [0u32, 3, 7, 24].iter()
+            .map(|&row| (row, Some(text_at(ROWS_PER_GROUP + row as usize).to_string())))
+            .collect();
+        assert_eq!(cells[0].rows, expected);
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_non_matching_candidates() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        // Rows 0-5 of row group 0 cover every text once; rows 0, 3 and 4 contain the phrase
+        let result = SearchResult {
+            query: "alpha-beta".to_string(),
+            found: true,
+            tokens: vec!["alpha".to_string(), "alpha-beta".to_string(), "beta".to_string()],
+            verified_matches: None,
+            needs_verification: Some(candidates("text", 0, &[0, 1, 2, 3, 4, 5])),
+        };
+
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(verified.candidates_checked, 6);
+        assert_eq!(verified.candidates_rejected, 3);
+        assert!(verified.result.found);
+        assert!(verified.result.needs_verification.is_none());
+
+        let rows = location_rows(verified.result.verified_matches.as_ref().unwrap());
+        let expected: BTreeSet<_> = [0u32, 3, 4].iter().map(|&row| ("text".to_string(), 0u32, row)).collect();
+        assert_eq!(rows, expected);
+    }
+
+    #[tokio::test]
+    async fn test_verify_all_rejected() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        let result = SearchResult {
+            query: "alpha-beta".to_string(),
+            found: true,
+            tokens: vec!["alpha-beta".to_string()],
+            verified_matches: None,
+            needs_verification: Some(candidates("text", 0, &[1, 2, 5])),
+        };
+
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(verified.candidates_rejected, 3);
+        assert!(!verified.result.found);
+        assert!(verified.result.verified_matches.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_verify_matches_at_token_boundaries() {
+        let bytes = create_parquet();
+        let searcher = build_index(bytes.clone(), &BuildOptions::default()).await;
+        let reader = PrunedParquetReader::from_bytes(bytes);
+
+        // Row 6 holds "john smith bigjohn-doe": both tokens, but "john-doe" only inside a token
+        let result = SearchResult {
+            query: "john-doe".to_string(),
+            found: true,
+            tokens: vec!["doe".to_string(), "john".to_string()],
+            verified_matches: None,
+            needs_verification: Some(candidates("text", 0, &[6])),
+        };
+
+        let verified = searcher.verify_search_result(result, &reader).await.unwrap();
+        assert_eq!(verified.candidates_rejected, 1);
+        assert!(!verified.result.found);
+
+        assert!(contains_phrase("smith john-doe", "john-doe"));
+        assert!(contains_phrase("x@john-doe.org", "john-doe"));
+        assert!(!contains_phrase("john-doey", "john-doe"));
+        assert!(contains_phrase("bigjohn-doe", "-doe"));
+    }
+
+    #[tokio::test]
+    async fn test_search_verified_matches_data() {
+        let path = "memory://verify-tests.parquet";
+        register_memory_file(path, create_parquet()).await.unwrap();
+        let searcher = build_index_in_memory(ParquetSource::Path(path.to_string()), None, None).await.unwrap();
+
+        for (phrase, column) in [("alpha-beta", None), ("beta-alpha", None), ("alpha beta", None),
+                                 ("beta gamma", None), ("alpha_beta", None), ("alpha-beta", Some("other"))] {
+            let unverified = searcher.search(phrase, column, false).await.unwrap();
+            let verified = searcher.search_verified(path, phrase, column).await.unwrap();
+            assert!(verified.result.needs_verification.is_none());
+
+            let rows = verified.result.verified_matches.as_ref().map(location_rows).unwrap_or_default();
+            assert_eq!(rows, expected_rows(&unverified), "Mismatch for phrase {:?} in {:?}", phrase, column);
+            assert_eq!(verified.result.found, !rows.is_empty());
+
+            // Every returned cell really contains the phrase
+            assert!(rows.iter().all(|(column, rg, row)| contains_phrase(cell_value(column, *rg, *row), phrase)));
+
+            let candidates = unverified.needs_verification.as_ref().map(location_rows).unwrap_or_default();
+            assert_eq!(verified.candidates_checked, candidates.len() as u64);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use crate::searching::search_results::VocabularyEntry;
+    use crate::searching::tests::search_tests::tests::get_searcher;
+    use crate::searching::vocabulary::VocabularyOptions;
+
+    async fn collect(options: VocabularyOptions) -> Vec<VocabularyEntry> {
+        let searcher = get_searcher().await;
+        searcher.vocabulary(options).try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_is_sorted_and_complete() {
+        let entries = collect(VocabularyOptions::default()).await;
+
+        assert!(!entries.is_empty());
+        assert!(entries.windows(2).all(|w| w[0].keyword < w[1].keyword), "Keywords should be sorted and unique");
+        assert!(entries.iter().all(|e| e.total_occurrences.is_none() && e.column_counts.is_none()));
+
+        for keyword in ["active", "user_0", "test0.com"] {
+            assert!(entries.iter().any(|e| e.keyword == keyword), "Missing keyword {}", keyword);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_prefix_and_range() {
+        let entries = collect(VocabularyOptions {
+            prefix: Some("user_1".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|e| e.keyword.starts_with("user_1")));
+
+        let entries = collect(VocabularyOptions {
+            start: Some("inactive".to_string()),
+            end: Some("suspended".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(entries.iter().all(|e| e.keyword.as_str() >= "inactive" && e.keyword.as_str() < "suspended"));
+        assert!(entries.iter().any(|e| e.keyword == "inactive"));
+        assert!(entries.iter().any(|e| e.keyword == "pending"));
+        assert!(!entries.iter().any(|e| e.keyword == "suspended"));
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_column_counts() {
+        let entries = collect(VocabularyOptions {
+            column: Some("status".to_string()),
+            with_counts: true,
+            ..Default::default()
+        }).await;
+
+        let keywords: Vec<&str> = entries.iter().map(|e| e.keyword.as_str()).collect();
+        assert_eq!(keywords, vec!["active", "inactive", "pending", "suspended"]);
+
+        // Every row has exactly one status value
+        let total: u64 = entries.iter().map(|e| e.total_occurrences.unwrap()).sum();
+        assert_eq!(total, 1000);
+
+        for entry in &entries {
+            let counts = entry.column_counts.as_ref().unwrap();
+            assert_eq!(counts.len(), 1);
+            assert_eq!(counts[0].column_name, "status");
+            assert_eq!(counts[0].occurrences, entry.total_occurrences.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_counts_all_columns() {
+        let entries = collect(VocabularyOptions {
+            prefix: Some("user_0".to_string()),
+            with_counts: true,
+            ..Default::default()
+        }).await;
+
+        let entry = entries.iter().find(|e| e.keyword == "user_0").unwrap();
+        let counts = entry.column_counts.as_ref().unwrap();
+        let columns: Vec<&str> = counts.iter().map(|c| c.column_name.as_str()).collect();
+        assert!(columns.contains(&"name"));
+        assert!(columns.contains(&"email"));
+        assert!(entry.total_occurrences.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary_unknown_column() {
+        let entries = collect(VocabularyOptions {
+            column: Some("no_such_column".to_string()),
+            ..Default::default()
+        }).await;
+        assert!(entries.is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+//! Resolution of phrase matches that the index alone cannot confirm.
+//!
+//! Phrase searches return `needs_verification` locations when parent keyword checks are
+//! inconclusive. This module reads exactly those cells from the Parquet file with
+//! [`PrunedParquetReader::read_cells`] and keeps the ones that actually contain the
+//! phrase, so callers get a result they can use without any further checking.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!     let verified = searcher.search_verified("data.parquet", "user@example.com", None).await?;
+//!
+//!     if let Some(matches) = &verified.result.verified_matches {
+//!         println!("{} rows contain the phrase", matches.total_occurrences);
+//!     }
+//!     println!("{} false candidates rejected", verified.candidates_rejected);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use crate::error::Error;
+use crate::keyword_shred::is_delimiter;
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::pruned_reader::PrunedParquetReader;
+use crate::searching::search_results::{
+    ColumnLocation, KeywordLocationData, PageSpan, RowGroupLocation, RowRange, SearchResult, VerifiedSearchResult,
+};
+
+/// Matching rows grouped by column name, then row group.
+pub(super) type RowsByColumn = BTreeMap<String, BTreeMap<u32, BTreeSet<u32>>>;
+
+/// Coarse locations by (column name, row group), whose rows are not known, with the
+/// pages holding them (empty when any row of the row group may match).
+pub(super) type CoarseRowGroups = BTreeMap<(String, u32), Vec<PageSpan>>;
+
+/// Add a coarse location to `coarse`, keeping the pages of every location of the
+/// same row group, or none if any of them covers the whole row group.
+pub(super) fn add_coarse(coarse: &mut CoarseRowGroups, column_name: &str, row_group_id: u32, pages: &[PageSpan]) {
+    match coarse.entry((column_name.to_string(), row_group_id)) {
+        Entry::Vacant(entry) => {
+            entry.insert(pages.to_vec());
+        }
+        Entry::Occupied(mut entry) => {
+            let known_pages = entry.get_mut();
+            if known_pages.is_empty() || pages.is_empty() {
+                known_pages.clear();
+            } else {
+                known_pages.extend_from_slice(pages);
+                known_pages.sort_unstable();
+                known_pages.dedup();
+            }
+        }
+    }
+}
+
+/// Byte offsets of the non-overlapping occurrences of `phrase` in `value` that start and
+/// end at token boundaries, as an index splitting values at the first `split_levels`
+/// delimiter levels does: each end must be the end of the value, next to a split
+/// character, or itself a split character of the phrase. This keeps `admin` from matching
+/// inside `administrator`, and `john-doe` inside `bigjohn-doe`.
+pub(super) fn phrase_offsets<'a>(value: &'a str, phrase: &'a str, split_levels: usize) -> impl Iterator<Item = usize> + 'a {
+    let is_split_char = move |c: char| (0..split_levels).any(|level| is_delimiter(c, level));
+    let starts_at_boundary = phrase.chars().next().is_some_and(is_split_char);
+    let ends_at_boundary = phrase.chars().next_back().is_some_and(is_split_char);
+    let mut from = 0;
+
+    std::iter::from_fn(move || {
+        if phrase.is_empty() {
+            return None;
+        }
+        while let Some(found) = value[from..].find(phrase) {
+            let offset = from + found;
+            let end = offset + phrase.len();
+            let start_ok = starts_at_boundary || value[..offset].chars().next_back().is_none_or(is_split_char);
+            let end_ok = ends_at_boundary || value[end..].chars().next().is_none_or(is_split_char);
+            if start_ok && end_ok {
+                from = end;
+                return Some(offset);
+            }
+            // Retry from the next character, an overlapping occurrence may be at a boundary
+            from = offset + value[offset..].chars().next().map_or(1, char::len_utf8);
+        }
+        from = value.len();
+        None
+    })
+}
+
+impl KeywordSearcher {
+    /// Search for a phrase and verify every match against the Parquet data.
+    ///
+    /// Runs a phrase search, then reads the cells of any `needs_verification` candidates
+    /// from `parquet_path` and checks that they contain the phrase. Only the affected rows
+    /// of the affected columns are read.
+    ///
+    /// # Arguments
+    ///
+    /// * `parquet_path` - Path of the Parquet file the index was built from
+    /// * `search_for` - The phrase to search for
+    /// * `in_columns` - Optional column name or glob pattern to restrict the search to
+    ///
+    /// # Returns
+    ///
+    /// A [`VerifiedSearchResult`] whose `result` has only verified matches, together
+    /// with the number of candidates checked and rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleIndex`] if the Parquet file changed since the index was
+    /// built, or an error if the search fails or the Parquet file cannot be read.
+    pub async fn search_verified(
+        &self,
+        parquet_path: &str,
+        search_for: &str,
+        in_columns: Option<&str>,
+    ) -> Result<VerifiedSearchResult, Error> {
+        let reader = PrunedParquetReader::from_path(parquet_path);
+
+        let result = self.search(search_for, in_columns, false).await?;
+        self.verify_search_result(result, &reader).await
+    }
+
+    /// Verify the `needs_verification` candidates of a search result.
+    ///
+    /// Reads the candidate cells through `reader` and moves those containing the query
+    /// into `verified_matches`. The query must start and end at token boundaries of the
+    /// cell, so `john-doe` does not match `bigjohn-doe`. Results without candidates are returned unchanged
+    /// without any I/O.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - Result of a phrase search on this index
+    /// * `reader` - Reader for the Parquet file the index was built from
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StaleIndex`] if the Parquet file changed since the index was
+    /// built, or an error if it cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # use keywords::searching::pruned_reader::PrunedParquetReader;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("s3://bucket/data.parquet", None).await?;
+    /// let result = searcher.search("john-doe", Some("name"), false).await?;
+    ///
+    /// let reader = PrunedParquetReader::from_path("s3://bucket/data.parquet");
+    /// let verified = searcher.verify_search_result(result, &reader).await?;
+    /// println!("{} candidates rejected", verified.candidates_rejected);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn verify_search_result(
+        &self,
+        mut result: SearchResult,
+        reader: &PrunedParquetReader,
+    ) -> Result<VerifiedSearchResult, Error> {
+        let Some(candidates) = result.needs_verification.take() else {
+            return Ok(VerifiedSearchResult { result, candidates_checked: 0, candidates_rejected: 0 });
+        };
+        self.check_not_stale(reader).await?;
+        let split_levels = self.filters.split_chars_inclusive.len();
+
+        let mut rows = RowsByColumn::new();
+        let mut candidates_checked = 0u64;
+        let mut accepted = 0u64;
+
+        // Coarse candidates are read in full, so count the cells actually checked. A row
+        // of a nested list column holds several values and matches if any of them does
+        for cells in reader.read_cells(&candidates).await? {
+            for cell in cells.rows.chunk_by(|a, b| a.0 == b.0) {
+                candidates_checked += 1;
+                let row = cell[0].0;
+                if cell.iter().any(|(_, value)| value.as_deref().is_some_and(|value| phrase_offsets(value, &result.query, split_levels).next().is_some())) {
+                    rows.entry(cells.column_name.clone())
+                        .or_default()
+                        .entry(cells.row_group_id)
+                        .or_default()
+                        .insert(row);
+                    accepted += 1;
+                }
+            }
+        }
+
+        if accepted > 0 {
+            let mut splits_matched = candidates.splits_matched;
+            let mut coarse = CoarseRowGroups::new();
+            if let Some(verified) = &result.verified_matches {
+                splits_matched |= verified.splits_matched;
+                add_location_rows(&mut rows, &mut coarse, verified);
+            }
+            result.verified_matches = Some(rows_to_location_data(rows, &coarse, splits_matched));
+        }
+
+        result.found = result.verified_matches.is_some();
+
+        Ok(VerifiedSearchResult {
+            result,
+            candidates_checked,
+            candidates_rejected: candidates_checked - accepted,
+        })
+    }
+
+    /// Returns [`Error::StaleIndex`] if the file read by `reader` is not the one the index
+    /// was built from, as the rows of its locations would not match.
+    pub(super) async fn check_not_stale(&self, reader: &PrunedParquetReader) -> Result<(), Error> {
+        if !self.validate_index(&reader.source).await? {
+            return Err(Error::StaleIndex(format!(
+                "The data file of index '{}' changed since the index was built", self.index_dir
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Add every row of a location set to the grouped rows, and its coarse row groups
+/// to `coarse`.
+pub(super) fn add_location_rows(rows: &mut RowsByColumn, coarse: &mut CoarseRowGroups, locations: &KeywordLocationData) {
+    for column in &locations.column_details {
+        let row_groups = rows.entry(column.column_name.clone()).or_default();
+        for rg in &column.row_groups {
+            if rg.coarse {
+                add_coarse(coarse, &column.column_name, rg.row_group_id, &rg.pages);
+            }
+            row_groups.entry(rg.row_group_id)
+                .or_default()
+                .extend(rg.row_ranges.iter().flat_map(|range| range.start_row..=range.end_row));
+        }
+    }
+}
+
+/// Build location data from grouped rows, merging consecutive rows into ranges.
+///
+/// Row groups listed in `coarse` become coarse locations, whatever rows they hold. Their
+/// pages are kept only if they contain all of these rows.
+pub(super) fn rows_to_location_data(
+    mut rows: RowsByColumn,
+    coarse: &CoarseRowGroups,
+    splits_matched: u16,
+) -> KeywordLocationData {
+    let mut total_occurrences = 0u64;
+    let mut column_details = Vec::new();
+
+    for (column_name, row_group_id) in coarse.keys() {
+        rows.entry(column_name.clone()).or_default().entry(*row_group_id).or_default();
+    }
+
+    for (column_name, row_groups) in rows {
+        let row_groups = row_groups.into_iter()
+            .map(|(row_group_id, rows)| {
+                if let Some(pages) = coarse.get(&(column_name.clone(), row_group_id)) {
+                    let in_pages = rows.iter()
+                        .all(|&row| pages.iter().any(|page| (page.start_row..=page.end_row).contains(&row)));
+                    let pages = if in_pages { pages.clone() } else { Vec::new() };
+                    return RowGroupLocation { row_group_id, row_ranges: Vec::new(), coarse: true, pages };
+                }
+                total_occurrences += rows.len() as u64;
+
+                let mut row_ranges: Vec<RowRange> = Vec::new();
+                for row in rows {
+                    match row_ranges.last_mut() {
+                        Some(range) if range.end_row + 1 == row => range.end_row = row,
+                        _ => row_ranges.push(RowRange {
+                            start_row: row,
+                            end_row: row,
+                            splits_matched,
+                            parent_chunk: None,
+                            parent_position: None,
+                        }),
+                    }
+                }
+
+                RowGroupLocation { row_group_id, row_ranges, coarse: false, pages: Vec::new() }
+            })
+            .collect();
+
+        column_details.push(ColumnLocation { column_name, row_groups });
+    }
+
+    KeywordLocationData {
+        columns: column_details.iter().map(|col| col.column_name.clone()).collect(),
+        total_occurrences,
+        splits_matched,
+        column_details,
+    }
+}
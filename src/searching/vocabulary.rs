@@ -0,0 +1,296 @@
+//! Enumeration of the keywords stored in an index.
+//!
+//! The data file stores keywords in sorted order, split into chunks whose first keyword
+//! is recorded in the chunk index. This makes it cheap to walk the whole dictionary (or
+//! just a key range) chunk by chunk without loading the full index into memory.
+//!
+//! Only the keyword list section of each chunk is read unless occurrence counts are
+//! requested or the enumeration is restricted to specific columns, in which case the
+//! chunk's data section is read as well.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use futures::TryStreamExt;
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//! use keywords::searching::vocabulary::VocabularyOptions;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let searcher = KeywordSearcher::load("data.parquet", None).await?;
+//!
+//!     let options = VocabularyOptions {
+//!         column: Some("email".to_string()),
+//!         with_counts: true,
+//!         ..Default::default()
+//!     };
+//!
+//!     let mut keywords = searcher.vocabulary(options);
+//!     while let Some(entry) = keywords.try_next().await? {
+//!         println!("{}: {:?}", entry.keyword, entry.total_occurrences);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use crate::error::Error;
+use crate::index_data::{count_distinct_rows, ColumnDataFlat, KeywordDataFlat};
+use crate::searching::keyword_search::KeywordSearcher;
+use crate::searching::search_results::{ColumnKeywordCount, VocabularyEntry};
+
+/// Options controlling which keywords [`KeywordSearcher::vocabulary`] returns.
+///
+/// All restrictions are optional and combine with AND. The default enumerates every
+/// keyword in the index without counts.
+#[derive(Debug, Clone, Default)]
+pub struct VocabularyOptions {
+    /// Only return keywords present in this column.
+    ///
+    /// Accepts an exact column name or a glob pattern (`*`, `?`), resolved the same way
+    /// as the column filter of [`KeywordSearcher::search`].
+    pub column: Option<String>,
+
+    /// Only return keywords starting with this prefix.
+    pub prefix: Option<String>,
+
+    /// Inclusive lower bound of the key range.
+    pub start: Option<String>,
+
+    /// Exclusive upper bound of the key range.
+    pub end: Option<String>,
+
+    /// Populate per-column occurrence counts on each entry.
+    pub with_counts: bool,
+}
+
+impl VocabularyOptions {
+    /// Whether a keyword falls within the prefix and key range restrictions.
+    fn in_range(&self, keyword: &str) -> bool {
+        self.start.as_deref().is_none_or(|start| keyword >= start)
+            && self.end.as_deref().is_none_or(|end| keyword < end)
+            && self.prefix.as_deref().is_none_or(|prefix| keyword.starts_with(prefix))
+    }
+
+    /// Whether no keyword at or after `keyword` can fall within the restrictions.
+    ///
+    /// Used to stop reading chunks once the enumeration has moved past the range.
+    fn past_range(&self, keyword: &str) -> bool {
+        self.end.as_deref().is_some_and(|end| keyword >= end)
+            || self.prefix.as_deref().is_some_and(|prefix| keyword > prefix && !keyword.starts_with(prefix))
+    }
+
+    /// The smallest keyword that can fall within the restrictions, if any.
+    fn lower_bound(&self) -> Option<&str> {
+        match (self.start.as_deref(), self.prefix.as_deref()) {
+            (Some(start), Some(prefix)) => Some(start.max(prefix)),
+            (start, prefix) => start.or(prefix),
+        }
+    }
+}
+
+/// Iteration state carried between chunks.
+struct VocabularyState {
+    next_chunk: usize,
+    pending: VecDeque<VocabularyEntry>,
+}
+
+impl KeywordSearcher {
+    /// Enumerate the keywords stored in the index, in sorted order.
+    ///
+    /// Returns a stream that reads one chunk at a time, so memory use stays bounded
+    /// by the chunk size regardless of vocabulary size. Chunks entirely outside the
+    /// requested prefix or key range are never read.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Column, prefix and key range restrictions, and whether to
+    ///   compute per-column occurrence counts
+    ///
+    /// # Returns
+    ///
+    /// A stream of [`VocabularyEntry`] items. Each item is `Err` if a chunk could not be
+    /// read or deserialized; the stream ends after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use futures::TryStreamExt;
+    /// # use keywords::searching::keyword_search::KeywordSearcher;
+    /// # use keywords::searching::vocabulary::VocabularyOptions;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// let searcher = KeywordSearcher::load("data.parquet", None).await?;
+    ///
+    /// // All keywords starting with "user_"
+    /// let options = VocabularyOptions { prefix: Some("user_".to_string()), ..Default::default() };
+    /// let keywords: Vec<_> = searcher.vocabulary(options).try_collect().await?;
+    /// println!("{} keywords", keywords.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn vocabulary(
+        &self,
+        options: VocabularyOptions,
+    ) -> BoxStream<'_, Result<VocabularyEntry, Error>> {
+        let column_ids: Option<Arc<[u32]>> = options.column.as_deref()
+            .map(|column| self.resolve_column_ids(&[column]).into());
+
+        let first_chunk = match (options.lower_bound(), &column_ids) {
+            // A column restriction that matches nothing yields nothing
+            (_, Some(ids)) if ids.is_empty() => self.filters.chunk_index.len(),
+            (Some(lower), _) => self.find_chunk_for_keyword(lower)
+                .map(|(idx, _)| idx as usize)
+                .unwrap_or(0),
+            (None, _) => 0,
+        };
+
+        let options = Arc::new(options);
+        let state = VocabularyState { next_chunk: first_chunk, pending: VecDeque::new() };
+
+        futures::stream::try_unfold(state, move |mut state| {
+            let options = Arc::clone(&options);
+            let column_ids = column_ids.clone();
+            async move {
+                loop {
+                    if let Some(entry) = state.pending.pop_front() {
+                        return Ok(Some((entry, state)));
+                    }
+
+                    let Some(chunk) = self.filters.chunk_index.get(state.next_chunk) else {
+                        return Ok(None);
+                    };
+                    if options.past_range(&chunk.start_keyword) {
+                        return Ok(None);
+                    }
+
+                    let chunk_number = state.next_chunk as u32;
+                    state.next_chunk += 1;
+                    state.pending = self.vocabulary_chunk(chunk_number, &options, column_ids.as_deref())
+                        .await?
+                        .into();
+                }
+            }
+        }).boxed()
+    }
+
+    /// Read one chunk and turn the keywords matching the options into vocabulary entries.
+    ///
+    /// The data section is only read when counts or a column restriction require it.
+    async fn vocabulary_chunk(
+        &self,
+        chunk_number: u32,
+        options: &VocabularyOptions,
+        column_ids: Option<&[u32]>,
+    ) -> Result<Vec<VocabularyEntry>, Error> {
+        if !options.with_counts && column_ids.is_none() {
+            let keywords = self.read_chunk_keywords(chunk_number).await?;
+            return Ok(keywords.into_iter()
+                .filter(|keyword| options.in_range(keyword))
+                .map(|keyword| VocabularyEntry { keyword, total_occurrences: None, column_counts: None })
+                .collect());
+        }
+
+        let (keywords, data) = self.read_full_chunk(chunk_number).await?;
+        let mut entries = Vec::new();
+
+        for (keyword, keyword_data) in keywords.into_iter().zip(data.iter()) {
+            if !options.in_range(&keyword) {
+                continue;
+            }
+            if column_ids.is_some_and(|ids| !keyword_data.columns.iter().any(|col| ids.contains(&col.column_id))) {
+                continue;
+            }
+
+            let (total_occurrences, column_counts) = if options.with_counts {
+                let (total, counts) = self.keyword_column_counts(keyword_data, column_ids);
+                (Some(total), Some(counts))
+            } else {
+                (None, None)
+            };
+
+            entries.push(VocabularyEntry { keyword, total_occurrences, column_counts });
+        }
+
+        Ok(entries)
+    }
+
+    /// Count the rows containing a keyword, per column and in total.
+    ///
+    /// Without a column restriction the total comes from the aggregate (column ID 0),
+    /// which counts each row once even if the keyword appears in several columns. With
+    /// a restriction to several columns, the rows of those columns are merged so that
+    /// each row is counted once as well (see [`distinct_rows`]).
+    fn keyword_column_counts(
+        &self,
+        keyword_data: &KeywordDataFlat,
+        column_ids: Option<&[u32]>,
+    ) -> (u64, Vec<ColumnKeywordCount>) {
+        let mut total = 0u64;
+        let mut counts: Vec<(u32, u64)> = Vec::new();
+
+        for col in &keyword_data.columns {
+            // Stored per row group, so coarse keywords are counted as well
+            let occurrences: u64 = col.row_groups.iter()
+                .map(|rg| rg.occurrences() as u64)
+                .chain(col.row_group_bitmap.as_ref().map(|bitmap| bitmap.occurrences as u64))
+                .sum();
+
+            match column_ids {
+                None if col.column_id == 0 => total = occurrences,
+                None => counts.push((col.column_id, occurrences)),
+                Some(ids) if ids.contains(&col.column_id) => {
+                    total += occurrences;
+                    counts.push((col.column_id, occurrences));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if counts.len() > 1 && column_ids.is_some() {
+            total = distinct_rows(keyword_data.columns.iter()
+                .filter(|col| column_ids.is_some_and(|ids| ids.contains(&col.column_id))));
+        }
+
+        counts.sort_unstable_by_key(|&(column_id, _)| column_id);
+        let counts = counts.into_iter()
+            .filter_map(|(column_id, occurrences)| {
+                self.filters.column_pool.get(column_id).map(|column_name| ColumnKeywordCount {
+                    column_name: column_name.to_string(),
+                    occurrences,
+                })
+            })
+            .collect();
+
+        (total, counts)
+    }
+}
+
+/// Count the rows of several columns containing a keyword, each row once.
+///
+/// Row ranges are merged per row group. Coarse entries and row group bitmaps store no
+/// rows, so their rows cannot be matched up between columns: a row group (or, for
+/// bitmaps, the whole file) then counts at least the largest count of a single column.
+fn distinct_rows<'a>(columns: impl Iterator<Item = &'a ColumnDataFlat>) -> u64 {
+    let mut row_groups: BTreeMap<u32, (Vec<(u32, u32)>, u64)> = BTreeMap::new();
+    let mut bitmap_occurrences = 0u64;
+
+    for col in columns {
+        for rg in &col.row_groups {
+            let (ranges, largest) = row_groups.entry(rg.row_group_id).or_default();
+            ranges.extend(rg.rows().map(|row| (row.row, row.row.saturating_add(row.additional_rows))));
+            *largest = (*largest).max(rg.occurrences() as u64);
+        }
+        if let Some(bitmap) = &col.row_group_bitmap {
+            bitmap_occurrences = bitmap_occurrences.max(bitmap.occurrences as u64);
+        }
+    }
+
+    let total: u64 = row_groups.into_values()
+        .map(|(ranges, largest)| count_distinct_rows(ranges).max(largest))
+        .sum();
+    total.max(bitmap_occurrences)
+}
@@ -0,0 +1,329 @@
+//! Keyword indexes of CSV and JSON lines (NDJSON) files.
+//!
+//! Raw exports can be searched before they are converted to Parquet. Text files have no
+//! row groups, so their rows are decoded into consecutive batches of
+//! [`TextOptions::rows_per_batch`] rows, and every batch is indexed as a pseudo row
+//! group by the same keyword shredding as Parquet files (see [`RecordBatchIndexer`]).
+//! The byte range of every batch is stored in the index as a [`TextLayout`], so that
+//! [`PrunedTextReader`](crate::searching::pruned_text_reader::PrunedTextReader) fetches
+//! and decodes only the batches holding matches.
+//!
+//! CSV values are indexed as written (every column is read as a string), JSON values with
+//! the types inferred from the file, nested objects and arrays becoming leaf columns
+//! such as `payload.user` or `tags[]`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use keywords::{build_and_save_text_index, BuildOptions};
+//! use keywords::index_data::TextFormat;
+//! use keywords::searching::keyword_search::KeywordSearcher;
+//! use keywords::searching::pruned_text_reader::PrunedTextReader;
+//! use keywords::text_source::TextOptions;
+//! use keywords::ParquetSource;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let text_options = TextOptions::new(TextFormat::JsonLines);
+//! build_and_save_text_index("events.ndjson", &text_options, None, None, None, &BuildOptions::default()).await?;
+//!
+//! let searcher = KeywordSearcher::load("events.ndjson", None).await?;
+//! let result = searcher.search("timeout", None, true).await?;
+//! let layout = searcher.text_layout().ok_or("Not a text index")?;
+//! let reader = PrunedTextReader::new(ParquetSource::Path("events.ndjson".to_string()), layout)?;
+//! let batches = reader.read_matching_rows(&result, None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use arrow::csv;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::{DictionaryTracker, IpcDataGenerator, IpcWriteOptions};
+use arrow::json;
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use tokio::runtime::Handle;
+use crate::error::Error;
+use crate::build_progress::BuildProgress;
+use crate::index_data::{TextFormat, TextLayout};
+use crate::record_batch_indexer::RecordBatchIndexer;
+use crate::utils::file_interaction_local_and_cloud::get_object_store;
+use crate::{BuildOptions, ParquetSource, ProcessResult};
+
+/// Default number of rows per batch (pseudo row group) of a text file.
+pub const DEFAULT_ROWS_PER_BATCH: usize = 10_000;
+
+/// How a CSV or JSON lines file is decoded and split into batches.
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    /// Format of the file
+    pub format: TextFormat,
+
+    /// Number of rows per batch. Each batch is a pseudo row group of the index, so
+    /// smaller batches make pruned reads more selective at the cost of a larger index.
+    pub rows_per_batch: usize,
+
+    /// Schema to decode the rows with. `None` (the default) reads CSV columns as strings
+    /// named by the header record (`column_1`, `column_2`, ... without a header), and
+    /// infers the schema of JSON lines from the whole file, in an extra pass over it.
+    /// JSON numbers and booleans are read as strings into `Utf8` fields.
+    pub schema: Option<SchemaRef>,
+}
+
+impl TextOptions {
+    /// Options for a file of `format`, with the default batch size and schema.
+    pub fn new(format: TextFormat) -> Self {
+        TextOptions {
+            format,
+            rows_per_batch: DEFAULT_ROWS_PER_BATCH,
+            schema: None,
+        }
+    }
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        TextOptions::new(TextFormat::CSV)
+    }
+}
+
+/// Processes a CSV or JSON lines file into keywords, one pseudo row group per batch.
+///
+/// Same as [`process_parquet_file_with_options`](crate::column_parquet_reader::process_parquet_file_with_options)
+/// for text files: the result carries the [`TextLayout`] of the file, and is laid out by
+/// [`build_distributed_index`](crate::index_data::build_distributed_index) with the
+/// same `source`. The file is streamed, so only one batch of rows is held in memory.
+///
+/// [`BuildOptions::cardinality_check`] and [`BuildOptions::checkpoint_directory`] are not
+/// supported, and [`BuildOptions::page_locations`] has no effect (text files have no pages).
+///
+/// # Arguments
+///
+/// * `source` - Path of the text file (local, `s3://` or `memory://`) or its bytes
+/// * `text_options` - Format, batch size and schema of the file
+/// * `exclude_columns` - Optional set of column names to skip during indexing
+/// * `error_rate` - Optional bloom filter false positive rate (default: 0.01 = 1%)
+/// * `options` - Optional index structures (see [`BuildOptions`])
+///
+/// # Errors
+///
+/// Returns error if:
+/// - `rows_per_batch` is 0 or exceeds `u32::MAX`, or the file has more than `u32::MAX` batches
+/// - The file cannot be read, or a record cannot be decoded with the schema
+/// - The indexer cannot be created (see [`RecordBatchIndexer::try_new`])
+pub async fn process_text_file(
+    source: ParquetSource,
+    text_options: &TextOptions,
+    exclude_columns: Option<HashSet<String>>,
+    error_rate: Option<f64>,
+    options: &BuildOptions,
+) -> Result<ProcessResult, Error> {
+    let rows_per_batch = text_options.rows_per_batch;
+    if rows_per_batch == 0 || u32::try_from(rows_per_batch).is_err() {
+        return Err(Error::InvalidArgument(format!("rows_per_batch must be between 1 and {}, got {}", u32::MAX, rows_per_batch)));
+    }
+
+    let schema = match &text_options.schema {
+        Some(schema) => schema.clone(),
+        None => Arc::new(infer_schema(&source, text_options.format).await?),
+    };
+    let mut indexer = RecordBatchIndexer::try_new(schema.clone(), exclude_columns, error_rate, options)?
+        .with_max_row_group_size(rows_per_batch);
+    let mut decoder = TextDecoder::try_new(text_options.format, schema.clone(), rows_per_batch, true)?;
+
+    // Every batch ends where the decoder stopped consuming input for it
+    let mut batch_offsets = vec![0];
+    let mut position = 0u64;
+    let (mut chunks, file_size) = byte_stream(&source).await?;
+    let monitor = options.monitor();
+    monitor.report(|progress| *progress = BuildProgress { total_bytes: file_size, ..Default::default() });
+    let mut index_batch = |batch: Option<RecordBatch>, position: u64| -> Result<(), Error> {
+        monitor.check_cancelled()?;
+        if let Some(batch) = batch {
+            indexer.write(&batch)?;
+            indexer.flush()?;
+            batch_offsets.push(position);
+        }
+        monitor.report(|progress| {
+            progress.bytes_streamed = position;
+            progress.column_chunks_processed = batch_offsets.len() - 1;
+            progress.keywords_found = indexer.keywords_found();
+        });
+        Ok(())
+    };
+
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        let mut buf = &chunk[..];
+        while !buf.is_empty() {
+            let consumed = decoder.decode(buf)?;
+            buf = &buf[consumed..];
+            position += consumed as u64;
+            if decoder.is_full() {
+                index_batch(decoder.flush()?, position)?;
+            } else if consumed == 0 {
+                return Err(ArrowError::ParseError(format!("Could not decode the record at byte {}", position)).into());
+            }
+        }
+    }
+    // Completes a last CSV record without a line terminator
+    decoder.decode(&[])?;
+    index_batch(decoder.flush()?, position)?;
+
+    let mut result = indexer.finish()?;
+    result.text_layout = Some(TextLayout {
+        format: text_options.format,
+        schema: encode_schema(&schema),
+        rows_per_batch: rows_per_batch as u32,
+        batch_offsets,
+    });
+    Ok(result)
+}
+
+/// Decoder of CSV or JSON lines records into record batches.
+pub(crate) enum TextDecoder {
+    Csv(Box<csv::reader::Decoder>),
+    Json(json::reader::Decoder, usize),
+}
+
+impl TextDecoder {
+    /// Creates a decoder of batches of `batch_size` rows. The CSV header record, if any,
+    /// is skipped when `at_header` is set (when decoding from the start of the file).
+    pub(crate) fn try_new(
+        format: TextFormat,
+        schema: SchemaRef,
+        batch_size: usize,
+        at_header: bool,
+    ) -> Result<Self, ArrowError> {
+        match format {
+            TextFormat::Csv { delimiter, has_header } => Ok(TextDecoder::Csv(Box::new(
+                csv::ReaderBuilder::new(schema)
+                    .with_header(has_header && at_header)
+                    .with_delimiter(delimiter)
+                    .with_batch_size(batch_size)
+                    .build_decoder(),
+            ))),
+            TextFormat::JsonLines => Ok(TextDecoder::Json(
+                json::ReaderBuilder::new(schema)
+                    .with_batch_size(batch_size)
+                    .with_coerce_primitive(true)
+                    .build_decoder()?,
+                batch_size,
+            )),
+        }
+    }
+
+    /// Decodes records from `buf`, returning the number of bytes consumed. Stops once a
+    /// full batch is decoded.
+    pub(crate) fn decode(&mut self, buf: &[u8]) -> Result<usize, ArrowError> {
+        match self {
+            TextDecoder::Csv(decoder) => decoder.decode(buf),
+            TextDecoder::Json(decoder, _) => decoder.decode(buf),
+        }
+    }
+
+    /// Whether a full batch is decoded and must be flushed before decoding more.
+    fn is_full(&self) -> bool {
+        match self {
+            TextDecoder::Csv(decoder) => decoder.capacity() == 0,
+            // `len` includes the record being decoded, which must be complete
+            TextDecoder::Json(decoder, batch_size) => decoder.len() == *batch_size && !decoder.has_partial_record(),
+        }
+    }
+
+    /// Returns the decoded records as a batch, or `None` if there are none.
+    pub(crate) fn flush(&mut self) -> Result<Option<RecordBatch>, ArrowError> {
+        match self {
+            TextDecoder::Csv(decoder) => decoder.flush(),
+            TextDecoder::Json(decoder, _) => decoder.flush(),
+        }
+    }
+}
+
+/// Encodes a schema as an Arrow IPC schema message, as stored in [`TextLayout::schema`].
+fn encode_schema(schema: &Schema) -> Vec<u8> {
+    IpcDataGenerator::default()
+        .schema_to_bytes_with_dictionary_tracker(schema, &mut DictionaryTracker::new(false), &IpcWriteOptions::default())
+        .ipc_message
+}
+
+/// Infers the schema of a text file without a schema in its [`TextOptions`].
+async fn infer_schema(
+    source: &ParquetSource,
+    format: TextFormat,
+) -> Result<Schema, Error> {
+    let infer = move |reader: Box<dyn Read + Send>| -> Result<Schema, ArrowError> {
+        match format {
+            TextFormat::Csv { delimiter, has_header } => {
+                // Only the first record is read, for the column names and count
+                let (schema, _) = csv::reader::Format::default()
+                    .with_header(has_header)
+                    .with_delimiter(delimiter)
+                    .infer_schema(reader, Some(0))?;
+                Ok(Schema::new(
+                    schema.fields().iter()
+                        .map(|field| Field::new(field.name(), DataType::Utf8, true))
+                        .collect::<Vec<_>>(),
+                ))
+            }
+            TextFormat::JsonLines => {
+                let (schema, _) = json::reader::infer_json_schema(std::io::BufReader::new(reader), None)?;
+                Ok(schema)
+            }
+        }
+    };
+
+    match source {
+        ParquetSource::Bytes(bytes) => Ok(infer(Box::new(Cursor::new(bytes.clone())))?),
+        ParquetSource::Path(_) => {
+            let reader = BlockingStreamReader {
+                chunks: byte_stream(source).await?.0,
+                current: Bytes::new(),
+                handle: Handle::current(),
+            };
+            Ok(tokio::task::spawn_blocking(move || infer(Box::new(reader))).await??)
+        }
+    }
+}
+
+/// Streams the bytes of a text file, with its size.
+async fn byte_stream(
+    source: &ParquetSource,
+) -> Result<(BoxStream<'static, Result<Bytes, object_store::Error>>, u64), Error> {
+    match source {
+        ParquetSource::Path(path) => {
+            let (store, obj_path) = get_object_store(path).await?;
+            let result = store.get(&obj_path).await?;
+            let size = result.meta.size;
+            Ok((result.into_stream(), size))
+        }
+        ParquetSource::Bytes(bytes) => Ok((stream::iter([Ok(bytes.clone())]).boxed(), bytes.len() as u64)),
+    }
+}
+
+/// Reads a byte stream from a blocking thread, for the synchronous schema inference
+/// of Arrow's readers.
+struct BlockingStreamReader {
+    chunks: BoxStream<'static, Result<Bytes, object_store::Error>>,
+    current: Bytes,
+    handle: Handle,
+}
+
+impl Read for BlockingStreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.current.is_empty() {
+            match self.handle.block_on(self.chunks.next()) {
+                Some(chunk) => self.current = chunk.map_err(std::io::Error::other)?,
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.current.len());
+        buf[..len].copy_from_slice(&self.current.split_to(len));
+        Ok(len)
+    }
+}
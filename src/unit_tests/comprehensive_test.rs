@@ -85,7 +85,7 @@ mod comprehensive_large_file_tests {
         };
 
         // Calculate expected row group and row within group for context
-        let expected_row_group = (expected_global_row / ROWS_PER_GROUP) as u16;
+        let expected_row_group = (expected_global_row / ROWS_PER_GROUP) as u32;
         let expected_row_in_group = (expected_global_row % ROWS_PER_GROUP) as u32;
 
         // Find the column detail
@@ -114,7 +114,7 @@ mod comprehensive_large_file_tests {
         let rg = col_detail.row_groups.iter()
             .find(|rg| rg.row_group_id == expected_row_group)
             .unwrap_or_else(|| {
-                let found_rgs: Vec<u16> = col_detail.row_groups.iter()
+                let found_rgs: Vec<u32> = col_detail.row_groups.iter()
                     .map(|rg| rg.row_group_id)
                     .collect();
                 panic!("
@@ -5,7 +5,7 @@ mod tests {
     use arrow::array::{StringArray};
     use indexmap::IndexSet;
     use crate::{build_index_in_memory, ParquetSource};
-    use crate::keyword_shred::{perform_split, build_column_keywords_map};
+    use crate::keyword_shred::{perform_split, build_column_keywords_map, Tokenization, SPLIT_CHARS_COUNT};
     use crate::index_structure::column_filter::ColumnFilter;
     use crate::utils::column_pool::ColumnPool;
     use crate::column_parquet_reader::{process_arrow_string_array, process_parquet_file};
@@ -468,7 +468,7 @@ mod tests {
             Some("foo-bar_baz"),
         ]);
 
-        process_arrow_string_array(&array, "test_column", 0, 0, &mut keyword_map, &mut column_pool);
+        process_arrow_string_array(&array, column_pool.intern("test_column"), 0, 0, None, Tokenization::Split { levels: SPLIT_CHARS_COUNT }, &mut keyword_map);
 
         // Verify keywords were extracted
         assert!(keyword_map.contains_key("hello"));
@@ -287,19 +287,19 @@ mod tests {
             };
             let mut row_groups = Vec::new();
             for rg in col.row_groups.iter() {
-                let row_group_id: u16 = rg.row_group_id.to_native();  // Convert from archived
+                let row_group_id: u32 = rg.row_group_id.to_native();  // Convert from archived
                 let mut row_ranges = Vec::new();
-                for flat_row in rg.rows.iter() {
+                for flat_row in rg.rows() {
                     row_ranges.push(RowRange {
-                        start_row: flat_row.row.to_native(),  // Convert from archived
-                        end_row: flat_row.row.to_native() + flat_row.additional_rows.to_native(),
-                        splits_matched: flat_row.splits_matched.to_native(),
-                        parent_chunk: flat_row.parent_chunk.as_ref().map(|c| c.to_native()),
-                        parent_position: flat_row.parent_position.as_ref().map(|p| p.to_native()),
+                        start_row: flat_row.row,
+                        end_row: flat_row.row + flat_row.additional_rows,
+                        splits_matched: flat_row.splits_matched,
+                        parent_chunk: flat_row.parent_chunk,
+                        parent_position: flat_row.parent_position,
                     });
-                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows.to_native() as u64 + 1);
+                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows as u64 + 1);
                 }
-                row_groups.push(RowGroupLocation { row_group_id, row_ranges });
+                row_groups.push(RowGroupLocation { row_group_id, row_ranges, coarse: false, pages: Vec::new() });
             }
             column_details.push(ColumnLocation { column_name, row_groups });
         }
@@ -900,19 +900,19 @@ mod tests {
             };
             let mut row_groups = Vec::new();
             for rg in col.row_groups.iter() {
-                let row_group_id: u16 = rg.row_group_id.to_native();  // Convert from archived
+                let row_group_id: u32 = rg.row_group_id.to_native();  // Convert from archived
                 let mut row_ranges = Vec::new();
-                for flat_row in rg.rows.iter() {
+                for flat_row in rg.rows() {
                     row_ranges.push(RowRange {
-                        start_row: flat_row.row.to_native(),  // Convert from archived
-                        end_row: flat_row.row.to_native() + flat_row.additional_rows.to_native(),
-                        splits_matched: flat_row.splits_matched.to_native(),
-                        parent_chunk: flat_row.parent_chunk.as_ref().map(|c| c.to_native()),
-                        parent_position: flat_row.parent_position.as_ref().map(|p| p.to_native()),
+                        start_row: flat_row.row,
+                        end_row: flat_row.row + flat_row.additional_rows,
+                        splits_matched: flat_row.splits_matched,
+                        parent_chunk: flat_row.parent_chunk,
+                        parent_position: flat_row.parent_position,
                     });
-                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows.to_native() as u64 + 1);
+                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows as u64 + 1);
                 }
-                row_groups.push(RowGroupLocation { row_group_id, row_ranges });
+                row_groups.push(RowGroupLocation { row_group_id, row_ranges, coarse: false, pages: Vec::new() });
             }
             column_details.push(ColumnLocation { column_name, row_groups });
         }
@@ -1514,19 +1514,19 @@ mod tests {
             };
             let mut row_groups = Vec::new();
             for rg in col.row_groups.iter() {
-                let row_group_id: u16 = rg.row_group_id.to_native();  // Convert from archived
+                let row_group_id: u32 = rg.row_group_id.to_native();  // Convert from archived
                 let mut row_ranges = Vec::new();
-                for flat_row in rg.rows.iter() {
+                for flat_row in rg.rows() {
                     row_ranges.push(RowRange {
-                        start_row: flat_row.row.to_native(),  // Convert from archived
-                        end_row: flat_row.row.to_native() + flat_row.additional_rows.to_native(),
-                        splits_matched: flat_row.splits_matched.to_native(),
-                        parent_chunk: flat_row.parent_chunk.as_ref().map(|c| c.to_native()),
-                        parent_position: flat_row.parent_position.as_ref().map(|p| p.to_native()),
+                        start_row: flat_row.row,
+                        end_row: flat_row.row + flat_row.additional_rows,
+                        splits_matched: flat_row.splits_matched,
+                        parent_chunk: flat_row.parent_chunk,
+                        parent_position: flat_row.parent_position,
                     });
-                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows.to_native() as u64 + 1);
+                    total_occurrences = total_occurrences.saturating_add(flat_row.additional_rows as u64 + 1);
                 }
-                row_groups.push(RowGroupLocation { row_group_id, row_ranges });
+                row_groups.push(RowGroupLocation { row_group_id, row_ranges, coarse: false, pages: Vec::new() });
             }
             column_details.push(ColumnLocation { column_name, row_groups });
         }
@@ -5,6 +5,7 @@ use object_store::{ObjectStore, aws::AmazonS3Builder, local::LocalFileSystem, pa
 use object_store::memory::InMemory;
 use url::Url;
 use bytes::Bytes;
+use crate::error::Error;
 
 /// Cache key for S3 stores that distinguishes between authenticated and anonymous access
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
@@ -80,7 +81,7 @@ static MEMORY_STORE: Lazy<Arc<InMemory>> = Lazy::new(|| Arc::new(InMemory::new()
 /// // build_and_save_index("memory://test.parquet", None, None, None).await?;
 /// # });
 /// ```
-pub async fn register_memory_file(path: &str, data: Bytes) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+pub async fn register_memory_file(path: &str, data: Bytes) -> Result<(), Error> {
     use object_store::PutPayload;
 
     let normalized = normalize_memory_path(path);
@@ -98,7 +99,7 @@ pub async fn register_memory_file(path: &str, data: Bytes) -> Result<(), Box<dyn
 /// # Returns
 ///
 /// Ok(Some(bytes)) if the file existed, Ok(None) if not found, or Err on failure
-pub async fn unregister_memory_file(path: &str) -> Result<Option<Bytes>, Box<dyn std::error::Error + Send + Sync>> {
+pub async fn unregister_memory_file(path: &str) -> Result<Option<Bytes>, Error> {
     let normalized = normalize_memory_path(path);
     let obj_path = ObjectPath::from(normalized);
 
@@ -185,7 +186,7 @@ fn normalize_memory_path(path: &str) -> String {
 pub fn get_cached_s3_store(
     bucket: &str,
     anonymous: bool
-) -> Result<Arc<dyn ObjectStore>, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<Arc<dyn ObjectStore>, Error> {
     let cache_key = S3CacheKey {
         bucket: bucket.to_string(),
         anonymous,
@@ -298,7 +299,7 @@ pub fn get_cached_s3_store(
 /// ```
 pub async fn get_object_store(
     file_path: &str,
-) -> Result<(Arc<dyn ObjectStore>, ObjectPath), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(Arc<dyn ObjectStore>, ObjectPath), Error> {
 
     if file_path.starts_with("memory://") {
         let normalized = normalize_memory_path(file_path);
@@ -306,9 +307,10 @@ pub async fn get_object_store(
         let path = ObjectPath::from(normalized);
         Ok((store, path))
     } else if file_path.starts_with("s3://") {
-        let url = Url::parse(file_path)?;
+        let url = Url::parse(file_path)
+            .map_err(|e| Error::InvalidArgument(format!("Invalid S3 URL '{}': {}", file_path, e)))?;
         let bucket = url.host_str()
-            .ok_or("Invalid S3 URL - no bucket specified")?;
+            .ok_or_else(|| Error::InvalidArgument("Invalid S3 URL - no bucket specified".to_string()))?;
         let key = url.path().trim_start_matches('/');
 
         // Check for anonymous access flag in query parameters
@@ -415,7 +417,7 @@ pub async fn get_object_store(
 pub fn create_s3_store(
     bucket: &str,
     anonymous: bool
-) -> Result<Arc<dyn ObjectStore>, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<Arc<dyn ObjectStore>, Error> {
     let mut builder = AmazonS3Builder::from_env()
         .with_bucket_name(bucket);
 
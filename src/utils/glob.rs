@@ -0,0 +1,145 @@
+//! Minimal glob matching for column names.
+//!
+//! Column selections accept either exact column names or simple glob patterns such as
+//! `http_*`, `headers.*` or `col_?`. Only two wildcards are supported:
+//!
+//! - `*` matches any sequence of characters (including an empty one)
+//! - `?` matches exactly one character
+//!
+//! All other characters (including `.`) match literally. Matching is case-sensitive,
+//! consistent with how column names are stored in the [`ColumnPool`](crate::utils::column_pool::ColumnPool).
+
+/// Returns `true` if the pattern contains any glob wildcard characters.
+///
+/// Patterns without wildcards are plain column names and can be compared directly.
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::utils::glob::is_glob_pattern;
+/// assert!(is_glob_pattern("http_*"));
+/// assert!(!is_glob_pattern("email"));
+/// ```
+pub fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Checks whether `text` matches the glob `pattern`.
+///
+/// Uses an iterative matcher that backtracks to the most recent `*`, so the cost is
+/// O(pattern × text) in the worst case and linear for typical column name patterns.
+///
+/// # Arguments
+///
+/// * `pattern` - Glob pattern (`*` and `?` wildcards, everything else literal)
+/// * `text` - Column name to test
+///
+/// # Returns
+///
+/// `true` if the whole of `text` matches the whole of `pattern`.
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::utils::glob::glob_match;
+/// assert!(glob_match("http_*", "http_status"));
+/// assert!(glob_match("headers.*", "headers.host"));
+/// assert!(glob_match("col_?", "col_1"));
+/// assert!(!glob_match("col_?", "col_10"));
+/// assert!(glob_match("email", "email"));
+/// ```
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let mut p = 0;
+    let mut t = 0;
+    // Position of the last `*` seen in the pattern, and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` absorb one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    // Any trailing `*` can match the empty remainder
+    while p < pattern.len() && pattern[p] == '*' {
+        p += 1;
+    }
+
+    p == pattern.len()
+}
+
+/// Checks whether `column_name` is selected by `pattern`, an exact column name or a
+/// glob pattern.
+///
+/// # Examples
+///
+/// ```
+/// # use keywords::utils::glob::column_matches;
+/// assert!(column_matches("http_*", "http_status"));
+/// assert!(column_matches("email", "email"));
+/// assert!(!column_matches("email", "emails"));
+/// ```
+pub fn column_matches(pattern: &str, column_name: &str) -> bool {
+    if is_glob_pattern(pattern) {
+        glob_match(pattern, column_name)
+    } else {
+        pattern == column_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_match() {
+        assert!(glob_match("email", "email"));
+        assert!(!glob_match("email", "emails"));
+        assert!(!glob_match("emails", "email"));
+    }
+
+    #[test]
+    fn test_star_wildcard() {
+        assert!(glob_match("http_*", "http_"));
+        assert!(glob_match("http_*", "http_status_code"));
+        assert!(glob_match("*_id", "user_id"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(!glob_match("a*b*c", "aXXbYY"));
+        assert!(!glob_match("http_*", "https_status"));
+    }
+
+    #[test]
+    fn test_question_wildcard() {
+        assert!(glob_match("col_?", "col_1"));
+        assert!(!glob_match("col_?", "col_"));
+        assert!(!glob_match("col_?", "col_12"));
+    }
+
+    #[test]
+    fn test_dot_is_literal() {
+        assert!(glob_match("headers.*", "headers.host"));
+        assert!(!glob_match("headers.*", "headersXhost"));
+    }
+
+    #[test]
+    fn test_is_glob_pattern() {
+        assert!(is_glob_pattern("*"));
+        assert!(is_glob_pattern("col_?"));
+        assert!(!is_glob_pattern("headers.host"));
+    }
+}
@@ -1,2 +1,4 @@
 pub mod column_pool;
-pub mod file_interaction_local_and_cloud;
\ No newline at end of file
+pub mod file_interaction_local_and_cloud;
+pub mod glob;
+pub mod nested_columns;
@@ -0,0 +1,280 @@
+//! Leaf columns of nested (struct, list and map) Parquet schemas.
+//!
+//! Every Parquet leaf column is indexed as its own column, named by its path from the
+//! top-level field:
+//!
+//! - struct fields are joined with `.`, e.g. `payload.user.email`
+//! - list elements add `[]`, e.g. `tags[]`, or `items[].name` for a list of structs
+//! - maps are lists of key/value entries, e.g. `attributes[].key` and `attributes[].value`
+//!   (with the entry field names stored in the file)
+//!
+//! Top-level columns of primitive types keep their plain name. Values of repeated leaves
+//! belong to the top-level row they were read from, so a row matches a search in `tags[]`
+//! when any of its tags matches.
+
+use std::ops::Range;
+use std::sync::Arc;
+use arrow::array::{Array, ArrayRef, AsArray, UInt32Array};
+use arrow::compute::take;
+use arrow::datatypes::{DataType, Schema};
+use arrow::error::ArrowError;
+use parquet::arrow::parquet_to_arrow_schema;
+use parquet::file::metadata::KeyValue;
+use parquet::schema::types::SchemaDescriptor;
+
+/// A Parquet leaf column and how to decode it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeafColumn {
+    /// Column name: the path of the leaf from its top-level field
+    pub name: String,
+
+    /// Index of the top-level field containing the leaf
+    pub root: usize,
+
+    /// Arrow type of the leaf's values (`Null` if the Arrow schema cannot be derived)
+    pub data_type: DataType,
+
+    /// Leaf columns that must be decoded to read this leaf: the leaf itself, or every
+    /// leaf of its outermost enclosing map, as maps cannot be partially decoded
+    pub projection: Vec<usize>,
+
+    /// Struct fields (including map entry fields) from the top-level field to the leaf
+    route: Vec<String>,
+}
+
+/// Lists the leaf columns of a Parquet schema, in leaf column order.
+///
+/// Names follow the Arrow schema derived from the Parquet schema (including an Arrow
+/// schema embedded by Arrow writers). Should the conversion fail, leaves are named by
+/// their dotted Parquet path and can only be decoded if they are not nested.
+///
+/// # Arguments
+///
+/// * `schema_descr` - Parquet schema of the file
+/// * `key_value_metadata` - Key-value metadata of the file, which may embed an Arrow schema
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow::datatypes::{DataType, Field, Schema};
+/// # use parquet::arrow::ArrowSchemaConverter;
+/// # use keywords::utils::nested_columns::leaf_columns;
+/// let tags = DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+/// let schema = Schema::new(vec![Field::new("id", DataType::Utf8, false), Field::new("tags", tags, true)]);
+/// let schema_descr = ArrowSchemaConverter::new().convert(&schema).unwrap();
+///
+/// let names: Vec<String> = leaf_columns(&schema_descr, None).into_iter().map(|leaf| leaf.name).collect();
+/// assert_eq!(names, vec!["id", "tags[]"]);
+/// ```
+pub fn leaf_columns(schema_descr: &SchemaDescriptor, key_value_metadata: Option<&Vec<KeyValue>>) -> Vec<LeafColumn> {
+    if let Ok(arrow_schema) = parquet_to_arrow_schema(schema_descr, key_value_metadata) {
+        let leaves = arrow_leaf_columns(&arrow_schema);
+        if leaves.len() == schema_descr.num_columns() {
+            return leaves;
+        }
+    }
+
+    schema_descr.columns().iter()
+        .enumerate()
+        .map(|(index, column)| LeafColumn {
+            name: column.path().string(),
+            root: schema_descr.get_column_root_idx(index),
+            data_type: DataType::Null,
+            projection: vec![index],
+            route: Vec::new(),
+        })
+        .collect()
+}
+
+/// Lists the leaf columns an Arrow schema is written as, in leaf column order.
+///
+/// Same as [`leaf_columns`] for a Parquet file written from record batches of `schema`
+/// by an Arrow writer.
+pub fn arrow_leaf_columns(schema: &Schema) -> Vec<LeafColumn> {
+    let mut leaves = Vec::new();
+    for (root, field) in schema.fields().iter().enumerate() {
+        collect_leaves(field.data_type(), field.name().clone(), Vec::new(), root, None, &mut leaves);
+    }
+    leaves
+}
+
+/// Adds the leaves of a field to `leaves`, depth first.
+///
+/// `map_start` is the index of the first leaf of the outermost enclosing map, if any.
+fn collect_leaves(
+    data_type: &DataType,
+    name: String,
+    route: Vec<String>,
+    root: usize,
+    map_start: Option<usize>,
+    leaves: &mut Vec<LeafColumn>,
+) {
+    match data_type {
+        DataType::Struct(fields) => {
+            for field in fields {
+                let mut route = route.clone();
+                route.push(field.name().clone());
+                collect_leaves(field.data_type(), format!("{}.{}", name, field.name()), route, root, map_start, leaves);
+            }
+        }
+        DataType::List(field) | DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            collect_leaves(field.data_type(), format!("{}[]", name), route, root, map_start, leaves);
+        }
+        DataType::Map(entries, _) => {
+            let start = leaves.len();
+            if let DataType::Struct(fields) = entries.data_type() {
+                for field in fields {
+                    let mut route = route.clone();
+                    route.push(field.name().clone());
+                    collect_leaves(
+                        field.data_type(),
+                        format!("{}[].{}", name, field.name()),
+                        route,
+                        root,
+                        Some(map_start.unwrap_or(start)),
+                        leaves,
+                    );
+                }
+            }
+            // The outermost map decodes all of its leaves together
+            if map_start.is_none() {
+                let projection: Vec<usize> = (start..leaves.len()).collect();
+                for leaf in &mut leaves[start..] {
+                    leaf.projection = projection.clone();
+                }
+            }
+        }
+        _ => {
+            let index = leaves.len();
+            leaves.push(LeafColumn { name, root, data_type: data_type.clone(), projection: vec![index], route });
+        }
+    }
+}
+
+/// Finds the top-level fields to read for a set of column names.
+///
+/// Each name may be a top-level field or a leaf column name (see [`leaf_columns`]);
+/// names matching neither are ignored.
+///
+/// # Returns
+///
+/// Indices of the top-level fields, ascending and without duplicates, for use with
+/// `ProjectionMask::roots`.
+pub fn root_indices(
+    schema_descr: &SchemaDescriptor,
+    key_value_metadata: Option<&Vec<KeyValue>>,
+    columns: &[String],
+) -> Vec<usize> {
+    let root_fields = schema_descr.root_schema().get_fields();
+    let leaves = leaf_columns(schema_descr, key_value_metadata);
+
+    let mut indices: Vec<usize> = columns.iter()
+        .filter_map(|column| {
+            root_fields.iter().position(|field| field.name() == column)
+                .or_else(|| leaves.iter().find(|leaf| &leaf.name == column).map(|leaf| leaf.root))
+        })
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// Values of one leaf column, extracted from the decoded array of its top-level field.
+pub(crate) struct LeafValues {
+    /// The leaf's values, in row order
+    pub values: ArrayRef,
+
+    /// The row (within the top-level array) of each value; `None` when the leaf is not
+    /// nested and values and rows match one to one
+    pub rows: Option<Vec<u32>>,
+}
+
+/// Extracts the values of a leaf column from the decoded array of its top-level field.
+///
+/// Null structs, lists and maps contribute no values; every list element and map entry
+/// contributes one value, attributed to its top-level row.
+///
+/// # Errors
+///
+/// Returns error if the array does not have the structure of the leaf's field.
+pub(crate) fn leaf_values(array: &ArrayRef, leaf: &LeafColumn) -> Result<LeafValues, ArrowError> {
+    if !array.data_type().is_nested() {
+        return Ok(LeafValues { values: Arc::clone(array), rows: None });
+    }
+
+    let mut array = Arc::clone(array);
+    let mut indices: Vec<u32> = (0..array.len() as u32).collect();
+    let mut rows = indices.clone();
+    let mut route = leaf.route.iter();
+
+    loop {
+        match array.data_type() {
+            DataType::Struct(_) => {
+                let struct_array = array.as_struct();
+                let child = route.next()
+                    .and_then(|field| struct_array.column_by_name(field))
+                    .ok_or_else(|| ArrowError::SchemaError(format!(
+                        "Decoded data does not match nested column '{}'", leaf.name
+                    )))?;
+                let child = Arc::clone(child);
+                select(&mut indices, &mut rows, |i| struct_array.is_valid(i).then_some(i..i + 1));
+                array = child;
+            }
+            DataType::List(_) => {
+                let list = array.as_list::<i32>();
+                let offsets = list.value_offsets();
+                select(&mut indices, &mut rows, |i| {
+                    list.is_valid(i).then(|| offsets[i] as usize..offsets[i + 1] as usize)
+                });
+                array = Arc::clone(list.values());
+            }
+            DataType::LargeList(_) => {
+                let list = array.as_list::<i64>();
+                let offsets = list.value_offsets();
+                select(&mut indices, &mut rows, |i| {
+                    list.is_valid(i).then(|| offsets[i] as usize..offsets[i + 1] as usize)
+                });
+                array = Arc::clone(list.values());
+            }
+            DataType::FixedSizeList(_, _) => {
+                let list = array.as_fixed_size_list();
+                let length = list.value_length() as usize;
+                select(&mut indices, &mut rows, |i| {
+                    list.is_valid(i).then(|| {
+                        let start = list.value_offset(i) as usize;
+                        start..start + length
+                    })
+                });
+                array = Arc::clone(list.values());
+            }
+            DataType::Map(_, _) => {
+                let map = array.as_map();
+                let offsets = map.value_offsets();
+                select(&mut indices, &mut rows, |i| {
+                    map.is_valid(i).then(|| offsets[i] as usize..offsets[i + 1] as usize)
+                });
+                array = Arc::new(map.entries().clone());
+            }
+            _ => break,
+        }
+    }
+
+    let values = take(array.as_ref(), &UInt32Array::from(indices), None)?;
+    Ok(LeafValues { values, rows: Some(rows) })
+}
+
+/// Replaces each index with the child indices `children` returns for it (none for
+/// `None`), keeping the row each index belongs to.
+fn select(indices: &mut Vec<u32>, rows: &mut Vec<u32>, children: impl Fn(usize) -> Option<Range<usize>>) {
+    let mut child_indices = Vec::with_capacity(indices.len());
+    let mut child_rows = Vec::with_capacity(rows.len());
+    for (&index, &row) in indices.iter().zip(rows.iter()) {
+        for child in children(index as usize).into_iter().flatten() {
+            child_indices.push(child as u32);
+            child_rows.push(row);
+        }
+    }
+    *indices = child_indices;
+    *rows = child_rows;
+}